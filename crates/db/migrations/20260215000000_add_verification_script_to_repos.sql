@@ -0,0 +1,3 @@
+-- Add verification_script column to repos table
+-- This script must pass in the worktree before the merge queue lands a workspace
+ALTER TABLE repos ADD COLUMN verification_script TEXT;
@@ -0,0 +1,14 @@
+-- Per-repo policies controlling how agent changes are committed
+CREATE TABLE IF NOT EXISTS repo_commit_policies (
+    repo_id          BLOB PRIMARY KEY,
+    message_style    TEXT NOT NULL DEFAULT 'agent_summary'
+                        CHECK (message_style IN ('agent_summary', 'conventional')),
+    squash_on_finish INTEGER NOT NULL DEFAULT 0,
+    signoff          INTEGER NOT NULL DEFAULT 0,
+    gpg_sign         INTEGER NOT NULL DEFAULT 0,
+    author_name      TEXT,
+    author_email     TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
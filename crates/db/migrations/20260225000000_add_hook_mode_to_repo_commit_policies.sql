@@ -0,0 +1,3 @@
+-- How repository commit hooks (pre-commit, commit-msg) are handled for agent commits
+ALTER TABLE repo_commit_policies ADD COLUMN hook_mode TEXT NOT NULL DEFAULT 'run'
+    CHECK (hook_mode IN ('run', 'skip', 'feedback'));
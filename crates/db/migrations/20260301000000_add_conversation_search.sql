@@ -0,0 +1,21 @@
+-- Full-text index over normalized conversation entries (messages, commands, file paths)
+CREATE VIRTUAL TABLE IF NOT EXISTS conversation_search USING fts5(
+    content,
+    kind UNINDEXED,
+    execution_process_id UNINDEXED,
+    entry_index UNINDEXED,
+    tokenize = 'unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS conversation_search_delete_process
+AFTER DELETE ON execution_processes
+BEGIN
+    DELETE FROM conversation_search WHERE execution_process_id = old.id;
+END;
+
+-- Processes whose conversation has been indexed, so ones finished while the server
+-- wasn't running (or that crashed before indexing) can be found and backfilled
+CREATE TABLE IF NOT EXISTS conversation_search_indexed (
+    execution_process_id BLOB PRIMARY KEY REFERENCES execution_processes(id) ON DELETE CASCADE,
+    indexed_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
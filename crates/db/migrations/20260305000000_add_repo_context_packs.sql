@@ -0,0 +1,12 @@
+-- Per-repo settings for the context pack generated when a workspace is set up
+CREATE TABLE IF NOT EXISTS repo_context_packs (
+    repo_id      BLOB PRIMARY KEY,
+    enabled      INTEGER NOT NULL DEFAULT 0,
+    delivery     TEXT NOT NULL DEFAULT 'file'
+                    CHECK (delivery IN ('file', 'prompt')),
+    token_budget INTEGER NOT NULL DEFAULT 2000,
+    notes        TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
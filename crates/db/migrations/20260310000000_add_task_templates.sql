@@ -0,0 +1,40 @@
+-- Parameterized task templates with executor and repo defaults
+CREATE TABLE task_templates (
+    id                  BLOB PRIMARY KEY,
+    project_id          BLOB,  -- NULL for global templates
+    name                TEXT NOT NULL,
+    description         TEXT,
+    title               TEXT NOT NULL,
+    prompt              TEXT NOT NULL,
+    variables           TEXT NOT NULL DEFAULT '[]',  -- JSON array of TemplateVariable
+    executor_config     TEXT,                         -- JSON ExecutorConfig
+    repos               TEXT NOT NULL DEFAULT '[]',  -- JSON array of TemplateRepo
+    verification_script TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_templates_project_id ON task_templates(project_id);
+
+CREATE UNIQUE INDEX idx_task_templates_unique_name_project
+ON task_templates(project_id, name)
+WHERE project_id IS NOT NULL;
+
+CREATE UNIQUE INDEX idx_task_templates_unique_name_global
+ON task_templates(name)
+WHERE project_id IS NULL;
+
+-- Images attached to every task created from a template
+CREATE TABLE task_template_images (
+    id                    BLOB PRIMARY KEY,
+    template_id           BLOB NOT NULL,
+    image_id              BLOB NOT NULL,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (template_id) REFERENCES task_templates(id) ON DELETE CASCADE,
+    FOREIGN KEY (image_id) REFERENCES images(id) ON DELETE CASCADE,
+    UNIQUE(template_id, image_id)
+);
+
+CREATE INDEX idx_task_template_images_template_id ON task_template_images(template_id);
+CREATE INDEX idx_task_template_images_image_id ON task_template_images(image_id);
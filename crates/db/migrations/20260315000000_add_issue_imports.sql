@@ -0,0 +1,36 @@
+-- External issue trackers that tasks are imported from
+CREATE TABLE issue_import_sources (
+    id               BLOB PRIMARY KEY,
+    project_id       BLOB NOT NULL,
+    tracker          TEXT NOT NULL CHECK (tracker IN ('github', 'gitlab', 'jira')),
+    base_url         TEXT,                          -- API root for GitLab and Jira
+    repository       TEXT NOT NULL,                 -- owner/repo, GitLab project path or Jira project key
+    query            TEXT,
+    labels           TEXT NOT NULL DEFAULT '[]',    -- JSON array of label names
+    api_token        TEXT,                          -- encrypted with the secrets key
+    api_user         TEXT,                          -- Jira account for basic auth
+    comment_pr_link  INTEGER NOT NULL DEFAULT 0,
+    last_synced_at   TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_issue_import_sources_project_id ON issue_import_sources(project_id);
+
+-- Link from an imported task back to its issue. The link outlives a deleted task so the
+-- issue is not imported again.
+CREATE TABLE task_issue_links (
+    id               BLOB PRIMARY KEY,
+    task_id          BLOB UNIQUE,
+    source_id        BLOB NOT NULL,
+    external_id      TEXT NOT NULL,                 -- issue number or key
+    url              TEXT NOT NULL,
+    issue_updated_at TEXT,
+    pr_comment_url   TEXT,                          -- PR link last commented on the issue
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL,
+    FOREIGN KEY (source_id) REFERENCES issue_import_sources(id) ON DELETE CASCADE,
+    UNIQUE(source_id, external_id)
+);
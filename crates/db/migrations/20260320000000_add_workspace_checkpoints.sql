@@ -0,0 +1,28 @@
+-- Named snapshots of every repo in a workspace: HEAD plus the uncommitted changes
+CREATE TABLE workspace_checkpoints (
+    id           BLOB PRIMARY KEY,
+    workspace_id BLOB NOT NULL,
+    name         TEXT NOT NULL,
+    reason       TEXT NOT NULL DEFAULT 'manual'
+                    CHECK (reason IN ('manual', 'before_rebase', 'before_reset', 'before_conflict_abort', 'before_restore')),
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_workspace_checkpoints_workspace_id ON workspace_checkpoints(workspace_id);
+
+CREATE TABLE workspace_checkpoint_repos (
+    id              BLOB PRIMARY KEY,
+    checkpoint_id   BLOB NOT NULL,
+    repo_id         BLOB NOT NULL,
+    head_commit     TEXT NOT NULL,
+    -- Commit on top of head_commit holding the uncommitted changes; kept alive by the
+    -- refs/vibe-kanban/checkpoints/<checkpoint_id> ref in the repo
+    snapshot_commit TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (checkpoint_id) REFERENCES workspace_checkpoints(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    UNIQUE(checkpoint_id, repo_id)
+);
+
+CREATE INDEX idx_workspace_checkpoint_repos_checkpoint_id ON workspace_checkpoint_repos(checkpoint_id);
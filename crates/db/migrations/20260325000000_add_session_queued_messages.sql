@@ -0,0 +1,22 @@
+-- Ordered follow-up messages waiting to run in a session, drained one at a time
+CREATE TABLE session_queued_messages (
+    id              BLOB PRIMARY KEY,
+    session_id      BLOB NOT NULL,
+    position        INTEGER NOT NULL,
+    message         TEXT NOT NULL,
+    executor_config TEXT,  -- JSON ExecutorConfig; NULL reuses the session's latest executor
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_session_queued_messages_session_id
+ON session_queued_messages(session_id, position);
+
+-- Sessions whose queue stopped draining because an execution failed or was stopped
+CREATE TABLE session_queue_pauses (
+    session_id BLOB PRIMARY KEY,
+    reason     TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+-- Screenshots of a dev server route at one viewport size, optionally compared against
+-- the same route served from the target branch
+CREATE TABLE visual_snapshots (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    route                TEXT NOT NULL,
+    viewport_width       INTEGER NOT NULL,
+    viewport_height      INTEGER NOT NULL,
+    image_id             BLOB NOT NULL,
+    baseline_image_id    BLOB,
+    diff_image_id        BLOB,
+    -- Fraction of pixels that differ from the baseline, NULL without a baseline
+    diff_ratio           REAL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (image_id) REFERENCES images(id) ON DELETE CASCADE,
+    FOREIGN KEY (baseline_image_id) REFERENCES images(id) ON DELETE SET NULL,
+    FOREIGN KEY (diff_image_id) REFERENCES images(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_visual_snapshots_execution_process_id ON visual_snapshots(execution_process_id);
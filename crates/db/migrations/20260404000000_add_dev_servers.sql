@@ -0,0 +1,33 @@
+-- Port and health of a repo's dev server in a workspace. The row is kept across restarts
+-- so the workspace keeps its port.
+CREATE TABLE dev_servers (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    repo_id              BLOB NOT NULL,
+    -- Latest execution of the dev server script
+    execution_process_id BLOB NOT NULL,
+    port                 INTEGER NOT NULL,
+    status               TEXT NOT NULL DEFAULT 'starting'
+                            CHECK (status IN ('starting', 'ready', 'crashed', 'stopped')),
+    -- Automatic restarts since the dev server was last started by the user
+    restart_count        INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    UNIQUE (workspace_id, repo_id)
+);
+
+CREATE INDEX idx_dev_servers_execution_process_id ON dev_servers(execution_process_id);
+
+-- Per-repo settings for detecting when a dev server is ready and restarting it on crash
+CREATE TABLE IF NOT EXISTS repo_dev_server_settings (
+    repo_id       BLOB PRIMARY KEY,
+    health_path   TEXT,
+    ready_pattern TEXT,
+    auto_restart  INTEGER NOT NULL DEFAULT 1,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
@@ -0,0 +1,19 @@
+-- Encrypted environment variables, each scoped to exactly one project, repo or coding
+-- agent. Values are only ever stored encrypted with the local secrets key.
+CREATE TABLE secrets (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB,
+    repo_id    BLOB,
+    executor   TEXT,
+    name       TEXT NOT NULL,
+    -- base64 of the nonce followed by the AES-256-GCM ciphertext
+    ciphertext TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    CHECK ((project_id IS NOT NULL) + (repo_id IS NOT NULL) + (executor IS NOT NULL) = 1)
+);
+
+CREATE UNIQUE INDEX idx_secrets_scope_name
+    ON secrets(IFNULL(project_id, x''), IFNULL(repo_id, x''), IFNULL(executor, ''), name);
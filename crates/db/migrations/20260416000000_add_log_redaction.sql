@@ -0,0 +1,17 @@
+-- Values and variable names a project never wants masked in process logs, e.g. test
+-- fixtures that look like credentials
+CREATE TABLE IF NOT EXISTS project_redaction_settings (
+    project_id BLOB PRIMARY KEY,
+    allowlist  TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Number of values masked in the logs of an execution process, by kind
+CREATE TABLE IF NOT EXISTS execution_process_redactions (
+    execution_process_id BLOB PRIMARY KEY,
+    counts               TEXT NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
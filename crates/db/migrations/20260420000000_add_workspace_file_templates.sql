@@ -0,0 +1,21 @@
+-- Files rendered into each worktree with per-workspace values, and directories linked
+-- into each worktree instead of copied
+ALTER TABLE repos ADD COLUMN template_files TEXT;
+ALTER TABLE repos ADD COLUMN symlink_dirs TEXT;
+
+-- Ports handed out to templated files before the workspace's dev servers start, so the
+-- dev servers later listen where the files point
+CREATE TABLE IF NOT EXISTS workspace_ports (
+    id           BLOB PRIMARY KEY,
+    workspace_id BLOB NOT NULL,
+    repo_id      BLOB NOT NULL,
+    -- Dev process the port is for, NULL for the repo's dev server script
+    process_name TEXT,
+    port         INTEGER NOT NULL,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workspace_ports_process
+    ON workspace_ports(workspace_id, repo_id, IFNULL(process_name, ''));
@@ -0,0 +1,24 @@
+-- Workspaces waiting to be landed by the merge queue, one serial lane per repo and
+-- target branch. Rows outlive a restart so queued merges are picked up again.
+CREATE TABLE merge_queue_entries (
+    id             BLOB PRIMARY KEY,
+    workspace_id   BLOB NOT NULL,
+    repo_id        BLOB NOT NULL,
+    target_branch  TEXT NOT NULL,
+    branch         TEXT NOT NULL,
+    commit_message TEXT NOT NULL,
+    status         TEXT NOT NULL DEFAULT 'queued'
+                      CHECK (status IN ('queued', 'rebasing', 'verifying', 'merging',
+                                        'merged', 'failed', 'cancelled')),
+    -- Set once merged
+    merge_commit   TEXT,
+    -- JSON-encoded failure, set once failed
+    failure        TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_merge_queue_entries_lane
+    ON merge_queue_entries(repo_id, target_branch, status);
@@ -0,0 +1,71 @@
+-- Named dev processes of a repo (backend, frontend, worker, ...), started together as a
+-- group in place of the repo's single dev server script
+CREATE TABLE repo_dev_processes (
+    id         BLOB PRIMARY KEY,
+    repo_id    BLOB NOT NULL,
+    name       TEXT NOT NULL,
+    command    TEXT NOT NULL,
+    -- JSON array of process names that must be ready before this one starts
+    depends_on TEXT NOT NULL DEFAULT '[]',
+    -- JSON object of extra environment variables
+    env        TEXT NOT NULL DEFAULT '{}',
+    position   INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    UNIQUE (repo_id, name)
+);
+
+-- Dev servers are tracked per process: add process_name and replace the
+-- (workspace_id, repo_id) uniqueness with one per process. SQLite can't drop a table
+-- constraint, so the table is rebuilt with its rows, keeping each workspace's ports.
+CREATE TABLE dev_servers_new (
+    id                   BLOB PRIMARY KEY,
+    workspace_id         BLOB NOT NULL,
+    repo_id              BLOB NOT NULL,
+    -- Name of the repo's dev process, NULL for its single dev server script
+    process_name         TEXT,
+    -- Latest execution of the dev server script
+    execution_process_id BLOB NOT NULL,
+    port                 INTEGER NOT NULL,
+    status               TEXT NOT NULL DEFAULT 'starting'
+                            CHECK (status IN ('starting', 'ready', 'crashed', 'stopped')),
+    -- Automatic restarts since the dev server was last started by the user
+    restart_count        INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+INSERT INTO dev_servers_new (
+    id,
+    workspace_id,
+    repo_id,
+    execution_process_id,
+    port,
+    status,
+    restart_count,
+    created_at,
+    updated_at
+)
+SELECT
+    id,
+    workspace_id,
+    repo_id,
+    execution_process_id,
+    port,
+    status,
+    restart_count,
+    created_at,
+    updated_at
+FROM dev_servers;
+
+DROP TABLE dev_servers;
+
+ALTER TABLE dev_servers_new RENAME TO dev_servers;
+
+CREATE UNIQUE INDEX idx_dev_servers_process
+    ON dev_servers(workspace_id, repo_id, IFNULL(process_name, ''));
+CREATE INDEX idx_dev_servers_execution_process_id ON dev_servers(execution_process_id);
@@ -8,6 +8,7 @@ use sqlx::{
 use utils::assets::asset_dir;
 
 pub mod models;
+pub mod test_utils;
 
 async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     use std::collections::HashSet;
@@ -86,6 +87,21 @@ impl DBService {
         Ok(DBService { pool })
     }
 
+    /// A private in-memory database with all migrations applied, for tests.
+    pub async fn new_in_memory() -> Result<DBService, Error> {
+        // Each `:memory:` URL gets its own shared-cache database, which lives as long as
+        // one connection to it stays open
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")?;
+        let pool = SqlitePoolOptions::new()
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await?;
+        run_migrations(&pool).await?;
+        Ok(DBService { pool })
+    }
+
     pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
     where
         F: for<'a> Fn(
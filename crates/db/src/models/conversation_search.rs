@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What part of a conversation an indexed document came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ConversationSearchKind {
+    UserMessage,
+    AssistantMessage,
+    ToolCommand,
+    FilePath,
+    Error,
+}
+
+/// A single searchable piece of a normalized conversation entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversationSearchDocument {
+    pub entry_index: i64,
+    pub kind: ConversationSearchKind,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ConversationSearchMatch {
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Uuid,
+    pub session_id: Uuid,
+    pub execution_process_id: Uuid,
+    /// Index of the matching entry in the process's normalized conversation
+    pub entry_index: i64,
+    pub kind: ConversationSearchKind,
+    /// Matching text with the hits wrapped in `**`
+    pub snippet: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct ConversationSearch;
+
+impl ConversationSearch {
+    /// Replace everything indexed for an execution process with `documents`.
+    pub async fn replace_for_execution(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        documents: &[ConversationSearchDocument],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "DELETE FROM conversation_search WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for document in documents {
+            sqlx::query!(
+                r#"INSERT INTO conversation_search (content, kind, execution_process_id, entry_index)
+                   VALUES ($1, $2, $3, $4)"#,
+                document.content,
+                document.kind,
+                execution_process_id,
+                document.entry_index
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"INSERT INTO conversation_search_indexed (execution_process_id)
+               VALUES ($1)
+               ON CONFLICT(execution_process_id) DO UPDATE SET indexed_at = datetime('now', 'subsec')"#,
+            execution_process_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Finished coding agent processes whose conversation hasn't been indexed yet, oldest
+    /// first.
+    pub async fn find_unindexed_execution_processes(
+        pool: &SqlitePool,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT ep.id as "id!: Uuid"
+               FROM execution_processes ep
+               LEFT JOIN conversation_search_indexed csi ON csi.execution_process_id = ep.id
+               WHERE ep.status != 'running'
+                 AND ep.run_reason = 'codingagent'
+                 AND csi.execution_process_id IS NULL
+               ORDER BY ep.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Search indexed conversations, best matches first. `query` is plain text: every
+    /// whitespace-separated term must match, and punctuation inside a term (paths,
+    /// error codes) is matched as a phrase.
+    pub async fn search(
+        pool: &SqlitePool,
+        query: &str,
+        project_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<ConversationSearchMatch>, sqlx::Error> {
+        let Some(fts_query) = to_fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        sqlx::query_as!(
+            ConversationSearchMatch,
+            r#"SELECT t.project_id as "project_id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      t.title as "task_title!",
+                      w.id as "workspace_id!: Uuid",
+                      s.id as "session_id!: Uuid",
+                      ep.id as "execution_process_id!: Uuid",
+                      cs.entry_index as "entry_index!: i64",
+                      cs.kind as "kind!: ConversationSearchKind",
+                      snippet(conversation_search, 0, '**', '**', '...', 16) as "snippet!: String",
+                      ep.created_at as "created_at!: DateTime<Utc>"
+               FROM conversation_search cs
+               JOIN execution_processes ep ON ep.id = cs.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE conversation_search MATCH $1
+                 AND ($2 IS NULL OR t.project_id = $2)
+               ORDER BY cs.rank
+               LIMIT $3"#,
+            fts_query,
+            project_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+/// Turn free text into an FTS5 query that can't fail to parse: each term becomes a
+/// quoted string, and the terms are ANDed together.
+fn to_fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
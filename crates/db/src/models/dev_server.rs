@@ -0,0 +1,315 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DevServerStatus {
+    /// Running, but not answering on its port yet
+    Starting,
+    Ready,
+    /// Exited with an error; an automatic restart may be pending
+    Crashed,
+    Stopped,
+}
+
+/// Dev server of one repo in a workspace, with the port it was assigned.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DevServer {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    /// Name of the repo's dev process, `None` for its single dev server script
+    pub process_name: Option<String>,
+    /// Latest execution of the repo's dev server script
+    pub execution_process_id: Uuid,
+    /// Port passed to the dev server script as `PORT`
+    pub port: i64,
+    pub status: DevServerStatus,
+    /// Automatic restarts since the dev server was last started by the user or came up ready
+    pub restart_count: i64,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateDevServer {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub process_name: Option<String>,
+    pub execution_process_id: Uuid,
+    pub port: i64,
+}
+
+impl DevServer {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      process_name,
+                      execution_process_id as "execution_process_id!: Uuid",
+                      port as "port!: i64",
+                      status as "status!: DevServerStatus",
+                      restart_count as "restart_count!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_servers
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      process_name,
+                      execution_process_id as "execution_process_id!: Uuid",
+                      port as "port!: i64",
+                      status as "status!: DevServerStatus",
+                      restart_count as "restart_count!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_servers
+               WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      process_name,
+                      execution_process_id as "execution_process_id!: Uuid",
+                      port as "port!: i64",
+                      status as "status!: DevServerStatus",
+                      restart_count as "restart_count!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_servers
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      process_name,
+                      execution_process_id as "execution_process_id!: Uuid",
+                      port as "port!: i64",
+                      status as "status!: DevServerStatus",
+                      restart_count as "restart_count!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_servers
+               WHERE workspace_id = $1
+               ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_process(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        process_name: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      process_name,
+                      execution_process_id as "execution_process_id!: Uuid",
+                      port as "port!: i64",
+                      status as "status!: DevServerStatus",
+                      restart_count as "restart_count!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_servers
+               WHERE workspace_id = $1 AND repo_id = $2 AND process_name IS $3"#,
+            workspace_id,
+            repo_id,
+            process_name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      process_name,
+                      execution_process_id as "execution_process_id!: Uuid",
+                      port as "port!: i64",
+                      status as "status!: DevServerStatus",
+                      restart_count as "restart_count!: i64",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_servers
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Ports held by dev servers that haven't been stopped, which may still come back
+    /// after a crash.
+    pub async fn reserved_ports(pool: &SqlitePool) -> Result<Vec<i64>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT port as "port!: i64"
+               FROM dev_servers
+               WHERE status != 'stopped'"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record a new execution of a dev server. An existing row for the same repo and
+    /// process is reused, so its restart count is kept.
+    pub async fn upsert(pool: &SqlitePool, data: &CreateDevServer) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            DevServer,
+            r#"INSERT INTO dev_servers (id, workspace_id, repo_id, process_name, execution_process_id, port)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(workspace_id, repo_id, IFNULL(process_name, '')) DO UPDATE SET
+                   execution_process_id = excluded.execution_process_id,
+                   port = excluded.port,
+                   status = 'starting',
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         process_name,
+                         execution_process_id as "execution_process_id!: Uuid",
+                         port as "port!: i64",
+                         status as "status!: DevServerStatus",
+                         restart_count as "restart_count!: i64",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.repo_id,
+            data.process_name,
+            data.execution_process_id,
+            data.port
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Set the status of the dev server started by `execution_process_id`. Does nothing
+    /// once the dev server has been restarted under another execution.
+    pub async fn update_status(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        status: DevServerStatus,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dev_servers
+               SET status = $2, updated_at = datetime('now', 'subsec')
+               WHERE execution_process_id = $1 AND status != $2"#,
+            execution_process_id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark the dev server ready, unless it exited while its readiness was being checked.
+    /// A dev server that came back up after crashing gets its full restart budget again.
+    pub async fn mark_ready(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dev_servers
+               SET status = 'ready', restart_count = 0, updated_at = datetime('now', 'subsec')
+               WHERE execution_process_id = $1 AND status = 'starting'"#,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn increment_restart_count(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dev_servers
+               SET restart_count = restart_count + 1, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Give one dev server process a fresh restart budget, when the user starts it.
+    pub async fn reset_restart_count(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        process_name: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dev_servers
+               SET restart_count = 0
+               WHERE workspace_id = $1 AND repo_id = $2 AND process_name IS $3
+                 AND restart_count != 0"#,
+            workspace_id,
+            repo_id,
+            process_name
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark every dev server stopped, at startup when none of them survived.
+    pub async fn stop_all(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dev_servers
+               SET status = 'stopped', updated_at = datetime('now', 'subsec')
+               WHERE status != 'stopped'"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
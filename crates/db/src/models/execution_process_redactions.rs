@@ -0,0 +1,42 @@
+use sqlx::{SqlitePool, types::Json};
+use utils::redact::RedactionCounts;
+use uuid::Uuid;
+
+/// Redactions made in the logs of finished execution processes. Running processes keep
+/// their counts in memory.
+pub struct ExecutionProcessRedactions;
+
+impl ExecutionProcessRedactions {
+    pub async fn find_counts(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<RedactionCounts>, sqlx::Error> {
+        let counts = sqlx::query_scalar!(
+            r#"SELECT counts as "counts!: Json<RedactionCounts>"
+               FROM execution_process_redactions
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(counts.map(|counts| counts.0))
+    }
+
+    pub async fn save(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        counts: &RedactionCounts,
+    ) -> Result<(), sqlx::Error> {
+        let counts = Json(counts);
+        sqlx::query!(
+            r#"INSERT INTO execution_process_redactions (execution_process_id, counts)
+               VALUES ($1, $2)
+               ON CONFLICT(execution_process_id) DO UPDATE SET counts = excluded.counts"#,
+            execution_process_id,
+            counts
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,347 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum IssueTrackerKind {
+    /// Read through the GitHub CLI, using its authentication.
+    Github,
+    Gitlab,
+    Jira,
+}
+
+/// A tracker query whose matching issues are imported as tasks into a project.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct IssueImportSource {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub tracker: IssueTrackerKind,
+    /// API root for GitLab and Jira, e.g. `https://gitlab.com`.
+    pub base_url: Option<String>,
+    /// `owner/repo` on GitHub, the project path on GitLab, the project key on Jira.
+    pub repository: String,
+    /// Search text (GitHub, GitLab) or a JQL condition (Jira) issues must match.
+    pub query: Option<String>,
+    #[ts(type = "Array<string>")]
+    pub labels: Json<Vec<String>>,
+    /// Encrypted with the secrets key; never sent to the client.
+    #[serde(skip_serializing)]
+    #[ts(skip)]
+    pub api_token: Option<String>,
+    /// Account the API token belongs to, for Jira basic auth.
+    pub api_user: Option<String>,
+    /// Comment the workspace PR link on the issue once the PR is created.
+    pub comment_pr_link: bool,
+    #[ts(type = "Date | null")]
+    pub last_synced_at: Option<DateTime<Utc>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create and (full) update payload for an import source.
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateIssueImportSource {
+    pub project_id: Uuid,
+    pub tracker: IssueTrackerKind,
+    pub base_url: Option<String>,
+    pub repository: String,
+    pub query: Option<String>,
+    pub labels: Vec<String>,
+    /// Left unchanged on update when not set.
+    pub api_token: Option<String>,
+    pub api_user: Option<String>,
+    pub comment_pr_link: bool,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskIssueLink {
+    pub id: Uuid,
+    /// `None` once the imported task has been deleted.
+    pub task_id: Option<Uuid>,
+    pub source_id: Uuid,
+    /// Issue number (GitHub, GitLab) or key (Jira).
+    pub external_id: String,
+    pub url: String,
+    #[ts(type = "Date | null")]
+    pub issue_updated_at: Option<DateTime<Utc>>,
+    pub pr_comment_url: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IssueImportSource {
+    /// Sources of `project_id`, or of every project.
+    pub async fn find_all(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            IssueImportSource,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      tracker as "tracker!: IssueTrackerKind",
+                      base_url,
+                      repository,
+                      query,
+                      labels as "labels!: Json<Vec<String>>",
+                      api_token,
+                      api_user,
+                      comment_pr_link as "comment_pr_link!: bool",
+                      last_synced_at as "last_synced_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM issue_import_sources
+               WHERE $1 IS NULL OR project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            IssueImportSource,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      tracker as "tracker!: IssueTrackerKind",
+                      base_url,
+                      repository,
+                      query,
+                      labels as "labels!: Json<Vec<String>>",
+                      api_token,
+                      api_user,
+                      comment_pr_link as "comment_pr_link!: bool",
+                      last_synced_at as "last_synced_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM issue_import_sources
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateIssueImportSource,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let labels = Json(&data.labels);
+        sqlx::query_as!(
+            IssueImportSource,
+            r#"INSERT INTO issue_import_sources (id, project_id, tracker, base_url, repository, query, labels, api_token, api_user, comment_pr_link)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         tracker as "tracker!: IssueTrackerKind",
+                         base_url,
+                         repository,
+                         query,
+                         labels as "labels!: Json<Vec<String>>",
+                         api_token,
+                         api_user,
+                         comment_pr_link as "comment_pr_link!: bool",
+                         last_synced_at as "last_synced_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.tracker,
+            data.base_url,
+            data.repository,
+            data.query,
+            labels,
+            data.api_token,
+            data.api_user,
+            data.comment_pr_link
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreateIssueImportSource,
+    ) -> Result<Self, sqlx::Error> {
+        let labels = Json(&data.labels);
+        sqlx::query_as!(
+            IssueImportSource,
+            r#"UPDATE issue_import_sources
+               SET tracker = $2, base_url = $3, repository = $4, query = $5, labels = $6,
+                   api_token = COALESCE($7, api_token), api_user = $8, comment_pr_link = $9,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         tracker as "tracker!: IssueTrackerKind",
+                         base_url,
+                         repository,
+                         query,
+                         labels as "labels!: Json<Vec<String>>",
+                         api_token,
+                         api_user,
+                         comment_pr_link as "comment_pr_link!: bool",
+                         last_synced_at as "last_synced_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.tracker,
+            data.base_url,
+            data.repository,
+            data.query,
+            labels,
+            data.api_token,
+            data.api_user,
+            data.comment_pr_link
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Whether any source stores an API token, which is encrypted under the secrets key.
+    pub async fn exists_encrypted_credentials(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM issue_import_sources WHERE api_token IS NOT NULL) as "exists!: bool""#
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn mark_synced(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE issue_import_sources SET last_synced_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM issue_import_sources WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl TaskIssueLink {
+    pub async fn find_by_external_id(
+        pool: &SqlitePool,
+        source_id: Uuid,
+        external_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id: Uuid",
+                      source_id as "source_id!: Uuid",
+                      external_id,
+                      url,
+                      issue_updated_at as "issue_updated_at: DateTime<Utc>",
+                      pr_comment_url,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issue_links
+               WHERE source_id = $1 AND external_id = $2"#,
+            source_id,
+            external_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id: Uuid",
+                      source_id as "source_id!: Uuid",
+                      external_id,
+                      url,
+                      issue_updated_at as "issue_updated_at: DateTime<Utc>",
+                      pr_comment_url,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issue_links
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        source_id: Uuid,
+        external_id: &str,
+        url: &str,
+        issue_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO task_issue_links (id, task_id, source_id, external_id, url, issue_updated_at)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+            id,
+            task_id,
+            source_id,
+            external_id,
+            url,
+            issue_updated_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_issue(
+        pool: &SqlitePool,
+        id: Uuid,
+        url: &str,
+        issue_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_issue_links
+               SET url = $2, issue_updated_at = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            url,
+            issue_updated_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_pr_comment_url(
+        pool: &SqlitePool,
+        id: Uuid,
+        pr_url: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_issue_links
+               SET pr_comment_url = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            pr_url
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
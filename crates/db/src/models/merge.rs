@@ -250,6 +250,24 @@ impl Merge {
 
         Ok(())
     }
+
+    /// Update the branch a PR targets (e.g. after retargeting a stacked PR)
+    pub async fn update_target_branch_name(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        target_branch_name: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE merges SET target_branch_name = $1 WHERE id = $2",
+            target_branch_name,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Find all merges for a workspace (returns both direct and PR merges)
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
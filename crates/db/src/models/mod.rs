@@ -1,16 +1,32 @@
 pub mod coding_agent_turn;
+pub mod conversation_search;
+pub mod dev_server;
 pub mod execution_process;
 pub mod execution_process_logs;
+pub mod execution_process_redactions;
 pub mod execution_process_repo_state;
 pub mod image;
+pub mod issue_import;
 pub mod merge;
 pub mod migration_state;
 pub mod project;
+pub mod project_redaction_settings;
 pub mod project_repo;
+pub mod queued_merge;
+pub mod queued_message;
 pub mod repo;
+pub mod repo_commit_policy;
+pub mod repo_context_pack;
+pub mod repo_dev_process;
+pub mod repo_dev_server_settings;
 pub mod scratch;
+pub mod secret;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod task_template;
+pub mod visual_snapshot;
 pub mod workspace;
+pub mod workspace_checkpoint;
+pub mod workspace_port;
 pub mod workspace_repo;
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a project keeps visible in process logs although it looks like a secret.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectRedactionSettings {
+    pub project_id: Uuid,
+    /// Exact values, or names of variables whose values, that are never masked
+    #[ts(type = "Array<string>")]
+    pub allowlist: Json<Vec<String>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateProjectRedactionSettings {
+    pub allowlist: Vec<String>,
+}
+
+impl ProjectRedactionSettings {
+    /// Settings used for projects that have never been configured.
+    pub fn default_for(project_id: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            project_id,
+            allowlist: Json(Vec::new()),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectRedactionSettings,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      allowlist as "allowlist!: Json<Vec<String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_redaction_settings
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_or_default(pool: &SqlitePool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .unwrap_or_else(|| Self::default_for(project_id)))
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectRedactionSettings,
+    ) -> Result<Self, sqlx::Error> {
+        let mut allowlist: Vec<String> = data
+            .allowlist
+            .iter()
+            .map(|entry| entry.trim().to_string())
+            .filter(|entry| !entry.is_empty())
+            .collect();
+        allowlist.dedup();
+        let allowlist = Json(allowlist);
+
+        sqlx::query_as!(
+            ProjectRedactionSettings,
+            r#"INSERT INTO project_redaction_settings (project_id, allowlist)
+               VALUES ($1, $2)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   allowlist = excluded.allowlist,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid",
+                         allowlist as "allowlist!: Json<Vec<String>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            allowlist
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
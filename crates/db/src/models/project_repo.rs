@@ -80,7 +80,10 @@ impl ProjectRepo {
                       r.setup_script,
                       r.cleanup_script,
                       r.archive_script,
+                      r.verification_script,
                       r.copy_files,
+                      r.template_files,
+                      r.symlink_dirs,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
@@ -0,0 +1,262 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum QueuedMergeStatus {
+    Queued,
+    Rebasing,
+    Verifying,
+    Merging,
+    Merged,
+    Failed,
+    Cancelled,
+}
+
+/// A workspace waiting in (or processed by) the merge queue of a repo and target branch.
+#[derive(Debug, Clone, FromRow)]
+pub struct QueuedMerge {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub branch: String,
+    pub commit_message: String,
+    pub status: QueuedMergeStatus,
+    pub merge_commit: Option<String>,
+    /// JSON-encoded reason the merge failed
+    pub failure: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateQueuedMerge {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub branch: String,
+    pub commit_message: String,
+}
+
+impl QueuedMerge {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMerge,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch,
+                      branch,
+                      commit_message,
+                      status as "status!: QueuedMergeStatus",
+                      merge_commit,
+                      failure,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Entries in enqueue order, optionally limited to a single repository.
+    pub async fn find_all(
+        pool: &SqlitePool,
+        repo_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMerge,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch,
+                      branch,
+                      commit_message,
+                      status as "status!: QueuedMergeStatus",
+                      merge_commit,
+                      failure,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE $1 IS NULL OR repo_id = $1
+               ORDER BY created_at ASC"#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Entries still waiting in a lane, in the order they will be processed.
+    pub async fn find_queued_in_lane(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        target_branch: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMerge,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch,
+                      branch,
+                      commit_message,
+                      status as "status!: QueuedMergeStatus",
+                      merge_commit,
+                      failure,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE repo_id = $1 AND target_branch = $2 AND status = 'queued'
+               ORDER BY created_at ASC"#,
+            repo_id,
+            target_branch
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether the workspace already has an unfinished entry for the repository.
+    pub async fn exists_unfinished(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM merge_queue_entries
+                   WHERE workspace_id = $1 AND repo_id = $2
+                     AND status IN ('queued', 'rebasing', 'verifying', 'merging')
+               ) as "exists!: bool""#,
+            workspace_id,
+            repo_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Lanes (repo and target branch) with entries waiting to be processed.
+    pub async fn find_queued_lanes(pool: &SqlitePool) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT DISTINCT repo_id as "repo_id!: Uuid", target_branch
+               FROM merge_queue_entries
+               WHERE status = 'queued'"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.repo_id, row.target_branch))
+            .collect())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateQueuedMerge,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMerge,
+            r#"INSERT INTO merge_queue_entries
+                   (id, workspace_id, repo_id, target_branch, branch, commit_message)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         target_branch,
+                         branch,
+                         commit_message,
+                         status as "status!: QueuedMergeStatus",
+                         merge_commit,
+                         failure,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.repo_id,
+            data.target_branch,
+            data.branch,
+            data.commit_message
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: QueuedMergeStatus,
+        merge_commit: Option<&str>,
+        failure: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = $2, merge_commit = $3, failure = $4,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            merge_commit,
+            failure
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Move an entry from `from` to `to`, returning whether it was still in `from`.
+    pub async fn transition(
+        pool: &SqlitePool,
+        id: Uuid,
+        from: QueuedMergeStatus,
+        to: QueuedMergeStatus,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = $2"#,
+            id,
+            from,
+            to
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Fail entries that were being processed when the server stopped; their worktrees
+    /// may have been left half-rebased, so they are not retried automatically.
+    pub async fn fail_in_progress(pool: &SqlitePool, failure: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'failed', failure = $1, updated_at = datetime('now', 'subsec')
+               WHERE status IN ('rebasing', 'verifying', 'merging')"#,
+            failure
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Drop finished entries last updated more than `max_age` ago.
+    pub async fn delete_finished_older_than(
+        pool: &SqlitePool,
+        max_age: chrono::Duration,
+    ) -> Result<u64, sqlx::Error> {
+        let modifier = format!("-{} seconds", max_age.num_seconds());
+        let result = sqlx::query!(
+            r#"DELETE FROM merge_queue_entries
+               WHERE status IN ('merged', 'failed', 'cancelled')
+                 AND updated_at < datetime('now', 'subsec', $1)"#,
+            modifier
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
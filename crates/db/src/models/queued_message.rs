@@ -0,0 +1,290 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A follow-up message waiting to run in a session once the current execution finishes.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedMessage {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    /// Messages run in ascending position order
+    pub position: i64,
+    pub message: String,
+    /// Executor override for this message; the session's latest executor is used when unset
+    #[ts(type = "ExecutorConfig | null")]
+    pub executor_config: Option<Json<ExecutorConfig>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Why a session's queue stopped draining.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuePause {
+    pub session_id: Uuid,
+    pub reason: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl QueuedMessage {
+    /// Queued messages of a session in the order they will run.
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT id as "id!: Uuid",
+                      session_id as "session_id!: Uuid",
+                      position as "position!: i64",
+                      message,
+                      executor_config as "executor_config: Json<ExecutorConfig>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM session_queued_messages
+               WHERE session_id = $1
+               ORDER BY position ASC, created_at ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT id as "id!: Uuid",
+                      session_id as "session_id!: Uuid",
+                      position as "position!: i64",
+                      message,
+                      executor_config as "executor_config: Json<ExecutorConfig>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM session_queued_messages
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Remove and return the message that runs next in a session, unless its queue is
+    /// paused. A single statement, so concurrent callers never take the same message.
+    pub async fn take_next(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"DELETE FROM session_queued_messages
+               WHERE id = (SELECT id FROM session_queued_messages
+                           WHERE session_id = $1
+                           ORDER BY position ASC, created_at ASC
+                           LIMIT 1)
+                 AND NOT EXISTS (SELECT 1 FROM session_queue_pauses WHERE session_id = $1)
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         position as "position!: i64",
+                         message,
+                         executor_config as "executor_config: Json<ExecutorConfig>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Put a taken message back at the front of its session's queue.
+    pub async fn restore(pool: &SqlitePool, queued: &QueuedMessage) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO session_queued_messages
+                   (id, session_id, position, message, executor_config, created_at, updated_at)
+               VALUES ($1, $2,
+                       (SELECT COALESCE(MIN(position) - 1, 0) FROM session_queued_messages WHERE session_id = $2),
+                       $3, $4, $5, $6)"#,
+            queued.id,
+            queued.session_id,
+            queued.message,
+            queued.executor_config,
+            queued.created_at,
+            queued.updated_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn exists_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM session_queued_messages WHERE session_id = $1) as "exists!: bool""#,
+            session_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(result)
+    }
+
+    /// Append a message to the end of a session's queue.
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        session_id: Uuid,
+        message: &str,
+        executor_config: Option<&ExecutorConfig>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_config = executor_config.map(Json);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"INSERT INTO session_queued_messages (id, session_id, position, message, executor_config)
+               VALUES ($1, $2,
+                       (SELECT COALESCE(MAX(position) + 1, 0) FROM session_queued_messages WHERE session_id = $2),
+                       $3, $4)
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         position as "position!: i64",
+                         message,
+                         executor_config as "executor_config: Json<ExecutorConfig>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            session_id,
+            message,
+            executor_config
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        message: &str,
+        executor_config: Option<&ExecutorConfig>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_config = executor_config.map(Json);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"UPDATE session_queued_messages
+               SET message = $2, executor_config = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         position as "position!: i64",
+                         message,
+                         executor_config as "executor_config: Json<ExecutorConfig>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            message,
+            executor_config
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Renumber a session's queue to follow `ordered_ids`. Callers are expected to pass
+    /// every queued message of the session exactly once.
+    pub async fn reorder(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        ordered_ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for (position, id) in ordered_ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                r#"UPDATE session_queued_messages
+                   SET position = $1, updated_at = datetime('now', 'subsec')
+                   WHERE id = $2 AND session_id = $3"#,
+                position,
+                id,
+                session_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM session_queued_messages WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM session_queued_messages WHERE session_id = $1",
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl QueuePause {
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuePause,
+            r#"SELECT session_id as "session_id!: Uuid",
+                      reason,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM session_queue_pauses
+               WHERE session_id = $1"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Pause a session's queue, replacing the reason of an existing pause.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        reason: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            QueuePause,
+            r#"INSERT INTO session_queue_pauses (session_id, reason)
+               VALUES ($1, $2)
+               ON CONFLICT(session_id) DO UPDATE SET
+                   reason = excluded.reason,
+                   created_at = datetime('now', 'subsec')
+               RETURNING session_id as "session_id!: Uuid",
+                         reason,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            session_id,
+            reason
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, session_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM session_queue_pauses WHERE session_id = $1",
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
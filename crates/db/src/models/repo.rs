@@ -25,7 +25,13 @@ pub struct Repo {
     pub setup_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub archive_script: Option<String>,
+    pub verification_script: Option<String>,
     pub copy_files: Option<String>,
+    /// Files copied like `copy_files`, with `{{placeholders}}` such as `{{port}}` or
+    /// `{{db_name}}` filled in with the workspace's values
+    pub template_files: Option<String>,
+    /// Directories, like `node_modules`, linked into each worktree instead of copied
+    pub symlink_dirs: Option<String>,
     pub parallel_setup_script: bool,
     pub dev_server_script: Option<String>,
     pub default_target_branch: Option<String>,
@@ -70,6 +76,14 @@ pub struct UpdateRepo {
     #[ts(optional, type = "string | null")]
     pub archive_script: Option<Option<String>>,
 
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub verification_script: Option<Option<String>>,
+
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -78,6 +92,22 @@ pub struct UpdateRepo {
     #[ts(optional, type = "string | null")]
     pub copy_files: Option<Option<String>>,
 
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub template_files: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub symlink_dirs: Option<Option<String>>,
+
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -124,7 +154,10 @@ impl Repo {
                       setup_script,
                       cleanup_script,
                       archive_script,
+                      verification_script,
                       copy_files,
+                      template_files,
+                      symlink_dirs,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
@@ -165,7 +198,10 @@ impl Repo {
                       setup_script,
                       cleanup_script,
                       archive_script,
+                      verification_script,
                       copy_files,
+                      template_files,
+                      symlink_dirs,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
@@ -223,7 +259,10 @@ impl Repo {
                          setup_script,
                          cleanup_script,
                          archive_script,
+                         verification_script,
                          copy_files,
+                         template_files,
+                         symlink_dirs,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
                          default_target_branch,
@@ -260,7 +299,10 @@ impl Repo {
                       setup_script,
                       cleanup_script,
                       archive_script,
+                      verification_script,
                       copy_files,
+                      template_files,
+                      symlink_dirs,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
@@ -286,7 +328,10 @@ impl Repo {
                       r.setup_script,
                       r.cleanup_script,
                       r.archive_script,
+                      r.verification_script,
                       r.copy_files,
+                      r.template_files,
+                      r.symlink_dirs,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
@@ -333,10 +378,22 @@ impl Repo {
             None => existing.archive_script,
             Some(v) => v.clone(),
         };
+        let verification_script = match &payload.verification_script {
+            None => existing.verification_script,
+            Some(v) => v.clone(),
+        };
         let copy_files = match &payload.copy_files {
             None => existing.copy_files,
             Some(v) => v.clone(),
         };
+        let template_files = match &payload.template_files {
+            None => existing.template_files,
+            Some(v) => v.clone(),
+        };
+        let symlink_dirs = match &payload.symlink_dirs {
+            None => existing.symlink_dirs,
+            Some(v) => v.clone(),
+        };
         let parallel_setup_script = match &payload.parallel_setup_script {
             None => existing.parallel_setup_script,
             Some(v) => v.unwrap_or(false),
@@ -361,13 +418,16 @@ impl Repo {
                    setup_script = $2,
                    cleanup_script = $3,
                    archive_script = $4,
-                   copy_files = $5,
-                   parallel_setup_script = $6,
-                   dev_server_script = $7,
-                   default_target_branch = $8,
-                   default_working_dir = $9,
+                   verification_script = $5,
+                   copy_files = $6,
+                   template_files = $7,
+                   symlink_dirs = $8,
+                   parallel_setup_script = $9,
+                   dev_server_script = $10,
+                   default_target_branch = $11,
+                   default_working_dir = $12,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $10
+               WHERE id = $13
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -375,7 +435,10 @@ impl Repo {
                          setup_script,
                          cleanup_script,
                          archive_script,
+                         verification_script,
                          copy_files,
+                         template_files,
+                         symlink_dirs,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
                          default_target_branch,
@@ -386,7 +449,10 @@ impl Repo {
             setup_script,
             cleanup_script,
             archive_script,
+            verification_script,
             copy_files,
+            template_files,
+            symlink_dirs,
             parallel_setup_script,
             dev_server_script,
             default_target_branch,
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How commit messages are produced for agent turns and squashed commits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CommitMessageStyle {
+    /// Use the agent's own turn summary (the historical behaviour).
+    #[default]
+    AgentSummary,
+    /// Ask the agent for a Conventional Commits message.
+    Conventional,
+}
+
+/// What happens to repository commit hooks (pre-commit, commit-msg) on agent commits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CommitHookMode {
+    /// Run hooks; a failing hook leaves the changes uncommitted.
+    #[default]
+    Run,
+    /// Commit with `--no-verify`.
+    Skip,
+    /// Run hooks and send failures back to the agent as a follow-up.
+    Feedback,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RepoCommitPolicy {
+    pub repo_id: Uuid,
+    pub message_style: CommitMessageStyle,
+    /// Squash all per-turn commits into one when a PR is opened or the branch is merged.
+    pub squash_on_finish: bool,
+    pub signoff: bool,
+    pub gpg_sign: bool,
+    pub hook_mode: CommitHookMode,
+    /// Identity used for agent commits instead of the user's git config.
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateRepoCommitPolicy {
+    pub message_style: CommitMessageStyle,
+    pub squash_on_finish: bool,
+    pub signoff: bool,
+    pub gpg_sign: bool,
+    pub hook_mode: CommitHookMode,
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+}
+
+impl RepoCommitPolicy {
+    /// Policy used for repos that have never been configured.
+    pub fn default_for(repo_id: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            repo_id,
+            message_style: CommitMessageStyle::default(),
+            squash_on_finish: false,
+            signoff: false,
+            gpg_sign: false,
+            hook_mode: CommitHookMode::default(),
+            author_name: None,
+            author_email: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoCommitPolicy,
+            r#"SELECT repo_id as "repo_id!: Uuid",
+                      message_style as "message_style!: CommitMessageStyle",
+                      squash_on_finish as "squash_on_finish!: bool",
+                      signoff as "signoff!: bool",
+                      gpg_sign as "gpg_sign!: bool",
+                      hook_mode as "hook_mode!: CommitHookMode",
+                      author_name,
+                      author_email,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_commit_policies
+               WHERE repo_id = $1"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Returns the stored policy, or the default one if the repo has none.
+    pub async fn find_or_default(pool: &SqlitePool, repo_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self::find_by_repo_id(pool, repo_id)
+            .await?
+            .unwrap_or_else(|| Self::default_for(repo_id)))
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpdateRepoCommitPolicy,
+    ) -> Result<Self, sqlx::Error> {
+        let author_name = data
+            .author_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        let author_email = data
+            .author_email
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+
+        sqlx::query_as!(
+            RepoCommitPolicy,
+            r#"INSERT INTO repo_commit_policies
+                   (repo_id, message_style, squash_on_finish, signoff, gpg_sign, hook_mode, author_name, author_email)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   message_style = excluded.message_style,
+                   squash_on_finish = excluded.squash_on_finish,
+                   signoff = excluded.signoff,
+                   gpg_sign = excluded.gpg_sign,
+                   hook_mode = excluded.hook_mode,
+                   author_name = excluded.author_name,
+                   author_email = excluded.author_email,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING repo_id as "repo_id!: Uuid",
+                         message_style as "message_style!: CommitMessageStyle",
+                         squash_on_finish as "squash_on_finish!: bool",
+                         signoff as "signoff!: bool",
+                         gpg_sign as "gpg_sign!: bool",
+                         hook_mode as "hook_mode!: CommitHookMode",
+                         author_name,
+                         author_email,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id,
+            data.message_style,
+            data.squash_on_finish,
+            data.signoff,
+            data.gpg_sign,
+            data.hook_mode,
+            author_name,
+            author_email,
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Default context pack size, in approximate tokens.
+pub const DEFAULT_CONTEXT_PACK_TOKEN_BUDGET: i64 = 2000;
+
+/// How a generated context pack reaches the agent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ContextPackDelivery {
+    /// Write the pack next to the worktrees and point the agent at it.
+    #[default]
+    File,
+    /// Append the whole pack to the initial prompt.
+    Prompt,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RepoContextPack {
+    pub repo_id: Uuid,
+    pub enabled: bool,
+    pub delivery: ContextPackDelivery,
+    /// Upper bound on the pack size, in approximate tokens (4 characters each).
+    pub token_budget: i64,
+    /// Free-form project notes included at the top of the pack.
+    pub notes: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateRepoContextPack {
+    pub enabled: bool,
+    pub delivery: ContextPackDelivery,
+    pub token_budget: i64,
+    pub notes: Option<String>,
+}
+
+impl RepoContextPack {
+    /// Settings used for repos that have never been configured.
+    pub fn default_for(repo_id: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            repo_id,
+            enabled: false,
+            delivery: ContextPackDelivery::default(),
+            token_budget: DEFAULT_CONTEXT_PACK_TOKEN_BUDGET,
+            notes: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoContextPack,
+            r#"SELECT repo_id as "repo_id!: Uuid",
+                      enabled as "enabled!: bool",
+                      delivery as "delivery!: ContextPackDelivery",
+                      token_budget as "token_budget!: i64",
+                      notes,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_context_packs
+               WHERE repo_id = $1"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Returns the stored settings, or the default (disabled) ones if the repo has none.
+    pub async fn find_or_default(pool: &SqlitePool, repo_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self::find_by_repo_id(pool, repo_id)
+            .await?
+            .unwrap_or_else(|| Self::default_for(repo_id)))
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpdateRepoContextPack,
+    ) -> Result<Self, sqlx::Error> {
+        let notes = data
+            .notes
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+
+        sqlx::query_as!(
+            RepoContextPack,
+            r#"INSERT INTO repo_context_packs (repo_id, enabled, delivery, token_budget, notes)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   enabled = excluded.enabled,
+                   delivery = excluded.delivery,
+                   token_budget = excluded.token_budget,
+                   notes = excluded.notes,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING repo_id as "repo_id!: Uuid",
+                         enabled as "enabled!: bool",
+                         delivery as "delivery!: ContextPackDelivery",
+                         token_budget as "token_budget!: i64",
+                         notes,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id,
+            data.enabled,
+            data.delivery,
+            data.token_budget,
+            notes,
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// One named process of a repo's dev environment, like a line of a Procfile. A repo with
+/// dev processes starts all of them instead of its `dev_server_script`.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RepoDevProcess {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub name: String,
+    /// Shell command run from the repo's worktree
+    pub command: String,
+    /// Processes of the same repo that must be ready before this one starts
+    #[ts(type = "Array<string>")]
+    pub depends_on: Json<Vec<String>>,
+    /// Extra environment variables for the process
+    #[ts(type = "Record<string, string>")]
+    pub env: Json<HashMap<String, String>>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertRepoDevProcess {
+    pub name: String,
+    pub command: String,
+    pub depends_on: Vec<String>,
+    pub env: HashMap<String, String>,
+}
+
+impl RepoDevProcess {
+    /// The repo's dev processes, in the order they were defined.
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoDevProcess,
+            r#"SELECT id as "id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      name,
+                      command,
+                      depends_on as "depends_on!: Json<Vec<String>>",
+                      env as "env!: Json<HashMap<String, String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_dev_processes
+               WHERE repo_id = $1
+               ORDER BY position ASC"#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_name(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        name: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoDevProcess,
+            r#"SELECT id as "id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      name,
+                      command,
+                      depends_on as "depends_on!: Json<Vec<String>>",
+                      env as "env!: Json<HashMap<String, String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_dev_processes
+               WHERE repo_id = $1 AND name = $2"#,
+            repo_id,
+            name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Replace all dev processes of a repo with `processes`, kept in the given order.
+    pub async fn replace_for_repo(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        processes: &[UpsertRepoDevProcess],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!("DELETE FROM repo_dev_processes WHERE repo_id = $1", repo_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, process) in processes.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let position = position as i64;
+            let depends_on = Json(&process.depends_on);
+            let env = Json(&process.env);
+            sqlx::query!(
+                r#"INSERT INTO repo_dev_processes (id, repo_id, name, command, depends_on, env, position)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+                id,
+                repo_id,
+                process.name,
+                process.command,
+                depends_on,
+                env,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_by_repo_id(pool, repo_id).await
+    }
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How a repo's dev server is checked for readiness and kept running. Without a health
+/// path or ready pattern, the dev server is ready once its port accepts connections.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RepoDevServerSettings {
+    pub repo_id: Uuid,
+    /// Path polled over HTTP until it answers without a server error, e.g. `/health`
+    pub health_path: Option<String>,
+    /// Regex matched against the dev server output, e.g. `ready in \d+ ms`
+    pub ready_pattern: Option<String>,
+    /// Restart the dev server with backoff when it crashes
+    pub auto_restart: bool,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateRepoDevServerSettings {
+    pub health_path: Option<String>,
+    pub ready_pattern: Option<String>,
+    pub auto_restart: bool,
+}
+
+impl RepoDevServerSettings {
+    /// Settings used for repos that have never been configured.
+    pub fn default_for(repo_id: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            repo_id,
+            health_path: None,
+            ready_pattern: None,
+            auto_restart: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RepoDevServerSettings,
+            r#"SELECT repo_id as "repo_id!: Uuid",
+                      health_path,
+                      ready_pattern,
+                      auto_restart as "auto_restart!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM repo_dev_server_settings
+               WHERE repo_id = $1"#,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_or_default(pool: &SqlitePool, repo_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self::find_by_repo_id(pool, repo_id)
+            .await?
+            .unwrap_or_else(|| Self::default_for(repo_id)))
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        data: &UpdateRepoDevServerSettings,
+    ) -> Result<Self, sqlx::Error> {
+        let health_path = data
+            .health_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        let ready_pattern = data.ready_pattern.as_deref().filter(|s| !s.is_empty());
+
+        sqlx::query_as!(
+            RepoDevServerSettings,
+            r#"INSERT INTO repo_dev_server_settings (repo_id, health_path, ready_pattern, auto_restart)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(repo_id) DO UPDATE SET
+                   health_path = excluded.health_path,
+                   ready_pattern = excluded.ready_pattern,
+                   auto_restart = excluded.auto_restart,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING repo_id as "repo_id!: Uuid",
+                         health_path,
+                         ready_pattern,
+                         auto_restart as "auto_restart!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id,
+            health_path,
+            ready_pattern,
+            data.auto_restart,
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use executors::executors::BaseCodingAgent;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// An encrypted environment variable, injected into the processes of a project, a repo or
+/// one coding agent. Exactly one of `project_id`, `repo_id` and `executor` is set.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Secret {
+    pub id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub repo_id: Option<Uuid>,
+    pub executor: Option<BaseCodingAgent>,
+    /// Environment variable name
+    pub name: String,
+    #[serde(skip_serializing)]
+    #[ts(skip)]
+    pub ciphertext: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Where a secret applies. Exactly one field must be set.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct SecretScope {
+    pub project_id: Option<Uuid>,
+    pub repo_id: Option<Uuid>,
+    pub executor: Option<BaseCodingAgent>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertSecret {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub scope: SecretScope,
+    pub name: String,
+    /// Plaintext value; stored encrypted and never returned
+    pub value: String,
+}
+
+impl Secret {
+    /// Secrets of one scope, by name.
+    pub async fn find_by_scope(
+        pool: &SqlitePool,
+        scope: &SecretScope,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Secret,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      repo_id as "repo_id: Uuid",
+                      executor as "executor: BaseCodingAgent",
+                      name,
+                      ciphertext,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM secrets
+               WHERE project_id IS $1 AND repo_id IS $2 AND executor IS $3
+               ORDER BY name ASC"#,
+            scope.project_id,
+            scope.repo_id,
+            scope.executor
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Store an encrypted value, replacing the secret of the same name in the scope.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        scope: &SecretScope,
+        name: &str,
+        ciphertext: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            Secret,
+            r#"INSERT INTO secrets (id, project_id, repo_id, executor, name, ciphertext)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(IFNULL(project_id, x''), IFNULL(repo_id, x''), IFNULL(executor, ''), name) DO UPDATE SET
+                   ciphertext = excluded.ciphertext,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         repo_id as "repo_id: Uuid",
+                         executor as "executor: BaseCodingAgent",
+                         name,
+                         ciphertext,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            scope.project_id,
+            scope.repo_id,
+            scope.executor,
+            name,
+            ciphertext
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn exists(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT EXISTS(SELECT 1 FROM secrets) as "exists!: bool""#)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM secrets WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
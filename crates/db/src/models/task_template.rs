@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqliteConnection, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What kind of value a template variable accepts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateVariableKind {
+    String,
+    Choice {
+        options: Vec<String>,
+    },
+    /// A path relative to the root of one of the task's repositories.
+    RepoFilePath,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct TemplateVariable {
+    /// Referenced as `{{name}}` in the template title and prompt.
+    pub name: String,
+    pub label: Option<String>,
+    pub kind: TemplateVariableKind,
+    pub required: bool,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct TemplateRepo {
+    pub repo_id: Uuid,
+    /// Falls back to the repo's default target branch when not set.
+    pub target_branch: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskTemplate {
+    pub id: Uuid,
+    /// `None` for templates available in every project.
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub title: String,
+    pub prompt: String,
+    #[ts(type = "Array<TemplateVariable>")]
+    pub variables: Json<Vec<TemplateVariable>>,
+    #[ts(type = "ExecutorConfig | null")]
+    pub executor_config: Option<Json<ExecutorConfig>>,
+    #[ts(type = "Array<TemplateRepo>")]
+    pub repos: Json<Vec<TemplateRepo>>,
+    /// Shell script the agent is asked to run before finishing.
+    pub verification_script: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskTemplateWithImages {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub template: TaskTemplate,
+    pub image_ids: Vec<Uuid>,
+}
+
+/// Create and (full) update payload for a template.
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTaskTemplate {
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub title: String,
+    pub prompt: String,
+    pub variables: Vec<TemplateVariable>,
+    pub executor_config: Option<ExecutorConfig>,
+    pub repos: Vec<TemplateRepo>,
+    pub verification_script: Option<String>,
+    pub image_ids: Vec<Uuid>,
+}
+
+impl TaskTemplate {
+    /// Templates usable in `project_id` (its own plus global ones), or all templates.
+    pub async fn find_all(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskTemplate,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      description,
+                      title,
+                      prompt,
+                      variables as "variables!: Json<Vec<TemplateVariable>>",
+                      executor_config as "executor_config: Json<ExecutorConfig>",
+                      repos as "repos!: Json<Vec<TemplateRepo>>",
+                      verification_script,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_templates
+               WHERE $1 IS NULL OR project_id IS NULL OR project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskTemplate,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      description,
+                      title,
+                      prompt,
+                      variables as "variables!: Json<Vec<TemplateVariable>>",
+                      executor_config as "executor_config: Json<ExecutorConfig>",
+                      repos as "repos!: Json<Vec<TemplateRepo>>",
+                      verification_script,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_templates
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskTemplate,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let variables = Json(&data.variables);
+        let executor_config = data.executor_config.as_ref().map(Json);
+        let repos = Json(&data.repos);
+
+        let template = sqlx::query_as!(
+            TaskTemplate,
+            r#"INSERT INTO task_templates (id, project_id, name, description, title, prompt, variables, executor_config, repos, verification_script)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         name,
+                         description,
+                         title,
+                         prompt,
+                         variables as "variables!: Json<Vec<TemplateVariable>>",
+                         executor_config as "executor_config: Json<ExecutorConfig>",
+                         repos as "repos!: Json<Vec<TemplateRepo>>",
+                         verification_script,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.description,
+            data.title,
+            data.prompt,
+            variables,
+            executor_config,
+            repos,
+            data.verification_script
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        TaskTemplateImage::replace(&mut tx, id, &data.image_ids).await?;
+        tx.commit().await?;
+        Ok(template)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &CreateTaskTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let variables = Json(&data.variables);
+        let executor_config = data.executor_config.as_ref().map(Json);
+        let repos = Json(&data.repos);
+
+        let template = sqlx::query_as!(
+            TaskTemplate,
+            r#"UPDATE task_templates
+               SET project_id = $2, name = $3, description = $4, title = $5, prompt = $6,
+                   variables = $7, executor_config = $8, repos = $9, verification_script = $10,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         name,
+                         description,
+                         title,
+                         prompt,
+                         variables as "variables!: Json<Vec<TemplateVariable>>",
+                         executor_config as "executor_config: Json<ExecutorConfig>",
+                         repos as "repos!: Json<Vec<TemplateRepo>>",
+                         verification_script,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.description,
+            data.title,
+            data.prompt,
+            variables,
+            executor_config,
+            repos,
+            data.verification_script
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        TaskTemplateImage::replace(&mut tx, id, &data.image_ids).await?;
+        tx.commit().await?;
+        Ok(template)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_templates WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct TaskTemplateImage;
+
+impl TaskTemplateImage {
+    async fn replace(
+        conn: &mut SqliteConnection,
+        template_id: Uuid,
+        image_ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM task_template_images WHERE template_id = $1",
+            template_id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        for image_id in image_ids {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT OR IGNORE INTO task_template_images (id, template_id, image_id)
+                   VALUES ($1, $2, $3)"#,
+                id,
+                template_id,
+                image_id
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn find_image_ids(
+        pool: &SqlitePool,
+        template_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT image_id as "image_id!: Uuid"
+               FROM task_template_images
+               WHERE template_id = $1
+               ORDER BY created_at ASC"#,
+            template_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Image ids of every template, keyed by template id.
+    pub async fn find_all_grouped(
+        pool: &SqlitePool,
+    ) -> Result<HashMap<Uuid, Vec<Uuid>>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT template_id as "template_id!: Uuid", image_id as "image_id!: Uuid"
+               FROM task_template_images
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut grouped: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for row in rows {
+            grouped
+                .entry(row.template_id)
+                .or_default()
+                .push(row.image_id);
+        }
+        Ok(grouped)
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Screenshot of one dev server route at one viewport size.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct VisualSnapshot {
+    pub id: Uuid,
+    /// Dev server the screenshot was taken from
+    pub execution_process_id: Uuid,
+    pub route: String,
+    pub viewport_width: i64,
+    pub viewport_height: i64,
+    pub image_id: Uuid,
+    /// Same route served from the target branch
+    pub baseline_image_id: Option<Uuid>,
+    /// Changed pixels highlighted on top of the screenshot
+    pub diff_image_id: Option<Uuid>,
+    /// Fraction of pixels that differ from the baseline, between 0 and 1
+    pub diff_ratio: Option<f64>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateVisualSnapshot {
+    pub execution_process_id: Uuid,
+    pub route: String,
+    pub viewport_width: i64,
+    pub viewport_height: i64,
+    pub image_id: Uuid,
+    pub baseline_image_id: Option<Uuid>,
+    pub diff_image_id: Option<Uuid>,
+    pub diff_ratio: Option<f64>,
+}
+
+impl VisualSnapshot {
+    /// Snapshots taken from any dev server of a workspace, newest first.
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VisualSnapshot,
+            r#"SELECT vs.id as "id!: Uuid",
+                      vs.execution_process_id as "execution_process_id!: Uuid",
+                      vs.route,
+                      vs.viewport_width as "viewport_width!: i64",
+                      vs.viewport_height as "viewport_height!: i64",
+                      vs.image_id as "image_id!: Uuid",
+                      vs.baseline_image_id as "baseline_image_id: Uuid",
+                      vs.diff_image_id as "diff_image_id: Uuid",
+                      vs.diff_ratio,
+                      vs.created_at as "created_at!: DateTime<Utc>"
+               FROM visual_snapshots vs
+               JOIN execution_processes ep ON ep.id = vs.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE s.workspace_id = $1
+               ORDER BY vs.created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateVisualSnapshot,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            VisualSnapshot,
+            r#"INSERT INTO visual_snapshots (id, execution_process_id, route, viewport_width, viewport_height, image_id, baseline_image_id, diff_image_id, diff_ratio)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid",
+                         execution_process_id as "execution_process_id!: Uuid",
+                         route,
+                         viewport_width as "viewport_width!: i64",
+                         viewport_height as "viewport_height!: i64",
+                         image_id as "image_id!: Uuid",
+                         baseline_image_id as "baseline_image_id: Uuid",
+                         diff_image_id as "diff_image_id: Uuid",
+                         diff_ratio,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.execution_process_id,
+            data.route,
+            data.viewport_width,
+            data.viewport_height,
+            data.image_id,
+            data.baseline_image_id,
+            data.diff_image_id,
+            data.diff_ratio
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
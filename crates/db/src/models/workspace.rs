@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
@@ -9,6 +11,7 @@ use uuid::Uuid;
 const WORKSPACE_NAME_MAX_LEN: usize = 60;
 
 use super::{
+    dev_server::DevServer,
     project::Project,
     task::Task,
     workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
@@ -57,6 +60,8 @@ pub struct WorkspaceWithStatus {
     pub workspace: Workspace,
     pub is_running: bool,
     pub is_errored: bool,
+    /// Dev servers started in the workspace, one per repo
+    pub dev_servers: Vec<DevServer>,
 }
 
 impl std::ops::Deref for WorkspaceWithStatus {
@@ -576,6 +581,7 @@ impl Workspace {
                 },
                 is_running: rec.is_running != 0,
                 is_errored: rec.is_errored != 0,
+                dev_servers: Vec::new(),
             })
             // Apply archived filter if provided
             .filter(|ws| archived.is_none_or(|a| ws.workspace.archived == a))
@@ -586,7 +592,16 @@ impl Workspace {
             workspaces.truncate(lim as usize);
         }
 
+        let mut dev_servers: HashMap<Uuid, Vec<DevServer>> = HashMap::new();
+        for dev_server in DevServer::find_all(pool).await? {
+            dev_servers
+                .entry(dev_server.workspace_id)
+                .or_default()
+                .push(dev_server);
+        }
+
         for ws in &mut workspaces {
+            ws.dev_servers = dev_servers.remove(&ws.workspace.id).unwrap_or_default();
             if ws.workspace.name.is_none()
                 && let Some(prompt) = Self::get_first_user_message(pool, ws.workspace.id).await?
             {
@@ -680,6 +695,7 @@ impl Workspace {
             },
             is_running: rec.is_running != 0,
             is_errored: rec.is_errored != 0,
+            dev_servers: DevServer::find_by_workspace_id(pool, id).await?,
         };
 
         if ws.workspace.name.is_none()
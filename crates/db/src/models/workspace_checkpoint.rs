@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CheckpointReason {
+    Manual,
+    BeforeRebase,
+    BeforeReset,
+    BeforeConflictAbort,
+    BeforeRestore,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceCheckpoint {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub reason: CheckpointReason,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// State of one repo in a checkpoint.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceCheckpointRepo {
+    pub id: Uuid,
+    pub checkpoint_id: Uuid,
+    pub repo_id: Uuid,
+    pub head_commit: String,
+    /// Commit on top of `head_commit` holding the uncommitted changes, untracked files
+    /// included.
+    pub snapshot_commit: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkspaceCheckpointWithRepos {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub checkpoint: WorkspaceCheckpoint,
+    pub repos: Vec<WorkspaceCheckpointRepo>,
+}
+
+impl WorkspaceCheckpoint {
+    /// Ref in each repo that keeps the checkpoint's snapshot commit alive.
+    pub fn ref_name(id: Uuid) -> String {
+        format!("refs/vibe-kanban/checkpoints/{id}")
+    }
+
+    /// Checkpoints of a workspace, newest first.
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceCheckpoint,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      name,
+                      reason as "reason!: CheckpointReason",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_checkpoints
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceCheckpoint,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      name,
+                      reason as "reason!: CheckpointReason",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_checkpoints
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+        name: &str,
+        reason: CheckpointReason,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceCheckpoint,
+            r#"INSERT INTO workspace_checkpoints (id, workspace_id, name, reason)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         name,
+                         reason as "reason!: CheckpointReason",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            name,
+            reason
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM workspace_checkpoints WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl WorkspaceCheckpointRepo {
+    pub async fn find_by_checkpoint_id(
+        pool: &SqlitePool,
+        checkpoint_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceCheckpointRepo,
+            r#"SELECT id as "id!: Uuid",
+                      checkpoint_id as "checkpoint_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      head_commit,
+                      snapshot_commit,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_checkpoint_repos
+               WHERE checkpoint_id = $1"#,
+            checkpoint_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Repo states of every checkpoint in a workspace.
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceCheckpointRepo,
+            r#"SELECT r.id as "id!: Uuid",
+                      r.checkpoint_id as "checkpoint_id!: Uuid",
+                      r.repo_id as "repo_id!: Uuid",
+                      r.head_commit,
+                      r.snapshot_commit,
+                      r.created_at as "created_at!: DateTime<Utc>"
+               FROM workspace_checkpoint_repos r
+               JOIN workspace_checkpoints c ON c.id = r.checkpoint_id
+               WHERE c.workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        checkpoint_id: Uuid,
+        repo_id: Uuid,
+        head_commit: &str,
+        snapshot_commit: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            WorkspaceCheckpointRepo,
+            r#"INSERT INTO workspace_checkpoint_repos (id, checkpoint_id, repo_id, head_commit, snapshot_commit)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         checkpoint_id as "checkpoint_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         head_commit,
+                         snapshot_commit,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            checkpoint_id,
+            repo_id,
+            head_commit,
+            snapshot_commit
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
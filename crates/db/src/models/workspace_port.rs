@@ -0,0 +1,71 @@
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Ports handed out to a workspace's templated files before its dev servers start. A dev
+/// server prefers the port recorded here, so it listens where the files point.
+pub struct WorkspacePort;
+
+impl WorkspacePort {
+    pub async fn find_port(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        process_name: Option<&str>,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT port as "port!: i64"
+               FROM workspace_ports
+               WHERE workspace_id = $1 AND repo_id = $2 AND process_name IS $3"#,
+            workspace_id,
+            repo_id,
+            process_name
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Ports recorded for every workspace, kept out of the way of new allocations.
+    pub async fn reserved_ports(pool: &SqlitePool) -> Result<Vec<i64>, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT port as "port!: i64" FROM workspace_ports"#)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        process_name: Option<&str>,
+        port: i64,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO workspace_ports (id, workspace_id, repo_id, process_name, port)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(workspace_id, repo_id, IFNULL(process_name, '')) DO UPDATE SET
+                   port = excluded.port"#,
+            id,
+            workspace_id,
+            repo_id,
+            process_name,
+            port
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Release a workspace's ports once it is archived or its worktree is cleaned up.
+    pub async fn delete_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM workspace_ports WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
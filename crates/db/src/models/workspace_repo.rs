@@ -33,13 +33,15 @@ pub struct RepoWithTargetBranch {
     pub target_branch: String,
 }
 
-/// Repo info with copy_files configuration.
+/// Repo info with the configuration for files brought into its worktrees.
 #[derive(Debug, Clone)]
 pub struct RepoWithCopyFiles {
     pub id: Uuid,
     pub path: PathBuf,
     pub name: String,
     pub copy_files: Option<String>,
+    pub template_files: Option<String>,
+    pub symlink_dirs: Option<String>,
 }
 
 impl WorkspaceRepo {
@@ -117,7 +119,10 @@ impl WorkspaceRepo {
                       r.setup_script,
                       r.cleanup_script,
                       r.archive_script,
+                      r.verification_script,
                       r.copy_files,
+                      r.template_files,
+                      r.symlink_dirs,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
@@ -146,7 +151,10 @@ impl WorkspaceRepo {
                       r.setup_script,
                       r.cleanup_script,
                       r.archive_script,
+                      r.verification_script,
                       r.copy_files,
+                      r.template_files,
+                      r.symlink_dirs,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
@@ -174,7 +182,10 @@ impl WorkspaceRepo {
                     setup_script: row.setup_script,
                     cleanup_script: row.cleanup_script,
                     archive_script: row.archive_script,
+                    verification_script: row.verification_script,
                     copy_files: row.copy_files,
+                    template_files: row.template_files,
+                    symlink_dirs: row.symlink_dirs,
                     parallel_setup_script: row.parallel_setup_script,
                     dev_server_script: row.dev_server_script,
                     default_target_branch: row.default_target_branch,
@@ -263,7 +274,10 @@ impl WorkspaceRepo {
                       r.setup_script,
                       r.cleanup_script,
                       r.archive_script,
+                      r.verification_script,
                       r.copy_files,
+                      r.template_files,
+                      r.symlink_dirs,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
@@ -281,13 +295,14 @@ impl WorkspaceRepo {
         .await
     }
 
-    /// Find repos for a workspace with their copy_files configuration.
+    /// Find repos for a workspace with their copy_files, template_files and symlink_dirs
+    /// configuration.
     pub async fn find_repos_with_copy_files(
         pool: &SqlitePool,
         workspace_id: Uuid,
     ) -> Result<Vec<RepoWithCopyFiles>, sqlx::Error> {
         let rows = sqlx::query!(
-            r#"SELECT r.id as "id!: Uuid", r.path, r.name, r.copy_files
+            r#"SELECT r.id as "id!: Uuid", r.path, r.name, r.copy_files, r.template_files, r.symlink_dirs
                FROM repos r
                JOIN workspace_repos wr ON r.id = wr.repo_id
                WHERE wr.workspace_id = $1"#,
@@ -303,6 +318,8 @@ impl WorkspaceRepo {
                 path: PathBuf::from(row.path),
                 name: row.name,
                 copy_files: row.copy_files,
+                template_files: row.template_files,
+                symlink_dirs: row.symlink_dirs,
             })
             .collect())
     }
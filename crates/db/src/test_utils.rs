@@ -0,0 +1,125 @@
+//! Rows for tests to hang their records on, in a database from
+//! [`DBService::new_in_memory`](crate::DBService::new_in_memory).
+
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+    },
+    executors::BaseCodingAgent,
+    profile::ExecutorConfig,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::models::{
+    execution_process::{CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason},
+    execution_process_repo_state::CreateExecutionProcessRepoState,
+    project::{CreateProject, Project},
+    session::{CreateSession, Session},
+    task::{CreateTask, Task},
+    workspace::{CreateWorkspace, Workspace},
+};
+
+/// A session with the project, task and workspace it belongs to.
+pub struct SessionChain {
+    pub project: Project,
+    pub task: Task,
+    pub workspace: Workspace,
+    pub session: Session,
+}
+
+pub async fn create_project(pool: &SqlitePool, name: &str) -> Project {
+    Project::create(
+        pool,
+        &CreateProject {
+            name: name.to_string(),
+            repositories: vec![],
+        },
+        Uuid::new_v4(),
+    )
+    .await
+    .unwrap()
+}
+
+pub async fn create_task(pool: &SqlitePool, project_id: Uuid, title: &str) -> Task {
+    Task::create(
+        pool,
+        &CreateTask::from_title_description(project_id, title.to_string(), None),
+        Uuid::new_v4(),
+    )
+    .await
+    .unwrap()
+}
+
+pub async fn create_workspace(pool: &SqlitePool, task_id: Uuid, branch: &str) -> Workspace {
+    Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: branch.to_string(),
+            agent_working_dir: None,
+        },
+        Uuid::new_v4(),
+        task_id,
+    )
+    .await
+    .unwrap()
+}
+
+pub async fn create_session(pool: &SqlitePool, workspace_id: Uuid) -> Session {
+    Session::create(
+        pool,
+        &CreateSession { executor: None },
+        Uuid::new_v4(),
+        workspace_id,
+    )
+    .await
+    .unwrap()
+}
+
+/// A project, task, workspace on `vk/{name}` and session, all named after `name`.
+pub async fn create_session_chain(pool: &SqlitePool, name: &str) -> SessionChain {
+    let project = create_project(pool, name).await;
+    let task = create_task(pool, project.id, name).await;
+    let workspace = create_workspace(pool, task.id, &format!("vk/{name}")).await;
+    let session = create_session(pool, workspace.id).await;
+    SessionChain {
+        project,
+        task,
+        workspace,
+        session,
+    }
+}
+
+/// A Claude Code request starting a conversation with `prompt`.
+pub fn coding_agent_action(prompt: &str) -> ExecutorAction {
+    ExecutorAction::new(
+        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+            prompt: prompt.to_string(),
+            executor_config: ExecutorConfig::new(BaseCodingAgent::ClaudeCode),
+            working_dir: None,
+        }),
+        None,
+    )
+}
+
+/// A running execution of `action` in the session.
+pub async fn create_execution_process(
+    pool: &SqlitePool,
+    session_id: Uuid,
+    action: ExecutorAction,
+    run_reason: ExecutionProcessRunReason,
+    repo_states: &[CreateExecutionProcessRepoState],
+) -> ExecutionProcess {
+    ExecutionProcess::create(
+        pool,
+        &CreateExecutionProcess {
+            session_id,
+            executor_action: action,
+            run_reason,
+        },
+        Uuid::new_v4(),
+        repo_states,
+    )
+    .await
+    .unwrap()
+}
@@ -22,15 +22,20 @@ use services::services::{
     auth::AuthContext,
     config::{Config, ConfigError},
     container::{ContainerError, ContainerService},
+    context_pack::ContextPackCache,
     events::{EventError, EventService},
     file_search::FileSearchCache,
     filesystem::{FilesystemError, FilesystemService},
     filesystem_watcher::FilesystemWatcherError,
     image::{ImageError, ImageService},
+    merge_queue::MergeQueueService,
+    pr_monitor::PrMonitorService,
     project::ProjectService,
     queued_message::QueuedMessageService,
     remote_client::RemoteClient,
     repo::RepoService,
+    secrets::SecretService,
+    symbol_index::SymbolIndexCache,
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
@@ -104,10 +109,24 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn file_search_cache(&self) -> &Arc<FileSearchCache>;
 
+    fn symbol_index_cache(&self) -> &Arc<SymbolIndexCache>;
+
+    fn context_pack_cache(&self) -> &Arc<ContextPackCache>;
+
     fn approvals(&self) -> &Approvals;
 
     fn queued_message_service(&self) -> &QueuedMessageService;
 
+    fn merge_queue(
+        &self,
+    ) -> &MergeQueueService<impl ContainerService + Clone + Send + Sync + 'static>;
+
+    fn pr_monitor(
+        &self,
+    ) -> &PrMonitorService<impl ContainerService + Clone + Send + Sync + 'static>;
+
+    fn secrets(&self) -> &SecretService;
+
     fn auth_context(&self) -> &AuthContext;
 
     fn remote_client(&self) -> Result<RemoteClient, RemoteClientNotConfigured> {
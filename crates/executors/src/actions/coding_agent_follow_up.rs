@@ -80,7 +80,7 @@ impl Executable for CodingAgentFollowUpRequest {
             }
             agent.use_approvals(approvals.clone());
 
-            agent
+            let spawned = agent
                 .spawn_follow_up(
                     &effective_dir,
                     &self.prompt,
@@ -88,7 +88,8 @@ impl Executable for CodingAgentFollowUpRequest {
                     self.reset_to_message_id.as_deref(),
                     env,
                 )
-                .await
+                .await?;
+            Ok(spawned.with_executor(agent))
         }
     }
 }
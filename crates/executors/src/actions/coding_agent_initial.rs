@@ -69,7 +69,8 @@ impl Executable for CodingAgentInitialRequest {
             }
             agent.use_approvals(approvals.clone());
 
-            agent.spawn(&effective_dir, &self.prompt, env).await
+            let spawned = agent.spawn(&effective_dir, &self.prompt, env).await?;
+            Ok(spawned.with_executor(agent))
         }
     }
 }
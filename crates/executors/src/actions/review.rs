@@ -68,13 +68,14 @@ impl Executable for ReviewRequest {
         }
         agent.use_approvals(approvals.clone());
 
-        agent
+        let spawned = agent
             .spawn_review(
                 &effective_dir,
                 &self.prompt,
                 self.session_id.as_deref(),
                 env,
             )
-            .await
+            .await?;
+        Ok(spawned.with_executor(agent))
     }
 }
@@ -37,6 +37,9 @@ pub struct ScriptRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Name of the repo dev process this script runs, for repos with several
+    #[serde(default)]
+    pub dev_process: Option<String>,
 }
 
 #[async_trait]
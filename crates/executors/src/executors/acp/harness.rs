@@ -23,7 +23,8 @@ use crate::{
     approvals::ExecutorApprovalService,
     command::{CmdOverrides, CommandParts},
     env::ExecutionEnv,
-    executors::{ExecutorError, ExecutorExitResult, SpawnedChild, acp::AcpEvent},
+    executors::{BufferedReceiver, ExecutorError, ExecutorExitResult, SpawnedChild, acp::AcpEvent},
+    logs::{InjectedUserMessage, TurnInterrupted},
 };
 
 /// Reusable harness for ACP-based conns (Gemini, Qwen, etc.)
@@ -79,6 +80,7 @@ impl AcpAgentHarness {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn spawn_with_command(
         &self,
         current_dir: &Path,
@@ -87,6 +89,8 @@ impl AcpAgentHarness {
         env: &ExecutionEnv,
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
+        user_input: BufferedReceiver<String>,
+        interrupt: BufferedReceiver<()>,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
         let mut command = Command::new(program_path);
@@ -120,6 +124,8 @@ impl AcpAgentHarness {
             self.mode.clone(),
             approvals,
             cancel.clone(),
+            user_input,
+            interrupt,
         )
         .await?;
 
@@ -127,6 +133,7 @@ impl AcpAgentHarness {
             child,
             exit_signal: Some(exit_rx),
             cancel: Some(cancel),
+            executor: None,
         })
     }
 
@@ -140,6 +147,8 @@ impl AcpAgentHarness {
         env: &ExecutionEnv,
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
+        user_input: BufferedReceiver<String>,
+        interrupt: BufferedReceiver<()>,
     ) -> Result<SpawnedChild, ExecutorError> {
         let (program_path, args) = command_parts.into_resolved().await?;
         let mut command = Command::new(program_path);
@@ -173,6 +182,8 @@ impl AcpAgentHarness {
             self.mode.clone(),
             approvals,
             cancel.clone(),
+            user_input,
+            interrupt,
         )
         .await?;
 
@@ -180,6 +191,7 @@ impl AcpAgentHarness {
             child,
             exit_signal: Some(exit_rx),
             cancel: Some(cancel),
+            executor: None,
         })
     }
 
@@ -195,6 +207,8 @@ impl AcpAgentHarness {
         mode: Option<String>,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
         cancel: CancellationToken,
+        mut user_input: BufferedReceiver<String>,
+        mut interrupt: BufferedReceiver<()>,
     ) -> Result<(), ExecutorError> {
         // Take child's stdio for ACP wiring
         let orig_stdout = child.inner().stdout.take().ok_or_else(|| {
@@ -440,6 +454,23 @@ impl AcpAgentHarness {
                             }
                         });
 
+                        // Interrupts cancel the running prompt; the loop below then waits
+                        // for the next user message instead of finishing
+                        let interrupted = Rc::new(std::cell::Cell::new(false));
+                        let interrupted_for_task = interrupted.clone();
+                        let conn_for_interrupt = conn.clone();
+                        let acp_session_id_for_interrupt = acp_session_id.clone();
+                        tokio::task::spawn_local(async move {
+                            while interrupt.recv().await.is_some() {
+                                interrupted_for_task.set(true);
+                                let _ = conn_for_interrupt
+                                    .cancel(proto::CancelNotification::new(proto::SessionId::new(
+                                        acp_session_id_for_interrupt.clone(),
+                                    )))
+                                    .await;
+                            }
+                        });
+
                         // Save prompt to session
                         let _ = session_manager.append_raw_line(
                             &display_session_id,
@@ -513,6 +544,35 @@ impl AcpAgentHarness {
                                     ))],
                                 );
                                 current_req = Some(feedback_req);
+                                continue;
+                            }
+
+                            // Continue with the next user message: wait for one after an
+                            // interrupt, otherwise only take messages sent during the turn
+                            let next_input = if interrupted.replace(false) {
+                                let _ = log_tx.send(TurnInterrupted::raw());
+                                tokio::select! {
+                                    _ = cancel.cancelled() => None,
+                                    message = user_input.recv() => message,
+                                }
+                            } else {
+                                user_input.take_or_close()
+                            };
+                            if let Some(message) = next_input {
+                                let _ =
+                                    log_tx.send(InjectedUserMessage::new(message.clone()).raw());
+                                client_feedback_handle.record_user_prompt_event(&message);
+                                let _ = session_manager.append_raw_line(
+                                    &display_session_id,
+                                    &serde_json::to_string(&serde_json::json!({ "user": message }))
+                                        .unwrap_or_default(),
+                                );
+                                current_req = Some(proto::PromptRequest::new(
+                                    proto::SessionId::new(acp_session_id.clone()),
+                                    vec![proto::ContentBlock::Text(proto::TextContent::new(
+                                        message,
+                                    ))],
+                                ));
                             }
                         }
 
@@ -15,8 +15,9 @@ use super::AcpEvent;
 use crate::{
     approvals::ToolCallMetadata,
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolResult, ToolResultValueType, ToolStatus as LogToolStatus,
+        ActionType, FileChange, InjectedUserMessage, NormalizedEntry, NormalizedEntryError,
+        NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
+        ToolStatus as LogToolStatus, TurnInterrupted,
         stderr_processor::normalize_stderr_logs,
         utils::{ConversationPatch, EntryIndexProvider, shell_command_parsing::CommandCategory},
     },
@@ -39,6 +40,19 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
 
         let mut stdout_lines = msg_store.stdout_lines_stream();
         while let Some(Ok(line)) = stdout_lines.next().await {
+            if serde_json::from_str::<TurnInterrupted>(line.trim()).is_ok() {
+                TurnInterrupted::normalize(&msg_store, &entry_index);
+                continue;
+            }
+            if let Ok(injected) = serde_json::from_str::<InjectedUserMessage>(line.trim()) {
+                let idx = entry_index.next();
+                msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                    idx,
+                    injected.to_normalized_entry(),
+                ));
+                continue;
+            }
+
             if let Some(parsed) = AcpEventParser::parse_line(&line) {
                 tracing::trace!("Parsed ACP line: {:?}", parsed);
                 match parsed {
@@ -35,12 +35,13 @@ use crate::{
     command::{CmdOverrides, CommandBuildError, CommandBuilder, CommandParts, apply_overrides},
     env::ExecutionEnv,
     executors::{
-        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorError, SpawnedChild,
-        StandardCodingAgentExecutor, codex::client::LogWriter, utils::reorder_slash_commands,
+        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorChannel, ExecutorError,
+        ExecutorRequest, SpawnedChild, StandardCodingAgentExecutor, codex::client::LogWriter,
+        utils::reorder_slash_commands,
     },
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolStatus,
+        ActionType, FileChange, InjectedUserMessage, NormalizedEntry, NormalizedEntryError,
+        NormalizedEntryType, TodoItem, ToolStatus, TurnInterrupted,
         plain_text_processor::PlainTextLogProcessor,
         utils::{
             EntryIndexProvider,
@@ -126,6 +127,14 @@ pub struct ClaudeCode {
     #[ts(skip)]
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
     approvals_service: Option<Arc<dyn ExecutorApprovalService>>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    user_input: ExecutorChannel<String>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    interrupt: ExecutorChannel<()>,
 }
 
 impl ClaudeCode {
@@ -302,6 +311,14 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         self.approvals_service = Some(approvals);
     }
 
+    async fn send_user_input(&self, message: String) -> Result<(), ExecutorError> {
+        self.user_input.send(message).await
+    }
+
+    async fn interrupt_turn(&self) -> Result<(), ExecutorError> {
+        self.interrupt.send(()).await
+    }
+
     async fn spawn(
         &self,
         current_dir: &Path,
@@ -607,6 +624,8 @@ impl ClaudeCode {
 
         // Create cancellation token for graceful shutdown
         let cancel = CancellationToken::new();
+        let mut user_input_rx = self.user_input.connect();
+        let mut interrupt_rx = self.interrupt.connect();
 
         // Spawn task to handle the SDK client with control protocol
         let prompt_clone = combined_prompt.clone();
@@ -646,12 +665,43 @@ impl ClaudeCode {
                     .log_raw(&format!("Error: Failed to send prompt - {e}"))
                     .await;
             }
+
+            // Forward interrupts and user messages injected while the session is running
+            let closed = protocol_peer.closed();
+            loop {
+                let input: ExecutorRequest<String> = tokio::select! {
+                    _ = closed.cancelled() => break,
+                    Some(interrupt) = interrupt_rx.recv() => {
+                        let result = protocol_peer.interrupt_turn().await;
+                        if let Err(e) = &result {
+                            tracing::warn!("Failed to interrupt Claude turn: {e}");
+                        }
+                        let _ = interrupt.reply.send(result);
+                        continue;
+                    }
+                    input = user_input_rx.recv() => match input {
+                        Some(input) => input,
+                        None => break,
+                    },
+                };
+                let _ = log_writer
+                    .log_raw(&InjectedUserMessage::new(input.payload.clone()).raw())
+                    .await;
+                let result = protocol_peer
+                    .send_injected_user_message(input.payload)
+                    .await;
+                if let Err(e) = &result {
+                    tracing::warn!("Failed to send injected user message: {e}");
+                }
+                let _ = input.reply.send(result);
+            }
         });
 
         Ok(SpawnedChild {
             child,
             exit_signal: None,
             cancel: Some(cancel),
+            executor: None,
         })
     }
 }
@@ -753,6 +803,20 @@ impl ClaudeLogProcessor {
                         continue;
                     }
 
+                    if serde_json::from_str::<TurnInterrupted>(trimmed).is_ok() {
+                        TurnInterrupted::normalize(&msg_store, &entry_index_provider);
+                        continue;
+                    }
+
+                    if let Ok(injected) = serde_json::from_str::<InjectedUserMessage>(trimmed) {
+                        let patch_id = entry_index_provider.next();
+                        msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                            patch_id,
+                            injected.to_normalized_entry(),
+                        ));
+                        continue;
+                    }
+
                     match serde_json::from_str::<ClaudeJson>(trimmed) {
                         Ok(claude_json) => {
                             if !session_id_extracted
@@ -2653,6 +2717,8 @@ mod tests {
                 env: None,
             },
             approvals_service: None,
+            user_input: ExecutorChannel::default(),
+            interrupt: ExecutorChannel::default(),
             disable_api_key: None,
         };
         let msg_store = Arc::new(MsgStore::new());
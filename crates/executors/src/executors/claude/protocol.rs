@@ -1,4 +1,10 @@
-use std::sync::Arc;
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
@@ -17,12 +23,19 @@ use crate::{
             types::{Message, PermissionMode, SDKControlRequest, SDKControlRequestType},
         },
     },
+    logs::TurnInterrupted,
 };
 
 /// Handles bidirectional control protocol communication
 #[derive(Clone)]
 pub struct ProtocolPeer {
     stdin: Arc<Mutex<ChildStdin>>,
+    /// Injected user messages the CLI has not echoed back yet
+    pending_input: PendingInput,
+    /// Cancelled once the reader loop exits and the CLI stops accepting input
+    closed: CancellationToken,
+    /// Set when the current turn was interrupted and the session waits for the next message
+    turn_interrupted: Arc<AtomicBool>,
 }
 
 impl ProtocolPeer {
@@ -34,6 +47,9 @@ impl ProtocolPeer {
     ) -> Self {
         let peer = Self {
             stdin: Arc::new(Mutex::new(stdin)),
+            pending_input: PendingInput::default(),
+            closed: CancellationToken::new(),
+            turn_interrupted: Arc::new(AtomicBool::new(false)),
         };
 
         let reader_peer = peer.clone();
@@ -41,6 +57,7 @@ impl ProtocolPeer {
             if let Err(e) = reader_peer.read_loop(stdout, client, cancel).await {
                 tracing::error!("Protocol reader loop error: {}", e);
             }
+            reader_peer.closed.cancel();
         });
 
         peer
@@ -87,8 +104,28 @@ impl ProtocolPeer {
                                     self.handle_control_request(&client, request_id, request)
                                         .await;
                                 }
+                                Ok(CLIMessage::User {
+                                    is_replay: true,
+                                    uuid: Some(uuid),
+                                    ..
+                                }) => {
+                                    self.pending_input.acknowledge(&uuid);
+                                }
                                 Ok(CLIMessage::Result(_)) => {
-                                    break;
+                                    if interrupt_sent {
+                                        break;
+                                    }
+                                    // An interrupted turn keeps the session open for the
+                                    // next injected message.
+                                    if self.turn_interrupted.swap(false, Ordering::SeqCst) {
+                                        client.log_message(&TurnInterrupted::raw()).await?;
+                                        continue;
+                                    }
+                                    // Injected messages the CLI has not picked up yet run
+                                    // in another turn, so keep reading until its result.
+                                    if self.pending_input.is_empty() {
+                                        break;
+                                    }
                                 }
                                 _ => {}
                             }
@@ -200,6 +237,24 @@ impl ProtocolPeer {
         self.send_json(&message).await
     }
 
+    /// Send a user message while a turn is in progress. The session is kept open until
+    /// the CLI has echoed the message back and finished the turn that handles it.
+    pub async fn send_injected_user_message(&self, content: String) -> Result<(), ExecutorError> {
+        let uuid = uuid::Uuid::new_v4().to_string();
+        self.pending_input.insert(uuid.clone());
+        let message = Message::new_user(content).with_uuid(uuid.clone());
+        if let Err(e) = self.send_json(&message).await {
+            self.pending_input.acknowledge(&uuid);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Token cancelled once the CLI stops accepting input.
+    pub fn closed(&self) -> CancellationToken {
+        self.closed.clone()
+    }
+
     pub async fn initialize(&self, hooks: Option<serde_json::Value>) -> Result<(), ExecutorError> {
         self.send_json(&SDKControlRequest::new(SDKControlRequestType::Initialize {
             hooks,
@@ -211,6 +266,16 @@ impl ProtocolPeer {
             .await
     }
 
+    /// Interrupt the current turn without ending the session.
+    pub async fn interrupt_turn(&self) -> Result<(), ExecutorError> {
+        self.turn_interrupted.store(true, Ordering::SeqCst);
+        if let Err(e) = self.interrupt().await {
+            self.turn_interrupted.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+        Ok(())
+    }
+
     pub async fn set_permission_mode(&self, mode: PermissionMode) -> Result<(), ExecutorError> {
         self.send_json(&SDKControlRequest::new(
             SDKControlRequestType::SetPermissionMode { mode },
@@ -218,3 +283,59 @@ impl ProtocolPeer {
         .await
     }
 }
+
+/// Ids of injected user messages that have been sent but not replayed by the CLI yet
+#[derive(Clone, Default)]
+struct PendingInput(Arc<std::sync::Mutex<HashSet<String>>>);
+
+impl PendingInput {
+    fn insert(&self, uuid: String) {
+        self.0.lock().unwrap().insert(uuid);
+    }
+
+    fn acknowledge(&self, uuid: &str) {
+        self.0.lock().unwrap().remove(uuid);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.lock().unwrap().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replayed_user_messages_are_matched_by_uuid() {
+        let pending = PendingInput::default();
+        pending.insert("first".to_string());
+        pending.insert("second".to_string());
+
+        let replay = r#"{"type":"user","isReplay":true,"uuid":"first","message":{"role":"user","content":"Use the second message text"}}"#;
+        let Ok(CLIMessage::User {
+            is_replay: true,
+            uuid: Some(uuid),
+            ..
+        }) = serde_json::from_str::<CLIMessage>(replay)
+        else {
+            panic!("expected a replayed user message");
+        };
+        pending.acknowledge(&uuid);
+        assert!(!pending.is_empty());
+
+        pending.acknowledge("second");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn injected_messages_carry_their_uuid() {
+        let message = Message::new_user("steer".to_string()).with_uuid("abc".to_string());
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["uuid"], "abc");
+        assert_eq!(json["message"]["content"], "steer");
+
+        let plain = serde_json::to_value(Message::new_user("hi".to_string())).unwrap();
+        assert!(plain.get("uuid").is_none());
+    }
+}
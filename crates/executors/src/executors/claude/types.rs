@@ -20,6 +20,14 @@ pub enum CLIMessage {
         request_id: String,
     },
     Result(serde_json::Value),
+    /// User message; echoed back for messages we send because of `--replay-user-messages`
+    User {
+        message: serde_json::Value,
+        #[serde(default, rename = "isReplay")]
+        is_replay: bool,
+        #[serde(default)]
+        uuid: Option<String>,
+    },
     #[serde(untagged)]
     Other(serde_json::Value),
 }
@@ -168,7 +176,12 @@ pub enum ControlResponseType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Message {
-    User { message: ClaudeUserMessage },
+    User {
+        message: ClaudeUserMessage,
+        /// Echoed back on the replayed message, so it can be matched to what was sent
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        uuid: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -184,6 +197,16 @@ impl Message {
                 role: "user".to_string(),
                 content,
             },
+            uuid: None,
+        }
+    }
+
+    pub fn with_uuid(self, uuid: String) -> Self {
+        match self {
+            Self::User { message, .. } => Self::User {
+                message,
+                uuid: Some(uuid),
+            },
         }
     }
 }
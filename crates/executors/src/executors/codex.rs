@@ -52,8 +52,8 @@ use crate::{
     env::ExecutionEnv,
     executor_discovery::ExecutorDiscoveredOptions,
     executors::{
-        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorError, ExecutorExitResult,
-        SlashCommandDescription, SpawnedChild, StandardCodingAgentExecutor,
+        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorChannel, ExecutorError,
+        ExecutorExitResult, SlashCommandDescription, SpawnedChild, StandardCodingAgentExecutor,
     },
     logs::utils::patch,
     model_selector::{ModelInfo, ModelSelectorConfig, PermissionPolicy, ReasoningOption},
@@ -165,6 +165,14 @@ pub struct Codex {
     #[ts(skip)]
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
     approvals: Option<Arc<dyn ExecutorApprovalService>>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    user_input: ExecutorChannel<String>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    interrupt: ExecutorChannel<()>,
 }
 
 #[async_trait]
@@ -197,6 +205,14 @@ impl StandardCodingAgentExecutor for Codex {
         self.approvals = Some(approvals);
     }
 
+    async fn send_user_input(&self, message: String) -> Result<(), ExecutorError> {
+        self.user_input.send(message).await
+    }
+
+    async fn interrupt_turn(&self) -> Result<(), ExecutorError> {
+        self.interrupt.send(()).await
+    }
+
     async fn spawn(
         &self,
         current_dir: &Path,
@@ -577,6 +593,8 @@ impl Codex {
         let new_stdout = create_stdout_pipe_writer(&mut child)?;
         let (exit_signal_tx, exit_signal_rx) = tokio::sync::oneshot::channel();
         let cancel = tokio_util::sync::CancellationToken::new();
+        let mut user_input_rx = self.user_input.connect();
+        let mut interrupt_rx = self.interrupt.connect();
 
         let auto_approve = matches!(
             (&self.sandbox, &self.ask_for_approval),
@@ -611,6 +629,28 @@ impl Codex {
             );
             client.connect(rpc_peer);
 
+            // Forward user messages injected while the session is running
+            let input_client = client.clone();
+            tokio::spawn(async move {
+                while let Some(input) = user_input_rx.recv().await {
+                    let result = input_client.inject_user_message(input.payload).await;
+                    if let Err(e) = &result {
+                        tracing::warn!("Failed to send injected user message: {e}");
+                    }
+                    let _ = input.reply.send(result);
+                }
+            });
+            let interrupt_client = client.clone();
+            tokio::spawn(async move {
+                while let Some(interrupt) = interrupt_rx.recv().await {
+                    let result = interrupt_client.interrupt_turn().await;
+                    if let Err(e) = &result {
+                        tracing::warn!("Failed to interrupt Codex turn: {e}");
+                    }
+                    let _ = interrupt.reply.send(result);
+                }
+            });
+
             let result = async {
                 client.initialize().await?;
                 task(client, exit_signal_tx.clone()).await
@@ -653,6 +693,7 @@ impl Codex {
             child,
             exit_signal: Some(exit_signal_rx),
             cancel: Some(cancel),
+            executor: None,
         })
     }
 }
@@ -13,11 +13,11 @@ use codex_app_server_protocol::{
     AddConversationListenerParams, AddConversationSubscriptionResponse, ApplyPatchApprovalResponse,
     ClientInfo, ClientNotification, ClientRequest, ExecCommandApprovalResponse,
     GetAuthStatusParams, GetAuthStatusResponse, InitializeParams, InitializeResponse, InputItem,
-    JSONRPCError, JSONRPCNotification, JSONRPCRequest, JSONRPCResponse, ListMcpServerStatusParams,
-    ListMcpServerStatusResponse, NewConversationParams, NewConversationResponse, RequestId,
-    ResumeConversationParams, ResumeConversationResponse, ReviewStartParams, ReviewStartResponse,
-    ReviewTarget, SendUserMessageParams, SendUserMessageResponse, ServerNotification,
-    ServerRequest,
+    InterruptConversationParams, InterruptConversationResponse, JSONRPCError, JSONRPCNotification,
+    JSONRPCRequest, JSONRPCResponse, ListMcpServerStatusParams, ListMcpServerStatusResponse,
+    NewConversationParams, NewConversationResponse, RequestId, ResumeConversationParams,
+    ResumeConversationResponse, ReviewStartParams, ReviewStartResponse, ReviewTarget,
+    SendUserMessageParams, SendUserMessageResponse, ServerNotification, ServerRequest,
 };
 use codex_protocol::{ThreadId, protocol::ReviewDecision};
 use serde::{Serialize, de::DeserializeOwned};
@@ -34,6 +34,7 @@ use crate::{
     approvals::{ExecutorApprovalError, ExecutorApprovalService},
     env::RepoContext,
     executors::{ExecutorError, codex::normalize_logs::Approval},
+    logs::{InjectedUserMessage, TurnInterrupted},
 };
 
 pub struct AppServerClient {
@@ -47,6 +48,10 @@ pub struct AppServerClient {
     commit_reminder: bool,
     commit_reminder_prompt: String,
     commit_reminder_sent: AtomicBool,
+    /// Set while an interrupt requested by the user waits for the turn to abort
+    turn_interrupted: AtomicBool,
+    /// Injected messages Codex has not recorded in the conversation yet
+    pending_input: std::sync::Mutex<Vec<String>>,
     cancel: CancellationToken,
 }
 
@@ -71,6 +76,8 @@ impl AppServerClient {
             commit_reminder,
             commit_reminder_prompt,
             commit_reminder_sent: AtomicBool::new(false),
+            turn_interrupted: AtomicBool::new(false),
+            pending_input: std::sync::Mutex::new(Vec::new()),
             cancel,
         })
     }
@@ -165,6 +172,25 @@ impl AppServerClient {
         self.send_request(request, "sendUserMessage").await
     }
 
+    /// Interrupt the running turn. The conversation stays open for the next user message.
+    pub async fn interrupt_turn(&self) -> Result<(), ExecutorError> {
+        let Some(conversation_id) = *self.conversation_id.lock().await else {
+            return Ok(());
+        };
+        self.turn_interrupted.store(true, Ordering::SeqCst);
+        let request = ClientRequest::InterruptConversation {
+            request_id: self.next_request_id(),
+            params: InterruptConversationParams { conversation_id },
+        };
+        let result: Result<InterruptConversationResponse, _> =
+            self.send_request(request, "interruptConversation").await;
+        if let Err(err) = result {
+            self.turn_interrupted.store(false, Ordering::SeqCst);
+            return Err(err);
+        }
+        Ok(())
+    }
+
     pub async fn get_auth_status(&self) -> Result<GetAuthStatusResponse, ExecutorError> {
         let request = ClientRequest::GetAuthStatus {
             request_id: self.next_request_id(),
@@ -375,6 +401,36 @@ impl AppServerClient {
         Ok(outcome)
     }
 
+    /// Send a user message into the running conversation and record it in the log.
+    /// Returns once Codex has accepted the message; the execution keeps running until
+    /// Codex records it in the conversation.
+    pub async fn inject_user_message(&self, message: String) -> Result<(), ExecutorError> {
+        let Some(conversation_id) = *self.conversation_id.lock().await else {
+            return Err(ExecutorError::InputUnavailable(
+                "Codex conversation has not started yet".to_string(),
+            ));
+        };
+        self.log_writer
+            .log_raw(&InjectedUserMessage::new(message.clone()).raw())
+            .await?;
+        self.pending_input.lock().unwrap().push(message.clone());
+        if let Err(err) = self
+            .send_user_message(conversation_id, message.clone())
+            .await
+        {
+            self.acknowledge_input(&message);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn acknowledge_input(&self, message: &str) {
+        let mut pending = self.pending_input.lock().unwrap();
+        if let Some(index) = pending.iter().position(|pending| pending == message) {
+            pending.remove(index);
+        }
+    }
+
     async fn enqueue_feedback(&self, message: String) {
         if message.trim().is_empty() {
             return;
@@ -507,14 +563,31 @@ impl JsonRpcCallbacks for AppServerClient {
         }
 
         if method.ends_with("turn_aborted") {
+            if self.turn_interrupted.swap(false, Ordering::SeqCst) {
+                self.log_writer.log_raw(&TurnInterrupted::raw()).await?;
+            }
             tracing::debug!("codex turn aborted; flushing feedback queue");
             self.flush_pending_feedback().await;
             return Ok(false);
         }
 
+        if method.ends_with("user_message")
+            && let Some(message) = notification
+                .params
+                .as_ref()
+                .and_then(|params| params.pointer("/msg/message"))
+                .and_then(Value::as_str)
+        {
+            self.acknowledge_input(message);
+            return Ok(false);
+        }
+
+        // A turn completing before Codex records an injected message is followed by
+        // the turn for that message
         let has_finished = method
             .strip_prefix("codex/event/")
-            .is_some_and(|suffix| suffix == "task_complete");
+            .is_some_and(|suffix| suffix == "task_complete")
+            && self.pending_input.lock().unwrap().is_empty();
 
         if has_finished
             && self.commit_reminder
@@ -591,3 +664,48 @@ impl LogWriter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn injected_message_fails_before_conversation_starts() {
+        let client = AppServerClient::new(
+            LogWriter::new(tokio::io::sink()),
+            None,
+            false,
+            RepoContext::default(),
+            false,
+            String::new(),
+            CancellationToken::new(),
+        );
+
+        let result = client.inject_user_message("steer".to_string()).await;
+        assert!(matches!(result, Err(ExecutorError::InputUnavailable(_))));
+    }
+
+    #[test]
+    fn injected_messages_are_acknowledged_once_each() {
+        let client = AppServerClient::new(
+            LogWriter::new(tokio::io::sink()),
+            None,
+            false,
+            RepoContext::default(),
+            false,
+            String::new(),
+            CancellationToken::new(),
+        );
+        client
+            .pending_input
+            .lock()
+            .unwrap()
+            .extend(["again".to_string(), "again".to_string()]);
+
+        client.acknowledge_input("something else");
+        client.acknowledge_input("again");
+        assert_eq!(client.pending_input.lock().unwrap().len(), 1);
+        client.acknowledge_input("again");
+        assert!(client.pending_input.lock().unwrap().is_empty());
+    }
+}
@@ -34,9 +34,9 @@ use crate::{
     approvals::ToolCallMetadata,
     executors::codex::session::SessionHandler,
     logs::{
-        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
-        ToolStatus,
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, InjectedUserMessage,
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult,
+        ToolResultValueType, ToolStatus, TurnInterrupted,
         plain_text_processor::PlainTextLogProcessor,
         utils::{
             ConversationPatch, EntryIndexProvider,
@@ -484,6 +484,16 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 continue;
             }
 
+            if serde_json::from_str::<TurnInterrupted>(&line).is_ok() {
+                TurnInterrupted::normalize(&msg_store, &entry_index);
+                continue;
+            }
+
+            if let Ok(injected) = serde_json::from_str::<InjectedUserMessage>(&line) {
+                add_normalized_entry(&msg_store, &entry_index, injected.to_normalized_entry());
+                continue;
+            }
+
             if let Ok(approval) = serde_json::from_str::<Approval>(&line) {
                 if let Some(entry) = approval.to_normalized_entry_opt() {
                     add_normalized_entry(&msg_store, &entry_index, entry);
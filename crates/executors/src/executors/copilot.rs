@@ -14,8 +14,8 @@ use crate::{
     env::ExecutionEnv,
     executor_discovery::ExecutorDiscoveredOptions,
     executors::{
-        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorError, SpawnedChild,
-        StandardCodingAgentExecutor,
+        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorChannel, ExecutorError,
+        SpawnedChild, StandardCodingAgentExecutor,
     },
     logs::utils::patch,
     model_selector::{ModelInfo, ModelSelectorConfig, PermissionPolicy},
@@ -45,6 +45,14 @@ pub struct Copilot {
     #[ts(skip)]
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
     pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    user_input: ExecutorChannel<String>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    interrupt: ExecutorChannel<()>,
 }
 
 impl Copilot {
@@ -91,6 +99,14 @@ impl StandardCodingAgentExecutor for Copilot {
         self.approvals = Some(approvals);
     }
 
+    async fn send_user_input(&self, message: String) -> Result<(), ExecutorError> {
+        self.user_input.send(message).await
+    }
+
+    async fn interrupt_turn(&self) -> Result<(), ExecutorError> {
+        self.interrupt.send(()).await
+    }
+
     fn apply_overrides(&mut self, executor_config: &ExecutorConfig) {
         if let Some(model_id) = &executor_config.model_id {
             self.model = Some(model_id.clone());
@@ -121,6 +137,8 @@ impl StandardCodingAgentExecutor for Copilot {
                 env,
                 &self.cmd,
                 self.approvals.clone(),
+                self.user_input.connect_buffered(),
+                self.interrupt.connect_buffered(),
             )
             .await
     }
@@ -145,6 +163,8 @@ impl StandardCodingAgentExecutor for Copilot {
                 env,
                 &self.cmd,
                 self.approvals.clone(),
+                self.user_input.connect_buffered(),
+                self.interrupt.connect_buffered(),
             )
             .await
     }
@@ -14,8 +14,8 @@ use crate::{
     env::ExecutionEnv,
     executor_discovery::ExecutorDiscoveredOptions,
     executors::{
-        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorError, SpawnedChild,
-        StandardCodingAgentExecutor,
+        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorChannel, ExecutorError,
+        SpawnedChild, StandardCodingAgentExecutor,
     },
     logs::utils::patch,
     model_selector::{ModelInfo, ModelSelectorConfig, PermissionPolicy},
@@ -37,6 +37,14 @@ pub struct Gemini {
     #[ts(skip)]
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
     pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    user_input: ExecutorChannel<String>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    interrupt: ExecutorChannel<()>,
 }
 
 impl Gemini {
@@ -76,6 +84,14 @@ impl StandardCodingAgentExecutor for Gemini {
         self.approvals = Some(approvals);
     }
 
+    async fn send_user_input(&self, message: String) -> Result<(), ExecutorError> {
+        self.user_input.send(message).await
+    }
+
+    async fn interrupt_turn(&self) -> Result<(), ExecutorError> {
+        self.interrupt.send(()).await
+    }
+
     async fn spawn(
         &self,
         current_dir: &Path,
@@ -101,6 +117,8 @@ impl StandardCodingAgentExecutor for Gemini {
                 env,
                 &self.cmd,
                 approvals,
+                self.user_input.connect_buffered(),
+                self.interrupt.connect_buffered(),
             )
             .await
     }
@@ -133,6 +151,8 @@ impl StandardCodingAgentExecutor for Gemini {
                 env,
                 &self.cmd,
                 approvals,
+                self.user_input.connect_buffered(),
+                self.interrupt.connect_buffered(),
             )
             .await
     }
@@ -22,7 +22,7 @@ use crate::{
     env::ExecutionEnv,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode, replay::Replay,
     },
     logs::utils::patch,
     mcp_config::McpConfig,
@@ -41,6 +41,7 @@ pub mod opencode;
 #[cfg(feature = "qa-mode")]
 pub mod qa_mock;
 pub mod qwen;
+pub mod replay;
 pub mod utils;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
@@ -60,6 +61,10 @@ pub enum BaseAgentCapability {
     SetupHelper,
     /// Agent reports context/token usage information
     ContextUsage,
+    /// Agent accepts user messages while a turn is in progress
+    UserInput,
+    /// Agent can interrupt the current turn and keep the session open for the next message
+    Interrupt,
 }
 
 #[derive(Debug, Error)]
@@ -88,6 +93,12 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Agent does not accept messages while it is running")]
+    UserInputNotSupported,
+    #[error("Agent is not accepting input: {0}")]
+    InputUnavailable(String),
+    #[error("Agent cannot interrupt a turn")]
+    InterruptNotSupported,
 }
 
 #[enum_dispatch]
@@ -118,6 +129,7 @@ pub enum CodingAgent {
     QwenCode,
     Copilot,
     Droid,
+    Replay,
     #[cfg(feature = "qa-mode")]
     QaMock(QaMockExecutor),
 }
@@ -178,21 +190,33 @@ impl CodingAgent {
             Self::ClaudeCode(_) => vec![
                 BaseAgentCapability::SessionFork,
                 BaseAgentCapability::ContextUsage,
+                BaseAgentCapability::UserInput,
+                BaseAgentCapability::Interrupt,
             ],
             Self::Opencode(_) => vec![
                 BaseAgentCapability::SessionFork,
                 BaseAgentCapability::ContextUsage,
+                BaseAgentCapability::UserInput,
+                BaseAgentCapability::Interrupt,
             ],
             Self::Codex(_) => vec![
                 BaseAgentCapability::SessionFork,
                 BaseAgentCapability::SetupHelper,
                 BaseAgentCapability::ContextUsage,
+                BaseAgentCapability::UserInput,
+                BaseAgentCapability::Interrupt,
+            ],
+            Self::Gemini(_) | Self::QwenCode(_) => vec![
+                BaseAgentCapability::SessionFork,
+                BaseAgentCapability::UserInput,
+                BaseAgentCapability::Interrupt,
             ],
-            Self::Gemini(_) | Self::QwenCode(_) => {
-                vec![BaseAgentCapability::SessionFork]
-            }
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Amp(_) | Self::Copilot(_) | Self::Droid(_) => vec![],
+            Self::Copilot(_) => vec![
+                BaseAgentCapability::UserInput,
+                BaseAgentCapability::Interrupt,
+            ],
+            Self::Amp(_) | Self::Droid(_) | Self::Replay(_) => vec![],
             #[cfg(feature = "qa-mode")]
             Self::QaMock(_) => vec![], // QA mock doesn't need special capabilities
         }
@@ -292,6 +316,21 @@ pub trait StandardCodingAgentExecutor {
 
     /// Returns the default overrides defined by this preset/variant.
     fn get_preset_options(&self) -> ExecutorConfig;
+
+    /// Send a user message to the execution this executor spawned while it is running.
+    /// Executors that advertise [`BaseAgentCapability::UserInput`] hand it to the agent as
+    /// steering input for the turn in progress.
+    async fn send_user_input(&self, _message: String) -> Result<(), ExecutorError> {
+        Err(ExecutorError::UserInputNotSupported)
+    }
+
+    /// Interrupt the turn in progress of the execution this executor spawned, keeping the
+    /// session open. Executors that advertise [`BaseAgentCapability::Interrupt`] cancel the
+    /// turn through their protocol and then wait for the next message from
+    /// [`Self::send_user_input`].
+    async fn interrupt_turn(&self) -> Result<(), ExecutorError> {
+        Err(ExecutorError::InterruptNotSupported)
+    }
 }
 
 /// Result communicated through the exit signal
@@ -312,6 +351,156 @@ pub type ExecutorExitSignal = tokio::sync::oneshot::Receiver<ExecutorExitResult>
 /// When cancelled, the executor should attempt to cancel gracefully before being killed.
 pub type CancellationToken = tokio_util::sync::CancellationToken;
 
+/// Request sent from the container to a running execution, answered once the executor
+/// has handed the payload to the agent.
+#[derive(Debug)]
+pub struct ExecutorRequest<T> {
+    pub payload: T,
+    pub reply: tokio::sync::oneshot::Sender<Result<(), ExecutorError>>,
+}
+
+/// Connection between an executor and the execution it spawned.
+///
+/// The executor connects when it spawns and drains the receiver while the execution runs.
+/// Clones start disconnected, so every execution spawned from a cached profile gets its own.
+#[derive(Debug)]
+pub struct ExecutorChannel<T> {
+    sender: std::sync::Mutex<Option<tokio::sync::mpsc::UnboundedSender<ExecutorRequest<T>>>>,
+}
+
+impl<T> Default for ExecutorChannel<T> {
+    fn default() -> Self {
+        Self {
+            sender: std::sync::Mutex::new(None),
+        }
+    }
+}
+
+impl<T> Clone for ExecutorChannel<T> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Send + 'static> ExecutorChannel<T> {
+    /// Connect a newly spawned execution, replacing any previous connection.
+    pub fn connect(&self) -> tokio::sync::mpsc::UnboundedReceiver<ExecutorRequest<T>> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        *self.sender.lock().unwrap() = Some(tx);
+        rx
+    }
+
+    /// Connect an execution that picks payloads up at its own pace. Each request is
+    /// answered as soon as the payload is buffered for it, and refused once the
+    /// execution stops taking payloads.
+    pub fn connect_buffered(&self) -> BufferedReceiver<T> {
+        let mut requests = self.connect();
+        let receiver = BufferedReceiver::default();
+        let buffer = receiver.buffer.clone();
+        let notify = receiver.notify.clone();
+        tokio::spawn(async move {
+            while let Some(request) = requests.recv().await {
+                let result = {
+                    let mut buffer = buffer.lock().unwrap();
+                    if buffer.closed {
+                        Err(ExecutorError::InputUnavailable(
+                            "execution has finished".to_string(),
+                        ))
+                    } else {
+                        buffer.payloads.push_back(request.payload);
+                        Ok(())
+                    }
+                };
+                notify.notify_one();
+                let _ = request.reply.send(result);
+            }
+            buffer.lock().unwrap().disconnected = true;
+            notify.notify_one();
+        });
+        receiver
+    }
+
+    /// Send a payload to the connected execution and wait for the executor's answer.
+    pub async fn send(&self, payload: T) -> Result<(), ExecutorError> {
+        let not_running = || ExecutorError::InputUnavailable("execution is not running".into());
+        let sender = self
+            .sender
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(not_running)?;
+        let (reply, response) = tokio::sync::oneshot::channel();
+        sender
+            .send(ExecutorRequest { payload, reply })
+            .map_err(|_| not_running())?;
+        response.await.map_err(|_| not_running())?
+    }
+}
+
+struct Buffer<T> {
+    payloads: std::collections::VecDeque<T>,
+    /// The execution takes no more payloads
+    closed: bool,
+    /// The channel was reconnected or dropped, so nothing more arrives
+    disconnected: bool,
+}
+
+/// Payloads buffered by [`ExecutorChannel::connect_buffered`]. Dropping the receiver
+/// refuses anything sent afterwards.
+pub struct BufferedReceiver<T> {
+    buffer: Arc<std::sync::Mutex<Buffer<T>>>,
+    notify: Arc<tokio::sync::Notify>,
+}
+
+impl<T> Default for BufferedReceiver<T> {
+    fn default() -> Self {
+        Self {
+            buffer: Arc::new(std::sync::Mutex::new(Buffer {
+                payloads: std::collections::VecDeque::new(),
+                closed: false,
+                disconnected: false,
+            })),
+            notify: Arc::new(tokio::sync::Notify::new()),
+        }
+    }
+}
+
+impl<T> BufferedReceiver<T> {
+    /// Wait for the next payload. Returns `None` once the channel is disconnected.
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            {
+                let mut buffer = self.buffer.lock().unwrap();
+                if let Some(payload) = buffer.payloads.pop_front() {
+                    return Some(payload);
+                }
+                if buffer.disconnected || buffer.closed {
+                    return None;
+                }
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// Take the next buffered payload, or stop taking payloads when there is none.
+    /// Used where the execution would otherwise finish, so a payload sent from now on
+    /// is refused instead of acknowledged and never delivered.
+    pub fn take_or_close(&mut self) -> Option<T> {
+        let mut buffer = self.buffer.lock().unwrap();
+        let payload = buffer.payloads.pop_front();
+        if payload.is_none() {
+            buffer.closed = true;
+        }
+        payload
+    }
+}
+
+impl<T> Drop for BufferedReceiver<T> {
+    fn drop(&mut self) {
+        self.buffer.lock().unwrap().closed = true;
+    }
+}
+
 #[derive(Debug)]
 pub struct SpawnedChild {
     pub child: AsyncGroupChild,
@@ -319,6 +508,8 @@ pub struct SpawnedChild {
     pub exit_signal: Option<ExecutorExitSignal>,
     /// Container → Executor: signals when container wants to cancel the execution
     pub cancel: Option<CancellationToken>,
+    /// Executor that spawned the execution; receives input and interrupts while it runs
+    pub executor: Option<Arc<CodingAgent>>,
 }
 
 impl From<AsyncGroupChild> for SpawnedChild {
@@ -327,10 +518,19 @@ impl From<AsyncGroupChild> for SpawnedChild {
             child,
             exit_signal: None,
             cancel: None,
+            executor: None,
         }
     }
 }
 
+impl SpawnedChild {
+    /// Keep the executor that spawned this child so the container can reach it.
+    pub fn with_executor(mut self, executor: CodingAgent) -> Self {
+        self.executor = Some(Arc::new(executor));
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
 #[serde(transparent)]
 #[schemars(
@@ -413,4 +613,96 @@ mod tests {
         assert!(result.is_ok(), "CURSOR should deserialize via serde");
         assert_eq!(result.unwrap(), BaseCodingAgent::CursorAgent);
     }
+
+    #[tokio::test]
+    async fn test_executor_channel_requires_connection() {
+        let channel = ExecutorChannel::<String>::default();
+        let result = channel.send("hello".to_string()).await;
+        assert!(matches!(result, Err(ExecutorError::InputUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_executor_channel_returns_executor_reply() {
+        let channel = ExecutorChannel::<String>::default();
+        let mut requests = channel.connect();
+        tokio::spawn(async move {
+            while let Some(request) = requests.recv().await {
+                let result = if request.payload == "ok" {
+                    Ok(())
+                } else {
+                    Err(ExecutorError::UserInputNotSupported)
+                };
+                let _ = request.reply.send(result);
+            }
+        });
+
+        assert!(channel.send("ok".to_string()).await.is_ok());
+        assert!(matches!(
+            channel.send("rejected".to_string()).await,
+            Err(ExecutorError::UserInputNotSupported)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_executor_channel_fails_once_execution_is_gone() {
+        let channel = ExecutorChannel::<String>::default();
+        drop(channel.connect());
+        let result = channel.send("hello".to_string()).await;
+        assert!(matches!(result, Err(ExecutorError::InputUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_executor_channel_clones_start_disconnected() {
+        let channel = ExecutorChannel::<String>::default();
+        let mut buffered = channel.connect_buffered();
+        let clone = channel.clone();
+
+        channel.send("first".to_string()).await.unwrap();
+        assert_eq!(buffered.recv().await.as_deref(), Some("first"));
+        assert!(clone.send("second".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_buffered_input_is_refused_once_execution_stops_taking_it() {
+        let channel = ExecutorChannel::<String>::default();
+        let mut buffered = channel.connect_buffered();
+
+        channel.send("during turn".to_string()).await.unwrap();
+        assert_eq!(buffered.take_or_close().as_deref(), Some("during turn"));
+        assert_eq!(buffered.take_or_close(), None);
+
+        let late = channel.send("after turn".to_string()).await;
+        assert!(matches!(late, Err(ExecutorError::InputUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_buffered_input_is_refused_after_execution_ends() {
+        let channel = ExecutorChannel::<String>::default();
+        drop(channel.connect_buffered());
+        let result = channel.send("hello".to_string()).await;
+        assert!(matches!(result, Err(ExecutorError::InputUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_match_implemented_controls() {
+        let configs = crate::profile::ExecutorConfigs::from_defaults();
+        for (executor, profile) in &configs.executors {
+            let agent = profile.get_default().expect("default variant");
+            let capabilities = agent.capabilities();
+
+            let input = agent.send_user_input("hello".to_string()).await;
+            assert_eq!(
+                capabilities.contains(&BaseAgentCapability::UserInput),
+                !matches!(input, Err(ExecutorError::UserInputNotSupported)),
+                "{executor} advertises UserInput inconsistently"
+            );
+
+            let interrupt = agent.interrupt_turn().await;
+            assert_eq!(
+                capabilities.contains(&BaseAgentCapability::Interrupt),
+                !matches!(interrupt, Err(ExecutorError::InterruptNotSupported)),
+                "{executor} advertises Interrupt inconsistently"
+            );
+        }
+    }
 }
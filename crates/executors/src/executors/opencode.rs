@@ -17,8 +17,8 @@ use crate::{
     command::{CmdOverrides, CommandBuildError, CommandBuilder, apply_overrides},
     env::{ExecutionEnv, RepoContext},
     executors::{
-        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorError, ExecutorExitResult,
-        SlashCommandDescription, SpawnedChild, StandardCodingAgentExecutor,
+        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorChannel, ExecutorError,
+        ExecutorExitResult, SlashCommandDescription, SpawnedChild, StandardCodingAgentExecutor,
         opencode::types::OpencodeExecutorEvent, utils::reorder_slash_commands,
     },
     logs::utils::patch,
@@ -34,7 +34,7 @@ mod slash_commands;
 pub(crate) mod types;
 
 use sdk::{
-    AgentInfo as SDKAgentInfo, LogWriter, RunConfig, build_authenticated_client,
+    AgentInfo as SDKAgentInfo, LogWriter, RunConfig, SessionControl, build_authenticated_client,
     generate_server_password, list_agents, list_commands, list_providers, run_session,
     run_slash_command,
 };
@@ -64,6 +64,14 @@ pub struct Opencode {
     #[ts(skip)]
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
     pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    user_input: ExecutorChannel<String>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    interrupt: ExecutorChannel<()>,
 }
 
 /// Represents a spawned OpenCode server with its base URL
@@ -179,6 +187,8 @@ impl Opencode {
 
         let (exit_signal_tx, exit_signal_rx) = tokio::sync::oneshot::channel();
         let cancel = tokio_util::sync::CancellationToken::new();
+        let user_input_rx = self.user_input.connect_buffered();
+        let interrupt_rx = self.interrupt.connect_buffered();
 
         // Prepare config values that will be moved into the spawned task
         let directory = current_dir.to_string_lossy().to_string();
@@ -231,9 +241,17 @@ impl Opencode {
 
             let result = match slash_command {
                 Some(command) => {
+                    // Slash commands run a single turn, so input and interrupts are refused
+                    drop((user_input_rx, interrupt_rx));
                     run_slash_command(config, log_writer.clone(), command, cancel_for_task).await
                 }
-                None => run_session(config, log_writer.clone(), cancel_for_task).await,
+                None => {
+                    let session_control = SessionControl {
+                        user_input: user_input_rx,
+                        interrupt: interrupt_rx,
+                    };
+                    run_session(config, log_writer.clone(), session_control, cancel_for_task).await
+                }
             };
             let exit_result = match result {
                 Ok(()) => ExecutorExitResult::Success,
@@ -251,6 +269,7 @@ impl Opencode {
             child,
             exit_signal: Some(exit_signal_rx),
             cancel: Some(cancel),
+            executor: None,
         })
     }
 
@@ -415,6 +434,14 @@ impl StandardCodingAgentExecutor for Opencode {
         self.approvals = Some(approvals);
     }
 
+    async fn send_user_input(&self, message: String) -> Result<(), ExecutorError> {
+        self.user_input.send(message).await
+    }
+
+    async fn interrupt_turn(&self) -> Result<(), ExecutorError> {
+        self.interrupt.send(()).await
+    }
+
     async fn spawn(
         &self,
         current_dir: &Path,
@@ -12,9 +12,9 @@ use super::types::{
 use crate::{
     approvals::ToolCallMetadata,
     logs::{
-        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsageInfo, ToolResult,
-        ToolStatus,
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, InjectedUserMessage,
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsageInfo,
+        ToolResult, ToolStatus, TurnInterrupted,
         stderr_processor::normalize_stderr_logs,
         utils::{
             EntryIndexProvider,
@@ -44,6 +44,15 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
 
         let mut stdout_lines = msg_store.stdout_lines_stream();
         while let Some(Ok(line)) = stdout_lines.next().await {
+            if serde_json::from_str::<TurnInterrupted>(line.trim()).is_ok() {
+                TurnInterrupted::normalize(&msg_store, &entry_index);
+                continue;
+            }
+            if let Ok(injected) = serde_json::from_str::<InjectedUserMessage>(line.trim()) {
+                add_normalized_entry(&msg_store, &entry_index, injected.to_normalized_entry());
+                continue;
+            }
+
             let Some(event) = parse_event(&line) else {
                 let trimmed = line.trim();
                 if trimmed.is_empty() {
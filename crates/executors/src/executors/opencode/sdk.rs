@@ -2,7 +2,10 @@ use std::{
     collections::{HashMap, HashSet},
     future::Future,
     io,
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Duration,
 };
 
@@ -27,7 +30,8 @@ use super::{
 use crate::{
     approvals::{ExecutorApprovalError, ExecutorApprovalService},
     env::RepoContext,
-    executors::{ExecutorError, opencode::models::maybe_emit_token_usage},
+    executors::{BufferedReceiver, ExecutorError, opencode::models::maybe_emit_token_usage},
+    logs::{InjectedUserMessage, TurnInterrupted},
 };
 
 #[derive(Clone)]
@@ -58,7 +62,7 @@ impl LogWriter {
             .await
     }
 
-    async fn log_raw(&self, raw: &str) -> Result<(), ExecutorError> {
+    pub async fn log_raw(&self, raw: &str) -> Result<(), ExecutorError> {
         let mut guard = self.writer.lock().await;
         guard
             .write_all(raw.as_bytes())
@@ -191,9 +195,16 @@ pub enum ControlEvent {
     Disconnected,
 }
 
+/// Channels the container uses to steer a running session.
+pub struct SessionControl {
+    pub user_input: BufferedReceiver<String>,
+    pub interrupt: BufferedReceiver<()>,
+}
+
 pub async fn run_session(
     config: RunConfig,
     log_writer: LogWriter,
+    session_control: SessionControl,
     cancel: CancellationToken,
 ) -> Result<(), ExecutorError> {
     let client = reqwest::Client::builder()
@@ -204,7 +215,7 @@ pub async fn run_session(
         .build()
         .map_err(|err| ExecutorError::Io(io::Error::other(err)))?;
 
-    run_session_inner(config, log_writer, client, cancel).await
+    run_session_inner(config, log_writer, client, session_control, cancel).await
 }
 
 pub async fn run_slash_command(
@@ -228,8 +239,13 @@ async fn run_session_inner(
     config: RunConfig,
     log_writer: LogWriter,
     client: reqwest::Client,
+    session_control: SessionControl,
     cancel: CancellationToken,
 ) -> Result<(), ExecutorError> {
+    let SessionControl {
+        mut user_input,
+        mut interrupt,
+    } = session_control;
     tokio::select! {
         _ = cancel.cancelled() => return Ok(()),
         res = wait_for_health(&client, &config.base_url) => res?,
@@ -278,28 +294,70 @@ async fn run_session_inner(
         event_resp,
     ));
 
-    let prompt_fut = Box::pin(prompt(
-        &client,
-        &config.base_url,
-        &config.directory,
-        &session_id,
-        &config.prompt,
-        model.clone(),
-        config.model_variant.clone(),
-        config.agent.clone(),
-    ));
-    let prompt_result = run_request_with_control(prompt_fut, &mut control_rx, cancel.clone()).await;
+    // Interrupts abort the running prompt; the session then waits for the next user message
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let interrupt_handle = tokio::spawn({
+        let client = client.clone();
+        let base_url = config.base_url.clone();
+        let directory = config.directory.clone();
+        let session_id = session_id.clone();
+        let interrupted = interrupted.clone();
+        async move {
+            while interrupt.recv().await.is_some() {
+                interrupted.store(true, Ordering::SeqCst);
+                send_abort(&client, &base_url, &directory, &session_id).await;
+            }
+        }
+    });
 
-    if cancel.is_cancelled() {
-        send_abort(&client, &config.base_url, &config.directory, &session_id).await;
-        event_handle.abort();
-        return Ok(());
-    }
+    let mut next_prompt = Some(config.prompt.clone());
+    while let Some(message) = next_prompt.take() {
+        let prompt_fut = Box::pin(prompt(
+            &client,
+            &config.base_url,
+            &config.directory,
+            &session_id,
+            &message,
+            model.clone(),
+            config.model_variant.clone(),
+            config.agent.clone(),
+        ));
+        let prompt_result =
+            run_request_with_control(prompt_fut, &mut control_rx, cancel.clone()).await;
 
-    if let Err(err) = prompt_result {
-        event_handle.abort();
-        return Err(err);
+        if cancel.is_cancelled() {
+            send_abort(&client, &config.base_url, &config.directory, &session_id).await;
+            interrupt_handle.abort();
+            event_handle.abort();
+            return Ok(());
+        }
+
+        // Continue with the next user message: wait for one after an interrupt,
+        // otherwise only take messages sent during the turn
+        next_prompt = if interrupted.swap(false, Ordering::SeqCst) {
+            log_writer.log_raw(&TurnInterrupted::raw()).await?;
+            // Drop events left over from the aborted prompt
+            while control_rx.try_recv().is_ok() {}
+            tokio::select! {
+                _ = cancel.cancelled() => None,
+                message = user_input.recv() => message,
+            }
+        } else {
+            if let Err(err) = prompt_result {
+                interrupt_handle.abort();
+                event_handle.abort();
+                return Err(err);
+            }
+            user_input.take_or_close()
+        };
+
+        if let Some(message) = &next_prompt {
+            log_writer
+                .log_raw(&InjectedUserMessage::new(message.clone()).raw())
+                .await?;
+        }
     }
+    interrupt_handle.abort();
 
     // Handle commit reminder if enabled
     if config.commit_reminder
@@ -13,8 +13,8 @@ use crate::{
     env::ExecutionEnv,
     executor_discovery::ExecutorDiscoveredOptions,
     executors::{
-        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorError, SpawnedChild,
-        StandardCodingAgentExecutor, gemini::AcpAgentHarness,
+        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorChannel, ExecutorError,
+        SpawnedChild, StandardCodingAgentExecutor, gemini::AcpAgentHarness,
     },
     logs::utils::patch,
     model_selector::{ModelSelectorConfig, PermissionPolicy},
@@ -38,6 +38,14 @@ pub struct QwenCode {
     #[ts(skip)]
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
     pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    user_input: ExecutorChannel<String>,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    interrupt: ExecutorChannel<()>,
 }
 
 impl QwenCode {
@@ -78,6 +86,14 @@ impl StandardCodingAgentExecutor for QwenCode {
         self.approvals = Some(approvals);
     }
 
+    async fn send_user_input(&self, message: String) -> Result<(), ExecutorError> {
+        self.user_input.send(message).await
+    }
+
+    async fn interrupt_turn(&self) -> Result<(), ExecutorError> {
+        self.interrupt.send(()).await
+    }
+
     async fn spawn(
         &self,
         current_dir: &Path,
@@ -106,6 +122,8 @@ impl StandardCodingAgentExecutor for QwenCode {
                 env,
                 &self.cmd,
                 approvals,
+                self.user_input.connect_buffered(),
+                self.interrupt.connect_buffered(),
             )
             .await
     }
@@ -141,6 +159,8 @@ impl StandardCodingAgentExecutor for QwenCode {
                 env,
                 &self.cmd,
                 approvals,
+                self.user_input.connect_buffered(),
+                self.interrupt.connect_buffered(),
             )
             .await
     }
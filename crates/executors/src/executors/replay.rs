@@ -0,0 +1,334 @@
+//! Replay executor for deterministic end-to-end tests
+//!
+//! A [`ReplayFixture`] captures a real execution: the raw stdout/stderr chunks the agent
+//! produced (with their timing), the executor that produced them, and the file edits the
+//! run left behind. [`Replay`] plays a fixture back:
+//! 1. Applies the recorded file changes to the workspace
+//! 2. Re-emits the recorded output in-process with original or accelerated timing
+//! 3. Normalizes it with the recorded executor's own `normalize_logs`
+//!
+//! It is selected like any other agent, through a profile pointing at a fixture exported
+//! from a finished execution.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tracing::{info, warn};
+use ts_rs::TS;
+use workspace_utils::msg_store::MsgStore;
+
+use crate::{
+    env::ExecutionEnv,
+    executors::{
+        AvailabilityInfo, BaseCodingAgent, ExecutorError, ExecutorExitResult, SpawnedChild,
+        StandardCodingAgentExecutor,
+    },
+    profile::{ExecutorConfig, ExecutorConfigs},
+    stdout_dup::{create_stderr_pipe_writer, spawn_local_output_process},
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayStream {
+    Stdout,
+    Stderr,
+}
+
+/// One raw output chunk, exactly as it was read from the agent process.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReplayLogEntry {
+    /// Milliseconds since the first recorded chunk
+    pub offset_ms: u64,
+    pub stream: ReplayStream,
+    pub content: String,
+}
+
+/// Final state of a file touched by the recorded run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReplayFileChange {
+    /// Repository (workspace subdirectory) the file belongs to
+    pub repo: String,
+    /// Path relative to the repository root
+    pub path: String,
+    /// New file contents; `None` when the run deleted the file
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReplayFixture {
+    /// Executor that produced the logs; its normalizer is used on replay
+    pub executor_config: ExecutorConfig,
+    pub logs: Vec<ReplayLogEntry>,
+    #[serde(default)]
+    pub file_changes: Vec<ReplayFileChange>,
+}
+
+impl ReplayFixture {
+    pub fn load(path: &Path) -> Result<Self, ExecutorError> {
+        let content = std::fs::read_to_string(path).map_err(ExecutorError::Io)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// Replay executor configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+pub struct Replay {
+    #[schemars(
+        title = "Fixture Path",
+        description = "Fixture exported from a finished execution"
+    )]
+    pub fixture_path: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Playback Speed",
+        description = "Multiplier for the recorded timing; 0 replays without delays"
+    )]
+    pub speed: Option<f64>,
+}
+
+impl Replay {
+    fn load_fixture(&self) -> Result<ReplayFixture, ExecutorError> {
+        ReplayFixture::load(Path::new(&self.fixture_path))
+    }
+
+    /// Playback speed multiplier: `1.0` keeps the recorded timing, `0.0` disables delays
+    fn speed(&self) -> f64 {
+        self.speed
+            .filter(|speed| speed.is_finite() && *speed >= 0.0)
+            .unwrap_or(1.0)
+    }
+
+    /// Delay before each log entry, scaled by the playback speed.
+    fn delays(&self, fixture: &ReplayFixture) -> Vec<Duration> {
+        let speed = self.speed();
+        let mut previous = 0;
+        fixture
+            .logs
+            .iter()
+            .map(|entry| {
+                let gap = entry.offset_ms.saturating_sub(previous);
+                previous = entry.offset_ms;
+                if speed == 0.0 {
+                    Duration::ZERO
+                } else {
+                    Duration::from_secs_f64(gap as f64 / 1000.0 / speed)
+                }
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Replay {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        _prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let fixture = self.load_fixture()?;
+        info!(
+            "Replay executor: replaying {} log entries recorded with {}",
+            fixture.logs.len(),
+            fixture.executor_config.executor
+        );
+
+        let workspace_root = if env.repo_context.workspace_root.as_os_str().is_empty() {
+            current_dir.to_path_buf()
+        } else {
+            env.repo_context.workspace_root.clone()
+        };
+        apply_file_changes(&workspace_root, &fixture.file_changes).await;
+
+        let (mut spawned, stdout) = spawn_local_output_process()?;
+        let stderr = create_stderr_pipe_writer(&mut spawned.child)?;
+        let (exit_signal_tx, exit_signal_rx) = tokio::sync::oneshot::channel();
+
+        let delays = self.delays(&fixture);
+        tokio::spawn(async move {
+            let result = play_back(&fixture.logs, &delays, stdout, stderr).await;
+            let exit_result = match result {
+                Ok(()) => ExecutorExitResult::Success,
+                Err(e) => {
+                    warn!("Replay executor: failed to write recorded output: {}", e);
+                    ExecutorExitResult::Failure
+                }
+            };
+            let _ = exit_signal_tx.send(exit_result);
+        });
+
+        spawned.exit_signal = Some(exit_signal_rx);
+        Ok(spawned)
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        _session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        // A fixture is a single recorded turn, so follow-ups replay it again
+        self.spawn(current_dir, prompt, env).await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
+        let fixture = match self.load_fixture() {
+            Ok(fixture) => fixture,
+            Err(e) => {
+                warn!("Failed to load replay fixture {}: {}", self.fixture_path, e);
+                return;
+            }
+        };
+        ExecutorConfigs::get_cached()
+            .get_coding_agent_or_default(&fixture.executor_config.profile_id())
+            .normalize_logs(msg_store, current_dir);
+    }
+
+    fn default_mcp_config_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        if Path::new(&self.fixture_path).is_file() {
+            AvailabilityInfo::InstallationFound
+        } else {
+            AvailabilityInfo::NotFound
+        }
+    }
+
+    fn get_preset_options(&self) -> ExecutorConfig {
+        ExecutorConfig::new(BaseCodingAgent::Replay)
+    }
+}
+
+/// Write the recorded chunks to the replay's stdout and stderr, closing both at the end.
+async fn play_back(
+    logs: &[ReplayLogEntry],
+    delays: &[Duration],
+    mut stdout: impl AsyncWrite + Unpin + Send,
+    mut stderr: impl AsyncWrite + Unpin + Send,
+) -> std::io::Result<()> {
+    for (entry, delay) in logs.iter().zip(delays) {
+        if !delay.is_zero() {
+            tokio::time::sleep(*delay).await;
+        }
+        let writer: &mut (dyn AsyncWrite + Unpin + Send) = match entry.stream {
+            ReplayStream::Stdout => &mut stdout,
+            ReplayStream::Stderr => &mut stderr,
+        };
+        writer.write_all(entry.content.as_bytes()).await?;
+        writer.flush().await?;
+    }
+    stdout.shutdown().await?;
+    stderr.shutdown().await
+}
+
+async fn apply_file_changes(workspace_root: &Path, changes: &[ReplayFileChange]) {
+    for change in changes {
+        let Some(path) = resolve_change_path(workspace_root, change) else {
+            warn!(
+                "Replay: skipping file change outside the workspace: {}/{}",
+                change.repo, change.path
+            );
+            continue;
+        };
+        let result = match &change.content {
+            Some(content) => {
+                if let Some(parent) = path.parent() {
+                    let _ = tokio::fs::create_dir_all(parent).await;
+                }
+                tokio::fs::write(&path, content).await
+            }
+            None => match tokio::fs::remove_file(&path).await {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                result => result,
+            },
+        };
+        if let Err(e) = result {
+            warn!("Replay: failed to apply change to {:?}: {}", path, e);
+        }
+    }
+}
+
+/// Resolve a recorded change against the workspace, rejecting paths that escape it.
+fn resolve_change_path(workspace_root: &Path, change: &ReplayFileChange) -> Option<PathBuf> {
+    let relative = Path::new(&change.repo).join(&change.path);
+    let is_contained = relative
+        .components()
+        .all(|component| matches!(component, std::path::Component::Normal(_)));
+    is_contained.then(|| workspace_root.join(relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(offsets: &[u64]) -> ReplayFixture {
+        ReplayFixture {
+            executor_config: ExecutorConfig::new(BaseCodingAgent::ClaudeCode),
+            logs: offsets
+                .iter()
+                .map(|offset_ms| ReplayLogEntry {
+                    offset_ms: *offset_ms,
+                    stream: ReplayStream::Stdout,
+                    content: "{}\n".to_string(),
+                })
+                .collect(),
+            file_changes: vec![],
+        }
+    }
+
+    fn replay(speed: Option<f64>) -> Replay {
+        Replay {
+            fixture_path: String::new(),
+            speed,
+        }
+    }
+
+    #[test]
+    fn delays_follow_recorded_gaps_scaled_by_speed() {
+        let fixture = fixture(&[0, 500, 2500]);
+        assert_eq!(
+            replay(Some(2.0)).delays(&fixture),
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(250),
+                Duration::from_secs(1)
+            ]
+        );
+        assert_eq!(replay(Some(0.0)).delays(&fixture), vec![Duration::ZERO; 3]);
+        assert_eq!(replay(None).delays(&fixture)[2], Duration::from_secs(2));
+    }
+
+    #[test]
+    fn rejects_change_paths_outside_workspace() {
+        let root = Path::new("/workspace");
+        let change = |repo: &str, path: &str| ReplayFileChange {
+            repo: repo.to_string(),
+            path: path.to_string(),
+            content: None,
+        };
+        assert_eq!(
+            resolve_change_path(root, &change("app", "src/main.rs")),
+            Some(PathBuf::from("/workspace/app/src/main.rs"))
+        );
+        assert_eq!(
+            resolve_change_path(root, &change("app", "../../etc/passwd")),
+            None
+        );
+        assert_eq!(
+            resolve_change_path(root, &change("app", "/etc/passwd")),
+            None
+        );
+    }
+}
@@ -1,9 +1,15 @@
+use std::sync::Arc;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-use workspace_utils::approvals::ApprovalStatus;
+use workspace_utils::{approvals::ApprovalStatus, msg_store::MsgStore};
 
-use crate::logs::utils::shell_command_parsing::CommandCategory;
+use crate::logs::utils::{
+    EntryIndexProvider,
+    patch::{add_normalized_entry, cancel_in_flight_tools},
+    shell_command_parsing::CommandCategory,
+};
 
 pub mod plain_text_processor;
 pub mod stderr_processor;
@@ -133,6 +139,67 @@ impl NormalizedEntry {
     }
 }
 
+/// Marker line written to the raw log when a user message is injected into a running
+/// execution, so normalizers can show it in the conversation where it was sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InjectedUserMessage {
+    pub injected_user_message: String,
+}
+
+impl InjectedUserMessage {
+    pub fn new(message: String) -> Self {
+        Self {
+            injected_user_message: message,
+        }
+    }
+
+    pub fn raw(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn to_normalized_entry(&self) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::UserMessage,
+            content: self.injected_user_message.clone(),
+            metadata: None,
+        }
+    }
+}
+
+/// Marker line written to the raw log when the user interrupts the current turn.
+/// The session stays open and continues with the next injected user message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TurnInterrupted {
+    pub turn_interrupted: bool,
+}
+
+impl TurnInterrupted {
+    pub fn raw() -> String {
+        serde_json::to_string(&Self {
+            turn_interrupted: true,
+        })
+        .unwrap_or_default()
+    }
+
+    /// Cancel the tool calls still in flight and note the interruption in the conversation.
+    pub fn normalize(msg_store: &Arc<MsgStore>, entry_index: &EntryIndexProvider) {
+        cancel_in_flight_tools(msg_store);
+        add_normalized_entry(
+            msg_store,
+            entry_index,
+            NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::SystemMessage,
+                content: "Interrupted. Send a message to continue.".to_string(),
+                metadata: None,
+            },
+        );
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS, Default)]
 #[serde(tag = "status", rename_all = "snake_case")]
 pub enum ToolStatus {
@@ -149,6 +216,8 @@ pub enum ToolStatus {
         timeout_at: DateTime<Utc>,
     },
     TimedOut,
+    /// The turn was interrupted before the tool finished
+    Cancelled,
 }
 
 impl ToolStatus {
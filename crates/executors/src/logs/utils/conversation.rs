@@ -0,0 +1,197 @@
+//! Rebuilding a [`NormalizedConversation`] from the patch stream a normalizer emits.
+//!
+//! Normalizers never return their output directly; they push JSON patches against
+//! `/entries/{index}` into a [`MsgStore`]. These helpers replay that stream into the final
+//! conversation so it can be inspected or compared against golden files.
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use serde_json::Value;
+use tokio::sync::broadcast::error::RecvError;
+use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+use crate::{
+    executors::{BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor},
+    logs::{NormalizedConversation, NormalizedEntry, NormalizedEntryType},
+};
+
+/// Upper bound for a normalizer that never releases its store
+const NORMALIZE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Fold the JSON patches in `history` into the conversation they describe.
+pub fn conversation_from_history(
+    history: &[LogMsg],
+    executor_type: &str,
+) -> NormalizedConversation {
+    let entries: Vec<NormalizedEntry> = entries_from_history(history)
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .collect();
+    let session_id = history.iter().rev().find_map(|msg| match msg {
+        LogMsg::SessionId(id) => Some(id.clone()),
+        _ => None,
+    });
+
+    let summary = entries
+        .iter()
+        .rev()
+        .find(|entry| matches!(entry.entry_type, NormalizedEntryType::AssistantMessage))
+        .map(|entry| entry.content.clone());
+
+    NormalizedConversation {
+        entries,
+        session_id,
+        executor_type: executor_type.to_string(),
+        prompt: None,
+        summary,
+    }
+}
+
+/// Apply the JSON patches in `history` to an entry list the way the UI does, and return
+/// the normalized entries with their index in that list.
+///
+/// The list also holds raw stdout/stderr entries, so the index is the one the UI keys
+/// the entry by, not its position among normalized entries.
+pub fn entries_from_history(history: &[LogMsg]) -> Vec<(usize, NormalizedEntry)> {
+    let mut entries: Vec<Value> = Vec::new();
+    for msg in history {
+        let LogMsg::JsonPatch(patch) = msg else {
+            continue;
+        };
+        let Ok(Value::Array(ops)) = serde_json::to_value(patch) else {
+            continue;
+        };
+        for op in ops {
+            apply_entry_op(&mut entries, &op);
+        }
+    }
+
+    entries
+        .into_iter()
+        .enumerate()
+        .filter(|(_, value)| value.get("type").and_then(Value::as_str) == Some("NORMALIZED_ENTRY"))
+        .filter_map(|(index, value)| {
+            let content = value.get("content")?.clone();
+            Some((index, serde_json::from_value(content).ok()?))
+        })
+        .collect()
+}
+
+/// Array semantics of RFC 6902, with `replace` of a missing entry treated as `add`.
+fn apply_entry_op(entries: &mut Vec<Value>, op: &Value) {
+    let Some(index) = op
+        .get("path")
+        .and_then(Value::as_str)
+        .and_then(|path| path.strip_prefix("/entries/"))
+        .and_then(|index| index.parse::<usize>().ok())
+    else {
+        return;
+    };
+
+    match (op.get("op").and_then(Value::as_str), op.get("value")) {
+        (Some("remove"), _) if index < entries.len() => {
+            entries.remove(index);
+        }
+        (Some("replace"), Some(value)) if index < entries.len() => {
+            entries[index] = value.clone();
+        }
+        (Some("add" | "replace"), Some(value)) if index <= entries.len() => {
+            entries.insert(index, value.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Run `executor`'s normalizer over captured stdout and return the resulting conversation.
+///
+/// Normalizers run on background tasks that each hold the store until they have consumed
+/// the finished stream, so the store's channel closes exactly when normalization is done.
+pub async fn normalize_stdout(
+    executor: &CodingAgent,
+    stdout: &[String],
+    worktree_path: &Path,
+) -> NormalizedConversation {
+    let msg_store = Arc::new(MsgStore::new());
+    for chunk in stdout {
+        msg_store.push_stdout(chunk.clone());
+    }
+    msg_store.push_finished();
+
+    let mut receiver = msg_store.get_receiver();
+    executor.normalize_logs(msg_store, worktree_path);
+
+    let mut history = Vec::new();
+    let collect = async {
+        loop {
+            match receiver.recv().await {
+                Ok(msg) => history.push(msg),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Normalizer output overran the store; {skipped} messages lost")
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+    if tokio::time::timeout(NORMALIZE_TIMEOUT, collect)
+        .await
+        .is_err()
+    {
+        tracing::warn!("Normalizer still held the store after {NORMALIZE_TIMEOUT:?}");
+    }
+
+    conversation_from_history(&history, &BaseCodingAgent::from(executor).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::utils::ConversationPatch;
+
+    fn entry(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn folds_add_replace_and_remove_patches() {
+        let history = vec![
+            LogMsg::SessionId("session-1".to_string()),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(0, entry("draft"))),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(1, entry("scratch"))),
+            LogMsg::JsonPatch(ConversationPatch::add_stdout(2, "raw".to_string())),
+            LogMsg::JsonPatch(ConversationPatch::replace(0, entry("final"))),
+            LogMsg::JsonPatch(ConversationPatch::remove(1)),
+        ];
+
+        let conversation = conversation_from_history(&history, "CLAUDE_CODE");
+        assert_eq!(conversation.session_id.as_deref(), Some("session-1"));
+        assert_eq!(conversation.entries.len(), 1);
+        assert_eq!(conversation.entries[0].content, "final");
+        assert_eq!(conversation.summary.as_deref(), Some("final"));
+    }
+
+    #[test]
+    fn entry_indices_follow_the_ui_entry_list() {
+        let history = vec![
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(0, entry("first"))),
+            LogMsg::JsonPatch(ConversationPatch::add_stdout(1, "raw".to_string())),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(2, entry("pending"))),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(3, entry("last"))),
+            LogMsg::JsonPatch(ConversationPatch::remove(2)),
+        ];
+
+        let indexed: Vec<_> = entries_from_history(&history)
+            .into_iter()
+            .map(|(index, entry)| (index, entry.content))
+            .collect();
+        // The raw stdout entry keeps its slot; the removal shifts later entries down
+        assert_eq!(
+            indexed,
+            vec![(0, "first".to_string()), (2, "last".to_string())]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 //! Utility modules for executor framework
 
+pub mod conversation;
 pub mod entry_index;
 pub mod patch;
 
@@ -1,15 +1,15 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_value, json, to_value};
 use ts_rs::TS;
-use workspace_utils::{diff::Diff, msg_store::MsgStore};
+use workspace_utils::{diff::Diff, log_msg::LogMsg, msg_store::MsgStore};
 
 use crate::{
     executor_discovery::ExecutorDiscoveredOptions,
     executors::SlashCommandDescription,
-    logs::{NormalizedEntry, utils::EntryIndexProvider},
+    logs::{NormalizedEntry, NormalizedEntryType, ToolStatus, utils::EntryIndexProvider},
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, TS)]
@@ -180,6 +180,29 @@ pub fn replace_normalized_entry(
     upsert_normalized_entry(msg_store, index, normalized_entry, false);
 }
 
+/// Mark tool calls that have not finished yet as cancelled, e.g. after the turn was interrupted.
+pub fn cancel_in_flight_tools(msg_store: &Arc<MsgStore>) {
+    let mut entries = BTreeMap::new();
+    for msg in msg_store.get_history() {
+        if let LogMsg::JsonPatch(patch) = msg
+            && let Some((index, entry)) = extract_normalized_entry_from_patch(&patch)
+        {
+            entries.insert(index, entry);
+        }
+    }
+
+    for (index, entry) in entries {
+        if let NormalizedEntryType::ToolUse {
+            status: ToolStatus::Created | ToolStatus::PendingApproval { .. },
+            ..
+        } = &entry.entry_type
+            && let Some(cancelled) = entry.with_tool_status(ToolStatus::Cancelled)
+        {
+            replace_normalized_entry(msg_store, index, cancelled);
+        }
+    }
+}
+
 pub fn executor_discovered_options(options: ExecutorDiscoveredOptions) -> Patch {
     serde_json::from_value(json!([
         {"op": "replace", "path": "/options", "value": options},
@@ -0,0 +1,111 @@
+//! Golden-file tests for the log normalizers.
+//!
+//! Every `tests/fixtures/normalizers/<EXECUTOR>/<case>.jsonl` file holds raw stdout as
+//! captured from that executor's CLI. It is fed through the executor's `normalize_logs`
+//! and the resulting conversation is compared with `<case>.golden.json` next to it.
+//!
+//! Run with `UPDATE_GOLDEN=1` to write the golden files after an intentional change or for
+//! a new fixture; a fixture without a golden file fails otherwise.
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use executors::{
+    executors::BaseCodingAgent,
+    logs::{NormalizedConversation, utils::conversation::normalize_stdout},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+
+/// Fixed worktree so paths in the output don't depend on the machine running the tests
+const WORKTREE: &str = "/tmp/vibe-kanban-golden/worktree";
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/normalizers")
+}
+
+fn fixture_cases() -> Vec<(BaseCodingAgent, PathBuf)> {
+    let mut cases = Vec::new();
+    for executor_dir in std::fs::read_dir(fixtures_dir()).unwrap() {
+        let executor_dir = executor_dir.unwrap().path();
+        let name = executor_dir
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+        let executor = BaseCodingAgent::from_str(&name)
+            .unwrap_or_else(|_| panic!("Fixture directory `{name}` is not an executor name"));
+        for file in std::fs::read_dir(&executor_dir).unwrap() {
+            let path = file.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "jsonl") {
+                cases.push((executor, path));
+            }
+        }
+    }
+    cases.sort_by(|a, b| a.1.cmp(&b.1));
+    cases
+}
+
+/// Strip values that change between runs.
+fn scrub(mut conversation: NormalizedConversation) -> NormalizedConversation {
+    for entry in &mut conversation.entries {
+        entry.timestamp = None;
+    }
+    conversation
+}
+
+fn first_difference(expected: &str, actual: &str) -> String {
+    let line = expected
+        .lines()
+        .zip(actual.lines())
+        .position(|(e, a)| e != a)
+        .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+    format!(
+        "first difference at line {}:\n  expected: {}\n  actual:   {}",
+        line + 1,
+        expected.lines().nth(line).unwrap_or("<end of file>"),
+        actual.lines().nth(line).unwrap_or("<end of file>"),
+    )
+}
+
+#[tokio::test]
+async fn normalizers_match_golden_files() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let configs = ExecutorConfigs::from_defaults();
+    let mut failures = Vec::new();
+
+    let cases = fixture_cases();
+    assert!(!cases.is_empty(), "No normalizer fixtures found");
+
+    for (executor, fixture) in cases {
+        let agent = configs
+            .get_coding_agent(&ExecutorProfileId::new(executor))
+            .unwrap_or_else(|| panic!("No default profile for {executor}"));
+        let stdout: Vec<String> = std::fs::read_to_string(&fixture)
+            .unwrap()
+            .lines()
+            .map(|line| format!("{line}\n"))
+            .collect();
+
+        let conversation = scrub(normalize_stdout(&agent, &stdout, Path::new(WORKTREE)).await);
+        let actual = serde_json::to_string_pretty(&conversation).unwrap() + "\n";
+
+        let golden = fixture.with_extension("golden.json");
+        let name = fixture.strip_prefix(fixtures_dir()).unwrap().display();
+        match std::fs::read_to_string(&golden) {
+            Ok(expected) if expected == actual => {}
+            _ if update => std::fs::write(&golden, &actual).unwrap(),
+            Ok(expected) => {
+                failures.push(format!("{name}: {}", first_difference(&expected, &actual)))
+            }
+            Err(_) => failures.push(format!("{name}: no golden file")),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Normalizer output changed (rerun with UPDATE_GOLDEN=1 if intended):\n{}",
+        failures.join("\n")
+    );
+}
@@ -18,8 +18,8 @@
 use std::{
     ffi::{OsStr, OsString},
     io::Write as _,
-    path::Path,
-    process::{Command, Stdio},
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
 };
 
 use thiserror::Error;
@@ -39,6 +39,8 @@ pub enum GitCliError {
     PushRejected(String),
     #[error("rebase in progress in this worktree")]
     RebaseInProgress,
+    #[error("commit hook failed: {0}")]
+    HookFailed(String),
 }
 
 #[derive(Clone, Default)]
@@ -77,6 +79,60 @@ pub struct StatusDiffOptions {
     pub path_filter: Option<Vec<String>>, // pathspecs to limit diff
 }
 
+/// Per-commit settings layered on top of the repository's git config.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitOptions {
+    /// Author/committer identity overriding `user.name`/`user.email` when both are set.
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    /// Append a `Signed-off-by` trailer.
+    pub signoff: bool,
+    /// GPG-sign the commit using the user's configured signing key.
+    pub gpg_sign: bool,
+    /// Bypass pre-commit and commit-msg hooks (`--no-verify`).
+    pub no_verify: bool,
+}
+
+impl CommitOptions {
+    pub fn identity(&self) -> Option<(&str, &str)> {
+        match (self.author_name.as_deref(), self.author_email.as_deref()) {
+            (Some(name), Some(email)) if !name.is_empty() && !email.is_empty() => {
+                Some((name, email))
+            }
+            _ => None,
+        }
+    }
+
+    fn args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        if self.signoff {
+            args.push(OsString::from("--signoff"));
+        }
+        if self.gpg_sign {
+            args.push(OsString::from("-S"));
+        }
+        if self.no_verify {
+            args.push(OsString::from("--no-verify"));
+        }
+        args
+    }
+
+    fn envs(&self) -> Vec<(OsString, OsString)> {
+        let Some((name, email)) = self.identity() else {
+            return Vec::new();
+        };
+        [
+            ("GIT_AUTHOR_NAME", name),
+            ("GIT_AUTHOR_EMAIL", email),
+            ("GIT_COMMITTER_NAME", name),
+            ("GIT_COMMITTER_EMAIL", email),
+        ]
+        .into_iter()
+        .map(|(k, v)| (OsString::from(k), OsString::from(v)))
+        .collect()
+    }
+}
+
 impl GitCli {
     pub fn new() -> Self {
         Self {}
@@ -107,6 +163,27 @@ impl GitCli {
         Ok(())
     }
 
+    /// Run `git -C <repo> worktree add --detach <path> <commitish>`, checking out a commit
+    /// without taking its branch, which may be checked out elsewhere
+    pub fn worktree_add_detached(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        commitish: &str,
+    ) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        let args: Vec<OsString> = vec![
+            "worktree".into(),
+            "add".into(),
+            "--detach".into(),
+            worktree_path.as_os_str().into(),
+            OsString::from(commitish),
+        ];
+        self.git(repo_path, args)?;
+        let _ = self.git(worktree_path, ["sparse-checkout", "reapply"]);
+        Ok(())
+    }
+
     /// Run `git -C <repo> worktree remove <path>`
     pub fn worktree_remove(
         &self,
@@ -339,8 +416,120 @@ impl GitCli {
 
     /// Commit staged changes with the given message.
     pub fn commit(&self, worktree_path: &Path, message: &str) -> Result<(), GitCliError> {
-        self.git(worktree_path, ["commit", "-m", message])?;
-        Ok(())
+        self.commit_with_options(worktree_path, message, &CommitOptions::default())
+    }
+
+    /// Commit staged changes, applying identity/sign-off/signing options.
+    pub fn commit_with_options(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+        options: &CommitOptions,
+    ) -> Result<(), GitCliError> {
+        let mut args = vec![OsString::from("commit")];
+        args.extend(options.args());
+        args.push(OsString::from("-m"));
+        args.push(OsString::from(message));
+        let out = self.git_output(worktree_path, args, Some(&options.envs()), None)?;
+        if out.status.success() {
+            return Ok(());
+        }
+        let output = failure_output(&out);
+        // git dies with 128 on its own errors (signing, identity, ...), while a rejecting
+        // hook makes it exit with 1 after printing only the hook's output
+        let hook_rejected = out.status.code() == Some(1)
+            && !options.no_verify
+            && !output.contains("nothing to commit")
+            && !output.contains("no changes added to commit")
+            && !self.commit_hooks(worktree_path).is_empty();
+        if hook_rejected {
+            Err(GitCliError::HookFailed(output))
+        } else {
+            Err(GitCliError::CommandFailed(output))
+        }
+    }
+
+    /// Names of the hooks `git commit` would run in this worktree (pre-commit, commit-msg, ...).
+    /// Honors `core.hooksPath`, which is how husky and similar tools install themselves.
+    pub fn commit_hooks(&self, worktree_path: &Path) -> Vec<String> {
+        let hooks_dir = match self.git(worktree_path, ["config", "--get", "core.hooksPath"]) {
+            Ok(path) if !path.trim().is_empty() => PathBuf::from(path.trim()),
+            _ => match self.git(worktree_path, ["rev-parse", "--git-path", "hooks"]) {
+                Ok(path) => PathBuf::from(path.trim()),
+                Err(_) => return Vec::new(),
+            },
+        };
+        let hooks_dir = if hooks_dir.is_absolute() {
+            hooks_dir
+        } else {
+            worktree_path.join(hooks_dir)
+        };
+
+        ["pre-commit", "prepare-commit-msg", "commit-msg"]
+            .into_iter()
+            .filter(|hook| is_executable_file(&hooks_dir.join(hook)))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Create a commit object for `tree` with a single parent without touching any ref.
+    /// Returns the new commit sha.
+    pub fn commit_tree(
+        &self,
+        repo_path: &Path,
+        tree: &str,
+        parent: &str,
+        message: &str,
+        options: &CommitOptions,
+    ) -> Result<String, GitCliError> {
+        let mut args = vec![
+            OsString::from("commit-tree"),
+            OsString::from(tree),
+            OsString::from("-p"),
+            OsString::from(parent),
+        ];
+        if options.gpg_sign {
+            args.push(OsString::from("-S"));
+        }
+        args.push(OsString::from("-m"));
+        args.push(OsString::from(message));
+        // commit-tree has no --signoff; add the trailer `git commit --signoff` would
+        if options.signoff {
+            let trailer = format!(
+                "Signed-off-by: {}",
+                self.committer_ident(repo_path, options)?
+            );
+            if !message.lines().any(|line| line == trailer) {
+                args.push(OsString::from("-m"));
+                args.push(OsString::from(trailer));
+            }
+        }
+        Ok(self
+            .git_with_env(repo_path, args, &options.envs())?
+            .trim()
+            .to_string())
+    }
+
+    /// `Name <email>` of the committer git would use with these options.
+    fn committer_ident(
+        &self,
+        repo_path: &Path,
+        options: &CommitOptions,
+    ) -> Result<String, GitCliError> {
+        let ident =
+            self.git_with_env(repo_path, ["var", "GIT_COMMITTER_IDENT"], &options.envs())?;
+        // Drop the trailing `<timestamp> <tz>`
+        let ident = ident.trim();
+        Ok(match ident.rfind('>') {
+            Some(end) => ident[..=end].to_string(),
+            None => ident.to_string(),
+        })
+    }
+
+    /// Move HEAD to `target`, keeping the index and working tree (`git reset --soft`).
+    pub fn reset_soft(&self, worktree_path: &Path, target: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["reset", "--soft", target])
+            .map(|_| ())
     }
     /// Fetch a branch to the given remote using native git authentication.
     pub fn fetch_with_refspec(
@@ -647,11 +836,12 @@ impl GitCli {
         base_branch: &str,
         from_branch: &str,
         message: &str,
+        options: &CommitOptions,
     ) -> Result<String, GitCliError> {
         self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
         self.git(repo_path, ["merge", "--squash", "--no-commit", from_branch])
             .map(|_| ())?;
-        self.git(repo_path, ["commit", "-m", message]).map(|_| ())?;
+        self.commit_with_options(repo_path, message, options)?;
         let sha = self
             .git(repo_path, ["rev-parse", "HEAD"])?
             .trim()
@@ -670,6 +860,120 @@ impl GitCli {
             .map(|_| ())
     }
 
+    /// Update a ref to `sha` only if it still points at `old_sha`.
+    pub fn update_ref_from(
+        &self,
+        repo_path: &Path,
+        refname: &str,
+        sha: &str,
+        old_sha: &str,
+    ) -> Result<(), GitCliError> {
+        self.git(repo_path, ["update-ref", refname, sha, old_sha])
+            .map(|_| ())
+    }
+
+    /// Fast-forward the checked-out branch to `commit`, updating the working tree.
+    pub fn merge_ff_only(&self, worktree_path: &Path, commit: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["merge", "--ff-only", commit])
+            .map(|_| ())
+    }
+
+    /// Delete a ref; missing refs are not an error.
+    pub fn delete_ref(&self, repo_path: &Path, refname: &str) -> Result<(), GitCliError> {
+        let exists = self
+            .git(repo_path, ["rev-parse", "--verify", "--quiet", refname])
+            .is_ok();
+        if !exists {
+            return Ok(());
+        }
+        self.git(repo_path, ["update-ref", "-d", refname])
+            .map(|_| ())
+    }
+
+    /// Record HEAD plus all uncommitted changes (including untracked files) as a commit
+    /// whose parent is HEAD, using a temporary index so the real index is left alone.
+    /// Returns `(head_sha, snapshot_sha)`; the snapshot is not referenced by any ref.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+    ) -> Result<(String, String), GitCliError> {
+        let tmp_dir = tempfile::TempDir::new()
+            .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
+        let tmp_index = tmp_dir.path().join("index");
+        let mut envs = vec![(
+            OsString::from("GIT_INDEX_FILE"),
+            tmp_index.as_os_str().to_os_string(),
+        )];
+
+        let head = self
+            .git(worktree_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+        let add_args = Self::apply_default_excludes(["add", "-A"]);
+        self.git_with_env(worktree_path, add_args, &envs)?;
+        let tree = self
+            .git_with_env(worktree_path, ["write-tree"], &envs)?
+            .trim()
+            .to_string();
+
+        // Snapshots are internal objects, so don't depend on the user's identity config
+        envs.extend(
+            [
+                ("GIT_AUTHOR_NAME", "Vibe Kanban"),
+                ("GIT_AUTHOR_EMAIL", "noreply@vibekanban.com"),
+                ("GIT_COMMITTER_NAME", "Vibe Kanban"),
+                ("GIT_COMMITTER_EMAIL", "noreply@vibekanban.com"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (OsString::from(k), OsString::from(v))),
+        );
+        let snapshot = self
+            .git_with_env(
+                worktree_path,
+                [
+                    "commit-tree",
+                    tree.as_str(),
+                    "-p",
+                    head.as_str(),
+                    "-m",
+                    message,
+                ],
+                &envs,
+            )?
+            .trim()
+            .to_string();
+        Ok((head, snapshot))
+    }
+
+    /// Restore a snapshot taken by [`Self::snapshot_worktree`]: move the checked-out
+    /// branch back to `head`, drop files created since, and lay the snapshot's changes
+    /// back into the working tree as unstaged changes. Ignored files are left alone.
+    pub fn restore_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        head: &str,
+        snapshot: &str,
+    ) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["reset", "--hard", "--quiet", head])?;
+        let clean_args = Self::apply_default_excludes(["clean", "-f", "-d", "--quiet"]);
+        self.git(worktree_path, clean_args)?;
+        self.git(worktree_path, ["read-tree", "-u", "--reset", snapshot])?;
+        self.git(worktree_path, ["reset", "--quiet"])?;
+        Ok(())
+    }
+
+    /// Conclude an in-progress merge using the prepared merge message.
+    pub fn continue_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        if !self.is_merge_in_progress(worktree_path)? {
+            return Err(GitCliError::CommandFailed(
+                "No merge in progress".to_string(),
+            ));
+        }
+        self.git(worktree_path, ["commit", "--no-edit"]).map(|_| ())
+    }
+
     pub fn abort_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
         if !self.is_merge_in_progress(worktree_path)? {
             return Ok(());
@@ -761,6 +1065,25 @@ impl GitCli {
         envs: Option<&[(OsString, OsString)]>,
         stdin: Option<&[u8]>,
     ) -> Result<Vec<u8>, GitCliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let out = self.git_output(repo_path, args, envs, stdin)?;
+        if !out.status.success() {
+            return Err(GitCliError::CommandFailed(failure_output(&out)));
+        }
+        Ok(out.stdout)
+    }
+
+    /// Run git and return its output whether or not it succeeded.
+    fn git_output<I, S>(
+        &self,
+        repo_path: &Path,
+        args: I,
+        envs: Option<&[(OsString, OsString)]>,
+        stdin: Option<&[u8]>,
+    ) -> Result<Output, GitCliError>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
@@ -812,23 +1135,14 @@ impl GitCli {
             .wait_with_output()
             .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
 
-        if !out.status.success() {
-            let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
-            let stdout = String::from_utf8_lossy(&out.stdout).trim().to_string();
-            let combined = match (stdout.is_empty(), stderr.is_empty()) {
-                (true, true) => "Command failed with no output".to_string(),
-                (false, false) => format!("--- stderr\n{stderr}\n--- stdout\n{stdout}"),
-                (false, true) => format!("--- stderr\n{stdout}"),
-                (true, false) => format!("--- stdout\n{stderr}"),
-            };
-            return Err(GitCliError::CommandFailed(combined));
-        }
-        if let Some(Err(e)) = stdin_write_result {
+        if out.status.success()
+            && let Some(Err(e)) = stdin_write_result
+        {
             return Err(GitCliError::CommandFailed(format!(
                 "failed to write to git stdin: {e}"
             )));
         }
-        Ok(out.stdout)
+        Ok(out)
     }
 
     pub fn git<I, S>(&self, repo_path: &Path, args: I) -> Result<String, GitCliError>
@@ -937,3 +1251,34 @@ pub struct WorktreeStatus {
     pub untracked: usize,
     pub entries: Vec<StatusEntry>,
 }
+
+/// stdout and stderr of a failed git command, for error messages.
+fn failure_output(out: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
+    let stdout = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    match (stdout.is_empty(), stderr.is_empty()) {
+        (true, true) => "Command failed with no output".to_string(),
+        (false, false) => format!("--- stderr\n{stderr}\n--- stdout\n{stdout}"),
+        (false, true) => format!("--- stderr\n{stdout}"),
+        (true, false) => format!("--- stdout\n{stderr}"),
+    }
+}
+
+/// Git silently skips hooks that aren't executable, so only count those that would run.
+fn is_executable_file(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
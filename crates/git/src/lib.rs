@@ -14,7 +14,7 @@ mod cli;
 mod validation;
 
 use cli::{ChangeType, StatusDiffEntry, StatusDiffOptions};
-pub use cli::{GitCli, GitCliError, StatusEntry, WorktreeStatus};
+pub use cli::{CommitOptions, GitCli, GitCliError, StatusEntry, WorktreeStatus};
 pub use utils::path::ALWAYS_SKIP_DIRS;
 pub use validation::is_valid_branch_prefix;
 
@@ -158,6 +158,12 @@ pub enum DiffTarget<'p> {
         repo_path: &'p Path,
         commit_sha: &'p str,
     },
+    /// Everything between two commits
+    Range {
+        repo_path: &'p Path,
+        from_commit: &'p str,
+        to_commit: &'p str,
+    },
 }
 
 impl Default for GitService {
@@ -208,6 +214,32 @@ impl GitService {
         }
     }
 
+    /// Signature for libgit2 commits, preferring the identity from `options`.
+    fn signature_for_options<'a>(
+        &self,
+        repo: &'a Repository,
+        options: &CommitOptions,
+    ) -> Result<git2::Signature<'a>, GitServiceError> {
+        match options.identity() {
+            Some((name, email)) => git2::Signature::now(name, email).map_err(GitServiceError::from),
+            None => self.signature_with_fallback(repo),
+        }
+    }
+
+    /// Append a `Signed-off-by` trailer the way `git commit --signoff` would.
+    fn append_signoff(message: &str, signature: &git2::Signature) -> String {
+        let trailer = format!(
+            "Signed-off-by: {} <{}>",
+            signature.name().unwrap_or_default(),
+            signature.email().unwrap_or_default()
+        );
+        let message = message.trim_end();
+        if message.lines().any(|line| line == trailer) {
+            return message.to_string();
+        }
+        format!("{message}\n\n{trailer}")
+    }
+
     fn default_remote(
         &self,
         repo: &Repository,
@@ -302,6 +334,16 @@ impl GitService {
     }
 
     pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        self.commit_with_options(path, message, &CommitOptions::default())
+    }
+
+    /// Stage and commit all changes, applying the given identity/sign-off/signing options.
+    pub fn commit_with_options(
+        &self,
+        path: &Path,
+        message: &str,
+        options: &CommitOptions,
+    ) -> Result<bool, GitServiceError> {
         // Use Git CLI to respect sparse-checkout semantics for staging and commit
         let git = GitCli::new();
         let has_changes = git
@@ -316,11 +358,52 @@ impl GitService {
             .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
         // Only ensure identity once we know we're about to commit
         self.ensure_cli_commit_identity(path)?;
-        git.commit(path, message)
-            .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
+        git.commit_with_options(path, message, options)
+            .map_err(|e| match e {
+                GitCliError::HookFailed(_) => GitServiceError::GitCLI(e),
+                e => GitServiceError::InvalidRepository(format!("git commit failed: {e}")),
+            })?;
         Ok(true)
     }
 
+    /// Collapse every commit on `branch_name` since it forked from `base_branch` into a
+    /// single commit with `message`. Returns the new HEAD sha, or `None` when the branch
+    /// has no commits of its own.
+    pub fn squash_branch_commits(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        base_branch: &str,
+        message: &str,
+        options: &CommitOptions,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        self.check_worktree_clean(&repo)?;
+
+        let git = GitCli::new();
+        if git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
+            return Err(GitServiceError::RebaseInProgress);
+        }
+
+        let base_oid = self.get_fork_point(worktree_path, base_branch, branch_name)?;
+        let head_oid = repo.head()?.peel_to_commit()?.id().to_string();
+        if base_oid == head_oid {
+            return Ok(None);
+        }
+
+        self.ensure_cli_commit_identity(worktree_path)?;
+        git.reset_soft(worktree_path, &base_oid)?;
+        if let Err(e) = git.commit_with_options(worktree_path, message, options) {
+            // Restore the original history rather than leaving the changes staged
+            let _ = git.reset_soft(worktree_path, &head_oid);
+            return Err(GitServiceError::InvalidRepository(format!(
+                "git commit failed: {e}"
+            )));
+        }
+
+        Ok(Some(repo.head()?.peel_to_commit()?.id().to_string()))
+    }
+
     /// Get diffs between branches or worktree changes
     pub fn get_diffs(
         &self,
@@ -437,6 +520,37 @@ impl GitService {
                 let mut find_opts = git2::DiffFindOptions::new();
                 diff.find_similar(Some(&mut find_opts))?;
 
+                self.convert_diff_to_file_diffs(diff, &repo)
+            }
+            DiffTarget::Range {
+                repo_path,
+                from_commit,
+                to_commit,
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let find_tree = |sha: &str| -> Result<git2::Tree<'_>, GitServiceError> {
+                    let oid = git2::Oid::from_str(sha).map_err(|_| {
+                        GitServiceError::InvalidRepository(format!("Invalid commit SHA: {sha}"))
+                    })?;
+                    Ok(repo.find_commit(oid)?.tree()?)
+                };
+                let from_tree = find_tree(from_commit)?;
+                let to_tree = find_tree(to_commit)?;
+
+                let mut diff_opts = DiffOptions::new();
+                diff_opts.include_typechange(true);
+                if let Some(paths) = path_filter {
+                    for path in paths {
+                        diff_opts.pathspec(*path);
+                    }
+                }
+
+                let mut diff =
+                    repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut diff_opts))?;
+
+                let mut find_opts = DiffFindOptions::new();
+                diff.find_similar(Some(&mut find_opts))?;
+
                 self.convert_diff_to_file_diffs(diff, &repo)
             }
         }
@@ -822,6 +936,26 @@ impl GitService {
         task_branch_name: &str,
         base_branch_name: &str,
         commit_message: &str,
+    ) -> Result<String, GitServiceError> {
+        self.merge_changes_with_options(
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            &CommitOptions::default(),
+        )
+    }
+
+    /// Squash-merge a task branch into the base branch using the given commit options.
+    pub fn merge_changes_with_options(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        options: &CommitOptions,
     ) -> Result<String, GitServiceError> {
         // Open the repositories
         let task_repo = self.open_repo(task_worktree_path)?;
@@ -865,6 +999,7 @@ impl GitService {
                         base_branch_name,
                         task_branch_name,
                         commit_message,
+                        options,
                     )
                     .map_err(|e| {
                         GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
@@ -890,14 +1025,13 @@ impl GitService {
                 let task_commit = task_branch.get().peel_to_commit()?;
 
                 // Create the squash commit in-memory (no checkout) and update the base branch ref
-                let signature = self.signature_with_fallback(&task_repo)?;
                 let squash_commit_id = self.perform_squash_merge(
                     &task_repo,
                     &base_commit,
                     &task_commit,
-                    &signature,
                     commit_message,
                     base_branch_name,
+                    options,
                 )?;
 
                 // Update the task branch to the new squash commit so follow-up
@@ -914,6 +1048,61 @@ impl GitService {
             }
         }
     }
+    /// Fast-forward `base_branch_name` to `commit`, provided it still points at
+    /// `expected_base` and `commit` builds on it. Fails with `BranchesDiverged` otherwise,
+    /// leaving the branch untouched so the caller can rebase and try again.
+    pub fn fast_forward_branch(
+        &self,
+        repo_path: &Path,
+        base_branch_name: &str,
+        expected_base: &str,
+        commit: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let expected_oid = git2::Oid::from_str(expected_base)?;
+        let commit_oid = git2::Oid::from_str(commit)?;
+        if commit_oid != expected_oid && !repo.graph_descendant_of(commit_oid, expected_oid)? {
+            return Err(GitServiceError::BranchesDiverged(format!(
+                "Cannot fast-forward '{base_branch_name}': {commit} does not build on {expected_base}"
+            )));
+        }
+
+        let git_cli = GitCli::new();
+        let moved = || {
+            GitServiceError::BranchesDiverged(format!(
+                "Cannot fast-forward '{base_branch_name}': it moved past {expected_base}"
+            ))
+        };
+        match self.find_checkout_path_for_branch(repo_path, base_branch_name)? {
+            Some(base_checkout_path) => {
+                // Checked out somewhere: move the working tree along so it doesn't look dirty
+                if git_cli
+                    .has_staged_changes(&base_checkout_path)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git diff --cached failed: {e}"))
+                    })?
+                {
+                    return Err(GitServiceError::WorktreeDirty(
+                        base_branch_name.to_string(),
+                        "staged changes present".to_string(),
+                    ));
+                }
+                let head = git_cli.git(&base_checkout_path, ["rev-parse", "HEAD"])?;
+                if head.trim() != expected_base {
+                    return Err(moved());
+                }
+                git_cli.merge_ff_only(&base_checkout_path, commit)?;
+            }
+            None => {
+                let refname = format!("refs/heads/{base_branch_name}");
+                git_cli
+                    .update_ref_from(repo_path, &refname, commit, expected_base)
+                    .map_err(|_| moved())?;
+            }
+        }
+        Ok(())
+    }
+
     fn get_branch_status_inner(
         &self,
         repo: &Repository,
@@ -1211,6 +1400,49 @@ impl GitService {
         Ok(())
     }
 
+    /// Snapshot HEAD and all uncommitted changes of a worktree and point `refname` at the
+    /// snapshot so it survives garbage collection. Returns `(head_sha, snapshot_sha)`.
+    pub fn create_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        refname: &str,
+        message: &str,
+    ) -> Result<(String, String), GitServiceError> {
+        let git = GitCli::new();
+        let (head, snapshot) = git
+            .snapshot_worktree(worktree_path, message)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git snapshot failed: {e}")))?;
+        git.update_ref(worktree_path, refname, &snapshot)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        Ok((head, snapshot))
+    }
+
+    /// Put a worktree back into the state captured by [`Self::create_worktree_snapshot`],
+    /// aborting any in-progress rebase or merge first. Discards the current changes.
+    pub fn restore_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        head: &str,
+        snapshot: &str,
+    ) -> Result<(), GitServiceError> {
+        self.abort_conflicts(worktree_path)?;
+        let git = GitCli::new();
+        git.restore_worktree_snapshot(worktree_path, head, snapshot)
+            .map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git snapshot restore failed: {e}"))
+            })?;
+        // Reapply sparse-checkout if configured (non-fatal)
+        let _ = git.git(worktree_path, ["sparse-checkout", "reapply"]);
+        Ok(())
+    }
+
+    pub fn delete_ref(&self, repo_path: &Path, refname: &str) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.delete_ref(repo_path, refname)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        Ok(())
+    }
+
     /// Add a worktree for a branch, optionally creating the branch
     pub fn add_worktree(
         &self,
@@ -1225,6 +1457,19 @@ impl GitService {
         Ok(())
     }
 
+    /// Add a worktree with a detached checkout of a branch
+    pub fn add_detached_worktree(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        branch: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.worktree_add_detached(repo_path, worktree_path, branch)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+        Ok(())
+    }
+
     /// Remove a worktree
     pub fn remove_worktree(
         &self,
@@ -1339,9 +1584,9 @@ impl GitService {
         repo: &Repository,
         base_commit: &git2::Commit,
         task_commit: &git2::Commit,
-        signature: &git2::Signature,
         commit_message: &str,
         base_branch_name: &str,
+        options: &CommitOptions,
     ) -> Result<git2::Oid, GitServiceError> {
         // In-memory merge to detect conflicts without touching the working tree
         let mut merge_opts = git2::MergeOptions::new();
@@ -1363,15 +1608,36 @@ impl GitService {
         let tree_id = index.write_tree_to(repo)?;
         let tree = repo.find_tree(tree_id)?;
 
+        let signature = self.signature_for_options(repo, options)?;
+
         // Create a squash commit: use merged tree with base_commit as sole parent
-        let squash_commit_id = repo.commit(
-            None,           // Don't update any reference yet
-            signature,      // Author
-            signature,      // Committer
-            commit_message, // Custom message
-            &tree,          // Merged tree content
-            &[base_commit], // Single parent: base branch commit
-        )?;
+        let squash_commit_id = if options.gpg_sign {
+            // libgit2 can't sign with the user's gpg setup; let the CLI build the object
+            let repo_path = repo.workdir().unwrap_or(repo.path());
+            self.ensure_cli_commit_identity(repo_path)?;
+            let sha = GitCli::new().commit_tree(
+                repo_path,
+                &tree_id.to_string(),
+                &base_commit.id().to_string(),
+                commit_message,
+                options,
+            )?;
+            git2::Oid::from_str(&sha)?
+        } else {
+            let commit_message = if options.signoff {
+                Self::append_signoff(commit_message, &signature)
+            } else {
+                commit_message.to_string()
+            };
+            repo.commit(
+                None,            // Don't update any reference yet
+                &signature,      // Author
+                &signature,      // Committer
+                &commit_message, // Custom message
+                &tree,           // Merged tree content
+                &[base_commit],  // Single parent: base branch commit
+            )?
+        };
 
         // Update the base branch reference to point to the new commit
         let refname = format!("refs/heads/{base_branch_name}");
@@ -1588,6 +1854,54 @@ impl GitService {
         })
     }
 
+    /// Stage the resolved files and continue the in-progress rebase or merge.
+    pub fn continue_conflict_op(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(());
+        };
+        if !matches!(op, ConflictOp::Rebase | ConflictOp::Merge) {
+            return Err(GitServiceError::InvalidRepository(format!(
+                "Cannot continue {op:?} automatically"
+            )));
+        }
+
+        git.add_all(worktree_path)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        match op {
+            ConflictOp::Rebase => self.continue_rebase(worktree_path),
+            _ => {
+                self.ensure_cli_commit_identity(worktree_path)?;
+                git.continue_merge(worktree_path).map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("git commit failed: {e}"))
+                })
+            }
+        }
+    }
+
+    /// Short hash and subject of the commits on either side of a conflict: the commit
+    /// being built on (`HEAD`) and the one being applied.
+    pub fn get_conflict_commit_subjects(
+        &self,
+        worktree_path: &Path,
+        op: &ConflictOp,
+    ) -> (Option<String>, Option<String>) {
+        let git = GitCli::new();
+        let subject = |rev: &str| {
+            git.git(worktree_path, ["log", "-1", "--format=%h %s", rev])
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let incoming = match op {
+            ConflictOp::Rebase => "REBASE_HEAD",
+            ConflictOp::Merge => "MERGE_HEAD",
+            ConflictOp::CherryPick => "CHERRY_PICK_HEAD",
+            ConflictOp::Revert => "REVERT_HEAD",
+        };
+        (subject("HEAD"), subject(incoming))
+    }
+
     pub fn abort_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
         if git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
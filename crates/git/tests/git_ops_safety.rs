@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use git::{GitCli, GitCliError, GitService};
+use git::{CommitOptions, ConflictOp, GitCli, GitCliError, GitService};
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
@@ -683,6 +683,203 @@ fn libgit2_merge_updates_task_ref_and_feature_head_preserves_dirty() {
     assert_eq!(head.oid, sha);
 }
 
+#[test]
+fn libgit2_merge_applies_commit_options() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+
+    let options = CommitOptions {
+        author_name: Some("Agent Bot".to_string()),
+        author_email: Some("agent@example.com".to_string()),
+        signoff: true,
+        gpg_sign: false,
+        no_verify: false,
+    };
+    let sha = s
+        .merge_changes_with_options(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "feat: squash",
+            &options,
+        )
+        .expect("merge should succeed via libgit2 path");
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let commit = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    assert_eq!(commit.author().name(), Some("Agent Bot"));
+    assert_eq!(commit.committer().email(), Some("agent@example.com"));
+    assert_eq!(
+        commit.message(),
+        Some("feat: squash\n\nSigned-off-by: Agent Bot <agent@example.com>")
+    );
+}
+
+#[test]
+fn commit_tree_adds_signoff_trailer() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, _worktree_path) = setup_repo_with_worktree(&td);
+    let git = GitCli::new();
+
+    let tree = git.git(&repo_path, ["rev-parse", "main^{tree}"]).unwrap();
+    let parent = git.git(&repo_path, ["rev-parse", "main"]).unwrap();
+    let options = CommitOptions {
+        author_name: Some("Agent Bot".to_string()),
+        author_email: Some("agent@example.com".to_string()),
+        signoff: true,
+        ..Default::default()
+    };
+    let sha = git
+        .commit_tree(
+            &repo_path,
+            tree.trim(),
+            parent.trim(),
+            "feat: squash",
+            &options,
+        )
+        .unwrap();
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let commit = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    assert_eq!(
+        commit.message(),
+        Some("feat: squash\n\nSigned-off-by: Agent Bot <agent@example.com>\n")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn failing_pre_commit_hook_is_reported_and_can_be_skipped() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+
+    let hooks_dir = td.path().join("hooks");
+    std::fs::create_dir_all(&hooks_dir).unwrap();
+    let hook = hooks_dir.join("pre-commit");
+    std::fs::write(
+        &hook,
+        "#!/bin/sh\necho 'lint: missing semicolon' >&2\nexit 1\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    wt_repo
+        .config()
+        .unwrap()
+        .set_str("core.hooksPath", hooks_dir.to_str().unwrap())
+        .unwrap();
+
+    write_file(&worktree_path, "hooked.txt", "change\n");
+    match s.commit(&worktree_path, "blocked by hook") {
+        Err(git::GitServiceError::GitCLI(GitCliError::HookFailed(output))) => {
+            assert!(output.contains("missing semicolon"), "{output}");
+        }
+        other => panic!("expected hook failure, got {other:?}"),
+    }
+
+    let options = CommitOptions {
+        no_verify: true,
+        ..Default::default()
+    };
+    assert!(
+        s.commit_with_options(&worktree_path, "skip hooks", &options)
+            .unwrap()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn signing_failure_with_hooks_installed_is_not_a_hook_failure() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+
+    let hooks_dir = td.path().join("hooks");
+    std::fs::create_dir_all(&hooks_dir).unwrap();
+    let hook = hooks_dir.join("pre-commit");
+    std::fs::write(&hook, "#!/bin/sh\nexit 0\n").unwrap();
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    let mut cfg = wt_repo.config().unwrap();
+    cfg.set_str("core.hooksPath", hooks_dir.to_str().unwrap())
+        .unwrap();
+    cfg.set_str("gpg.program", "false").unwrap();
+
+    write_file(&worktree_path, "signed.txt", "change\n");
+    let options = CommitOptions {
+        gpg_sign: true,
+        ..Default::default()
+    };
+    match s.commit_with_options(&worktree_path, "needs a signature", &options) {
+        Err(git::GitServiceError::InvalidRepository(msg)) => {
+            assert!(msg.contains("gpg"), "{msg}");
+        }
+        other => panic!("expected a plain commit failure, got {other:?}"),
+    }
+}
+
+#[test]
+fn squash_branch_commits_collapses_feature_history() {
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+
+    write_file(&worktree_path, "feat2.txt", "second change\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    commit_all(&wt_repo, "second feature commit");
+    let fork_point = s.get_branch_oid(&worktree_path, "old-base").unwrap();
+
+    let sha = s
+        .squash_branch_commits(
+            &worktree_path,
+            "feature",
+            "old-base",
+            "feat: add feature files",
+            &CommitOptions::default(),
+        )
+        .expect("squash should succeed")
+        .expect("feature has commits to squash");
+
+    let head = wt_repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.id().to_string(), sha);
+    assert_eq!(head.summary(), Some("feat: add feature files"));
+    assert_eq!(head.parent_count(), 1);
+    assert_eq!(head.parent_id(0).unwrap().to_string(), fork_point);
+    assert!(worktree_path.join("feat.txt").exists());
+    assert!(worktree_path.join("feat2.txt").exists());
+
+    // Nothing left to squash once the branch sits on its fork point
+    wt_repo
+        .reset(
+            &wt_repo.revparse_single(&fork_point).unwrap(),
+            git2::ResetType::Hard,
+            None,
+        )
+        .unwrap();
+    assert!(
+        s.squash_branch_commits(
+            &worktree_path,
+            "feature",
+            "old-base",
+            "unused",
+            &CommitOptions::default(),
+        )
+        .unwrap()
+        .is_none()
+    );
+}
+
 #[test]
 fn rebase_refuses_to_abort_existing_rebase() {
     let td = TempDir::new().unwrap();
@@ -713,6 +910,39 @@ fn rebase_refuses_to_abort_existing_rebase() {
     // Note: We do not auto-abort; user should resolve or abort explicitly
 }
 
+#[test]
+fn continue_conflict_op_finishes_resolved_rebase() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+
+    let svc = GitService::new();
+    let _ = svc
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+        )
+        .expect_err("rebase should stop on conflicts");
+    assert_eq!(
+        svc.detect_conflict_op(&worktree_path).unwrap(),
+        Some(ConflictOp::Rebase)
+    );
+
+    let (ours, theirs) = svc.get_conflict_commit_subjects(&worktree_path, &ConflictOp::Rebase);
+    assert!(ours.unwrap().ends_with("new-base change"));
+    assert!(theirs.unwrap().ends_with("feature conflicting change"));
+
+    write_file(&worktree_path, "conflict.txt", "resolved version\n");
+    svc.continue_conflict_op(&worktree_path)
+        .expect("continue after resolving");
+
+    assert_eq!(svc.detect_conflict_op(&worktree_path).unwrap(), None);
+    let content = fs::read_to_string(worktree_path.join("conflict.txt")).unwrap();
+    assert_eq!(content, "resolved version\n");
+}
+
 #[test]
 fn rebase_fast_forwards_when_no_unique_commits() {
     let td = TempDir::new().unwrap();
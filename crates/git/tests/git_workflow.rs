@@ -256,6 +256,93 @@ fn commit_and_is_worktree_clean() {
     );
 }
 
+#[test]
+fn worktree_snapshot_restores_head_and_uncommitted_changes() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "tracked.txt", "v1\n");
+    write_file(&repo_path, "removed.txt", "bye\n");
+    let s = GitService::new();
+    s.commit(&repo_path, "base").unwrap();
+
+    write_file(&repo_path, "tracked.txt", "v2\n");
+    fs::remove_file(repo_path.join("removed.txt")).unwrap();
+    write_file(&repo_path, "untracked.txt", "new\n");
+    let refname = "refs/vibe-kanban/checkpoints/test";
+    let (head, snapshot) = s
+        .create_worktree_snapshot(&repo_path, refname, "checkpoint")
+        .unwrap();
+    assert_eq!(head, s.get_head_info(&repo_path).unwrap().oid);
+
+    // Commit on top and leave more changes behind
+    s.commit(&repo_path, "later").unwrap();
+    write_file(&repo_path, "tracked.txt", "v3\n");
+    write_file(&repo_path, "after.txt", "after\n");
+
+    s.restore_worktree_snapshot(&repo_path, &head, &snapshot)
+        .unwrap();
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, head);
+    assert_eq!(
+        fs::read_to_string(repo_path.join("tracked.txt")).unwrap(),
+        "v2\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("untracked.txt")).unwrap(),
+        "new\n"
+    );
+    assert!(!repo_path.join("removed.txt").exists());
+    assert!(!repo_path.join("after.txt").exists());
+    // Restored changes are left unstaged
+    assert_eq!(s.get_worktree_change_counts(&repo_path).unwrap(), (2, 1));
+
+    s.delete_ref(&repo_path, refname).unwrap();
+    let git = GitCli::new();
+    assert!(
+        git.git(&repo_path, ["rev-parse", "--verify", refname])
+            .is_err()
+    );
+}
+
+#[test]
+fn range_diff_covers_all_commits_between() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "keep.txt", "keep\n");
+    write_file(&repo_path, "gone.txt", "gone\n");
+    s.commit(&repo_path, "base").unwrap();
+    let from = s.get_head_info(&repo_path).unwrap().oid;
+
+    write_file(&repo_path, "new.txt", "one\n");
+    s.commit(&repo_path, "first").unwrap();
+    std::fs::remove_file(repo_path.join("gone.txt")).unwrap();
+    write_file(&repo_path, "new.txt", "two\n");
+    s.commit(&repo_path, "second").unwrap();
+    let to = s.get_head_info(&repo_path).unwrap().oid;
+
+    let diffs = s
+        .get_diffs(
+            DiffTarget::Range {
+                repo_path: Path::new(&repo_path),
+                from_commit: &from,
+                to_commit: &to,
+            },
+            None,
+        )
+        .unwrap();
+    assert_eq!(diffs.len(), 2);
+    let added = diffs
+        .iter()
+        .find(|d| d.new_path.as_deref() == Some("new.txt"))
+        .unwrap();
+    assert_eq!(added.new_content.as_deref(), Some("two\n"));
+    assert!(
+        diffs
+            .iter()
+            .any(|d| d.old_path.as_deref() == Some("gone.txt") && d.new_path.is_none())
+    );
+}
+
 #[test]
 fn commit_in_detached_head_succeeds_via_service() {
     let td = TempDir::new().unwrap();
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io,
     path::{Path, PathBuf},
     sync::Arc,
@@ -13,16 +13,23 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::CodingAgentTurn,
+        dev_server::{CreateDevServer, DevServer, DevServerStatus},
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
+        execution_process_logs::ExecutionProcessLogs,
         execution_process_repo_state::ExecutionProcessRepoState,
+        project_redaction_settings::ProjectRedactionSettings,
         repo::Repo,
-        scratch::{DraftFollowUpData, Scratch, ScratchType},
+        repo_commit_policy::{CommitHookMode, CommitMessageStyle, RepoCommitPolicy},
+        repo_dev_process::RepoDevProcess,
+        repo_dev_server_settings::RepoDevServerSettings,
+        scratch::DraftFollowUpData,
         session::{Session, SessionError},
         task::{Task, TaskStatus},
         workspace::Workspace,
-        workspace_repo::WorkspaceRepo,
+        workspace_port::WorkspacePort,
+        workspace_repo::{RepoWithCopyFiles, WorkspaceRepo},
     },
 };
 use deployment::DeploymentError;
@@ -34,23 +41,34 @@ use executors::{
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::{ExecutionEnv, RepoContext},
-    executors::{BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal},
+    executors::{
+        BaseCodingAgent, CancellationToken, CodingAgent, ExecutorError, ExecutorExitResult,
+        ExecutorExitSignal, StandardCodingAgentExecutor,
+    },
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    profile::ExecutorConfig,
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
-use git::GitService;
+use git::{GitCliError, GitService, GitServiceError};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
+    commit_policy,
     config::{Config, DEFAULT_COMMIT_REMINDER_PROMPT},
+    conflict_resolution::{ConflictResolution, ConflictResolutions, ResolutionStep},
     container::{ContainerError, ContainerRef, ContainerService},
+    context_pack::ContextPackCache,
+    conversation_search,
+    dev_server::{self, DevServerExit},
     diff_stream::{self, DiffStreamHandle},
     image::ImageService,
     notification::NotificationService,
     queued_message::QueuedMessageService,
     remote_client::RemoteClient,
     remote_sync,
+    secrets::SecretService,
+    symbol_index::SymbolIndexCache,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
@@ -58,6 +76,7 @@ use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
+    redact::{self, Redactor},
     text::{git_branch_id, short_uuid, truncate_to_char_boundary},
 };
 use uuid::Uuid;
@@ -65,18 +84,32 @@ use uuid::Uuid;
 use crate::{command, copy};
 
 const WORKSPACE_TOUCH_DEBOUNCE: Duration = Duration::from_mins(2);
+/// Follow-ups sent to the agent for failing commit hooks before giving up on a session
+const MAX_HOOK_FEEDBACK_ROUNDS: u32 = 3;
+/// Hook output forwarded to the agent, per repo
+const MAX_HOOK_OUTPUT_BYTES: usize = 8 * 1024;
+/// How long an interrupted execution waits for the next user message before it is stopped
+const INTERRUPTED_TURN_TIMEOUT: Duration = Duration::from_mins(10);
 
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
     cancellation_tokens: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
+    /// Executors of running executions, which receive user messages sent mid-turn
+    running_executors: Arc<RwLock<HashMap<Uuid, Arc<CodingAgent>>>>,
+    /// Interrupted executions waiting for the next user message, by interrupt time
+    interrupted_executions: Arc<RwLock<HashMap<Uuid, Instant>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
+    log_redactors: Arc<RwLock<HashMap<Uuid, Arc<Redactor>>>>,
     /// Tracks background tasks that stream logs to the database.
     /// When stopping execution, we await these to ensure logs are fully persisted.
     db_stream_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
     exit_monitor_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
     workspace_touch_times: Arc<RwLock<HashMap<Uuid, Instant>>>,
+    /// Consecutive hook-failure follow-ups per session, to stop an agent that can't satisfy the hooks
+    hook_feedback_rounds: Arc<RwLock<HashMap<Uuid, u32>>>,
+    conflict_resolutions: ConflictResolutions,
     config: Arc<RwLock<Config>>,
     git: GitService,
     image_service: ImageService,
@@ -85,6 +118,9 @@ pub struct LocalContainerService {
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
     remote_client: Option<RemoteClient>,
+    symbol_index_cache: Arc<SymbolIndexCache>,
+    context_pack_cache: Arc<ContextPackCache>,
+    secrets: SecretService,
 }
 
 impl LocalContainerService {
@@ -99,22 +135,35 @@ impl LocalContainerService {
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
         remote_client: Option<RemoteClient>,
+        symbol_index_cache: Arc<SymbolIndexCache>,
+        context_pack_cache: Arc<ContextPackCache>,
+        secrets: SecretService,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let cancellation_tokens = Arc::new(RwLock::new(HashMap::new()));
+        let running_executors = Arc::new(RwLock::new(HashMap::new()));
+        let interrupted_executions = Arc::new(RwLock::new(HashMap::new()));
+        let log_redactors = Arc::new(RwLock::new(HashMap::new()));
         let db_stream_handles = Arc::new(RwLock::new(HashMap::new()));
         let exit_monitor_handles = Arc::new(RwLock::new(HashMap::new()));
         let workspace_touch_times = Arc::new(RwLock::new(HashMap::new()));
+        let hook_feedback_rounds = Arc::new(RwLock::new(HashMap::new()));
+        let conflict_resolutions = ConflictResolutions::default();
         let notification_service = NotificationService::new(config.clone());
 
         let container = LocalContainerService {
             db,
             child_store,
             cancellation_tokens,
+            running_executors,
+            interrupted_executions,
             msg_stores,
+            log_redactors,
             db_stream_handles,
             exit_monitor_handles,
             workspace_touch_times,
+            hook_feedback_rounds,
+            conflict_resolutions,
             config,
             git,
             image_service,
@@ -123,6 +172,9 @@ impl LocalContainerService {
             queued_message_service,
             notification_service,
             remote_client,
+            symbol_index_cache,
+            context_pack_cache,
+            secrets,
         };
 
         container.spawn_workspace_cleanup();
@@ -155,6 +207,51 @@ impl LocalContainerService {
         map.remove(id)
     }
 
+    async fn add_running_executor(&self, id: Uuid, executor: Arc<CodingAgent>) {
+        let mut map = self.running_executors.write().await;
+        map.insert(id, executor);
+    }
+
+    async fn running_executor(&self, id: &Uuid) -> Result<Arc<CodingAgent>, ContainerError> {
+        let map = self.running_executors.read().await;
+        map.get(id).cloned().ok_or_else(|| {
+            ExecutorError::InputUnavailable("execution is not running".to_string()).into()
+        })
+    }
+
+    /// Stop an interrupted execution that received no message within
+    /// [`INTERRUPTED_TURN_TIMEOUT`], so it ends as stopped and pauses the follow-up queue.
+    async fn stop_idle_interrupted_execution(&self, id: Uuid, interrupted_at: Instant) {
+        {
+            let mut map = self.interrupted_executions.write().await;
+            if map.get(&id) != Some(&interrupted_at) {
+                return;
+            }
+            map.remove(&id);
+        }
+        let execution_process = match ExecutionProcess::find_by_id(&self.db.pool, id).await {
+            Ok(Some(process)) if process.status == ExecutionProcessStatus::Running => process,
+            Ok(_) => return,
+            Err(e) => {
+                tracing::error!("Failed to load interrupted execution {}: {}", id, e);
+                return;
+            }
+        };
+        tracing::info!("Stopping execution {} after an unanswered interrupt", id);
+        if let Err(e) = self
+            .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
+            .await
+        {
+            tracing::error!("Failed to stop interrupted execution {}: {}", id, e);
+        }
+    }
+
+    /// Drop the executor of an execution that is shutting down.
+    async fn remove_input_channels(&self, id: &Uuid) {
+        self.running_executors.write().await.remove(id);
+        self.interrupted_executions.write().await.remove(id);
+    }
+
     async fn add_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
         let mut map = self.db_stream_handles.write().await;
         map.insert(id, handle);
@@ -207,6 +304,14 @@ impl LocalContainerService {
                 });
         }
 
+        if let Err(e) = WorkspacePort::delete_by_workspace_id(&db.pool, workspace.id).await {
+            tracing::warn!(
+                "Failed to release ports for workspace {}: {}",
+                workspace.id,
+                e
+            );
+        }
+
         // Clear container_ref so this workspace won't be picked up again
         let _ = Workspace::clear_container_ref(&db.pool, workspace.id).await;
     }
@@ -380,32 +485,556 @@ impl LocalContainerService {
         Ok(false)
     }
 
-    /// Commit changes to each repo. Logs failures but continues with other repos.
-    fn commit_repos(&self, repos_with_changes: Vec<(Repo, PathBuf)>, message: &str) -> bool {
-        let mut any_committed = false;
+    /// Commit changes to each repo following its commit policy. Logs failures but
+    /// continues with other repos.
+    fn commit_repos(
+        &self,
+        repos_with_changes: Vec<(Repo, PathBuf, RepoCommitPolicy)>,
+        message: &str,
+    ) -> CommitOutcome {
+        let mut outcome = CommitOutcome::default();
 
-        for (repo, worktree_path) in repos_with_changes {
+        for (repo, worktree_path, policy) in repos_with_changes {
             tracing::debug!(
                 "Committing changes for repo '{}' at {:?}",
                 repo.name,
                 &worktree_path
             );
 
-            match self.git().commit(&worktree_path, message) {
+            let message = match policy.message_style {
+                CommitMessageStyle::AgentSummary => message.to_string(),
+                CommitMessageStyle::Conventional => commit_policy::to_conventional(message),
+            };
+            let options = commit_policy::commit_options(&policy);
+            match self
+                .git()
+                .commit_with_options(&worktree_path, &message, &options)
+            {
                 Ok(true) => {
-                    any_committed = true;
+                    outcome.committed = true;
                     tracing::info!("Committed changes in repo '{}'", repo.name);
                 }
                 Ok(false) => {
                     tracing::warn!("No changes committed in repo '{}' (unexpected)", repo.name);
                 }
+                Err(GitServiceError::GitCLI(GitCliError::HookFailed(output))) => {
+                    tracing::warn!(
+                        "Commit hooks failed in repo '{}', changes left uncommitted",
+                        repo.name
+                    );
+                    if policy.hook_mode == CommitHookMode::Feedback {
+                        outcome.hook_failures.push((repo.name.clone(), output));
+                    }
+                }
                 Err(e) => {
                     tracing::warn!("Failed to commit in repo '{}': {}", repo.name, e);
                 }
             }
         }
 
-        any_committed
+        outcome
+    }
+
+    /// Commit the changes left by an execution, reporting hook failures that should be
+    /// sent back to the agent.
+    async fn commit_execution_changes(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<CommitOutcome, ContainerError> {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CleanupScript,
+        ) {
+            return Ok(CommitOutcome::default());
+        }
+
+        let message = self.get_commit_message(ctx).await;
+
+        let container_ref = ctx
+            .workspace
+            .container_ref
+            .as_ref()
+            .ok_or_else(|| ContainerError::Other(anyhow!("Container reference not found")))?;
+        let workspace_root = PathBuf::from(container_ref);
+
+        // Committing now would record the conflict resolution as a regular commit in the
+        // middle of the operation
+        for repo in &ctx.repos {
+            if let Some(op) = self
+                .git
+                .detect_conflict_op(&workspace_root.join(&repo.name))?
+            {
+                tracing::info!(
+                    "Skipping commit in repo '{}': {:?} in progress",
+                    repo.name,
+                    op
+                );
+                return Ok(CommitOutcome::default());
+            }
+        }
+
+        let repos_with_changes = self.check_repos_for_changes(&workspace_root, &ctx.repos)?;
+        if repos_with_changes.is_empty() {
+            tracing::debug!("No changes to commit in any repository");
+            return Ok(CommitOutcome::default());
+        }
+
+        let mut repos_with_policies = Vec::with_capacity(repos_with_changes.len());
+        for (repo, worktree_path) in repos_with_changes {
+            let policy = RepoCommitPolicy::find_or_default(&self.db.pool, repo.id).await?;
+            repos_with_policies.push((repo, worktree_path, policy));
+        }
+
+        let mut outcome = self.commit_repos(repos_with_policies, &message);
+        // Only the coding agent can act on the feedback
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) {
+            outcome.hook_failures.clear();
+        }
+        Ok(outcome)
+    }
+
+    /// Ask the agent to fix failing commit hooks in a follow-up turn. Returns false when
+    /// the session has already used up its retries, in which case the changes stay
+    /// uncommitted for the user to look at.
+    async fn start_hook_feedback_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+        hook_failures: &[(String, String)],
+    ) -> Result<bool, ContainerError> {
+        {
+            let mut rounds = self.hook_feedback_rounds.write().await;
+            let round = rounds.entry(ctx.session.id).or_insert(0);
+            if *round < MAX_HOOK_FEEDBACK_ROUNDS {
+                *round += 1;
+            } else {
+                rounds.remove(&ctx.session.id);
+                drop(rounds);
+                tracing::warn!(
+                    "Commit hooks still failing for session {} after {} follow-ups, giving up",
+                    ctx.session.id,
+                    MAX_HOOK_FEEDBACK_ROUNDS
+                );
+                let repos = hook_failures
+                    .iter()
+                    .map(|(repo, _)| repo.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.report_to_user(
+                    ctx.execution_process.id,
+                    &format!(
+                        "Commit hooks still fail in {repos} after {MAX_HOOK_FEEDBACK_ROUNDS} \
+                         attempts to fix them; the changes were left uncommitted."
+                    ),
+                )
+                .await;
+                return Ok(false);
+            }
+        }
+
+        let Some(executor_profile_id) =
+            ExecutionProcess::latest_executor_profile_for_session(&self.db.pool, ctx.session.id)
+                .await?
+        else {
+            return Ok(false);
+        };
+
+        let data = DraftFollowUpData {
+            message: build_hook_feedback_prompt(hook_failures),
+            executor_config: ExecutorConfig::from(executor_profile_id),
+        };
+        self.start_queued_follow_up(ctx, &data).await?;
+        Ok(true)
+    }
+
+    async fn reset_hook_feedback_rounds(&self, session_id: Uuid) {
+        self.hook_feedback_rounds.write().await.remove(&session_id);
+    }
+
+    /// Show a message in the logs of a finished execution, where the user sees it in the
+    /// conversation. Stderr is normalized into an error entry and persisted with the rest
+    /// of the output.
+    async fn report_to_user(&self, exec_id: Uuid, message: &str) {
+        let log_message = LogMsg::Stderr(format!("{message}\n"));
+        if let Some(msg_store) = self.get_msg_store_by_id(&exec_id).await {
+            msg_store.push(log_message);
+            return;
+        }
+        if let Ok(json_line) = serde_json::to_string(&log_message)
+            && let Err(e) = ExecutionProcessLogs::append_log_line(
+                &self.db.pool,
+                exec_id,
+                &format!("{json_line}\n"),
+            )
+            .await
+        {
+            tracing::error!("Failed to append log line for execution {}: {}", exec_id, e);
+        }
+    }
+
+    /// Continue the conflict resolution whose round just finished, starting another round
+    /// when the operation stops on new conflicts. Returns whether a round was started.
+    async fn continue_conflict_resolution(
+        &self,
+        ctx: &ExecutionContext,
+        resolution: ConflictResolution,
+    ) -> bool {
+        let exec_id = ctx.execution_process.id;
+        match resolution.continue_after_round(&self.git, &ctx.execution_process.status) {
+            ResolutionStep::Resolved => {
+                tracing::info!(
+                    "Agent resolved conflicts in workspace {} after {} round(s)",
+                    ctx.workspace.id,
+                    resolution.round
+                );
+                if self.config.read().await.analytics_enabled
+                    && let Some(analytics) = &self.analytics
+                {
+                    analytics.analytics_service.track_event(
+                        &analytics.user_id,
+                        "conflicts_resolved_by_agent",
+                        Some(json!({
+                            "workspace_id": ctx.workspace.id.to_string(),
+                            "repo_id": resolution.repo.id.to_string(),
+                            "rounds": resolution.round,
+                        })),
+                    );
+                }
+                false
+            }
+            ResolutionStep::Stopped(reason) => {
+                tracing::info!(
+                    "Conflict resolution for workspace {} stopped: {}",
+                    ctx.workspace.id,
+                    reason
+                );
+                self.report_to_user(exec_id, &reason).await;
+                false
+            }
+            ResolutionStep::NextRound => {
+                let round = resolution.round + 1;
+                let next = ConflictResolution {
+                    round,
+                    ..resolution
+                };
+                match self.start_conflict_resolution_round(next).await {
+                    Ok(_) => true,
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to start conflict resolution round {} for workspace {}: {}",
+                            round,
+                            ctx.workspace.id,
+                            e
+                        );
+                        self.report_to_user(
+                            exec_id,
+                            &format!("Failed to start conflict resolution round {round}: {e}"),
+                        )
+                        .await;
+                        false
+                    }
+                }
+            }
+        }
+    }
+
+    /// Pick the port for a dev server script about to start, keeping the port its dev
+    /// server had before when it's still free.
+    async fn assign_dev_server_port(
+        &self,
+        workspace: &Workspace,
+        execution_process_id: Uuid,
+        executor_action: &ExecutorAction,
+        repos: &[Repo],
+    ) -> Result<Option<CreateDevServer>, ContainerError> {
+        let ExecutorActionType::ScriptRequest(script) = executor_action.typ() else {
+            return Ok(None);
+        };
+        let Some(repo) = repos
+            .iter()
+            .find(|repo| script.working_dir.as_deref() == Some(repo.name.as_str()))
+        else {
+            return Ok(None);
+        };
+
+        let pool = &self.db.pool;
+        let process_name = script.dev_process.clone();
+        let previous_port =
+            DevServer::find_by_process(pool, workspace.id, repo.id, process_name.as_deref())
+                .await?
+                .and_then(|dev_server| u16::try_from(dev_server.port).ok());
+        // Before its first start, the dev server takes the port its templated files use
+        let template_port =
+            WorkspacePort::find_port(pool, workspace.id, repo.id, process_name.as_deref())
+                .await?
+                .and_then(|port| u16::try_from(port).ok());
+        let port = dev_server::allocate_port(
+            previous_port.or(template_port),
+            &self.reserved_ports().await?,
+        )?;
+        if let Some(template_port) = template_port
+            && previous_port.is_none()
+            && port != template_port
+        {
+            tracing::warn!(
+                "Port {} used by the templated files of repo '{}' is taken, the dev server gets {}",
+                template_port,
+                repo.name,
+                port
+            );
+        }
+        Ok(Some(CreateDevServer {
+            workspace_id: workspace.id,
+            repo_id: repo.id,
+            process_name,
+            execution_process_id,
+            port: port.into(),
+        }))
+    }
+
+    /// Ports kept out of the way of new allocations: those of dev servers that may come
+    /// back and those already written into templated files.
+    async fn reserved_ports(&self) -> Result<HashSet<u16>, ContainerError> {
+        let pool = &self.db.pool;
+        let mut ports = DevServer::reserved_ports(pool).await?;
+        ports.extend(WorkspacePort::reserved_ports(pool).await?);
+        Ok(ports
+            .into_iter()
+            .filter_map(|port| u16::try_from(port).ok())
+            .collect())
+    }
+
+    /// Port for a `{{port}}` placeholder: the port the dev server has, or one handed out
+    /// now and kept for when it starts.
+    async fn template_port(
+        &self,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        process_name: Option<&str>,
+    ) -> Result<u16, ContainerError> {
+        let pool = &self.db.pool;
+        let existing =
+            match DevServer::find_by_process(pool, workspace_id, repo_id, process_name).await? {
+                Some(dev_server) => Some(dev_server.port),
+                None => WorkspacePort::find_port(pool, workspace_id, repo_id, process_name).await?,
+            };
+        if let Some(port) = existing.and_then(|port| u16::try_from(port).ok()) {
+            return Ok(port);
+        }
+
+        let port = dev_server::allocate_port(None, &self.reserved_ports().await?)?;
+        WorkspacePort::create(pool, workspace_id, repo_id, process_name, port.into()).await?;
+        Ok(port)
+    }
+
+    /// Environment for one of a repo's dev processes: the ports of its running siblings,
+    /// then the variables configured for the process.
+    async fn dev_process_env(
+        &self,
+        dev_server: &CreateDevServer,
+    ) -> Result<HashMap<String, String>, ContainerError> {
+        let Some(process_name) = dev_server.process_name.as_deref() else {
+            return Ok(HashMap::new());
+        };
+        let pool = &self.db.pool;
+        let mut env: HashMap<String, String> =
+            DevServer::find_by_workspace_id(pool, dev_server.workspace_id)
+                .await?
+                .into_iter()
+                .filter(|sibling| {
+                    sibling.repo_id == dev_server.repo_id
+                        && sibling.status != DevServerStatus::Stopped
+                })
+                .filter_map(|sibling| {
+                    let name = sibling.process_name?;
+                    (name != process_name)
+                        .then(|| (dev_server::port_env_var(&name), sibling.port.to_string()))
+                })
+                .collect();
+        if let Some(process) =
+            RepoDevProcess::find_by_name(pool, dev_server.repo_id, process_name).await?
+        {
+            env.extend(process.env.0);
+        }
+        Ok(env)
+    }
+
+    /// Decrypted secrets for a process of `workspace`. Failures are only logged, as the
+    /// process can still run without them.
+    async fn secret_env(
+        &self,
+        workspace: &Workspace,
+        project_id: Option<Uuid>,
+        repos: &[Repo],
+        executor_action: &ExecutorAction,
+    ) -> HashMap<String, String> {
+        let repo_ids: Vec<Uuid> = repos.iter().map(|repo| repo.id).collect();
+        match self
+            .secrets
+            .env_for(project_id, &repo_ids, executor_action.base_executor())
+            .await
+        {
+            Ok(env) => env,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load secrets for workspace {}: {}",
+                    workspace.id,
+                    e
+                );
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Masks the secrets of a process, the values of its sensitive-looking variables and
+    /// the credentials it inherits from the server, except what the project allowlists.
+    async fn log_redactor(
+        &self,
+        project_id: Option<Uuid>,
+        secrets: &HashMap<String, String>,
+        env: &ExecutionEnv,
+    ) -> Redactor {
+        let allowlist = match project_id {
+            Some(project_id) => {
+                match ProjectRedactionSettings::find_by_project_id(&self.db.pool, project_id).await
+                {
+                    Ok(settings) => settings.map(|settings| settings.allowlist.0),
+                    Err(e) => {
+                        tracing::error!("Failed to load redaction allowlist: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        // Server variables are often settings with credential-like names, so only those
+        // whose values look like credentials are masked wherever they appear
+        let mut values = redact::inherited_secrets(
+            std::env::vars_os()
+                .filter_map(|(name, value)| name.into_string().ok().zip(value.into_string().ok())),
+        );
+        values.extend(
+            env.vars
+                .iter()
+                .filter(|(name, _)| redact::is_sensitive_name(name))
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+        values.extend(secrets.clone());
+        Redactor::new(&values, &allowlist.unwrap_or_default())
+    }
+
+    /// Record a started dev server and mark it ready once it answers, as configured for
+    /// its repo.
+    async fn track_dev_server(&self, data: &CreateDevServer) -> Result<(), ContainerError> {
+        let pool = self.db.pool.clone();
+        DevServer::upsert(&pool, data).await?;
+
+        let execution_process_id = data.execution_process_id;
+        let Some(logs) = self.get_msg_store_by_id(&execution_process_id).await else {
+            return Ok(());
+        };
+        let Ok(port) = u16::try_from(data.port) else {
+            return Ok(());
+        };
+        let settings = RepoDevServerSettings::find_or_default(&pool, data.repo_id).await?;
+        tokio::spawn(async move {
+            if dev_server::wait_until_ready(port, &settings, logs).await
+                && let Err(e) = DevServer::mark_ready(&pool, execution_process_id).await
+            {
+                tracing::error!("Failed to mark dev server ready: {}", e);
+            }
+        });
+        Ok(())
+    }
+
+    /// Record how a dev server ended, and restart it with backoff when it crashed.
+    async fn handle_dev_server_exit(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        let Some(dev_server) =
+            DevServer::find_by_execution_process_id(pool, ctx.execution_process.id).await?
+        else {
+            return Ok(());
+        };
+
+        let settings = RepoDevServerSettings::find_or_default(pool, dev_server.repo_id).await?;
+        let exit = dev_server::on_exit(
+            ctx.execution_process.status == ExecutionProcessStatus::Failed,
+            dev_server.restart_count,
+            &settings,
+            ctx.workspace.archived,
+        );
+        DevServer::update_status(
+            pool,
+            dev_server.execution_process_id,
+            match exit {
+                DevServerExit::Stopped => DevServerStatus::Stopped,
+                DevServerExit::Crashed | DevServerExit::GaveUp | DevServerExit::Restart(_) => {
+                    DevServerStatus::Crashed
+                }
+            },
+        )
+        .await?;
+        let delay = match exit {
+            DevServerExit::Restart(delay) => delay,
+            DevServerExit::GaveUp => {
+                tracing::warn!(
+                    "Dev server for workspace {} crashed {} times, not restarting it",
+                    ctx.workspace.id,
+                    dev_server.restart_count + 1
+                );
+                return Ok(());
+            }
+            DevServerExit::Stopped | DevServerExit::Crashed => return Ok(()),
+        };
+
+        let action = ctx
+            .execution_process
+            .executor_action()
+            .map_err(ContainerError::Other)?
+            .clone();
+        let container = self.clone();
+        let workspace = ctx.workspace.clone();
+        let session = ctx.session.clone();
+        tracing::info!(
+            "Dev server for workspace {} crashed, restarting in {:?}",
+            workspace.id,
+            delay
+        );
+
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let pool = &container.db.pool;
+            // Skip when the user restarted or stopped the dev server in the meantime
+            match DevServer::find_by_id(pool, dev_server.id).await {
+                Ok(Some(current))
+                    if current.execution_process_id == dev_server.execution_process_id
+                        && current.status == DevServerStatus::Crashed => {}
+                _ => return,
+            }
+            if let Err(e) = DevServer::increment_restart_count(pool, dev_server.id).await {
+                tracing::error!("Failed to count dev server restart: {}", e);
+                return;
+            }
+            if let Err(e) = container
+                .start_execution(
+                    &workspace,
+                    &session,
+                    &action,
+                    &ExecutionProcessRunReason::DevServer,
+                )
+                .await
+            {
+                tracing::error!(
+                    "Failed to restart dev server for workspace {}: {}",
+                    workspace.id,
+                    e
+                );
+            }
+        });
+        Ok(())
     }
 
     /// Spawn a background task that polls the child process for completion and
@@ -480,11 +1109,23 @@ impl LocalContainerService {
             }
 
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::DevServer
+                ) && let Err(e) = container.handle_dev_server_exit(&ctx).await
+                {
+                    tracing::error!("Failed to handle dev server exit: {}", e);
+                }
+
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
+                if let Err(e) = container.index_conversation(&exec_id).await {
+                    tracing::warn!("Failed to index conversation for search: {}", e);
+                }
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
@@ -498,16 +1139,48 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                // A conflict resolution round hands over to the next round instead of the
+                // next action, so nothing else runs while the operation is in progress
+                let resolution = container.conflict_resolutions.take(exec_id).await;
+                let mut follow_up_started = match resolution {
+                    Some(resolution) => {
+                        container
+                            .continue_conflict_resolution(&ctx, resolution)
+                            .await
+                    }
+                    None => false,
+                };
+                if !follow_up_started && (success || cleanup_done) {
                     // Commit changes (if any) and get feedback about whether changes were made
-                    let changes_committed = match container.try_commit_changes(&ctx).await {
-                        Ok(committed) => committed,
+                    let outcome = match container.commit_execution_changes(&ctx).await {
+                        Ok(outcome) => outcome,
                         Err(e) => {
                             tracing::error!("Failed to commit changes after execution: {}", e);
                             // Treat commit failures as if changes were made to be safe
-                            true
+                            CommitOutcome {
+                                committed: true,
+                                hook_failures: Vec::new(),
+                            }
                         }
                     };
+                    let changes_committed = outcome.committed;
+
+                    if outcome.hook_failures.is_empty() {
+                        container.reset_hook_feedback_rounds(ctx.session.id).await;
+                    } else {
+                        // Give the agent a turn to fix what the hooks reported; the next
+                        // action and finalization then follow that turn instead
+                        follow_up_started = match container
+                            .start_hook_feedback_follow_up(&ctx, &outcome.hook_failures)
+                            .await
+                        {
+                            Ok(started) => started,
+                            Err(e) => {
+                                tracing::error!("Failed to start hook feedback follow-up: {}", e);
+                                false
+                            }
+                        };
+                    }
 
                     let should_start_next = if matches!(
                         ctx.execution_process.run_reason,
@@ -523,7 +1196,12 @@ impl LocalContainerService {
                         true
                     };
 
-                    if should_start_next {
+                    if follow_up_started {
+                        tracing::info!(
+                            "Sent commit hook failures back to the agent for workspace {}",
+                            ctx.workspace.id
+                        );
+                    } else if should_start_next {
                         // If the process exited successfully, start the next action
                         if let Err(e) = container.try_start_next_action(&ctx).await {
                             tracing::error!("Failed to start next action after completion: {}", e);
@@ -539,57 +1217,49 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
-                    // Only execute queued messages if the execution succeeded
-                    // If it failed or was killed, just clear the queue and finalize
-                    let should_execute_queued = !matches!(
-                        ctx.execution_process.status,
-                        ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
-                    );
-
-                    if let Some(queued_msg) =
-                        container.queued_message_service.take_queued(ctx.session.id)
-                    {
-                        if should_execute_queued {
-                            tracing::info!(
-                                "Found queued message for session {}, starting follow-up execution",
-                                ctx.session.id
-                            );
+                if !follow_up_started && container.should_finalize(&ctx) {
+                    // Only drain the queue if the execution succeeded. If it failed or was
+                    // killed, pause the queue so the remaining messages wait for the user.
+                    let pause_reason = match ctx.execution_process.status {
+                        ExecutionProcessStatus::Failed => Some("Execution failed".to_string()),
+                        ExecutionProcessStatus::Killed => Some("Execution was stopped".to_string()),
+                        _ => None,
+                    };
 
-                            // Delete the scratch since we're consuming the queued message
-                            if let Err(e) = Scratch::delete(
-                                &db.pool,
-                                ctx.session.id,
-                                &ScratchType::DraftFollowUp,
-                            )
+                    if let Some(reason) = pause_reason {
+                        if let Err(e) = container
+                            .queued_message_service
+                            .pause(ctx.session.id, &reason)
                             .await
-                            {
-                                tracing::warn!(
-                                    "Failed to delete scratch after consuming queued message: {}",
-                                    e
-                                );
-                            }
-
-                            // Execute the queued follow-up
-                            if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
-                                .await
-                            {
+                        {
+                            tracing::error!(
+                                "Failed to pause queue for session {}: {}",
+                                ctx.session.id,
+                                e
+                            );
+                        }
+                        container.finalize_task(&ctx).await;
+                    } else {
+                        match container.start_next_queued_message(&ctx).await {
+                            Ok(Some(_)) => {}
+                            Ok(None) => container.finalize_task(&ctx).await,
+                            Err(e) => {
                                 tracing::error!("Failed to start queued follow-up: {}", e);
+                                if let Err(pause_err) = container
+                                    .queued_message_service
+                                    .pause(ctx.session.id, &e.to_string())
+                                    .await
+                                {
+                                    tracing::error!(
+                                        "Failed to pause queue for session {}: {}",
+                                        ctx.session.id,
+                                        pause_err
+                                    );
+                                }
                                 // Fall back to finalization if follow-up fails
                                 container.finalize_task(&ctx).await;
                             }
-                        } else {
-                            // Execution failed or was killed - discard the queued message and finalize
-                            tracing::info!(
-                                "Discarding queued message for session {} due to execution status {:?}",
-                                ctx.session.id,
-                                ctx.execution_process.status
-                            );
-                            container.finalize_task(&ctx).await;
                         }
-                    } else {
-                        container.finalize_task(&ctx).await;
                     }
                 }
 
@@ -657,9 +1327,11 @@ impl LocalContainerService {
             if let Some(handle) = db_stream_handle {
                 let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
             }
+            container.log_redactors.write().await.remove(&exec_id);
 
-            // Cleanup child handle
+            // Cleanup child handle and input channels
             child_store.write().await.remove(&exec_id);
+            container.remove_input_channels(&exec_id).await;
         })
     }
 
@@ -705,19 +1377,26 @@ impl LocalContainerService {
         format!("{}-{}", short_uuid(workspace_id), task_title_id)
     }
 
-    async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
+    async fn track_child_msgs_in_store(
+        &self,
+        id: Uuid,
+        child: &mut AsyncGroupChild,
+        redactor: Arc<Redactor>,
+    ) {
         let store = Arc::new(MsgStore::new());
 
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
 
-        // Map stdout bytes -> LogMsg::Stdout
-        let out = ReaderStream::new(out)
-            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+        // Map stdout bytes -> LogMsg::Stdout, with secrets masked
+        let out =
+            ReaderStream::new(out).map_ok(|chunk| String::from_utf8_lossy(&chunk).into_owned());
+        let out = redact::redact_chunks(out, redactor.clone()).map_ok(LogMsg::Stdout);
 
-        // Map stderr bytes -> LogMsg::Stderr
-        let err = ReaderStream::new(err)
-            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+        // Map stderr bytes -> LogMsg::Stderr, with secrets masked
+        let err =
+            ReaderStream::new(err).map_ok(|chunk| String::from_utf8_lossy(&chunk).into_owned());
+        let err = redact::redact_chunks(err, redactor.clone()).map_ok(LogMsg::Stderr);
 
         // If you have a JSON Patch source, map it to LogMsg::JsonPatch too, then select all three.
 
@@ -727,6 +1406,7 @@ impl LocalContainerService {
 
         let mut map = self.msg_stores().write().await;
         map.insert(id, store);
+        self.log_redactors.write().await.insert(id, redactor);
     }
 
     /// Create a live diff log stream for ongoing attempts for WebSocket
@@ -790,8 +1470,18 @@ impl LocalContainerService {
         Ok(())
     }
 
-    /// Copy project files and images to the workspace.
-    /// Skips files/images that already exist (fast no-op if all exist).
+    /// Add the finished execution's conversation to the full-text search index
+    async fn index_conversation(&self, exec_id: &Uuid) -> Result<(), sqlx::Error> {
+        let Some(msg_store) = self.get_msg_store_by_id(exec_id).await else {
+            return Ok(());
+        };
+        conversation_search::index_execution(&self.db.pool, *exec_id, &msg_store.get_history())
+            .await
+    }
+
+    /// Copy project files, templated files and images to the workspace, and link
+    /// directories into it. Skips files/images that already exist (fast no-op if all
+    /// exist).
     async fn copy_files_and_images(
         &self,
         workspace_dir: &Path,
@@ -800,10 +1490,10 @@ impl LocalContainerService {
         let repos = WorkspaceRepo::find_repos_with_copy_files(&self.db.pool, workspace.id).await?;
 
         for repo in &repos {
+            let worktree_path = workspace_dir.join(&repo.name);
             if let Some(copy_files) = &repo.copy_files
                 && !copy_files.trim().is_empty()
             {
-                let worktree_path = workspace_dir.join(&repo.name);
                 self.copy_project_files(&repo.path, &worktree_path, copy_files)
                     .await
                     .unwrap_or_else(|e| {
@@ -814,6 +1504,27 @@ impl LocalContainerService {
                         );
                     });
             }
+            if let Some(template_files) = &repo.template_files
+                && !template_files.trim().is_empty()
+            {
+                self.render_template_files(workspace, repo, &worktree_path, template_files)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::warn!(
+                            "Failed to render template files for repo '{}': {}",
+                            repo.name,
+                            e
+                        );
+                    });
+            }
+            if let Some(symlink_dirs) = &repo.symlink_dirs
+                && !symlink_dirs.trim().is_empty()
+                && let Err(e) = self
+                    .link_project_dirs(&repo.path, &worktree_path, symlink_dirs)
+                    .await
+            {
+                tracing::warn!("Failed to link directories for repo '{}': {}", repo.name, e);
+            }
         }
 
         if let Err(e) = self
@@ -831,6 +1542,59 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Render a repo's templated files into its worktree, filling in the workspace's id,
+    /// branch, database name and dev server ports.
+    async fn render_template_files(
+        &self,
+        workspace: &Workspace,
+        repo: &RepoWithCopyFiles,
+        worktree_path: &Path,
+        template_files: &str,
+    ) -> Result<(), ContainerError> {
+        let source_dir = repo.path.clone();
+        let target_dir = worktree_path.to_path_buf();
+        let patterns = template_files.to_string();
+        let files = tokio::task::spawn_blocking(move || {
+            copy::read_template_files(&source_dir, &target_dir, &patterns)
+        })
+        .await
+        .map_err(|e| ContainerError::Other(anyhow!("Read template files task failed: {e}")))?;
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let short_id = short_uuid(&workspace.id);
+        let mut values = HashMap::from([
+            ("workspace_id".to_string(), workspace.id.to_string()),
+            ("workspace_short_id".to_string(), short_id.clone()),
+            ("branch".to_string(), workspace.branch.clone()),
+            ("repo_name".to_string(), repo.name.clone()),
+            (
+                "db_name".to_string(),
+                copy::database_name(&repo.name, &short_id),
+            ),
+        ]);
+        let port_placeholders: HashSet<String> = files
+            .iter()
+            .flat_map(|file| copy::template_placeholders(&file.content))
+            .filter(|name| *name == "port" || name.starts_with("port."))
+            .map(str::to_string)
+            .collect();
+        for name in port_placeholders {
+            let port = self
+                .template_port(workspace.id, repo.id, name.strip_prefix("port."))
+                .await?;
+            values.insert(name, port.to_string());
+        }
+
+        let target_dir = worktree_path.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            copy::write_template_files(&target_dir, &files, &values)
+        })
+        .await
+        .map_err(|e| ContainerError::Other(anyhow!("Write template files task failed: {e}")))?
+    }
+
     /// Create workspace-level CLAUDE.md and AGENTS.md files that import from each repo.
     /// Uses the @import syntax to reference each repo's config files.
     /// Skips creating files if they already exist or if no repos have the source file.
@@ -933,9 +1697,13 @@ impl LocalContainerService {
             .filter(|dir| !dir.is_empty())
             .cloned();
 
+        let (prompt, context_packs) = self
+            .apply_context_packs(&ctx.workspace, queued_data.message.clone())
+            .await?;
+
         let action_type = if let Some(info) = latest_session_info {
             ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
-                prompt: queued_data.message.clone(),
+                prompt,
                 session_id: info.session_id,
                 reset_to_message_id: None,
                 executor_config: queued_data.executor_config.clone(),
@@ -943,7 +1711,7 @@ impl LocalContainerService {
             })
         } else {
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
-                prompt: queued_data.message.clone(),
+                prompt,
                 executor_config: queued_data.executor_config.clone(),
                 working_dir,
             })
@@ -951,16 +1719,43 @@ impl LocalContainerService {
 
         let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
 
-        self.start_execution(
-            &ctx.workspace,
-            &ctx.session,
-            &action,
-            &ExecutionProcessRunReason::CodingAgent,
-        )
-        .await
+        let execution_process = self
+            .start_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await?;
+        self.context_pack_cache.mark_delivered(context_packs).await;
+        Ok(execution_process)
     }
 }
 
+/// Result of committing an execution's changes across the workspace repos.
+#[derive(Default)]
+struct CommitOutcome {
+    committed: bool,
+    /// `(repo name, hook output)` for repos whose policy feeds hook failures back to the agent
+    hook_failures: Vec<(String, String)>,
+}
+
+fn build_hook_feedback_prompt(hook_failures: &[(String, String)]) -> String {
+    let mut prompt = String::from(
+        "Your changes could not be committed because the repository's commit hooks failed.\n",
+    );
+    for (repo_name, output) in hook_failures {
+        let output = truncate_to_char_boundary(output, MAX_HOOK_OUTPUT_BYTES);
+        prompt.push_str(&format!("\n### {repo_name}\n```\n{output}\n```\n"));
+    }
+    prompt.push_str(
+        "\nFix the problems reported above (for example by running the formatter or resolving \
+         the lint errors). Do not commit or bypass the hooks yourself; your changes will be \
+         committed automatically once the hooks pass.",
+    );
+    prompt
+}
+
 fn failure_exit_status() -> std::process::ExitStatus {
     #[cfg(unix)]
     {
@@ -980,6 +1775,10 @@ impl ContainerService for LocalContainerService {
         &self.msg_stores
     }
 
+    fn log_redactors(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<Redactor>>>> {
+        &self.log_redactors
+    }
+
     fn db(&self) -> &DBService {
         &self.db
     }
@@ -992,6 +1791,18 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn conflict_resolutions(&self) -> &ConflictResolutions {
+        &self.conflict_resolutions
+    }
+
+    fn symbol_index_cache(&self) -> &Arc<SymbolIndexCache> {
+        &self.symbol_index_cache
+    }
+
+    fn context_pack_cache(&self) -> &Arc<ContextPackCache> {
+        &self.context_pack_cache
+    }
+
     async fn touch(&self, workspace: &Workspace) -> Result<(), ContainerError> {
         let now = Instant::now();
 
@@ -1232,6 +2043,47 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
         env.insert("VK_SESSION_ID", execution_process.session_id.to_string());
 
+        // Secrets of the project, its repos and the coding agent, masked in the output
+        let project_id = match workspace.parent_task(&self.db.pool).await {
+            Ok(task) => task.map(|task| task.project_id),
+            Err(e) => {
+                tracing::error!("Failed to load task of workspace {}: {}", workspace.id, e);
+                None
+            }
+        };
+        let secrets = self
+            .secret_env(workspace, project_id, &repos, executor_action)
+            .await;
+        env.merge(&secrets);
+
+        // Dev servers listen on a port picked for them, so parallel workspaces don't collide
+        let dev_server = if matches!(
+            execution_process.run_reason,
+            ExecutionProcessRunReason::DevServer
+        ) {
+            match self
+                .assign_dev_server_port(workspace, execution_process.id, executor_action, &repos)
+                .await
+            {
+                Ok(dev_server) => dev_server,
+                Err(e) => {
+                    tracing::error!("Failed to assign a dev server port: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        if let Some(dev_server) = &dev_server {
+            match self.dev_process_env(dev_server).await {
+                Ok(process_env) => env.merge(&process_env),
+                Err(e) => tracing::error!("Failed to load dev process environment: {}", e),
+            }
+            env.insert(dev_server::PORT_ENV_VAR, dev_server.port.to_string());
+        }
+
+        let redactor = Arc::new(self.log_redactor(project_id, &secrets, &env).await);
+
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
             Duration::from_secs(30),
@@ -1244,9 +2096,15 @@ impl ContainerService for LocalContainerService {
             ))
         })??;
 
-        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
+        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child, redactor)
             .await;
 
+        if let Some(dev_server) = &dev_server
+            && let Err(e) = self.track_dev_server(dev_server).await
+        {
+            tracing::error!("Failed to track dev server: {}", e);
+        }
+
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
@@ -1256,6 +2114,11 @@ impl ContainerService for LocalContainerService {
                 .await;
         }
 
+        if let Some(executor) = spawned.executor {
+            self.add_running_executor(execution_process.id, executor)
+                .await;
+        }
+
         // Spawn unified exit monitor: watches OS exit and optional executor signal
         let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
         self.add_exit_monitor_handle(execution_process.id, hn).await;
@@ -1282,6 +2145,18 @@ impl ContainerService for LocalContainerService {
 
         ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, exit_code)
             .await?;
+        if matches!(
+            execution_process.run_reason,
+            ExecutionProcessRunReason::DevServer
+        ) {
+            DevServer::update_status(
+                &self.db.pool,
+                execution_process.id,
+                DevServerStatus::Stopped,
+            )
+            .await?;
+        }
+        self.remove_input_channels(&execution_process.id).await;
 
         // Try graceful cancellation first, then force kill
         if let Some(cancel) = self.take_cancellation_token(&execution_process.id).await {
@@ -1325,6 +2200,10 @@ impl ContainerService for LocalContainerService {
         if let Some(handle) = db_stream_handle {
             let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
         }
+        self.log_redactors
+            .write()
+            .await
+            .remove(&execution_process.id);
 
         // Update task status to InReview when execution is stopped
         if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
@@ -1425,29 +2304,105 @@ impl ContainerService for LocalContainerService {
     }
 
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
-        if !matches!(
-            ctx.execution_process.run_reason,
-            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CleanupScript,
-        ) {
-            return Ok(false);
-        }
+        Ok(self.commit_execution_changes(ctx).await?.committed)
+    }
 
-        let message = self.get_commit_message(ctx).await;
+    async fn send_user_input(
+        &self,
+        execution_process_id: Uuid,
+        message: String,
+    ) -> Result<(), ContainerError> {
+        let executor = self.running_executor(&execution_process_id).await?;
+        executor.send_user_input(message).await?;
+        // The message resumes an interrupted session
+        self.interrupted_executions
+            .write()
+            .await
+            .remove(&execution_process_id);
+        Ok(())
+    }
 
-        let container_ref = ctx
-            .workspace
-            .container_ref
-            .as_ref()
-            .ok_or_else(|| ContainerError::Other(anyhow!("Container reference not found")))?;
-        let workspace_root = PathBuf::from(container_ref);
+    async fn interrupt_execution(&self, execution_process_id: Uuid) -> Result<(), ContainerError> {
+        let executor = self.running_executor(&execution_process_id).await?;
+        executor.interrupt_turn().await?;
 
-        let repos_with_changes = self.check_repos_for_changes(&workspace_root, &ctx.repos)?;
-        if repos_with_changes.is_empty() {
-            tracing::debug!("No changes to commit in any repository");
-            return Ok(false);
+        let interrupted_at = Instant::now();
+        self.interrupted_executions
+            .write()
+            .await
+            .insert(execution_process_id, interrupted_at);
+        let container = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(INTERRUPTED_TURN_TIMEOUT).await;
+            container
+                .stop_idle_interrupted_execution(execution_process_id, interrupted_at)
+                .await;
+        });
+        Ok(())
+    }
+
+    async fn start_next_queued_message(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        // An exit and a resume can drain at the same time; only one of them may start
+        // the next message, and only while nothing else runs in the workspace
+        let _drain = self.queued_message_service.lock_drain(ctx.session.id).await;
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+            &self.db.pool,
+            ctx.workspace.id,
+        )
+        .await?
+        {
+            return Ok(None);
         }
+        let Some(queued) = self
+            .queued_message_service
+            .take_next_message(ctx.session.id)
+            .await?
+        else {
+            return Ok(None);
+        };
 
-        Ok(self.commit_repos(repos_with_changes, &message))
+        tracing::info!(
+            "Starting queued message {} for session {}",
+            queued.id,
+            ctx.session.id
+        );
+        let started = async {
+            let executor_config = match &queued.executor_config {
+                Some(config) => config.0.clone(),
+                None => ExecutionProcess::latest_executor_profile_for_session(
+                    &self.db.pool,
+                    ctx.session.id,
+                )
+                .await?
+                .map(ExecutorConfig::from)
+                .ok_or_else(|| anyhow!("No executor configured for queued message"))?,
+            };
+            let data = DraftFollowUpData {
+                message: queued.message.clone(),
+                executor_config,
+            };
+            self.start_queued_follow_up(ctx, &data).await
+        }
+        .await;
+
+        match started {
+            Ok(execution_process) => Ok(Some(execution_process)),
+            Err(e) => {
+                // The message didn't run, so it stays first in the queue
+                if let Err(restore_err) = self.queued_message_service.restore_message(&queued).await
+                {
+                    tracing::error!(
+                        "Failed to put queued message {} back: {}",
+                        queued.id,
+                        restore_err
+                    );
+                }
+                Err(e)
+            }
+        }
     }
 
     /// Copy files from the original project directory to the worktree.
@@ -1473,6 +2428,22 @@ impl ContainerService for LocalContainerService {
         .map_err(|e| ContainerError::Other(anyhow!("Copy files task failed: {e}")))?
     }
 
+    async fn link_project_dirs(
+        &self,
+        source_dir: &Path,
+        target_dir: &Path,
+        symlink_dirs: &str,
+    ) -> Result<(), ContainerError> {
+        let source_dir = source_dir.to_path_buf();
+        let target_dir = target_dir.to_path_buf();
+        let symlink_dirs = symlink_dirs.to_string();
+        tokio::task::spawn_blocking(move || {
+            copy::symlink_dirs_impl(&source_dir, &target_dir, &symlink_dirs)
+        })
+        .await
+        .map_err(|e| ContainerError::Other(anyhow!("Link directories task failed: {e}")))?
+    }
+
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
         tracing::info!("Killing all running processes");
         let running_processes = ExecutionProcess::find_running(&self.db.pool).await?;
@@ -1,10 +1,10 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fs,
+    io::Write,
     path::{Path, PathBuf},
 };
 
-use anyhow::anyhow;
 use globwalk::GlobWalkerBuilder;
 use services::services::container::ContainerError;
 
@@ -13,35 +13,53 @@ fn normalize_pattern(pattern: &str) -> String {
     pattern.replace('\\', "/")
 }
 
-/// Copy project files from source to target directory based on glob patterns.
-/// Skips files that already exist at target with same size.
-pub(crate) fn copy_project_files_impl(
-    source_dir: &Path,
-    target_dir: &Path,
-    copy_files: &str,
-) -> Result<(), ContainerError> {
-    let patterns: Vec<&str> = copy_files
+fn split_patterns(patterns: &str) -> Vec<String> {
+    patterns
         .split(',')
         .map(|s| s.trim())
         .filter(|s| !s.is_empty())
-        .collect();
+        .map(normalize_pattern)
+        .collect()
+}
+
+/// Files of `source_dir` matching comma-separated glob patterns, as paths relative to
+/// `source_dir`. A directory matches all of its files. Files outside `source_dir` are
+/// skipped.
+fn matching_files(source_dir: &Path, patterns: &str) -> Vec<PathBuf> {
+    let Ok(canonical_source) = source_dir.canonicalize() else {
+        return Vec::new();
+    };
 
     // Track files to avoid duplicates
     let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    let mut add = |file: &Path| {
+        let canonical_file = match file.canonicalize() {
+            Ok(canonical_file) => canonical_file,
+            Err(e) => {
+                tracing::warn!("Failed to resolve file {:?}: {e}", file);
+                return;
+            }
+        };
+        // Validate path is within source_dir
+        if !canonical_file.starts_with(&canonical_source) {
+            tracing::warn!("File {:?} is outside project directory", file);
+            return;
+        }
+        if !seen.insert(canonical_file) {
+            return;
+        }
+        match file.strip_prefix(source_dir) {
+            Ok(relative_path) => files.push(relative_path.to_path_buf()),
+            Err(e) => tracing::warn!("Failed to get relative path for {:?}: {e}", file),
+        }
+    };
 
-    for pattern in patterns {
-        let pattern = normalize_pattern(pattern);
+    for pattern in split_patterns(patterns) {
         let pattern_path = source_dir.join(&pattern);
 
         if pattern_path.is_file() {
-            if let Err(e) = copy_single_file(&pattern_path, source_dir, target_dir, &mut seen) {
-                tracing::warn!(
-                    "Failed to copy file {} (from {}): {}",
-                    pattern,
-                    pattern_path.display(),
-                    e
-                );
-            }
+            add(&pattern_path);
             continue;
         }
 
@@ -64,54 +82,241 @@ pub(crate) fn copy_project_files_impl(
         };
 
         for entry in walker.flatten() {
-            if let Err(e) = copy_single_file(entry.path(), source_dir, target_dir, &mut seen) {
-                tracing::warn!("Failed to copy file {:?}: {e}", entry.path());
-            }
+            add(entry.path());
+        }
+    }
+
+    files
+}
+
+/// Copy project files from source to target directory based on glob patterns.
+/// Skips files that already exist at target.
+pub(crate) fn copy_project_files_impl(
+    source_dir: &Path,
+    target_dir: &Path,
+    copy_files: &str,
+) -> Result<(), ContainerError> {
+    for relative_path in matching_files(source_dir, copy_files) {
+        let target_file = target_dir.join(&relative_path);
+        if target_file.exists() {
+            continue;
+        }
+        if let Err(e) = copy_file(&source_dir.join(&relative_path), &target_file) {
+            tracing::warn!("Failed to copy file {:?}: {e}", relative_path);
         }
     }
 
     Ok(())
 }
 
-fn copy_single_file(
-    source_file: &Path,
-    source_root: &Path,
-    target_root: &Path,
-    seen: &mut HashSet<PathBuf>,
-) -> Result<bool, ContainerError> {
-    let canonical_source = source_root.canonicalize()?;
-    let canonical_file = source_file.canonicalize()?;
-    // Validate path is within source_dir
-    if !canonical_file.starts_with(canonical_source) {
-        return Err(ContainerError::Other(anyhow!(
-            "File {source_file:?} is outside project directory"
-        )));
+fn copy_file(source_file: &Path, target_file: &Path) -> std::io::Result<()> {
+    if let Some(parent) = target_file.parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)?;
     }
+    fs::copy(source_file, target_file)?;
+    Ok(())
+}
 
-    if !seen.insert(canonical_file.clone()) {
-        return Ok(false);
+/// A templated file waiting to be rendered into a worktree.
+pub(crate) struct TemplateFile {
+    pub relative_path: PathBuf,
+    pub content: String,
+}
+
+/// Read the files matching `template_files` that aren't in the worktree yet.
+pub(crate) fn read_template_files(
+    source_dir: &Path,
+    target_dir: &Path,
+    template_files: &str,
+) -> Vec<TemplateFile> {
+    matching_files(source_dir, template_files)
+        .into_iter()
+        .filter(|relative_path| !target_dir.join(relative_path).exists())
+        .filter_map(
+            |relative_path| match fs::read_to_string(source_dir.join(&relative_path)) {
+                Ok(content) => Some(TemplateFile {
+                    relative_path,
+                    content,
+                }),
+                Err(e) => {
+                    tracing::warn!("Failed to read template file {:?}: {e}", relative_path);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// Names of the `{{ placeholders }}` in a template.
+pub(crate) fn template_placeholders(content: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = after[..end].trim();
+        if is_placeholder_name(name) {
+            names.push(name);
+        }
+        rest = &after[end + 2..];
     }
+    names
+}
 
-    let relative_path = source_file.strip_prefix(source_root).map_err(|e| {
-        ContainerError::Other(anyhow!(
-            "Failed to get relative path for {source_file:?}: {e}"
-        ))
-    })?;
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
 
-    let target_file = target_root.join(relative_path);
+/// Replace each `{{ placeholder }}` with its value. Unknown placeholders are kept as
+/// they are, so files using the same braces for something else survive.
+pub(crate) fn render_template(content: &str, values: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        match values.get(after[..end].trim()) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
 
-    if target_file.exists() {
-        return Ok(false);
+/// Write rendered templates into the worktree, skipping files that appeared meanwhile.
+pub(crate) fn write_template_files(
+    target_dir: &Path,
+    files: &[TemplateFile],
+    values: &HashMap<String, String>,
+) -> Result<(), ContainerError> {
+    for file in files {
+        let target_file = target_dir.join(&file.relative_path);
+        if target_file.exists() {
+            continue;
+        }
+        for name in template_placeholders(&file.content) {
+            if !values.contains_key(name) {
+                tracing::warn!(
+                    "Unknown placeholder '{{{{ {name} }}}}' in {:?}, leaving it as is",
+                    file.relative_path
+                );
+            }
+        }
+        if let Some(parent) = target_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target_file, render_template(&file.content, values))?;
     }
+    Ok(())
+}
 
-    if let Some(parent) = target_file.parent()
-        && !parent.exists()
-    {
+/// Per-workspace database name for a repo, e.g. `my_app_1a2b`: lowercase letters, digits
+/// and underscores, short enough for Postgres and MySQL identifiers.
+pub(crate) fn database_name(repo_name: &str, short_id: &str) -> String {
+    let repo: String = repo_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let repo = repo.trim_matches('_');
+    let repo = if repo.is_empty() { "db" } else { repo };
+    let max_repo_len = 63 - short_id.len() - 1;
+    let repo = &repo[..repo.len().min(max_repo_len)];
+    format!("{}_{short_id}", repo.trim_end_matches('_'))
+}
+
+/// Link directories of the project, like `node_modules`, into the worktree instead of
+/// copying them. Existing paths in the worktree are left alone. Each linked path is added
+/// to the project's `.git/info/exclude`, shared by its worktrees, as `.gitignore` entries
+/// ending in `/` don't match symlinks.
+pub(crate) fn symlink_dirs_impl(
+    source_dir: &Path,
+    target_dir: &Path,
+    symlink_dirs: &str,
+) -> Result<(), ContainerError> {
+    let canonical_source = source_dir.canonicalize()?;
+
+    for dir in split_patterns(symlink_dirs) {
+        let dir = dir.trim_end_matches('/');
+        let Ok(canonical_dir) = source_dir.join(dir).canonicalize() else {
+            continue;
+        };
+        if !canonical_dir.is_dir()
+            || canonical_dir == canonical_source
+            || !canonical_dir.starts_with(&canonical_source)
+        {
+            tracing::warn!("Not linking '{dir}': not a directory inside the project");
+            continue;
+        }
+
+        let target = target_dir.join(dir);
+        if target.symlink_metadata().is_ok() {
+            continue;
+        }
+        if let Err(e) = exclude_from_git(source_dir, dir) {
+            tracing::warn!("Failed to exclude '{dir}' from git: {e}");
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if let Err(e) = symlink_dir(&canonical_dir, &target) {
+            tracing::warn!("Failed to link directory '{dir}' into the worktree: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+fn exclude_from_git(source_dir: &Path, dir: &str) -> std::io::Result<()> {
+    let git_dir = source_dir.join(".git");
+    if !git_dir.is_dir() {
+        return Ok(());
+    }
+    let exclude_path = git_dir.join("info").join("exclude");
+    let entry = format!("/{dir}");
+    let existing = fs::read_to_string(&exclude_path).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == entry) {
+        return Ok(());
+    }
+
+    if let Some(parent) = exclude_path.parent() {
         fs::create_dir_all(parent)?;
     }
-    fs::copy(source_file, &target_file)?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&exclude_path)?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "{entry}")
+}
+
+#[cfg(unix)]
+fn symlink_dir(original: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
+}
 
-    Ok(true)
+#[cfg(windows)]
+fn symlink_dir(original: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(original, link)
 }
 
 #[cfg(test)]
@@ -324,4 +529,101 @@ mod tests {
 
         assert_eq!(std::fs::read_dir(dst.path()).unwrap().count(), 0);
     }
+
+    #[test]
+    fn test_render_template_fills_known_placeholders() {
+        let values = HashMap::from([
+            ("port".to_string(), "4123".to_string()),
+            ("port.api".to_string(), "4124".to_string()),
+            ("db_name".to_string(), "app_1a2b".to_string()),
+        ]);
+        let content = "PORT={{port}}\nAPI_URL=http://localhost:{{ port.api }}\n\
+                       DATABASE_URL=postgres:///{{db_name}}\nOTHER={{ unknown }}\n";
+
+        assert_eq!(
+            render_template(content, &values),
+            "PORT=4123\nAPI_URL=http://localhost:4124\n\
+             DATABASE_URL=postgres:///app_1a2b\nOTHER={{ unknown }}\n"
+        );
+        assert_eq!(
+            template_placeholders(content),
+            vec!["port", "port.api", "db_name", "unknown"]
+        );
+        assert_eq!(render_template("a {{ b", &values), "a {{ b");
+    }
+
+    #[test]
+    fn test_template_files_skip_existing_targets() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+
+        fs::write(source_dir.path().join(".env"), "PORT={{port}}").unwrap();
+        fs::write(source_dir.path().join(".env.local"), "PORT={{port}}").unwrap();
+        fs::write(target_dir.path().join(".env.local"), "kept").unwrap();
+
+        let files = read_template_files(source_dir.path(), target_dir.path(), ".env*");
+        assert_eq!(files.len(), 1);
+
+        let values = HashMap::from([("port".to_string(), "4123".to_string())]);
+        write_template_files(target_dir.path(), &files, &values).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(target_dir.path().join(".env")).unwrap(),
+            "PORT=4123"
+        );
+        assert_eq!(
+            fs::read_to_string(target_dir.path().join(".env.local")).unwrap(),
+            "kept"
+        );
+    }
+
+    #[test]
+    fn test_database_name_is_a_valid_identifier() {
+        assert_eq!(database_name("My-App", "1a2b"), "my_app_1a2b");
+        assert_eq!(database_name("--", "1a2b"), "db_1a2b");
+        assert_eq!(database_name(&"x".repeat(100), "1a2b").len(), 63);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_dirs_links_and_excludes() {
+        let source_dir = TempDir::new().unwrap();
+        let target_dir = TempDir::new().unwrap();
+
+        fs::create_dir_all(source_dir.path().join(".git/info")).unwrap();
+        fs::create_dir_all(source_dir.path().join("node_modules/pkg")).unwrap();
+        fs::create_dir_all(source_dir.path().join("web/node_modules")).unwrap();
+        fs::create_dir_all(target_dir.path().join("web/node_modules")).unwrap();
+
+        symlink_dirs_impl(
+            source_dir.path(),
+            target_dir.path(),
+            "node_modules, web/node_modules, missing, ..",
+        )
+        .unwrap();
+
+        let linked = target_dir.path().join("node_modules");
+        assert!(linked.symlink_metadata().unwrap().file_type().is_symlink());
+        assert!(linked.join("pkg").is_dir());
+        // Existing directories are left alone
+        assert!(
+            !target_dir
+                .path()
+                .join("web/node_modules")
+                .symlink_metadata()
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert!(!target_dir.path().join("missing").exists());
+
+        let exclude = fs::read_to_string(source_dir.path().join(".git/info/exclude")).unwrap();
+        assert_eq!(exclude, "/node_modules\n");
+
+        // Linking again doesn't duplicate the exclude entry
+        fs::remove_file(&linked).unwrap();
+        symlink_dirs_impl(source_dir.path(), target_dir.path(), "node_modules").unwrap();
+        let exclude = fs::read_to_string(source_dir.path().join(".git/info/exclude")).unwrap();
+        assert_eq!(exclude, "/node_modules\n");
+    }
 }
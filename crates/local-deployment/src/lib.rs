@@ -12,16 +12,21 @@ use services::services::{
     auth::AuthContext,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
+    context_pack::ContextPackCache,
     events::EventService,
     file_search::FileSearchCache,
     filesystem::FilesystemService,
     image::ImageService,
+    issue_import::IssueImportService,
+    merge_queue::MergeQueueService,
     oauth_credentials::OAuthCredentials,
     pr_monitor::PrMonitorService,
     project::ProjectService,
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
+    secrets::SecretService,
+    symbol_index::SymbolIndexCache,
     worktree_manager::WorktreeManager,
 };
 use tokio::sync::RwLock;
@@ -51,8 +56,13 @@ pub struct LocalDeployment {
     filesystem: FilesystemService,
     events: EventService,
     file_search_cache: Arc<FileSearchCache>,
+    symbol_index_cache: Arc<SymbolIndexCache>,
+    context_pack_cache: Arc<ContextPackCache>,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
+    merge_queue: MergeQueueService<LocalContainerService>,
+    pr_monitor: PrMonitorService<LocalContainerService>,
+    secrets: SecretService,
     remote_client: Result<RemoteClient, RemoteClientNotConfigured>,
     shared_api_base: Option<String>,
     auth_context: AuthContext,
@@ -133,7 +143,7 @@ impl Deployment for LocalDeployment {
         }
 
         let approvals = Approvals::new(msg_stores.clone());
-        let queued_message_service = QueuedMessageService::new();
+        let queued_message_service = QueuedMessageService::new(db.clone());
 
         let oauth_credentials = Arc::new(OAuthCredentials::new(credentials_path()));
         if let Err(e) = oauth_credentials.load().await {
@@ -172,6 +182,10 @@ impl Deployment for LocalDeployment {
             user_id: user_id.clone(),
             analytics_service: s.clone(),
         });
+        let symbol_index_cache = Arc::new(SymbolIndexCache::new());
+        let context_pack_cache = Arc::new(ContextPackCache::new());
+        let secrets = SecretService::new(db.pool.clone());
+
         let container = LocalContainerService::new(
             db.clone(),
             msg_stores.clone(),
@@ -182,24 +196,39 @@ impl Deployment for LocalDeployment {
             approvals.clone(),
             queued_message_service.clone(),
             remote_client.clone().ok(),
+            symbol_index_cache.clone(),
+            context_pack_cache.clone(),
+            secrets.clone(),
         )
         .await;
 
+        let merge_queue = MergeQueueService::new(
+            container.clone(),
+            analytics.as_ref().map(|s| AnalyticsContext {
+                user_id: user_id.clone(),
+                analytics_service: s.clone(),
+            }),
+        );
+        if let Err(e) = merge_queue.resume().await {
+            tracing::error!("Failed to resume merge queue: {}", e);
+        }
+
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
 
         let file_search_cache = Arc::new(FileSearchCache::new());
 
         let pty = PtyService::new();
-        {
-            let db = db.clone();
-            let analytics = analytics.as_ref().map(|s| AnalyticsContext {
+        let pr_monitor = PrMonitorService::new(
+            db.clone(),
+            analytics.as_ref().map(|s| AnalyticsContext {
                 user_id: user_id.clone(),
                 analytics_service: s.clone(),
-            });
-            let container = container.clone();
-            let rc = remote_client.clone().ok();
-            PrMonitorService::spawn(db, analytics, container, rc).await;
-        }
+            }),
+            container.clone(),
+            remote_client.clone().ok(),
+        );
+        pr_monitor.spawn();
+        IssueImportService::spawn(db.clone(), secrets.clone()).await;
 
         let deployment = Self {
             config,
@@ -214,8 +243,13 @@ impl Deployment for LocalDeployment {
             filesystem,
             events,
             file_search_cache,
+            symbol_index_cache,
+            context_pack_cache,
             approvals,
             queued_message_service,
+            merge_queue,
+            pr_monitor,
+            secrets,
             remote_client,
             shared_api_base: api_base,
             auth_context,
@@ -274,6 +308,14 @@ impl Deployment for LocalDeployment {
         &self.file_search_cache
     }
 
+    fn symbol_index_cache(&self) -> &Arc<SymbolIndexCache> {
+        &self.symbol_index_cache
+    }
+
+    fn context_pack_cache(&self) -> &Arc<ContextPackCache> {
+        &self.context_pack_cache
+    }
+
     fn approvals(&self) -> &Approvals {
         &self.approvals
     }
@@ -282,6 +324,22 @@ impl Deployment for LocalDeployment {
         &self.queued_message_service
     }
 
+    fn merge_queue(
+        &self,
+    ) -> &MergeQueueService<impl ContainerService + Clone + Send + Sync + 'static> {
+        &self.merge_queue
+    }
+
+    fn pr_monitor(
+        &self,
+    ) -> &PrMonitorService<impl ContainerService + Clone + Send + Sync + 'static> {
+        &self.pr_monitor
+    }
+
+    fn secrets(&self) -> &SecretService {
+        &self.secrets
+    }
+
     fn auth_context(&self) -> &AuthContext {
         &self.auth_context
     }
@@ -16,16 +16,57 @@ fn generate_types_content() -> String {
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
+        db::models::conversation_search::ConversationSearchKind::decl(),
+        db::models::conversation_search::ConversationSearchMatch::decl(),
+        services::services::content_search::ContentSearchMatch::decl(),
+        services::services::content_search::ContentSearchEvent::decl(),
+        services::services::content_search::ContentSearchResponse::decl(),
+        services::services::symbol_index::SymbolKind::decl(),
+        services::services::symbol_index::CodeSymbol::decl(),
         db::models::repo::Repo::decl(),
         db::models::repo::UpdateRepo::decl(),
+        db::models::repo_commit_policy::CommitMessageStyle::decl(),
+        db::models::repo_commit_policy::CommitHookMode::decl(),
+        db::models::repo_commit_policy::RepoCommitPolicy::decl(),
+        db::models::repo_commit_policy::UpdateRepoCommitPolicy::decl(),
+        db::models::repo_context_pack::ContextPackDelivery::decl(),
+        db::models::repo_context_pack::RepoContextPack::decl(),
+        db::models::repo_context_pack::UpdateRepoContextPack::decl(),
+        db::models::repo_dev_server_settings::RepoDevServerSettings::decl(),
+        db::models::repo_dev_server_settings::UpdateRepoDevServerSettings::decl(),
+        db::models::repo_dev_process::RepoDevProcess::decl(),
+        db::models::repo_dev_process::UpsertRepoDevProcess::decl(),
+        db::models::secret::Secret::decl(),
+        db::models::secret::SecretScope::decl(),
+        db::models::secret::UpsertSecret::decl(),
+        db::models::project_redaction_settings::ProjectRedactionSettings::decl(),
+        db::models::project_redaction_settings::UpdateProjectRedactionSettings::decl(),
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
+        db::models::workspace_checkpoint::CheckpointReason::decl(),
+        db::models::workspace_checkpoint::WorkspaceCheckpoint::decl(),
+        db::models::workspace_checkpoint::WorkspaceCheckpointRepo::decl(),
+        db::models::workspace_checkpoint::WorkspaceCheckpointWithRepos::decl(),
+        db::models::visual_snapshot::VisualSnapshot::decl(),
         db::models::tag::Tag::decl(),
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
+        db::models::task_template::TemplateVariableKind::decl(),
+        db::models::task_template::TemplateVariable::decl(),
+        db::models::task_template::TemplateRepo::decl(),
+        db::models::task_template::TaskTemplate::decl(),
+        db::models::task_template::TaskTemplateWithImages::decl(),
+        db::models::task_template::CreateTaskTemplate::decl(),
+        services::services::task_template::RenderedTaskTemplate::decl(),
+        db::models::issue_import::IssueTrackerKind::decl(),
+        db::models::issue_import::IssueImportSource::decl(),
+        db::models::issue_import::CreateIssueImportSource::decl(),
+        db::models::issue_import::TaskIssueLink::decl(),
+        services::services::issue_import::IssueSyncSummary::decl(),
+        services::services::bulk_tasks::BulkTaskInput::decl(),
         db::models::task::TaskStatus::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
@@ -49,6 +90,8 @@ fn generate_types_content() -> String {
         db::models::image::Image::decl(),
         db::models::image::CreateImage::decl(),
         db::models::workspace::Workspace::decl(),
+        db::models::dev_server::DevServerStatus::decl(),
+        db::models::dev_server::DevServer::decl(),
         db::models::workspace::WorkspaceWithStatus::decl(),
         db::models::session::Session::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
@@ -65,6 +108,7 @@ fn generate_types_content() -> String {
         utils::approvals::ApprovalResponse::decl(),
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
+        utils::redact::RedactionCounts::decl(),
         utils::response::ApiResponse::<()>::decl(),
         api_types::LoginStatus::decl(),
         api_types::ProfileResponse::decl(),
@@ -99,6 +143,16 @@ fn generate_types_content() -> String {
         server::routes::repo::RegisterRepoRequest::decl(),
         server::routes::repo::InitRepoRequest::decl(),
         server::routes::tags::TagSearchParams::decl(),
+        server::routes::task_templates::RenderTaskTemplateRequest::decl(),
+        server::routes::task_templates::CreateTaskFromTemplateRequest::decl(),
+        server::routes::bulk::BulkItemResult::decl(),
+        server::routes::bulk::BulkOperationResponse::decl(),
+        server::routes::bulk::BulkCreateTasksRequest::decl(),
+        server::routes::bulk::BulkStartAttemptsRequest::decl(),
+        server::routes::bulk::BulkStopRequest::decl(),
+        server::routes::bulk::WorkspaceCleanupAction::decl(),
+        server::routes::bulk::BulkCleanupWorkspacesRequest::decl(),
+        server::routes::bulk::BulkUpdateStatusRequest::decl(),
         server::routes::oauth::TokenResponse::decl(),
         server::routes::config::UserSystemInfo::decl(),
         server::routes::config::Environment::decl(),
@@ -118,6 +172,9 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::PushTaskAttemptRequest::decl(),
         server::routes::task_attempts::RenameBranchRequest::decl(),
         server::routes::task_attempts::RenameBranchResponse::decl(),
+        server::routes::sessions::queue::QueueMessageRequest::decl(),
+        server::routes::sessions::queue::ReorderQueueRequest::decl(),
+        server::routes::execution_processes::SendUserInputRequest::decl(),
         server::routes::sessions::review::StartReviewRequest::decl(),
         server::routes::sessions::review::ReviewError::decl(),
         server::routes::task_attempts::OpenEditorRequest::decl(),
@@ -135,6 +192,8 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
         server::routes::task_attempts::ContinueRebaseRequest::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
+        server::routes::task_attempts::conflicts::ResolveConflictsRequest::decl(),
+        server::routes::task_attempts::conflicts::ResolveConflictsError::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
         server::routes::task_attempts::pr::PrError::decl(),
@@ -144,6 +203,28 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
         server::routes::task_attempts::pr::GetPrCommentsError::decl(),
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
+        server::routes::task_attempts::stack::StackQuery::decl(),
+        server::routes::task_attempts::stack::RestackRequest::decl(),
+        server::routes::task_attempts::stack::RestackOutcome::decl(),
+        server::routes::task_attempts::stack::RestackLayerResult::decl(),
+        server::routes::task_attempts::stack::RestackResponse::decl(),
+        services::services::pr_stack::StackLayer::decl(),
+        server::routes::task_attempts::checkpoints::CreateCheckpointRequest::decl(),
+        services::services::workspace_checkpoint::CheckpointRepoDiff::decl(),
+        server::routes::task_attempts::preview_requests::SetPreviewCaptureRequest::decl(),
+        server::preview_proxy::capture::CapturedHeader::decl(),
+        server::preview_proxy::capture::CapturedBody::decl(),
+        server::preview_proxy::capture::CapturedRequestSummary::decl(),
+        server::preview_proxy::capture::CapturedRequest::decl(),
+        server::preview_proxy::capture::PreviewCaptureStatus::decl(),
+        services::services::visual_snapshot::SnapshotViewport::decl(),
+        server::routes::task_attempts::visual_snapshots::CaptureVisualSnapshotsRequest::decl(),
+        server::routes::task_attempts::visual_snapshots::VisualSnapshotResponse::decl(),
+        server::routes::task_attempts::visual_snapshots::VisualSnapshotRunStatus::decl(),
+        server::routes::task_attempts::visual_snapshots::VisualSnapshotRun::decl(),
+        services::services::merge_queue::MergeQueueFailure::decl(),
+        services::services::merge_queue::MergeQueueEntryStatus::decl(),
+        services::services::merge_queue::MergeQueueEntry::decl(),
         services::services::git_host::UnifiedPrComment::decl(),
         services::services::git_host::ProviderKind::decl(),
         services::services::git_host::OpenPrInfo::decl(),
@@ -173,7 +254,8 @@ fn generate_types_content() -> String {
         services::services::config::ShowcaseState::decl(),
         services::services::config::SendMessageShortcut::decl(),
         git::GitBranch::decl(),
-        services::services::queued_message::QueuedMessage::decl(),
+        db::models::queued_message::QueuedMessage::decl(),
+        db::models::queued_message::QueuePause::decl(),
         services::services::queued_message::QueueStatus::decl(),
         git::ConflictOp::decl(),
         executors::actions::ExecutorAction::decl(),
@@ -207,6 +289,7 @@ fn generate_types_content() -> String {
         executors::executors::opencode::Opencode::decl(),
         executors::executors::qwen::QwenCode::decl(),
         executors::executors::droid::Droid::decl(),
+        executors::executors::replay::Replay::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
         executors::executors::AppendPrompt::decl(),
@@ -319,6 +402,10 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "replay",
+            generate_json_schema::<executors::executors::replay::Replay>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
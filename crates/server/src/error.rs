@@ -15,14 +15,22 @@ use git::GitServiceError;
 use git2::Error as Git2Error;
 use local_deployment::pty::PtyError;
 use services::services::{
+    bulk_tasks::BulkTaskParseError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
+    dev_server::DevProcessError,
     git_host::GitHostError,
     image::ImageError,
+    issue_import::IssueImportError,
     migration::MigrationError,
     project::ProjectServiceError,
+    queued_message::QueuedMessageError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
+    secrets::SecretError,
+    task_template::TaskTemplateError,
+    visual_snapshot::VisualSnapshotError,
+    workspace_checkpoint::CheckpointError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -553,3 +561,71 @@ impl From<ProjectRepoError> for ApiError {
         }
     }
 }
+
+impl From<DevProcessError> for ApiError {
+    fn from(err: DevProcessError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<SecretError> for ApiError {
+    fn from(err: SecretError) -> Self {
+        match err {
+            SecretError::Database(db_err) => ApiError::Database(db_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<TaskTemplateError> for ApiError {
+    fn from(err: TaskTemplateError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<BulkTaskParseError> for ApiError {
+    fn from(err: BulkTaskParseError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<IssueImportError> for ApiError {
+    fn from(err: IssueImportError) -> Self {
+        match err {
+            IssueImportError::Database(db_err) => ApiError::Database(db_err),
+            IssueImportError::GitHost(git_host_err) => ApiError::GitHost(git_host_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<CheckpointError> for ApiError {
+    fn from(err: CheckpointError) -> Self {
+        match err {
+            CheckpointError::Database(db_err) => ApiError::Database(db_err),
+            CheckpointError::Git(git_err) => ApiError::GitService(git_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<QueuedMessageError> for ApiError {
+    fn from(err: QueuedMessageError) -> Self {
+        match err {
+            QueuedMessageError::Database(db_err) => ApiError::Database(db_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<VisualSnapshotError> for ApiError {
+    fn from(err: VisualSnapshotError) -> Self {
+        match err {
+            VisualSnapshotError::Database(db_err) => ApiError::Database(db_err),
+            VisualSnapshotError::Image(image_err) => ApiError::Image(image_err),
+            VisualSnapshotError::Io(io_err) => ApiError::Io(io_err),
+            VisualSnapshotError::Git(git_err) => ApiError::GitService(git_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use anyhow::{self, Error as AnyhowError};
 use axum::Router;
 use deployment::{Deployment, DeploymentError};
-use server::{DeploymentImpl, preview_proxy, routes};
+use server::{
+    DeploymentImpl,
+    preview_proxy::{self, capture::PreviewCaptures},
+    routes,
+};
 use services::services::container::ContainerService;
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
@@ -85,12 +89,25 @@ async fn main() -> Result<(), VibeKanbanError> {
         }
     });
 
+    // Index conversations of processes that finished without being indexed
+    let deployment_for_search = deployment.clone();
+    tokio::spawn(async move {
+        if let Err(e) = deployment_for_search
+            .container()
+            .backfill_conversation_search()
+            .await
+        {
+            tracing::warn!("Failed to backfill conversation search: {}", e);
+        }
+    });
+
     // Preload global executor options cache for all executors with DEFAULT presets
     tokio::spawn(async move {
         executors::executors::utils::preload_global_executor_options_cache().await;
     });
 
-    let app_router = routes::router(deployment.clone());
+    let captures = PreviewCaptures::default();
+    let app_router = routes::router(deployment.clone(), captures.clone());
 
     let port = std::env::var("BACKEND_PORT")
         .or_else(|_| std::env::var("PORT"))
@@ -146,7 +163,7 @@ async fn main() -> Result<(), VibeKanbanError> {
         });
     }
 
-    let proxy_router: Router = preview_proxy::router();
+    let proxy_router: Router = preview_proxy::router(captures);
     let shutdown_token = CancellationToken::new();
 
     let main_shutdown = shutdown_token.clone();
@@ -0,0 +1,288 @@
+use std::str::FromStr;
+
+use db::models::task::TaskStatus;
+use executors::{executors::BaseCodingAgent, profile::ExecutorConfig};
+use rmcp::{
+    ErrorData, handler::server::tool::Parameters, model::CallToolResult, schemars, tool,
+    tool_router,
+};
+use serde::{Deserialize, Serialize};
+use services::services::bulk_tasks::BulkTaskInput;
+use uuid::Uuid;
+
+use super::TaskServer;
+use crate::routes::{
+    bulk::{
+        BulkCleanupWorkspacesRequest, BulkCreateTasksRequest, BulkOperationResponse,
+        BulkStartAttemptsRequest, BulkStopRequest, BulkUpdateStatusRequest, WorkspaceCleanupAction,
+    },
+    task_attempts::WorkspaceRepoInput,
+};
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct McpBulkTaskInput {
+    #[schemars(description = "The task title")]
+    title: String,
+    #[schemars(description = "Optional task description")]
+    description: Option<String>,
+    #[schemars(
+        description = "Optional initial status: 'todo', 'inprogress', 'inreview', 'done' or 'cancelled'"
+    )]
+    status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct McpBulkCreateTasksRequest {
+    #[schemars(description = "The local project to create the tasks in")]
+    project_id: Uuid,
+    #[schemars(description = "Tasks to create")]
+    tasks: Option<Vec<McpBulkTaskInput>>,
+    #[schemars(
+        description = "CSV with a header row: a 'title' column and optional 'description' and 'status' columns"
+    )]
+    csv: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct McpBulkRepoInput {
+    #[schemars(description = "The repository ID")]
+    repo_id: Uuid,
+    #[schemars(description = "The base branch for this repository")]
+    base_branch: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct McpBulkStartRequest {
+    #[schemars(description = "The local project whose tasks should be started")]
+    project_id: Uuid,
+    #[schemars(description = "Only start these tasks (defaults to every task in the project)")]
+    task_ids: Option<Vec<Uuid>>,
+    #[schemars(
+        description = "Only start tasks with this status: 'todo', 'inprogress', 'inreview', 'done' or 'cancelled'"
+    )]
+    status: Option<String>,
+    #[schemars(
+        description = "The coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID')"
+    )]
+    executor: String,
+    #[schemars(description = "Optional executor variant, if needed")]
+    variant: Option<String>,
+    #[schemars(description = "Base branch for each repository the workspaces should use")]
+    repos: Vec<McpBulkRepoInput>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct McpStopAllRequest {
+    #[schemars(description = "The local project whose running processes should be stopped")]
+    project_id: Uuid,
+    #[schemars(description = "Also stop dev servers (default false)")]
+    include_dev_servers: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct McpCleanupWorkspacesRequest {
+    #[schemars(description = "The local project whose workspaces should be cleaned up")]
+    project_id: Uuid,
+    #[schemars(description = "Clean up workspaces without activity for this many days")]
+    older_than_days: u32,
+    #[schemars(description = "Either 'archive' or 'delete'")]
+    action: String,
+    #[schemars(description = "Only list the matching workspaces without changing them")]
+    dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct McpBulkUpdateStatusRequest {
+    #[schemars(description = "The tasks to update")]
+    task_ids: Vec<Uuid>,
+    #[schemars(
+        description = "The new status: 'todo', 'inprogress', 'inreview', 'done' or 'cancelled'"
+    )]
+    status: String,
+}
+
+#[tool_router(router = bulk_tools_router, vis = "pub")]
+impl TaskServer {
+    #[tool(
+        description = "Create many tasks in a local project at once, from a list and/or CSV text. Returns a result for every task."
+    )]
+    async fn bulk_create_tasks(
+        &self,
+        Parameters(McpBulkCreateTasksRequest {
+            project_id,
+            tasks,
+            csv,
+        }): Parameters<McpBulkCreateTasksRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut inputs = Vec::new();
+        for task in tasks.unwrap_or_default() {
+            let status = match task.status.as_deref().map(parse_status).transpose() {
+                Ok(status) => status,
+                Err(e) => return Ok(e),
+            };
+            inputs.push(BulkTaskInput {
+                title: task.title,
+                description: task.description,
+                status,
+            });
+        }
+
+        let payload = BulkCreateTasksRequest {
+            project_id,
+            tasks: inputs,
+            csv,
+        };
+        self.post_bulk("/api/bulk/tasks", &payload).await
+    }
+
+    #[tool(
+        description = "Start a workspace with the same executor for every task in a local project matching the filters. Tasks that already have a running attempt are skipped."
+    )]
+    async fn bulk_start_workspaces(
+        &self,
+        Parameters(McpBulkStartRequest {
+            project_id,
+            task_ids,
+            status,
+            executor,
+            variant,
+            repos,
+        }): Parameters<McpBulkStartRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if repos.is_empty() {
+            return Self::err("At least one repository must be specified.", None::<&str>);
+        }
+        let status = match status.as_deref().map(parse_status).transpose() {
+            Ok(status) => status,
+            Err(e) => return Ok(e),
+        };
+        let executor_config = match parse_executor(&executor, variant) {
+            Ok(config) => config,
+            Err(e) => return Ok(e),
+        };
+
+        let payload = BulkStartAttemptsRequest {
+            project_id,
+            task_ids,
+            status,
+            executor_config,
+            repos: repos
+                .into_iter()
+                .map(|r| WorkspaceRepoInput {
+                    repo_id: r.repo_id,
+                    target_branch: r.base_branch,
+                })
+                .collect(),
+        };
+        self.post_bulk("/api/bulk/tasks/start", &payload).await
+    }
+
+    #[tool(description = "Stop all running coding agents and scripts in a local project.")]
+    async fn stop_all_processes(
+        &self,
+        Parameters(McpStopAllRequest {
+            project_id,
+            include_dev_servers,
+        }): Parameters<McpStopAllRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = BulkStopRequest {
+            project_id,
+            include_dev_servers: include_dev_servers.unwrap_or(false),
+        };
+        self.post_bulk("/api/bulk/stop", &payload).await
+    }
+
+    #[tool(
+        description = "Archive or delete unpinned workspaces in a local project that have had no activity for a number of days. Use dry_run to preview."
+    )]
+    async fn cleanup_workspaces(
+        &self,
+        Parameters(McpCleanupWorkspacesRequest {
+            project_id,
+            older_than_days,
+            action,
+            dry_run,
+        }): Parameters<McpCleanupWorkspacesRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let action = match action.trim().to_ascii_lowercase().as_str() {
+            "archive" => WorkspaceCleanupAction::Archive,
+            "delete" => WorkspaceCleanupAction::Delete,
+            _ => {
+                return Self::err(
+                    format!("Unknown action '{action}'. Use 'archive' or 'delete'."),
+                    None::<String>,
+                );
+            }
+        };
+
+        let payload = BulkCleanupWorkspacesRequest {
+            project_id,
+            older_than_days,
+            action,
+            dry_run: dry_run.unwrap_or(false),
+        };
+        self.post_bulk("/api/bulk/workspaces/cleanup", &payload)
+            .await
+    }
+
+    #[tool(description = "Change the status of many local tasks at once.")]
+    async fn bulk_update_task_status(
+        &self,
+        Parameters(McpBulkUpdateStatusRequest { task_ids, status }): Parameters<
+            McpBulkUpdateStatusRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let status = match parse_status(&status) {
+            Ok(status) => status,
+            Err(e) => return Ok(e),
+        };
+
+        let payload = BulkUpdateStatusRequest { task_ids, status };
+        self.post_bulk("/api/bulk/tasks/status", &payload).await
+    }
+}
+
+impl TaskServer {
+    async fn post_bulk<T: Serialize>(
+        &self,
+        path: &str,
+        payload: &T,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(path);
+        let response: BulkOperationResponse =
+            match self.send_json(self.client.post(&url).json(payload)).await {
+                Ok(response) => response,
+                Err(e) => return Ok(e),
+            };
+        TaskServer::success(&response)
+    }
+}
+
+fn parse_status(status: &str) -> Result<TaskStatus, CallToolResult> {
+    let normalized = status.trim().to_lowercase().replace([' ', '-', '_'], "");
+    TaskStatus::from_str(&normalized).map_err(|_| {
+        TaskServer::err(
+            format!(
+                "Unknown status '{status}'. Use 'todo', 'inprogress', 'inreview', 'done' or 'cancelled'."
+            ),
+            None,
+        )
+        .unwrap()
+    })
+}
+
+fn parse_executor(
+    executor: &str,
+    variant: Option<String>,
+) -> Result<ExecutorConfig, CallToolResult> {
+    let executor = executor.trim();
+    let normalized = executor.replace('-', "_").to_ascii_uppercase();
+    let base_executor = BaseCodingAgent::from_str(&normalized)
+        .map_err(|_| TaskServer::err(format!("Unknown executor '{executor}'."), None).unwrap())?;
+
+    let mut executor_config = ExecutorConfig::new(base_executor);
+    executor_config.variant = variant
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    Ok(executor_config)
+}
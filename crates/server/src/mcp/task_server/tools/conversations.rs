@@ -0,0 +1,97 @@
+use db::models::conversation_search::ConversationSearchMatch;
+use rmcp::{
+    ErrorData, handler::server::tool::Parameters, model::CallToolResult, schemars, tool,
+    tool_router,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::TaskServer;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct SearchConversationsRequest {
+    #[schemars(
+        description = "Text to search for. Every word must match; paths and error codes can be used as-is."
+    )]
+    query: String,
+    #[schemars(description = "Only search conversations from tasks in this local project")]
+    project_id: Option<Uuid>,
+    #[schemars(description = "Maximum number of matches to return (default 20, max 100)")]
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct SearchConversationsResponse {
+    matches: Vec<ConversationSearchMatchSummary>,
+    count: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ConversationSearchMatchSummary {
+    #[schemars(description = "Title of the task the conversation belongs to")]
+    task_title: String,
+    task_id: String,
+    workspace_id: String,
+    session_id: String,
+    execution_process_id: String,
+    #[schemars(description = "Index of the matching entry in the conversation")]
+    entry_index: i64,
+    #[schemars(
+        description = "Where the match was found: user_message, assistant_message, tool_command, file_path or error"
+    )]
+    kind: String,
+    #[schemars(description = "Matching text with the hits wrapped in `**`")]
+    snippet: String,
+}
+
+#[tool_router(router = conversations_tools_router, vis = "pub")]
+impl TaskServer {
+    #[tool(
+        description = "Search past agent conversations (messages, commands run, files touched and errors) across all tasks. Useful for finding how a similar problem was solved before."
+    )]
+    async fn search_conversations(
+        &self,
+        Parameters(SearchConversationsRequest {
+            query,
+            project_id,
+            limit,
+        }): Parameters<SearchConversationsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url("/api/search/conversations");
+        let mut params = vec![("q", query)];
+        if let Some(project_id) = project_id {
+            params.push(("project_id", project_id.to_string()));
+        }
+        if let Some(limit) = limit {
+            params.push(("limit", limit.to_string()));
+        }
+
+        let results: Vec<ConversationSearchMatch> =
+            match self.send_json(self.client.get(&url).query(&params)).await {
+                Ok(results) => results,
+                Err(e) => return Ok(e),
+            };
+
+        let matches: Vec<ConversationSearchMatchSummary> = results
+            .into_iter()
+            .map(|m| ConversationSearchMatchSummary {
+                task_title: m.task_title,
+                task_id: m.task_id.to_string(),
+                workspace_id: m.workspace_id.to_string(),
+                session_id: m.session_id.to_string(),
+                execution_process_id: m.execution_process_id.to_string(),
+                entry_index: m.entry_index,
+                kind: serde_json::to_value(m.kind)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+                snippet: m.snippet,
+            })
+            .collect();
+
+        TaskServer::success(&SearchConversationsResponse {
+            count: matches.len(),
+            matches,
+        })
+    }
+}
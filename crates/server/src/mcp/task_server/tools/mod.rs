@@ -10,7 +10,9 @@ use uuid::Uuid;
 
 use super::{ApiResponseEnvelope, TaskServer};
 
+mod bulk;
 mod context;
+mod conversations;
 mod issue_assignees;
 mod issue_tags;
 mod organizations;
@@ -33,7 +35,9 @@ impl TaskServer {
                 + Self::remote_issues_tools_router()
                 + Self::issue_assignees_tools_router()
                 + Self::issue_tags_tools_router()
-                + Self::task_attempts_tools_router(),
+                + Self::task_attempts_tools_router()
+                + Self::conversations_tools_router()
+                + Self::bulk_tools_router(),
             context: None,
         }
     }
@@ -7,10 +7,12 @@
 //! Host header subdomain. A request to `{port}.localhost:{proxy_port}/path`
 //! is forwarded to `localhost:{port}/path`.
 
+pub mod capture;
+
 use std::sync::OnceLock;
 
 use axum::{
-    Router,
+    Extension, Router,
     body::Body,
     extract::{FromRequestParts, Request, ws::WebSocketUpgrade},
     http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
@@ -21,6 +23,8 @@ use reqwest::Client;
 use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest};
 use tower_http::validate_request::ValidateRequestHeaderLayer;
 
+use self::capture::PreviewCaptures;
+
 /// Global storage for the preview proxy port once assigned.
 /// Set once during server startup, read by the config API.
 static PROXY_PORT: OnceLock<u16> = OnceLock::new();
@@ -99,7 +103,10 @@ fn extract_target_from_host(headers: &HeaderMap) -> Option<u16> {
     subdomain.parse::<u16>().ok()
 }
 
-async fn subdomain_proxy(request: Request) -> Response {
+async fn subdomain_proxy(
+    Extension(captures): Extension<PreviewCaptures>,
+    request: Request,
+) -> Response {
     let target_port = match extract_target_from_host(request.headers()) {
         Some(port) => port,
         None => {
@@ -109,10 +116,15 @@ async fn subdomain_proxy(request: Request) -> Response {
 
     let path = request.uri().path().trim_start_matches('/').to_string();
 
-    proxy_impl(target_port, path, request).await
+    proxy_impl(&captures, target_port, path, request).await
 }
 
-async fn proxy_impl(target_port: u16, path_str: String, request: Request) -> Response {
+async fn proxy_impl(
+    captures: &PreviewCaptures,
+    target_port: u16,
+    path_str: String,
+    request: Request,
+) -> Response {
     let (mut parts, body) = request.into_parts();
 
     // Extract query string and subprotocols before WebSocket upgrade.
@@ -158,10 +170,15 @@ async fn proxy_impl(target_port: u16, path_str: String, request: Request) -> Res
     }
 
     let request = Request::from_parts(parts, body);
-    http_proxy_handler(target_port, path_str, request).await
+    http_proxy_handler(captures, target_port, path_str, request).await
 }
 
-async fn http_proxy_handler(target_port: u16, path_str: String, request: Request) -> Response {
+async fn http_proxy_handler(
+    captures: &PreviewCaptures,
+    target_port: u16,
+    path_str: String,
+    request: Request,
+) -> Response {
     let (parts, body) = request.into_parts();
     let method = parts.method;
     let headers = parts.headers;
@@ -178,6 +195,16 @@ async fn http_proxy_handler(target_port: u16, path_str: String, request: Request
         )
     };
 
+    let mut capture = captures.begin(
+        target_port,
+        method.as_str(),
+        &original_uri
+            .path_and_query()
+            .map(|path| path.to_string())
+            .unwrap_or_else(|| format!("/{}", path_str)),
+        &headers,
+    );
+
     let client = http_client();
 
     let mut req_builder = client.request(
@@ -216,6 +243,10 @@ async fn http_proxy_handler(target_port: u16, path_str: String, request: Request
         }
     };
 
+    if let Some(capture) = &mut capture {
+        capture.request_body(&body_bytes);
+    }
+
     if !body_bytes.is_empty() {
         req_builder = req_builder.body(body_bytes.to_vec());
     }
@@ -224,6 +255,9 @@ async fn http_proxy_handler(target_port: u16, path_str: String, request: Request
         Ok(r) => r,
         Err(e) => {
             tracing::error!("Failed to proxy request to {}: {}", target_url, e);
+            if let Some(capture) = &mut capture {
+                capture.error(e.to_string());
+            }
             return (
                 StatusCode::BAD_GATEWAY,
                 format!("Dev server unreachable: {}", e),
@@ -257,9 +291,17 @@ async fn http_proxy_handler(target_port: u16, path_str: String, request: Request
 
     let status = StatusCode::from_u16(response.status().as_u16()).unwrap_or(StatusCode::OK);
 
+    if let Some(capture) = &mut capture {
+        capture.response(status.as_u16(), &response_headers);
+    }
+
     if is_html {
         match response.bytes().await {
             Ok(body_bytes) => {
+                if let Some(capture) = &mut capture {
+                    capture.response_chunk(&body_bytes);
+                }
+
                 let mut html = String::from_utf8_lossy(&body_bytes).to_string();
 
                 // Inject bippy bundle after <head> (must load before React)
@@ -293,6 +335,9 @@ async fn http_proxy_handler(target_port: u16, path_str: String, request: Request
             }
             Err(e) => {
                 tracing::error!("Failed to read HTML response: {}", e);
+                if let Some(capture) = &mut capture {
+                    capture.error(e.to_string());
+                }
                 (
                     StatusCode::BAD_GATEWAY,
                     "Failed to read response from dev server",
@@ -301,7 +346,13 @@ async fn http_proxy_handler(target_port: u16, path_str: String, request: Request
             }
         }
     } else {
-        let stream = response.bytes_stream();
+        // The capture is recorded when the stream is dropped, once the body is sent
+        let stream = response.bytes_stream().map(move |chunk| {
+            if let (Some(capture), Ok(bytes)) = (&mut capture, &chunk) {
+                capture.response_chunk(bytes);
+            }
+            chunk
+        });
         let body = Body::from_stream(stream);
 
         let mut builder = Response::builder().status(status);
@@ -439,12 +490,13 @@ async fn handle_ws_proxy(
     Ok(())
 }
 
-pub fn router<S>() -> Router<S>
+pub fn router<S>(captures: PreviewCaptures) -> Router<S>
 where
     S: Clone + Send + Sync + 'static,
 {
     Router::new()
         .fallback(subdomain_proxy)
+        .layer(Extension(captures))
         .layer(ValidateRequestHeaderLayer::custom(
             crate::middleware::validate_origin,
         ))
@@ -0,0 +1,672 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::post,
+};
+use chrono::{Duration, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    project::Project,
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus},
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    bulk_tasks::{BulkTaskInput, parse_task_csv},
+    container::ContainerService,
+};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    preview_proxy::capture::PreviewCaptures,
+    routes::{
+        task_attempts::{self, CreateTaskAttemptBody, DeleteWorkspaceQuery, WorkspaceRepoInput},
+        tasks,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct BulkItemResult {
+    /// Id of the task or workspace; `None` when a task could not be created
+    pub id: Option<Uuid>,
+    /// Task title or workspace branch, to identify the item in reports
+    pub name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct BulkOperationResponse {
+    pub results: Vec<BulkItemResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct BulkCreateTasksRequest {
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub tasks: Vec<BulkTaskInput>,
+    /// CSV with a `title` column and optional `description` and `status` columns; its
+    /// rows are created after `tasks`
+    pub csv: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct BulkStartAttemptsRequest {
+    pub project_id: Uuid,
+    /// Only start these tasks
+    pub task_ids: Option<Vec<Uuid>>,
+    /// Only start tasks with this status. Without `task_ids` or `status`, every todo and
+    /// in-progress task in the project is started.
+    pub status: Option<TaskStatus>,
+    pub executor_config: ExecutorConfig,
+    pub repos: Vec<WorkspaceRepoInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct BulkStopRequest {
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub include_dev_servers: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceCleanupAction {
+    Archive,
+    Delete,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct BulkCleanupWorkspacesRequest {
+    pub project_id: Uuid,
+    /// Workspaces without activity for this many days are cleaned up
+    pub older_than_days: u32,
+    pub action: WorkspaceCleanupAction,
+    /// Report the matching workspaces without changing them
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct BulkUpdateStatusRequest {
+    pub task_ids: Vec<Uuid>,
+    pub status: TaskStatus,
+}
+
+impl BulkItemResult {
+    fn from_result<T>(id: Option<Uuid>, name: String, result: Result<T, ApiError>) -> Self {
+        let error = result.err().map(|e| e.to_string());
+        Self {
+            id,
+            name,
+            success: error.is_none(),
+            error,
+        }
+    }
+}
+
+impl From<Vec<BulkItemResult>> for BulkOperationResponse {
+    fn from(results: Vec<BulkItemResult>) -> Self {
+        let succeeded = results.iter().filter(|r| r.success).count();
+        Self {
+            failed: results.len() - succeeded,
+            succeeded,
+            results,
+        }
+    }
+}
+
+async fn ensure_project(deployment: &DeploymentImpl, project_id: Uuid) -> Result<(), ApiError> {
+    Project::find_by_id(&deployment.db().pool, project_id)
+        .await?
+        .map(|_| ())
+        .ok_or_else(|| ApiError::BadRequest("Project not found".to_string()))
+}
+
+async fn track_bulk_operation(
+    deployment: &DeploymentImpl,
+    operation: &str,
+    response: &BulkOperationResponse,
+) {
+    deployment
+        .track_if_analytics_allowed(
+            "bulk_operation",
+            serde_json::json!({
+                "operation": operation,
+                "succeeded": response.succeeded,
+                "failed": response.failed,
+            }),
+        )
+        .await;
+}
+
+pub async fn bulk_create_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<BulkCreateTasksRequest>,
+) -> Result<ResponseJson<ApiResponse<BulkOperationResponse>>, ApiError> {
+    ensure_project(&deployment, payload.project_id).await?;
+    let mut inputs = payload.tasks;
+    if let Some(csv) = &payload.csv {
+        inputs.extend(parse_task_csv(csv)?);
+    }
+    if inputs.is_empty() {
+        return Err(ApiError::BadRequest("No tasks to create".to_string()));
+    }
+
+    let mut results = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let title = input.title.trim().to_string();
+        if title.is_empty() {
+            results.push(BulkItemResult::from_result::<()>(
+                None,
+                title,
+                Err(ApiError::BadRequest(
+                    "Task title cannot be empty".to_string(),
+                )),
+            ));
+            continue;
+        }
+        let created = tasks::create_task(
+            State(deployment.clone()),
+            Json(CreateTask {
+                project_id: payload.project_id,
+                title: title.clone(),
+                description: input.description,
+                status: input.status,
+                parent_workspace_id: None,
+                image_ids: None,
+            }),
+        )
+        .await
+        .map(|response| response.0.into_data());
+        let id = created
+            .as_ref()
+            .ok()
+            .and_then(Option::as_ref)
+            .map(|task| task.id);
+        results.push(BulkItemResult::from_result(id, title, created));
+    }
+
+    let response = BulkOperationResponse::from(results);
+    track_bulk_operation(&deployment, "create_tasks", &response).await;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+pub async fn bulk_start_attempts(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<BulkStartAttemptsRequest>,
+) -> Result<ResponseJson<ApiResponse<BulkOperationResponse>>, ApiError> {
+    ensure_project(&deployment, payload.project_id).await?;
+    if payload.repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let selection = tasks_to_start(
+        &deployment.db().pool,
+        payload.project_id,
+        payload.task_ids.as_deref(),
+        payload.status.as_ref(),
+    )
+    .await?;
+
+    let mut results: Vec<_> = selection
+        .missing
+        .into_iter()
+        .map(|task_id| {
+            BulkItemResult::from_result::<()>(
+                Some(task_id),
+                String::new(),
+                Err(ApiError::BadRequest("Task not found".to_string())),
+            )
+        })
+        .collect();
+    for task in selection.tasks {
+        if task.has_in_progress_attempt {
+            results.push(BulkItemResult::from_result::<()>(
+                Some(task.id),
+                task.task.title,
+                Err(ApiError::Conflict(
+                    "Task already has a running attempt".to_string(),
+                )),
+            ));
+            continue;
+        }
+        let started = task_attempts::create_task_attempt(
+            State(deployment.clone()),
+            Json(CreateTaskAttemptBody {
+                task_id: task.id,
+                executor_config: payload.executor_config.clone(),
+                repos: payload
+                    .repos
+                    .iter()
+                    .map(|r| WorkspaceRepoInput {
+                        repo_id: r.repo_id,
+                        target_branch: r.target_branch.clone(),
+                    })
+                    .collect(),
+            }),
+        )
+        .await;
+        results.push(BulkItemResult::from_result(
+            Some(task.id),
+            task.task.title,
+            started,
+        ));
+    }
+
+    let response = BulkOperationResponse::from(results);
+    track_bulk_operation(&deployment, "start_attempts", &response).await;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+struct TaskSelection {
+    tasks: Vec<TaskWithAttemptStatus>,
+    /// Selected ids that are not tasks of the project
+    missing: Vec<Uuid>,
+}
+
+/// Tasks a bulk start applies to. Without an explicit selection, tasks that are already
+/// in review, done or cancelled are left alone.
+async fn tasks_to_start(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    task_ids: Option<&[Uuid]>,
+    status: Option<&TaskStatus>,
+) -> Result<TaskSelection, sqlx::Error> {
+    let project_tasks = Task::find_by_project_id_with_attempt_status(pool, project_id).await?;
+    let known: HashSet<Uuid> = project_tasks.iter().map(|t| t.id).collect();
+    let mut missing = Vec::new();
+    for id in task_ids.into_iter().flatten() {
+        if !known.contains(id) && !missing.contains(id) {
+            missing.push(*id);
+        }
+    }
+
+    let task_ids: Option<HashSet<&Uuid>> = task_ids.map(|ids| ids.iter().collect());
+    let needs_work = |s: &TaskStatus| matches!(s, TaskStatus::Todo | TaskStatus::InProgress);
+    let tasks = project_tasks
+        .into_iter()
+        .filter(|t| task_ids.as_ref().is_none_or(|ids| ids.contains(&t.id)))
+        .filter(|t| match status {
+            Some(status) => &t.status == status,
+            None => task_ids.is_some() || needs_work(&t.status),
+        })
+        .collect();
+    Ok(TaskSelection { tasks, missing })
+}
+
+/// Running processes of the project's workspaces, grouped by workspace.
+async fn running_processes_by_workspace(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    include_dev_servers: bool,
+) -> Result<Vec<(Workspace, Vec<ExecutionProcess>)>, sqlx::Error> {
+    let mut workspaces: HashMap<Uuid, (Workspace, Vec<ExecutionProcess>)> = HashMap::new();
+    let mut project_tasks: HashMap<Uuid, bool> = HashMap::new();
+    for process in ExecutionProcess::find_running(pool).await? {
+        if process.run_reason == ExecutionProcessRunReason::DevServer && !include_dev_servers {
+            continue;
+        }
+        let Some((workspace, _)) = process.parent_workspace_and_session(pool).await? else {
+            continue;
+        };
+        if let Some((_, processes)) = workspaces.get_mut(&workspace.id) {
+            processes.push(process);
+            continue;
+        }
+        let in_project = match project_tasks.get(&workspace.task_id) {
+            Some(in_project) => *in_project,
+            None => {
+                let in_project = Task::find_by_id(pool, workspace.task_id)
+                    .await?
+                    .is_some_and(|t| t.project_id == project_id);
+                project_tasks.insert(workspace.task_id, in_project);
+                in_project
+            }
+        };
+        if in_project {
+            workspaces.insert(workspace.id, (workspace, vec![process]));
+        }
+    }
+
+    let mut workspaces: Vec<_> = workspaces.into_values().collect();
+    workspaces.sort_by(|(a, _), (b, _)| a.branch.cmp(&b.branch));
+    Ok(workspaces)
+}
+
+/// Stop every running process in the project's workspaces, one result per workspace. A
+/// workspace fails if any of its processes could not be stopped.
+pub async fn bulk_stop(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<BulkStopRequest>,
+) -> Result<ResponseJson<ApiResponse<BulkOperationResponse>>, ApiError> {
+    ensure_project(&deployment, payload.project_id).await?;
+    let workspaces = running_processes_by_workspace(
+        &deployment.db().pool,
+        payload.project_id,
+        payload.include_dev_servers,
+    )
+    .await?;
+
+    let mut results = Vec::with_capacity(workspaces.len());
+    for (workspace, processes) in workspaces {
+        let mut outcome = Ok(());
+        for process in &processes {
+            if let Err(e) = deployment
+                .container()
+                .stop_execution(process, ExecutionProcessStatus::Killed)
+                .await
+            {
+                tracing::warn!("Failed to stop execution process {}: {}", process.id, e);
+                outcome = Err(ApiError::from(e));
+            }
+        }
+        results.push(BulkItemResult::from_result(
+            Some(workspace.id),
+            workspace.branch,
+            outcome,
+        ));
+    }
+
+    let response = BulkOperationResponse::from(results);
+    track_bulk_operation(&deployment, "stop", &response).await;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+/// Archive or delete unpinned workspaces in the project that have not been updated for
+/// `older_than_days`. Workspaces with running processes fail to delete.
+pub async fn bulk_cleanup_workspaces(
+    Extension(captures): Extension<PreviewCaptures>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<BulkCleanupWorkspacesRequest>,
+) -> Result<ResponseJson<ApiResponse<BulkOperationResponse>>, ApiError> {
+    ensure_project(&deployment, payload.project_id).await?;
+    let pool = &deployment.db().pool;
+    let cutoff = Utc::now() - Duration::days(i64::from(payload.older_than_days));
+
+    let mut stale = Vec::new();
+    for task in Task::find_by_project_id_with_attempt_status(pool, payload.project_id).await? {
+        stale.extend(
+            Workspace::fetch_all(pool, Some(task.id))
+                .await?
+                .into_iter()
+                .filter(|w| !w.pinned && w.updated_at < cutoff)
+                .filter(|w| payload.action == WorkspaceCleanupAction::Delete || !w.archived),
+        );
+    }
+
+    let mut results = Vec::with_capacity(stale.len());
+    for workspace in stale {
+        let outcome = if payload.dry_run {
+            Ok(())
+        } else {
+            match payload.action {
+                WorkspaceCleanupAction::Archive => deployment
+                    .container()
+                    .archive_workspace(workspace.id)
+                    .await
+                    .map_err(ApiError::from),
+                WorkspaceCleanupAction::Delete => task_attempts::delete_workspace(
+                    Extension(workspace.clone()),
+                    Extension(captures.clone()),
+                    State(deployment.clone()),
+                    Query(DeleteWorkspaceQuery {
+                        delete_remote: false,
+                        delete_branches: false,
+                    }),
+                )
+                .await
+                .map(|_| ()),
+            }
+        };
+        results.push(BulkItemResult::from_result(
+            Some(workspace.id),
+            workspace.branch,
+            outcome,
+        ));
+    }
+
+    let response = BulkOperationResponse::from(results);
+    if !payload.dry_run {
+        let operation = match payload.action {
+            WorkspaceCleanupAction::Archive => "archive_workspaces",
+            WorkspaceCleanupAction::Delete => "delete_workspaces",
+        };
+        track_bulk_operation(&deployment, operation, &response).await;
+    }
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+pub async fn bulk_update_status(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<BulkUpdateStatusRequest>,
+) -> Result<ResponseJson<ApiResponse<BulkOperationResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let mut results = Vec::with_capacity(payload.task_ids.len());
+    for task_id in payload.task_ids {
+        let Some(task) = Task::find_by_id(pool, task_id).await? else {
+            results.push(BulkItemResult::from_result::<()>(
+                Some(task_id),
+                String::new(),
+                Err(ApiError::BadRequest("Task not found".to_string())),
+            ));
+            continue;
+        };
+        let updated = Task::update_status(pool, task.id, payload.status.clone())
+            .await
+            .map_err(ApiError::from);
+        results.push(BulkItemResult::from_result(
+            Some(task.id),
+            task.title,
+            updated,
+        ));
+    }
+
+    let response = BulkOperationResponse::from(results);
+    track_bulk_operation(&deployment, "update_status", &response).await;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/tasks", post(bulk_create_tasks))
+        .route("/tasks/start", post(bulk_start_attempts))
+        .route("/tasks/status", post(bulk_update_status))
+        .route("/stop", post(bulk_stop))
+        .route("/workspaces/cleanup", post(bulk_cleanup_workspaces));
+
+    Router::new().nest("/bulk", inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        DBService,
+        test_utils::{
+            create_execution_process, create_project, create_session, create_task, create_workspace,
+        },
+    };
+    use executors::actions::{
+        ExecutorAction, ExecutorActionType,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    };
+
+    use super::*;
+
+    async fn project(pool: &SqlitePool) -> Uuid {
+        create_project(pool, "bulk").await.id
+    }
+
+    async fn task(pool: &SqlitePool, project_id: Uuid, title: &str, status: TaskStatus) -> Uuid {
+        let task = create_task(pool, project_id, title).await;
+        Task::update_status(pool, task.id, status).await.unwrap();
+        task.id
+    }
+
+    async fn running_process(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        branch: &str,
+        run_reason: ExecutionProcessRunReason,
+    ) -> ExecutionProcess {
+        let workspace = create_workspace(pool, task_id, branch).await;
+        let session = create_session(pool, workspace.id).await;
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: "sleep 60".to_string(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::DevServer,
+                working_dir: None,
+                dev_process: None,
+            }),
+            None,
+        );
+        create_execution_process(pool, session.id, action, run_reason, &[]).await
+    }
+
+    #[tokio::test]
+    async fn starts_only_unfinished_tasks_without_a_selection() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project_id = project(pool).await;
+        let todo = task(pool, project_id, "todo", TaskStatus::Todo).await;
+        let in_progress = task(pool, project_id, "in progress", TaskStatus::InProgress).await;
+        let done = task(pool, project_id, "done", TaskStatus::Done).await;
+        task(pool, project_id, "in review", TaskStatus::InReview).await;
+
+        let ids = |selection: TaskSelection| {
+            let mut ids: Vec<Uuid> = selection.tasks.iter().map(|t| t.id).collect();
+            ids.sort();
+            ids
+        };
+        let mut expected = vec![todo, in_progress];
+        expected.sort();
+        assert_eq!(
+            ids(tasks_to_start(pool, project_id, None, None).await.unwrap()),
+            expected
+        );
+
+        // An explicit selection is started whatever its status
+        assert_eq!(
+            ids(tasks_to_start(pool, project_id, Some(&[done]), None)
+                .await
+                .unwrap()),
+            vec![done]
+        );
+        assert_eq!(
+            ids(
+                tasks_to_start(pool, project_id, None, Some(&TaskStatus::Done))
+                    .await
+                    .unwrap()
+            ),
+            vec![done]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_selected_ids_outside_the_project() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project_id = project(pool).await;
+        let todo = task(pool, project_id, "todo", TaskStatus::Todo).await;
+        let other_project = project(pool).await;
+        let foreign = task(pool, other_project, "foreign", TaskStatus::Todo).await;
+        let unknown = Uuid::new_v4();
+
+        let selection = tasks_to_start(pool, project_id, Some(&[todo, foreign, unknown]), None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            selection.tasks.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![todo]
+        );
+        assert_eq!(selection.missing, vec![foreign, unknown]);
+    }
+
+    #[tokio::test]
+    async fn groups_running_processes_of_the_project_by_workspace() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let project_id = project(pool).await;
+        let task_id = task(pool, project_id, "task", TaskStatus::InProgress).await;
+        let agent = running_process(
+            pool,
+            task_id,
+            "vk/agent",
+            ExecutionProcessRunReason::CodingAgent,
+        )
+        .await;
+        let dev_server = running_process(
+            pool,
+            task_id,
+            "vk/dev",
+            ExecutionProcessRunReason::DevServer,
+        )
+        .await;
+        let other_project = project(pool).await;
+        let other_task = task(pool, other_project, "other", TaskStatus::InProgress).await;
+        running_process(
+            pool,
+            other_task,
+            "vk/other",
+            ExecutionProcessRunReason::CodingAgent,
+        )
+        .await;
+
+        let summary = |workspaces: Vec<(Workspace, Vec<ExecutionProcess>)>| {
+            workspaces
+                .into_iter()
+                .map(|(w, processes)| (w.branch, processes.into_iter().map(|p| p.id).collect()))
+                .collect::<Vec<(String, Vec<Uuid>)>>()
+        };
+        assert_eq!(
+            summary(
+                running_processes_by_workspace(pool, project_id, false)
+                    .await
+                    .unwrap()
+            ),
+            vec![("vk/agent".to_string(), vec![agent.id])]
+        );
+        assert_eq!(
+            summary(
+                running_processes_by_workspace(pool, project_id, true)
+                    .await
+                    .unwrap()
+            ),
+            vec![
+                ("vk/agent".to_string(), vec![agent.id]),
+                ("vk/dev".to_string(), vec![dev_server.id]),
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_succeeded_and_failed_items() {
+        let response = BulkOperationResponse::from(vec![
+            BulkItemResult::from_result::<()>(None, "a".to_string(), Ok(())),
+            BulkItemResult::from_result::<()>(
+                None,
+                "b".to_string(),
+                Err(ApiError::Conflict("busy".to_string())),
+            ),
+        ]);
+        assert_eq!((response.succeeded, response.failed), (1, 1));
+        assert_eq!(response.results[1].error.as_deref(), Some("Conflict: busy"));
+    }
+}
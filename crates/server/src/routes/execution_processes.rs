@@ -1,6 +1,6 @@
 use anyhow;
 use axum::{
-    Extension, Router,
+    Extension, Json, Router,
     extract::{
         Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
@@ -11,13 +11,19 @@ use axum::{
 };
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus},
+    execution_process_redactions::ExecutionProcessRedactions,
     execution_process_repo_state::ExecutionProcessRepoState,
 };
 use deployment::Deployment;
+use executors::executors::{ExecutorError, replay::ReplayFixture};
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
-use services::services::container::ContainerService;
-use utils::{log_msg::LogMsg, response::ApiResponse};
+use services::services::{
+    container::{ContainerError, ContainerService},
+    replay_fixture::{self, ReplayFixtureError},
+};
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, redact::RedactionCounts, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware};
@@ -178,6 +184,90 @@ pub async fn stop_execution_process(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Request body for injecting a message into a running execution
+#[derive(Debug, Deserialize, TS)]
+pub struct SendUserInputRequest {
+    pub message: String,
+}
+
+/// Send a steering message to a coding agent while its turn is in progress
+pub async fn send_user_input(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SendUserInputRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let message = payload.message.trim();
+    if message.is_empty() {
+        return Err(ApiError::BadRequest("Message cannot be empty".to_string()));
+    }
+    if execution_process.status != ExecutionProcessStatus::Running {
+        return Err(ApiError::BadRequest(
+            "Execution process is not running".to_string(),
+        ));
+    }
+
+    match deployment
+        .container()
+        .send_user_input(execution_process.id, message.to_string())
+        .await
+    {
+        Ok(()) => {}
+        Err(ContainerError::ExecutorError(
+            e @ (ExecutorError::UserInputNotSupported | ExecutorError::InputUnavailable(_)),
+        )) => return Err(ApiError::BadRequest(e.to_string())),
+        Err(e) => return Err(e.into()),
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "execution_user_input_sent",
+            serde_json::json!({
+                "execution_process_id": execution_process.id.to_string(),
+                "session_id": execution_process.session_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Interrupt the agent's current turn. Unlike stopping, the process keeps running and
+/// continues with the next message sent to its input, or is stopped when none arrives.
+pub async fn interrupt_execution_process(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if execution_process.status != ExecutionProcessStatus::Running {
+        return Err(ApiError::BadRequest(
+            "Execution process is not running".to_string(),
+        ));
+    }
+
+    match deployment
+        .container()
+        .interrupt_execution(execution_process.id)
+        .await
+    {
+        Ok(()) => {}
+        Err(ContainerError::ExecutorError(
+            e @ (ExecutorError::InterruptNotSupported | ExecutorError::InputUnavailable(_)),
+        )) => return Err(ApiError::BadRequest(e.to_string())),
+        Err(e) => return Err(e.into()),
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "execution_interrupted",
+            serde_json::json!({
+                "execution_process_id": execution_process.id.to_string(),
+                "session_id": execution_process.session_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub async fn stream_execution_processes_by_session_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -243,11 +333,60 @@ pub async fn get_execution_process_repo_states(
     Ok(ResponseJson(ApiResponse::success(repo_states)))
 }
 
+/// Secrets masked in the process output so far.
+pub async fn get_execution_process_redactions(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RedactionCounts>>, ApiError> {
+    let live = {
+        let map = deployment.container().log_redactors().read().await;
+        map.get(&execution_process.id)
+            .map(|redactor| redactor.counts())
+    };
+    let counts = match live {
+        Some(counts) => counts,
+        None => {
+            ExecutionProcessRedactions::find_counts(&deployment.db().pool, execution_process.id)
+                .await?
+                .unwrap_or_default()
+        }
+    };
+    Ok(ResponseJson(ApiResponse::success(counts)))
+}
+
+/// Export a finished execution as a replay fixture for deterministic tests.
+pub async fn get_execution_process_fixture(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ReplayFixture>>, ApiError> {
+    if execution_process.status == ExecutionProcessStatus::Running {
+        return Err(ApiError::Conflict(
+            "Execution is still running; record the fixture once it has finished".to_string(),
+        ));
+    }
+
+    let fixture =
+        replay_fixture::record_fixture(&deployment.db().pool, deployment.git(), &execution_process)
+            .await
+            .map_err(|e| match e {
+                ReplayFixtureError::NotCodingAgent => ApiError::BadRequest(e.to_string()),
+                ReplayFixtureError::Database(e) => ApiError::Database(e),
+                ReplayFixtureError::GitService(e) => ApiError::GitService(e),
+                ReplayFixtureError::Logs(e) => ApiError::BadRequest(e.to_string()),
+            })?;
+
+    Ok(ResponseJson(ApiResponse::success(fixture)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
+        .route("/input", post(send_user_input))
+        .route("/interrupt", post(interrupt_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route("/redactions", get(get_execution_process_redactions))
+        .route("/fixture", get(get_execution_process_fixture))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
@@ -0,0 +1,169 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    issue_import::{CreateIssueImportSource, IssueImportSource, IssueTrackerKind},
+    project::Project,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::issue_import::{self, IssueSyncSummary};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_issue_import_source_middleware};
+
+#[derive(Debug, Deserialize)]
+pub struct IssueImportQuery {
+    pub project_id: Option<Uuid>,
+}
+
+/// The payload with its API token encrypted for storage. A blank token counts as not set.
+async fn seal_token(
+    deployment: &DeploymentImpl,
+    payload: CreateIssueImportSource,
+) -> Result<CreateIssueImportSource, ApiError> {
+    let api_token = match payload.api_token.as_deref().map(str::trim) {
+        Some(token) if !token.is_empty() => Some(deployment.secrets().encrypt_value(token).await?),
+        _ => None,
+    };
+    Ok(CreateIssueImportSource {
+        api_token,
+        ..payload
+    })
+}
+
+fn validate_source(payload: &CreateIssueImportSource) -> Result<(), ApiError> {
+    if payload.repository.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "A repository or project key is required".to_string(),
+        ));
+    }
+    let has_base_url = payload
+        .base_url
+        .as_deref()
+        .is_some_and(|url| !url.trim().is_empty());
+    if payload.tracker != IssueTrackerKind::Github && !has_base_url {
+        return Err(ApiError::BadRequest(
+            "GitLab and Jira sources need a base URL".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn get_issue_import_sources(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<IssueImportQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<IssueImportSource>>>, ApiError> {
+    let sources = IssueImportSource::find_all(&deployment.db().pool, query.project_id).await?;
+    Ok(ResponseJson(ApiResponse::success(sources)))
+}
+
+pub async fn get_issue_import_source(
+    Extension(source): Extension<IssueImportSource>,
+) -> Result<ResponseJson<ApiResponse<IssueImportSource>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(source)))
+}
+
+pub async fn create_issue_import_source(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateIssueImportSource>,
+) -> Result<ResponseJson<ApiResponse<IssueImportSource>>, ApiError> {
+    validate_source(&payload)?;
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, payload.project_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest("Project not found".to_string()));
+    }
+    let payload = seal_token(&deployment, payload).await?;
+    let source = IssueImportSource::create(pool, &payload, Uuid::new_v4()).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "issue_import_source_created",
+            serde_json::json!({
+                "source_id": source.id.to_string(),
+                "tracker": source.tracker,
+                "label_count": payload.labels.len(),
+                "comment_pr_link": payload.comment_pr_link,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(source)))
+}
+
+pub async fn update_issue_import_source(
+    Extension(source): Extension<IssueImportSource>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateIssueImportSource>,
+) -> Result<ResponseJson<ApiResponse<IssueImportSource>>, ApiError> {
+    validate_source(&payload)?;
+    let payload = seal_token(&deployment, payload).await?;
+    let source = IssueImportSource::update(&deployment.db().pool, source.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(source)))
+}
+
+pub async fn delete_issue_import_source(
+    Extension(source): Extension<IssueImportSource>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = IssueImportSource::delete(&deployment.db().pool, source.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub async fn sync_issue_import_source(
+    Extension(source): Extension<IssueImportSource>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<IssueSyncSummary>>, ApiError> {
+    let summary =
+        issue_import::sync_source(&deployment.db().pool, deployment.secrets(), &source).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "issue_import_source_synced",
+            serde_json::json!({
+                "source_id": source.id.to_string(),
+                "tracker": source.tracker,
+                "created": summary.created,
+                "updated": summary.updated,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let source_router = Router::new()
+        .route(
+            "/",
+            get(get_issue_import_source)
+                .put(update_issue_import_source)
+                .delete(delete_issue_import_source),
+        )
+        .route("/sync", post(sync_issue_import_source))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_issue_import_source_middleware,
+        ));
+
+    let inner = Router::new()
+        .route(
+            "/",
+            get(get_issue_import_sources).post(create_issue_import_source),
+        )
+        .nest("/{source_id}", source_router);
+
+    Router::new().nest("/issue-imports", inner)
+}
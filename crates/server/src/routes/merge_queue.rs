@@ -0,0 +1,103 @@
+use axum::{
+    Router,
+    extract::{
+        Path, Query, State,
+        ws::{WebSocket, WebSocketUpgrade},
+    },
+    response::{IntoResponse, Json as ResponseJson},
+    routing::{get, post},
+};
+use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt, TryStreamExt};
+use serde::Deserialize;
+use services::services::merge_queue::{MergeQueueEntry, MergeQueueError};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct MergeQueueQuery {
+    pub repo_id: Option<Uuid>,
+}
+
+pub async fn list_merge_queue(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<MergeQueueQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeQueueEntry>>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        deployment.merge_queue().list(query.repo_id).await?,
+    )))
+}
+
+pub async fn cancel_merge_queue_entry(
+    State(deployment): State<DeploymentImpl>,
+    Path(entry_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    let entry = deployment
+        .merge_queue()
+        .cancel(entry_id)
+        .await
+        .map_err(|e| match e {
+            MergeQueueError::NotFound => ApiError::BadRequest(e.to_string()),
+            MergeQueueError::Database(e) => ApiError::Database(e),
+            e => ApiError::Conflict(e.to_string()),
+        })?;
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+pub async fn stream_merge_queue_ws(
+    ws: WebSocketUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<MergeQueueQuery>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_merge_queue_ws(socket, deployment, query.repo_id).await {
+            tracing::warn!("merge queue WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_merge_queue_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    repo_id: Option<Uuid>,
+) -> anyhow::Result<()> {
+    let mut stream = deployment
+        .merge_queue()
+        .stream_raw(repo_id)
+        .await?
+        .map_ok(|msg| msg.to_ws_message_unchecked());
+
+    // Split socket into sender and receiver
+    let (mut sender, mut receiver) = socket.split();
+
+    // Drain (and ignore) any client->server messages so pings/pongs work
+    tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
+
+    // Forward server messages
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(msg) => {
+                if sender.send(msg).await.is_err() {
+                    break; // client disconnected
+                }
+            }
+            Err(e) => {
+                tracing::error!("stream error: {}", e);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/merge-queue", get(list_merge_queue))
+        .route("/merge-queue/stream/ws", get(stream_merge_queue_ws))
+        .route(
+            "/merge-queue/{entry_id}/cancel",
+            post(cancel_merge_queue_entry),
+        )
+}
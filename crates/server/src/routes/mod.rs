@@ -1,12 +1,13 @@
 use axum::{
-    Router,
+    Extension, Router,
     routing::{IntoMakeService, get},
 };
 use tower_http::validate_request::ValidateRequestHeaderLayer;
 
-use crate::{DeploymentImpl, middleware};
+use crate::{DeploymentImpl, middleware, preview_proxy::capture::PreviewCaptures};
 
 pub mod approvals;
+pub mod bulk;
 pub mod config;
 pub mod containers;
 pub mod filesystem;
@@ -16,6 +17,8 @@ pub mod execution_processes;
 pub mod frontend;
 pub mod health;
 pub mod images;
+pub mod issue_imports;
+pub mod merge_queue;
 pub mod migration;
 pub mod oauth;
 pub mod organizations;
@@ -24,13 +27,15 @@ pub mod remote;
 pub mod repo;
 pub mod scratch;
 pub mod search;
+pub mod secrets;
 pub mod sessions;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_templates;
 pub mod tasks;
 pub mod terminal;
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+pub fn router(deployment: DeploymentImpl, captures: PreviewCaptures) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
     let base_routes = Router::new()
         .route("/health", get(health::health_check))
@@ -41,14 +46,19 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
+        .merge(task_templates::router(&deployment))
+        .merge(bulk::router())
+        .merge(issue_imports::router(&deployment))
         .merge(oauth::router())
         .merge(organizations::router())
         .merge(filesystem::router())
         .merge(repo::router())
         .merge(events::router(&deployment))
         .merge(approvals::router())
+        .merge(merge_queue::router())
         .merge(scratch::router(&deployment))
         .merge(search::router(&deployment))
+        .merge(secrets::router())
         .merge(migration::router())
         .merge(sessions::router(&deployment))
         .merge(terminal::router())
@@ -57,6 +67,7 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .layer(ValidateRequestHeaderLayer::custom(
             middleware::validate_origin,
         ))
+        .layer(Extension(captures))
         .with_state(deployment);
 
     Router::new()
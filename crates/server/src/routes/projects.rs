@@ -14,6 +14,7 @@ use axum::{
 };
 use db::models::{
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
+    project_redaction_settings::{ProjectRedactionSettings, UpdateProjectRedactionSettings},
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
 };
@@ -433,6 +434,37 @@ pub async fn get_project_repository(
     }
 }
 
+pub async fn get_project_redaction_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectRedactionSettings>>, ApiError> {
+    let settings =
+        ProjectRedactionSettings::find_or_default(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Takes effect for processes started afterwards.
+pub async fn update_project_redaction_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectRedactionSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectRedactionSettings>>, ApiError> {
+    let settings =
+        ProjectRedactionSettings::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_redaction_settings_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "allowlist_size": settings.allowlist.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -441,6 +473,10 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
+        .route(
+            "/redaction-settings",
+            get(get_project_redaction_settings).put(update_project_redaction_settings),
+        )
         .route(
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
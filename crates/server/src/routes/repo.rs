@@ -7,12 +7,17 @@ use axum::{
 };
 use db::models::{
     project::SearchResult,
-    repo::{Repo, UpdateRepo},
+    repo::{Repo, RepoError, UpdateRepo},
+    repo_commit_policy::{RepoCommitPolicy, UpdateRepoCommitPolicy},
+    repo_context_pack::{RepoContextPack, UpdateRepoContextPack},
+    repo_dev_process::{RepoDevProcess, UpsertRepoDevProcess},
+    repo_dev_server_settings::{RepoDevServerSettings, UpdateRepoDevServerSettings},
 };
 use deployment::Deployment;
 use git::{GitBranch, GitRemote};
 use serde::{Deserialize, Serialize};
 use services::services::{
+    dev_server,
     file_search::SearchQuery,
     git_host::{GitHostError, GitHostProvider, GitHostService, OpenPrInfo, ProviderKind},
 };
@@ -144,6 +149,182 @@ pub async fn update_repo(
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
 
+pub async fn get_repo_commit_policy(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoCommitPolicy>>, ApiError> {
+    let policy = RepoCommitPolicy::find_or_default(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+pub async fn update_repo_commit_policy(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<UpdateRepoCommitPolicy>,
+) -> Result<ResponseJson<ApiResponse<RepoCommitPolicy>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let has_name = payload
+        .author_name
+        .as_deref()
+        .is_some_and(|s| !s.trim().is_empty());
+    let has_email = payload
+        .author_email
+        .as_deref()
+        .is_some_and(|s| !s.trim().is_empty());
+    if has_name != has_email {
+        return Err(ApiError::BadRequest(
+            "Commit author name and email must be set together".to_string(),
+        ));
+    }
+
+    let policy = RepoCommitPolicy::upsert(pool, repo_id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "repo_commit_policy_updated",
+            serde_json::json!({
+                "repo_id": repo_id.to_string(),
+                "message_style": policy.message_style,
+                "squash_on_finish": policy.squash_on_finish,
+                "signoff": policy.signoff,
+                "gpg_sign": policy.gpg_sign,
+                "custom_author": has_name,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+/// Below this a pack is little more than its header; above it the pack crowds out the task.
+const CONTEXT_PACK_TOKEN_BUDGET_RANGE: std::ops::RangeInclusive<i64> = 200..=32_000;
+
+pub async fn get_repo_context_pack(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoContextPack>>, ApiError> {
+    let settings = RepoContextPack::find_or_default(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_repo_context_pack(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<UpdateRepoContextPack>,
+) -> Result<ResponseJson<ApiResponse<RepoContextPack>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    if !CONTEXT_PACK_TOKEN_BUDGET_RANGE.contains(&payload.token_budget) {
+        return Err(ApiError::BadRequest(format!(
+            "Context pack token budget must be between {} and {}",
+            CONTEXT_PACK_TOKEN_BUDGET_RANGE.start(),
+            CONTEXT_PACK_TOKEN_BUDGET_RANGE.end()
+        )));
+    }
+
+    let settings = RepoContextPack::upsert(pool, repo_id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "repo_context_pack_updated",
+            serde_json::json!({
+                "repo_id": repo_id.to_string(),
+                "enabled": settings.enabled,
+                "delivery": settings.delivery,
+                "token_budget": settings.token_budget,
+                "has_notes": settings.notes.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_repo_dev_server_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoDevServerSettings>>, ApiError> {
+    let settings = RepoDevServerSettings::find_or_default(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_repo_dev_server_settings(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<UpdateRepoDevServerSettings>,
+) -> Result<ResponseJson<ApiResponse<RepoDevServerSettings>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    if let Some(pattern) = payload.ready_pattern.as_deref().filter(|s| !s.is_empty())
+        && let Err(e) = regex::Regex::new(pattern)
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid dev server ready pattern: {e}"
+        )));
+    }
+
+    let settings = RepoDevServerSettings::upsert(pool, repo_id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "repo_dev_server_settings_updated",
+            serde_json::json!({
+                "repo_id": repo_id.to_string(),
+                "has_health_path": settings.health_path.is_some(),
+                "has_ready_pattern": settings.ready_pattern.is_some(),
+                "auto_restart": settings.auto_restart,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_repo_dev_processes(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoDevProcess>>>, ApiError> {
+    let processes = RepoDevProcess::find_by_repo_id(&deployment.db().pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(processes)))
+}
+
+pub async fn update_repo_dev_processes(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<Vec<UpsertRepoDevProcess>>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoDevProcess>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    dev_server::validate_dev_processes(&payload)?;
+    let processes = RepoDevProcess::replace_for_repo(pool, repo_id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "repo_dev_processes_updated",
+            serde_json::json!({
+                "repo_id": repo_id.to_string(),
+                "process_count": processes.len(),
+                "has_dependencies": processes.iter().any(|p| !p.depends_on.is_empty()),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(processes)))
+}
+
 pub async fn open_repo_in_editor(
     State(deployment): State<DeploymentImpl>,
     Path(repo_id): Path<Uuid>,
@@ -297,6 +478,22 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/repos/init", post(init_repo))
         .route("/repos/batch", post(get_repos_batch))
         .route("/repos/{repo_id}", get(get_repo).put(update_repo))
+        .route(
+            "/repos/{repo_id}/commit-policy",
+            get(get_repo_commit_policy).put(update_repo_commit_policy),
+        )
+        .route(
+            "/repos/{repo_id}/context-pack",
+            get(get_repo_context_pack).put(update_repo_context_pack),
+        )
+        .route(
+            "/repos/{repo_id}/dev-server",
+            get(get_repo_dev_server_settings).put(update_repo_dev_server_settings),
+        )
+        .route(
+            "/repos/{repo_id}/dev-processes",
+            get(get_repo_dev_processes).put(update_repo_dev_processes),
+        )
         .route("/repos/{repo_id}/branches", get(get_repo_branches))
         .route("/repos/{repo_id}/remotes", get(get_repo_remotes))
         .route("/repos/{repo_id}/prs", get(list_open_prs))
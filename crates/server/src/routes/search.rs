@@ -1,13 +1,33 @@
+use std::path::{Path, PathBuf};
+
 use axum::{
     Router,
-    extract::{Query, State},
-    response::Json as ResponseJson,
+    extract::{
+        Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::{IntoResponse, Json as ResponseJson},
     routing::get,
 };
-use db::models::{project::SearchResult, repo::Repo};
+use db::models::{
+    conversation_search::{ConversationSearch, ConversationSearchMatch},
+    project::SearchResult,
+    project_repo::ProjectRepo,
+    repo::Repo,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
+};
 use deployment::Deployment;
+use futures_util::{SinkExt, StreamExt};
 use serde::Deserialize;
-use services::services::file_search::{SearchMode, SearchQuery};
+use services::services::{
+    content_search::{
+        self, ContentSearchEvent, ContentSearchOptions, ContentSearchResponse, ContentSearchRoot,
+    },
+    file_search::{SearchMode, SearchQuery},
+    symbol_index::{CodeSymbol, SymbolRoot},
+};
+use tokio::sync::mpsc;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -21,12 +41,8 @@ pub struct MultiRepoSearchQuery {
     pub repo_ids: String,
 }
 
-pub async fn search_files(
-    State(deployment): State<DeploymentImpl>,
-    Query(query): Query<MultiRepoSearchQuery>,
-) -> Result<ResponseJson<ApiResponse<Vec<SearchResult>>>, ApiError> {
-    let repo_ids: Vec<Uuid> = query
-        .repo_ids
+fn parse_repo_ids(repo_ids: &str) -> Result<Vec<Uuid>, ApiError> {
+    let repo_ids: Vec<Uuid> = repo_ids
         .split(',')
         .filter(|s| !s.trim().is_empty())
         .map(|s| s.trim().parse::<Uuid>())
@@ -38,6 +54,14 @@ pub async fn search_files(
             "repo_ids parameter is required".to_string(),
         ));
     }
+    Ok(repo_ids)
+}
+
+pub async fn search_files(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<MultiRepoSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SearchResult>>>, ApiError> {
+    let repo_ids = parse_repo_ids(&query.repo_ids)?;
 
     if query.q.trim().is_empty() {
         return Ok(ResponseJson(ApiResponse::error(
@@ -68,8 +92,208 @@ pub async fn search_files(
     Ok(ResponseJson(ApiResponse::success(results)))
 }
 
+const DEFAULT_SYMBOL_SEARCH_LIMIT: usize = 20;
+const MAX_SYMBOL_SEARCH_LIMIT: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct SymbolSearchQuery {
+    pub q: String,
+    pub repo_ids: String,
+    /// Index the workspace's worktrees instead of the repos' main checkouts
+    pub workspace_id: Option<Uuid>,
+    pub limit: Option<usize>,
+}
+
+pub async fn search_symbols(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SymbolSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<CodeSymbol>>>, ApiError> {
+    let repo_ids = parse_repo_ids(&query.repo_ids)?;
+
+    if query.q.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Query parameter 'q' is required and cannot be empty",
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    let repos = Repo::find_by_ids(pool, &repo_ids).await?;
+    let workspace_dir = match query.workspace_id {
+        Some(workspace_id) => {
+            let workspace =
+                Workspace::find_by_id(pool, workspace_id)
+                    .await?
+                    .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+                        "Workspace not found".to_string(),
+                    )))?;
+            Some(workspace.container_ref.ok_or_else(|| {
+                ApiError::BadRequest("Workspace has no worktrees yet".to_string())
+            })?)
+        }
+        None => None,
+    };
+    let roots = SymbolRoot::for_repos(&repos, workspace_dir.as_deref().map(Path::new));
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SYMBOL_SEARCH_LIMIT)
+        .clamp(1, MAX_SYMBOL_SEARCH_LIMIT);
+    let symbols = deployment
+        .symbol_index_cache()
+        .search(&roots, &query.q, limit)
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(symbols)))
+}
+
+const DEFAULT_CONVERSATION_SEARCH_LIMIT: i64 = 20;
+const MAX_CONVERSATION_SEARCH_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct ConversationSearchQuery {
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+pub async fn search_conversations(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ConversationSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ConversationSearchMatch>>>, ApiError> {
+    if query.q.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Query parameter 'q' is required and cannot be empty",
+        )));
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_CONVERSATION_SEARCH_LIMIT)
+        .clamp(1, MAX_CONVERSATION_SEARCH_LIMIT);
+    let matches =
+        ConversationSearch::search(&deployment.db().pool, &query.q, query.project_id, limit)
+            .await?;
+
+    Ok(ResponseJson(ApiResponse::success(matches)))
+}
+
+/// Content search over either all repos of a project or a workspace's worktrees
+#[derive(Debug, Deserialize)]
+pub struct ContentSearchQuery {
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    pub context_lines: Option<usize>,
+    pub max_results: Option<usize>,
+}
+
+impl ContentSearchQuery {
+    fn options(&self) -> ContentSearchOptions {
+        ContentSearchOptions {
+            regex: self.regex,
+            case_sensitive: self.case_sensitive,
+            context_lines: self.context_lines,
+            max_results: self.max_results,
+        }
+    }
+}
+
+async fn content_search_roots(
+    deployment: &DeploymentImpl,
+    query: &ContentSearchQuery,
+) -> Result<Vec<ContentSearchRoot>, ApiError> {
+    let pool = &deployment.db().pool;
+    match (query.project_id, query.workspace_id) {
+        (Some(project_id), None) => Ok(ProjectRepo::find_repos_for_project(pool, project_id)
+            .await?
+            .into_iter()
+            .map(|repo| ContentSearchRoot {
+                name: repo.name,
+                path: repo.path,
+            })
+            .collect()),
+        (None, Some(workspace_id)) => {
+            let workspace =
+                Workspace::find_by_id(pool, workspace_id)
+                    .await?
+                    .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+                        "Workspace not found".to_string(),
+                    )))?;
+            let container_ref = workspace.container_ref.ok_or_else(|| {
+                ApiError::BadRequest("Workspace has no worktrees yet".to_string())
+            })?;
+            Ok(WorkspaceRepo::find_repos_for_workspace(pool, workspace_id)
+                .await?
+                .into_iter()
+                .map(|repo| ContentSearchRoot {
+                    path: PathBuf::from(&container_ref).join(&repo.name),
+                    name: repo.name,
+                })
+                .collect())
+        }
+        _ => Err(ApiError::BadRequest(
+            "Exactly one of project_id or workspace_id is required".to_string(),
+        )),
+    }
+}
+
+pub async fn search_content(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ContentSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<ContentSearchResponse>>, ApiError> {
+    let roots = content_search_roots(&deployment, &query).await?;
+    let response = content_search::collect_content_search(roots, &query.q, &query.options())
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+/// Streams each match as a [`ContentSearchEvent`] JSON message as soon as it is found,
+/// ending with a `done` event.
+pub async fn stream_content_search_ws(
+    ws: WebSocketUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ContentSearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let roots = content_search_roots(&deployment, &query).await?;
+    let results = content_search::search_content(roots, &query.q, &query.options())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_content_search_ws(socket, results).await {
+            tracing::warn!("content search WS closed: {}", e);
+        }
+    }))
+}
+
+async fn handle_content_search_ws(
+    socket: WebSocket,
+    mut results: mpsc::Receiver<ContentSearchEvent>,
+) -> anyhow::Result<()> {
+    let (mut sender, mut receiver) = socket.split();
+    tokio::spawn(async move { while let Some(Ok(_)) = receiver.next().await {} });
+
+    // Dropping `results` when the client goes away stops the search
+    while let Some(event) = results.recv().await {
+        let text = serde_json::to_string(&event)?;
+        if sender.send(Message::Text(text.into())).await.is_err() {
+            break;
+        }
+    }
+    let _ = sender.close().await;
+    Ok(())
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         .route("/search", get(search_files))
+        .route("/search/symbols", get(search_symbols))
+        .route("/search/conversations", get(search_conversations))
+        .route("/search/content", get(search_content))
+        .route("/search/content/ws", get(stream_content_search_ws))
         .with_state(deployment.clone())
 }
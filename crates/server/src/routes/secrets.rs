@@ -0,0 +1,68 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::secret::{Secret, SecretScope, UpsertSecret};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Names of the secrets of one scope. Values are never returned.
+pub async fn get_secrets(
+    State(deployment): State<DeploymentImpl>,
+    Query(scope): Query<SecretScope>,
+) -> Result<ResponseJson<ApiResponse<Vec<Secret>>>, ApiError> {
+    let secrets = deployment.secrets().list(&scope).await?;
+    Ok(ResponseJson(ApiResponse::success(secrets)))
+}
+
+pub async fn set_secret(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertSecret>,
+) -> Result<ResponseJson<ApiResponse<Secret>>, ApiError> {
+    let secret = deployment.secrets().set(&payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "secret_saved",
+            serde_json::json!({
+                "scope": scope_kind(&payload.scope),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(secret)))
+}
+
+pub async fn delete_secret(
+    State(deployment): State<DeploymentImpl>,
+    Path(secret_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    deployment.secrets().delete(secret_id).await?;
+
+    deployment
+        .track_if_analytics_allowed("secret_deleted", serde_json::json!({}))
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+fn scope_kind(scope: &SecretScope) -> &'static str {
+    if scope.project_id.is_some() {
+        "project"
+    } else if scope.repo_id.is_some() {
+        "repo"
+    } else {
+        "executor"
+    }
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/secrets", get(get_secrets).put(set_secret))
+        .route("/secrets/{secret_id}", delete(delete_secret))
+}
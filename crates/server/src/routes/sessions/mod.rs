@@ -29,7 +29,10 @@ use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
+    preview_proxy::capture::PreviewCaptures,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct SessionQuery {
@@ -90,6 +93,8 @@ pub struct CreateFollowUpAttempt {
     pub retry_process_id: Option<Uuid>,
     pub force_when_dirty: Option<bool>,
     pub perform_git_reset: Option<bool>,
+    /// Requests recorded by the preview proxy to append to the prompt
+    pub preview_request_ids: Option<Vec<Uuid>>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -101,6 +106,7 @@ pub struct ResetProcessRequest {
 
 pub async fn follow_up(
     Extension(session): Extension<Session>,
+    Extension(captures): Extension<PreviewCaptures>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateFollowUpAttempt>,
 ) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
@@ -115,6 +121,15 @@ pub async fn follow_up(
 
     tracing::info!("{:?}", workspace);
 
+    let mut prompt = payload.prompt;
+    for request_id in payload.preview_request_ids.iter().flatten() {
+        let request = captures
+            .find_request(workspace.id, *request_id)
+            .ok_or_else(|| ApiError::BadRequest("Captured request not found".to_string()))?;
+        prompt.push_str("\n\n");
+        prompt.push_str(&request.to_prompt_context());
+    }
+
     deployment
         .container()
         .ensure_container_exists(&workspace)
@@ -155,8 +170,6 @@ pub async fn follow_up(
 
     let latest_session_info = CodingAgentTurn::find_latest_session_info(pool, session.id).await?;
 
-    let prompt = payload.prompt;
-
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
     let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
 
@@ -166,6 +179,12 @@ pub async fn follow_up(
         .filter(|dir| !dir.is_empty())
         .cloned();
 
+    // Hand the agent repository overviews that changed since it was last given them
+    let (prompt, context_packs) = deployment
+        .container()
+        .apply_context_packs(&workspace, prompt)
+        .await?;
+
     let action_type = if let Some(info) = latest_session_info {
         let is_reset = payload.retry_process_id.is_some();
         ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
@@ -196,6 +215,11 @@ pub async fn follow_up(
             &ExecutionProcessRunReason::CodingAgent,
         )
         .await?;
+    deployment
+        .container()
+        .context_pack_cache()
+        .mark_delivered(context_packs)
+        .await;
 
     // Clear the draft follow-up scratch on successful spawn
     // This ensures the scratch is wiped even if the user navigates away quickly
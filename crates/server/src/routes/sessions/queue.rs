@@ -1,38 +1,65 @@
 use axum::{
-    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
-    response::Json as ResponseJson, routing::get,
+    Extension, Json, Router,
+    extract::{Path, Request, State},
+    http::StatusCode,
+    middleware::{Next, from_fn_with_state},
+    response::{Json as ResponseJson, Response},
+    routing::{get, post, put},
+};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    queued_message::QueuedMessage,
+    session::Session,
 };
-use db::models::{scratch::DraftFollowUpData, session::Session};
 use deployment::Deployment;
 use executors::profile::ExecutorConfig;
 use serde::Deserialize;
-use services::services::queued_message::QueueStatus;
+use services::services::{container::ContainerService, queued_message::QueueStatus};
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
 
-/// Request body for queueing a follow-up message
+/// Request body for queueing or editing a follow-up message
 #[derive(Debug, Deserialize, TS)]
 pub struct QueueMessageRequest {
     pub message: String,
-    pub executor_config: ExecutorConfig,
+    /// Executor for this message; the session's latest executor is used when unset
+    pub executor_config: Option<ExecutorConfig>,
+}
+
+/// Request body for reordering a session's queue
+#[derive(Debug, Deserialize, TS)]
+pub struct ReorderQueueRequest {
+    /// Every queued message of the session, in the new order
+    pub message_ids: Vec<Uuid>,
+}
+
+fn validate_message(payload: &QueueMessageRequest) -> Result<(), ApiError> {
+    if payload.message.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Queued message cannot be empty".to_string(),
+        ));
+    }
+    Ok(())
 }
 
-/// Queue a follow-up message to be executed when the current execution finishes
+/// Append a follow-up message to be executed once the messages before it have run
 pub async fn queue_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<QueueMessageRequest>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let data = DraftFollowUpData {
-        message: payload.message,
-        executor_config: payload.executor_config,
-    };
-
-    let queued = deployment
-        .queued_message_service()
-        .queue_message(session.id, data);
+    validate_message(&payload)?;
+    let queue = deployment.queued_message_service();
+    queue
+        .queue_message(
+            session.id,
+            &payload.message,
+            payload.executor_config.as_ref(),
+        )
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -40,23 +67,25 @@ pub async fn queue_message(
             serde_json::json!({
                 "session_id": session.id.to_string(),
                 "workspace_id": session.workspace_id.to_string(),
+                "has_executor_override": payload.executor_config.is_some(),
             }),
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Queued {
-        message: queued,
-    })))
+    Ok(ResponseJson(ApiResponse::success(
+        queue.get_status(session.id).await?,
+    )))
 }
 
-/// Cancel a queued follow-up message
+/// Cancel every queued follow-up message of a session
 pub async fn cancel_queued_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
     deployment
         .queued_message_service()
-        .cancel_queued(session.id);
+        .clear(session.id)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -76,21 +105,142 @@ pub async fn get_queue_status(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let status = deployment.queued_message_service().get_status(session.id);
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
 
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
+pub async fn reorder_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let queue = deployment.queued_message_service();
+    queue.reorder(session.id, &payload.message_ids).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        queue.get_status(session.id).await?,
+    )))
+}
+
+/// Resume a paused queue. When the session is idle the next message starts right away,
+/// otherwise it runs once the current execution finishes.
+pub async fn resume_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let queue = deployment.queued_message_service();
+    queue.resume(session.id).await?;
+
+    let is_running = ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+        pool,
+        session.workspace_id,
+    )
+    .await?;
+    if !is_running
+        && let Some(latest) = ExecutionProcess::find_latest_by_session_and_run_reason(
+            pool,
+            session.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?
+    {
+        let ctx = ExecutionProcess::load_context(pool, latest.id).await?;
+        if let Err(e) = deployment.container().start_next_queued_message(&ctx).await {
+            queue.pause(session.id, &e.to_string()).await?;
+            return Err(e.into());
+        }
+    }
+
+    Ok(ResponseJson(ApiResponse::success(
+        queue.get_status(session.id).await?,
+    )))
+}
+
+pub async fn update_queued_message(
+    Extension(queued): Extension<QueuedMessage>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<QueueMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    validate_message(&payload)?;
+    let queue = deployment.queued_message_service();
+    queue
+        .update_message(
+            queued.id,
+            &payload.message,
+            payload.executor_config.as_ref(),
+        )
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(
+        queue.get_status(queued.session_id).await?,
+    )))
+}
+
+pub async fn delete_queued_message(
+    Extension(queued): Extension<QueuedMessage>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let queue = deployment.queued_message_service();
+    queue.remove_message(&queued).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        queue.get_status(queued.session_id).await?,
+    )))
+}
+
+/// Middleware to load one queued message of the session in the path.
+async fn load_queued_message_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let queued = match QueuedMessage::find_by_id(&deployment.db().pool, message_id).await {
+        Ok(Some(queued)) if queued.session_id == session_id => queued,
+        Ok(_) => {
+            tracing::warn!(
+                "Queued message {} not found in session {}",
+                message_id,
+                session_id
+            );
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch queued message {}: {}", message_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(queued);
+    Ok(next.run(request).await)
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
-    Router::new()
+    let queue_router = Router::new()
         .route(
             "/",
             get(get_queue_status)
                 .post(queue_message)
                 .delete(cancel_queued_message),
         )
+        .route("/order", put(reorder_queue))
+        .route("/resume", post(resume_queue))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
-        ))
+        ));
+
+    let message_router = Router::new()
+        .route(
+            "/{message_id}",
+            put(update_queued_message).delete(delete_queued_message),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_queued_message_middleware,
+        ));
+
+    queue_router.merge(message_router)
 }
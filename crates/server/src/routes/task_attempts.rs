@@ -1,8 +1,14 @@
+pub mod checkpoints;
 pub mod codex_setup;
+pub mod conflicts;
 pub mod cursor_setup;
+pub mod dev_servers;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod preview_requests;
+pub mod stack;
+pub mod visual_snapshots;
 pub mod workspace_summary;
 
 use std::{
@@ -24,12 +30,16 @@ use axum::{
 };
 use db::models::{
     coding_agent_turn::CodingAgentTurn,
+    dev_server::DevServer,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
     repo::{Repo, RepoError},
+    repo_commit_policy::RepoCommitPolicy,
+    repo_dev_process::RepoDevProcess,
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
+    workspace_checkpoint::CheckpointReason,
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
 };
 use deployment::Deployment;
@@ -45,7 +55,13 @@ use git::{ConflictOp, GitCliError, GitService, GitServiceError};
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService, diff_stream, remote_client::RemoteClientError, remote_sync,
+    commit_policy,
+    container::ContainerService,
+    diff_stream,
+    merge_queue::{MergeQueueEntry, MergeQueueError, MergeQueueRequest},
+    pr_stack,
+    remote_client::RemoteClientError,
+    remote_sync, workspace_checkpoint,
     workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
@@ -55,7 +71,7 @@ use uuid::Uuid;
 
 use crate::{
     DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
-    routes::task_attempts::gh_cli_setup::GhCliSetupError,
+    preview_proxy::capture::PreviewCaptures, routes::task_attempts::gh_cli_setup::GhCliSetupError,
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -479,25 +495,18 @@ async fn resolve_vibe_kanban_identifier(
     task_id.to_string()
 }
 
-#[axum::debug_handler]
-pub async fn merge_task_attempt(
-    Extension(workspace): Extension<Workspace>,
-    State(deployment): State<DeploymentImpl>,
-    Json(request): Json<MergeTaskAttemptRequest>,
-) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+/// Checks shared by direct merges and the merge queue. Returns the task and the
+/// squash commit message to land the workspace with.
+async fn prepare_direct_merge(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    workspace_repo: &WorkspaceRepo,
+    repo: &Repo,
+) -> Result<(Task, String), ApiError> {
     let pool = &deployment.db().pool;
 
-    let workspace_repo =
-        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-
-    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
-        .await?
-        .ok_or(RepoError::NotFound)?;
-
     // Prevent direct merge when there's an open PR for this repo
-    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id).await?;
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo.id).await?;
     let has_open_pr = merges
         .iter()
         .any(|m| matches!(m, Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open)));
@@ -518,18 +527,11 @@ pub async fn merge_task_attempt(
         ));
     }
 
-    let container_ref = deployment
-        .container()
-        .ensure_container_exists(&workspace)
-        .await?;
-    let workspace_path = Path::new(&container_ref);
-    let worktree_path = workspace_path.join(repo.name);
-
     let task = workspace
         .parent_task(pool)
         .await?
         .ok_or(ApiError::Workspace(WorkspaceError::TaskNotFound))?;
-    let vk_id = resolve_vibe_kanban_identifier(&deployment, workspace.id, task.id).await;
+    let vk_id = resolve_vibe_kanban_identifier(deployment, workspace.id, task.id).await;
 
     let mut commit_message = format!("{} (vibe-kanban {})", task.title, vk_id);
 
@@ -541,12 +543,45 @@ pub async fn merge_task_attempt(
         commit_message.push_str(description);
     }
 
-    let merge_commit_id = deployment.git().merge_changes(
+    Ok((task, commit_message))
+}
+
+#[axum::debug_handler]
+pub async fn merge_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<MergeTaskAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let (task, commit_message) =
+        prepare_direct_merge(&deployment, &workspace, &workspace_repo, &repo).await?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_path = Path::new(&container_ref);
+    let worktree_path = workspace_path.join(&repo.name);
+
+    let policy = RepoCommitPolicy::find_or_default(pool, repo.id).await?;
+    let commit_message = commit_policy::template_commit_message(&policy, &commit_message);
+    let merge_commit_id = deployment.git().merge_changes_with_options(
         &repo.path,
         &worktree_path,
         &workspace.branch,
         &workspace_repo.target_branch,
         &commit_message,
+        &commit_policy::commit_options(&policy),
     )?;
 
     Merge::create_direct(
@@ -558,6 +593,13 @@ pub async fn merge_task_attempt(
     )
     .await?;
     Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    pr_stack::retarget_after_landing(
+        pool,
+        &workspace,
+        workspace_repo.repo_id,
+        &workspace_repo.target_branch,
+    )
+    .await;
     if !workspace.pinned
         && let Err(e) = deployment.container().archive_workspace(workspace.id).await
     {
@@ -577,6 +619,58 @@ pub async fn merge_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Queue a direct merge instead of merging right away. The workspace is rebased onto the
+/// latest target and verified before it lands; progress is reported through the merge queue.
+pub async fn enqueue_merge_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<MergeTaskAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let (task, commit_message) =
+        prepare_direct_merge(&deployment, &workspace, &workspace_repo, &repo).await?;
+
+    let entry = deployment
+        .merge_queue()
+        .enqueue(
+            workspace.branch.clone(),
+            MergeQueueRequest {
+                workspace_id: workspace.id,
+                repo_id: repo.id,
+                target_branch: workspace_repo.target_branch.clone(),
+                commit_message,
+            },
+        )
+        .await
+        .map_err(|e| match e {
+            MergeQueueError::Database(e) => ApiError::Database(e),
+            e => ApiError::Conflict(e.to_string()),
+        })?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_merge_queued",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "position": entry.position,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
 pub async fn push_task_attempt_branch(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -1221,6 +1315,15 @@ pub async fn rebase_task_attempt(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
+    workspace_checkpoint::create_auto_checkpoint(
+        pool,
+        deployment.git(),
+        workspace.id,
+        workspace_path,
+        CheckpointReason::BeforeRebase,
+    )
+    .await;
+
     let result = deployment.git().rebase_branch(
         &repo.path,
         &worktree_path,
@@ -1285,6 +1388,15 @@ pub async fn abort_conflicts_task_attempt(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
+    workspace_checkpoint::create_auto_checkpoint(
+        pool,
+        deployment.git(),
+        workspace.id,
+        workspace_path,
+        CheckpointReason::BeforeConflictAbort,
+    )
+    .await;
+
     deployment.git().abort_conflicts(&worktree_path)?;
 
     Ok(ResponseJson(ApiResponse::success(())))
@@ -1367,14 +1479,23 @@ pub async fn start_dev_server(
     }
 
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
-    let repos_with_dev_script: Vec<_> = repos
-        .iter()
-        .filter(|r| r.dev_server_script.as_ref().is_some_and(|s| !s.is_empty()))
-        .collect();
+    // Repos with dev processes start those instead of their dev server script
+    let mut repos_with_dev_script = Vec::new();
+    for repo in repos {
+        let processes = RepoDevProcess::find_by_repo_id(pool, repo.id).await?;
+        if !processes.is_empty()
+            || repo
+                .dev_server_script
+                .as_ref()
+                .is_some_and(|s| !s.is_empty())
+        {
+            repos_with_dev_script.push((repo, processes));
+        }
+    }
 
     if repos_with_dev_script.is_empty() {
         return Ok(ResponseJson(ApiResponse::error(
-            "No dev server script configured for any repository in this workspace",
+            "No dev server script or dev processes configured for any repository in this workspace",
         )));
     }
 
@@ -1394,13 +1515,37 @@ pub async fn start_dev_server(
     };
 
     let mut execution_processes = Vec::new();
-    for repo in repos_with_dev_script {
+    for (repo, processes) in repos_with_dev_script {
+        // Starting by hand gives the started processes a fresh set of automatic restarts
+        if processes.is_empty() {
+            DevServer::reset_restart_count(pool, workspace.id, repo.id, None).await?;
+        }
+        for process in &processes {
+            DevServer::reset_restart_count(pool, workspace.id, repo.id, Some(&process.name))
+                .await?;
+        }
+
+        if !processes.is_empty() {
+            execution_processes.extend(
+                dev_servers::start_dev_processes(
+                    &deployment,
+                    &workspace,
+                    &session,
+                    &repo,
+                    processes,
+                )
+                .await?,
+            );
+            continue;
+        }
+
         let executor_action = ExecutorAction::new(
             ExecutorActionType::ScriptRequest(ScriptRequest {
                 script: repo.dev_server_script.clone().unwrap(),
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::DevServer,
                 working_dir: Some(repo.name.clone()),
+                dev_process: None,
             }),
             None,
         );
@@ -1781,6 +1926,7 @@ pub async fn get_first_user_message(
 
 pub async fn delete_workspace(
     Extension(workspace): Extension<Workspace>,
+    Extension(captures): Extension<PreviewCaptures>,
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<DeleteWorkspaceQuery>,
 ) -> Result<(StatusCode, ResponseJson<ApiResponse<()>>), ApiError> {
@@ -1842,6 +1988,8 @@ pub async fn delete_workspace(
         return Err(ApiError::Database(SqlxError::RowNotFound));
     }
 
+    captures.remove_workspace(workspace.id);
+
     deployment
         .track_if_analytics_allowed(
             "workspace_deleted",
@@ -2052,14 +2200,21 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .route("/branch-status", get(get_task_attempt_branch_status))
                 .route("/diff/ws", get(stream_task_attempt_diff_ws))
                 .route("/merge", post(merge_task_attempt))
+                .route("/merge/queue", post(enqueue_merge_task_attempt))
                 .route("/push", post(push_task_attempt_branch))
                 .route("/push/force", post(force_push_task_attempt_branch))
                 .route("/rebase", post(rebase_task_attempt))
                 .route("/rebase/continue", post(continue_rebase_task_attempt))
                 .route("/conflicts/abort", post(abort_conflicts_task_attempt))
+                .route(
+                    "/conflicts/resolve",
+                    post(conflicts::resolve_conflicts_with_agent),
+                )
                 .route("/pr", post(pr::create_pr))
                 .route("/pr/attach", post(pr::attach_existing_pr))
                 .route("/pr/comments", get(pr::get_pr_comments))
+                .route("/stack", get(stack::get_stack))
+                .route("/stack/restack", post(stack::restack))
                 .route("/open-editor", post(open_task_attempt_in_editor))
                 .route("/children", get(get_task_attempt_children))
                 .route("/stop", post(stop_task_attempt_execution))
@@ -2081,7 +2236,17 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/stream/ws", get(stream_workspaces_ws))
         .route("/summary", post(workspace_summary::get_workspace_summaries))
         .nest("/{id}", task_attempt_id_router)
-        .nest("/{id}/images", images::router(deployment));
+        .nest("/{id}/images", images::router(deployment))
+        .nest("/{id}/checkpoints", checkpoints::router(deployment))
+        .nest("/{id}/dev-servers", dev_servers::router())
+        .nest(
+            "/{id}/preview-requests",
+            preview_requests::router(deployment),
+        )
+        .nest(
+            "/{id}/visual-snapshots",
+            visual_snapshots::router(deployment),
+        );
 
     Router::new().nest("/task-attempts", task_attempts_router)
 }
@@ -0,0 +1,189 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Request, State},
+    http::StatusCode,
+    middleware::{Next, from_fn_with_state},
+    response::{Json as ResponseJson, Response},
+    routing::{delete, get, post},
+};
+use db::models::{
+    workspace::Workspace,
+    workspace_checkpoint::{CheckpointReason, WorkspaceCheckpoint, WorkspaceCheckpointWithRepos},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{
+    container::ContainerService,
+    workspace_checkpoint::{self, CheckpointRepoDiff},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_workspace_middleware};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateCheckpointRequest {
+    pub name: Option<String>,
+}
+
+async fn workspace_dir(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<PathBuf, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    Ok(PathBuf::from(container_ref))
+}
+
+pub async fn get_checkpoints(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkspaceCheckpointWithRepos>>>, ApiError> {
+    let checkpoints =
+        workspace_checkpoint::list_checkpoints(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(checkpoints)))
+}
+
+pub async fn create_checkpoint(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateCheckpointRequest>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceCheckpointWithRepos>>, ApiError> {
+    let workspace_dir = workspace_dir(&deployment, &workspace).await?;
+    let checkpoint = workspace_checkpoint::create_checkpoint(
+        &deployment.db().pool,
+        deployment.git(),
+        workspace.id,
+        &workspace_dir,
+        payload.name.as_deref(),
+        CheckpointReason::Manual,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_checkpoint_created",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_count": checkpoint.repos.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(checkpoint)))
+}
+
+/// Restores the checkpoint and returns the checkpoint taken of the state it replaced.
+pub async fn restore_checkpoint(
+    Extension(workspace): Extension<Workspace>,
+    Extension(checkpoint): Extension<WorkspaceCheckpoint>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceCheckpointWithRepos>>, ApiError> {
+    let workspace_dir = workspace_dir(&deployment, &workspace).await?;
+    // Running agents would keep writing into the restored worktrees
+    deployment.container().try_stop(&workspace, false).await;
+
+    let backup = workspace_checkpoint::restore_checkpoint(
+        &deployment.db().pool,
+        deployment.git(),
+        &checkpoint,
+        &workspace_dir,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_checkpoint_restored",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "reason": checkpoint.reason,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(backup)))
+}
+
+pub async fn get_checkpoint_diff(
+    Extension(workspace): Extension<Workspace>,
+    Extension(checkpoint): Extension<WorkspaceCheckpoint>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<CheckpointRepoDiff>>>, ApiError> {
+    let workspace_dir = workspace_dir(&deployment, &workspace).await?;
+    let diffs = workspace_checkpoint::diff_checkpoint(
+        &deployment.db().pool,
+        deployment.git(),
+        &checkpoint,
+        &workspace_dir,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(diffs)))
+}
+
+pub async fn delete_checkpoint(
+    Extension(checkpoint): Extension<WorkspaceCheckpoint>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    workspace_checkpoint::delete_checkpoint(&deployment.db().pool, deployment.git(), &checkpoint)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Middleware to load the Workspace and one of its checkpoints.
+async fn load_checkpoint_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, checkpoint_id)): Path<(Uuid, Uuid)>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let pool = &deployment.db().pool;
+    let workspace = match Workspace::find_by_id(pool, workspace_id).await {
+        Ok(Some(workspace)) => workspace,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    let checkpoint = match WorkspaceCheckpoint::find_by_id(pool, checkpoint_id).await {
+        Ok(Some(checkpoint)) if checkpoint.workspace_id == workspace.id => checkpoint,
+        Ok(_) => {
+            tracing::warn!(
+                "Checkpoint {} not found in workspace {}",
+                checkpoint_id,
+                workspace_id
+            );
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch checkpoint {}: {}", checkpoint_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(workspace);
+    request.extensions_mut().insert(checkpoint);
+    Ok(next.run(request).await)
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let list_router = Router::new()
+        .route("/", get(get_checkpoints).post(create_checkpoint))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_middleware,
+        ));
+
+    let checkpoint_router = Router::new()
+        .route("/{checkpoint_id}", delete(delete_checkpoint))
+        .route("/{checkpoint_id}/restore", post(restore_checkpoint))
+        .route("/{checkpoint_id}/diff", get(get_checkpoint_diff))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_checkpoint_middleware,
+        ));
+
+    list_router.merge(checkpoint_router)
+}
@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    execution_process::ExecutionProcess,
+    repo::{Repo, RepoError},
+    session::{Session, SessionError},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorConfig;
+use git::ConflictOp;
+use serde::{Deserialize, Serialize};
+use services::services::{conflict_resolution::ConflictResolution, container::ContainerService};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+    pub session_id: Uuid,
+    pub executor_config: ExecutorConfig,
+    pub additional_prompt: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum ResolveConflictsError {
+    NoConflictsInProgress,
+    UnsupportedOperation { op: ConflictOp },
+    ProcessAlreadyRunning,
+}
+
+/// Hand an in-progress rebase or merge to the coding agent. When the agent's execution
+/// exits, the container checks the conflicted files for leftover markers and continues
+/// the operation; if the next rebase step conflicts again, another round is started.
+#[axum::debug_handler]
+pub async fn resolve_conflicts_with_agent(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess, ResolveConflictsError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let session = Session::find_by_id(pool, payload.session_id)
+        .await?
+        .filter(|s| s.workspace_id == workspace.id)
+        .ok_or(SessionError::NotFound)?;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            ResolveConflictsError::ProcessAlreadyRunning,
+        )));
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = PathBuf::from(&container_ref).join(&repo.name);
+
+    match deployment.git().detect_conflict_op(&worktree_path)? {
+        None => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                ResolveConflictsError::NoConflictsInProgress,
+            )));
+        }
+        Some(op @ (ConflictOp::CherryPick | ConflictOp::Revert)) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                ResolveConflictsError::UnsupportedOperation { op },
+            )));
+        }
+        Some(_) => {}
+    }
+
+    let conflicted_files = deployment.git().get_conflicted_files(&worktree_path)?;
+    let analytics_props = serde_json::json!({
+        "workspace_id": workspace.id.to_string(),
+        "repo_id": repo.id.to_string(),
+        "conflicted_files": conflicted_files.len(),
+        "executor": payload.executor_config.executor.to_string(),
+    });
+    let resolution = ConflictResolution {
+        workspace,
+        session,
+        repo,
+        worktree_path,
+        target_branch: workspace_repo.target_branch,
+        executor_config: payload.executor_config,
+        additional_prompt: payload.additional_prompt,
+        round: 1,
+        conflicted_files,
+    };
+    let execution_process = deployment
+        .container()
+        .start_conflict_resolution_round(resolution)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed("conflict_resolution_started", analytics_props)
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::post,
+};
+use db::models::{
+    dev_server::DevServer,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    repo::Repo,
+    repo_dev_process::RepoDevProcess,
+    session::Session,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use services::services::{
+    container::{ContainerError, ContainerService},
+    dev_server,
+};
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+fn dev_process_action(repo: &Repo, process: &RepoDevProcess) -> ExecutorAction {
+    ExecutorAction::new(
+        ExecutorActionType::ScriptRequest(ScriptRequest {
+            script: process.command.clone(),
+            language: ScriptRequestLanguage::Bash,
+            context: ScriptContext::DevServer,
+            working_dir: Some(repo.name.clone()),
+            dev_process: Some(process.name.clone()),
+        }),
+        None,
+    )
+}
+
+/// Start a repo's dev processes, each once the processes it depends on are ready.
+/// Processes without dependencies start right away and are returned; the others start
+/// in the background.
+pub async fn start_dev_processes(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    session: &Session,
+    repo: &Repo,
+    processes: Vec<RepoDevProcess>,
+) -> Result<Vec<ExecutionProcess>, ApiError> {
+    let order = dev_server::start_order(&processes)?;
+
+    let mut started = HashMap::new();
+    let mut execution_processes = Vec::new();
+    let mut waiting = Vec::new();
+    for process in order {
+        if !process.depends_on.is_empty() {
+            waiting.push(process.clone());
+            continue;
+        }
+        let execution_process = deployment
+            .container()
+            .start_execution(
+                workspace,
+                session,
+                &dev_process_action(repo, process),
+                &ExecutionProcessRunReason::DevServer,
+            )
+            .await?;
+        started.insert(process.name.clone(), execution_process.id);
+        execution_processes.push(execution_process);
+    }
+    if waiting.is_empty() {
+        return Ok(execution_processes);
+    }
+
+    let deployment = deployment.clone();
+    let workspace = workspace.clone();
+    let session = session.clone();
+    let repo = repo.clone();
+    tokio::spawn(async move {
+        let pool = &deployment.db().pool;
+        for process in waiting {
+            let mut ready = true;
+            for dependency in process.depends_on.iter() {
+                ready = match started.get(dependency) {
+                    Some(execution_process_id) => {
+                        dev_server::wait_for_dev_server(pool, *execution_process_id).await
+                    }
+                    None => false,
+                };
+                if !ready {
+                    break;
+                }
+            }
+            if !ready {
+                tracing::warn!(
+                    "Not starting dev process '{}' for workspace {}: its dependencies didn't become ready",
+                    process.name,
+                    workspace.id
+                );
+                continue;
+            }
+
+            match deployment
+                .container()
+                .start_execution(
+                    &workspace,
+                    &session,
+                    &dev_process_action(&repo, &process),
+                    &ExecutionProcessRunReason::DevServer,
+                )
+                .await
+            {
+                Ok(execution_process) => {
+                    started.insert(process.name.clone(), execution_process.id);
+                }
+                Err(e) => tracing::error!(
+                    "Failed to start dev process '{}' for workspace {}: {}",
+                    process.name,
+                    workspace.id,
+                    e
+                ),
+            }
+        }
+    });
+
+    Ok(execution_processes)
+}
+
+/// Restart one dev server of a workspace, leaving the others running.
+pub async fn restart_dev_server(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, dev_server_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let dev_server = DevServer::find_by_id(pool, dev_server_id)
+        .await?
+        .filter(|dev_server| dev_server.workspace_id == workspace_id)
+        .ok_or_else(|| ApiError::BadRequest("Dev server not found".to_string()))?;
+    let workspace = Workspace::find_by_id(pool, workspace_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let execution_process = ExecutionProcess::find_by_id(pool, dev_server.execution_process_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let session = Session::find_by_id(pool, execution_process.session_id)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    let action = execution_process
+        .executor_action()
+        .map_err(ContainerError::Other)?
+        .clone();
+
+    if execution_process.status == ExecutionProcessStatus::Running {
+        deployment
+            .container()
+            .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
+            .await?;
+    }
+
+    // Restarting by hand gives the dev server a fresh set of automatic restarts
+    DevServer::reset_restart_count(
+        pool,
+        workspace.id,
+        dev_server.repo_id,
+        dev_server.process_name.as_deref(),
+    )
+    .await?;
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            &workspace,
+            &session,
+            &action,
+            &ExecutionProcessRunReason::DevServer,
+        )
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "dev_server_restarted",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "is_dev_process": dev_server.process_name.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/{dev_server_id}/restart", post(restart_dev_server))
+}
@@ -13,6 +13,7 @@ use db::models::{
     project::Project,
     project_repo::ProjectRepo,
     repo::{Repo, RepoError},
+    repo_commit_policy::RepoCommitPolicy,
     session::{CreateSession, Session},
     task::{CreateTask, Task, TaskStatus},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
@@ -26,13 +27,14 @@ use executors::actions::{
 use git::{GitCliError, GitRemote, GitServiceError};
 use serde::{Deserialize, Serialize};
 use services::services::{
+    commit_policy,
     config::DEFAULT_PR_DESCRIPTION_PROMPT,
     container::ContainerService,
     git_host::{
         self, CreatePrRequest, GitHostError, GitHostProvider, ProviderKind, UnifiedPrComment,
         github::GhCli,
     },
-    remote_sync,
+    issue_import, pr_stack, remote_sync,
     workspace_manager::WorkspaceManager,
 };
 use ts_rs::TS;
@@ -202,10 +204,11 @@ pub async fn create_pr(
         .ok_or(RepoError::NotFound)?;
 
     let repo_path = repo.path.clone();
+    // Stacked workspaces target their parent's branch until the parent lands
     let target_branch = if let Some(branch) = request.target_branch {
         branch
     } else {
-        workspace_repo.target_branch.clone()
+        pr_stack::resolve_base_branch(pool, &workspace, &workspace_repo).await?
     };
 
     let container_ref = deployment
@@ -253,7 +256,28 @@ pub async fn create_pr(
         Ok(true) => {}
     }
 
-    if let Err(e) = git.push_to_remote(&worktree_path, &workspace.branch, false) {
+    // Collapse the per-turn agent commits into one before the branch is published
+    let policy = RepoCommitPolicy::find_or_default(pool, repo.id).await?;
+    let mut squashed = false;
+    if policy.squash_on_finish {
+        let fallback = match request.body.as_deref().filter(|b| !b.trim().is_empty()) {
+            Some(body) => format!("{}\n\n{}", request.title, body),
+            None => request.title.clone(),
+        };
+        let message = commit_policy::template_commit_message(&policy, &fallback);
+        squashed = git
+            .squash_branch_commits(
+                &worktree_path,
+                &workspace.branch,
+                &target_branch,
+                &message,
+                &commit_policy::commit_options(&policy),
+            )?
+            .is_some();
+    }
+
+    // A squashed branch rewrites history that may already be on the remote
+    if let Err(e) = git.push_to_remote(&worktree_path, &workspace.branch, squashed) {
         tracing::error!("Failed to push branch to remote: {}", e);
         match e {
             GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
@@ -331,6 +355,19 @@ pub async fn create_pr(
                 });
             }
 
+            // Link the PR on the issue the task was imported from, if any
+            let pool_for_issue = pool.clone();
+            let secrets = deployment.secrets().clone();
+            let task_id = workspace.task_id;
+            let pr_url = pr_info.url.clone();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    issue_import::comment_pr_link(&pool_for_issue, &secrets, task_id, &pr_url).await
+                {
+                    tracing::warn!("Failed to comment PR link on imported issue: {}", e);
+                }
+            });
+
             // Auto-open PR in browser
             if let Err(e) = utils::browser::open_browser(&pr_info.url).await {
                 tracing::warn!("Failed to open PR in browser: {}", e);
@@ -506,6 +543,13 @@ pub async fn attach_existing_pr(
         // If PR is merged, mark task as done and archive workspace
         if matches!(pr_info.status, MergeStatus::Merged) {
             Task::update_status(pool, task.id, TaskStatus::Done).await?;
+            pr_stack::retarget_after_landing(
+                pool,
+                &workspace,
+                workspace_repo.repo_id,
+                &workspace_repo.target_branch,
+            )
+            .await;
             if !workspace.pinned
                 && let Err(e) = deployment.container().archive_workspace(workspace.id).await
             {
@@ -0,0 +1,127 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Request, State},
+    http::StatusCode,
+    middleware::{Next, from_fn_with_state},
+    response::{Json as ResponseJson, Response},
+    routing::{get, put},
+};
+use db::models::workspace::Workspace;
+use deployment::Deployment;
+use serde::Deserialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_workspace_middleware,
+    preview_proxy::capture::{CapturedRequest, PreviewCaptureStatus, PreviewCaptures},
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetPreviewCaptureRequest {
+    /// Dev server port to record through the preview proxy; `null` stops recording
+    pub port: Option<u16>,
+}
+
+pub async fn get_preview_requests(
+    Extension(workspace): Extension<Workspace>,
+    Extension(captures): Extension<PreviewCaptures>,
+) -> Result<ResponseJson<ApiResponse<PreviewCaptureStatus>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        captures.status(workspace.id),
+    )))
+}
+
+pub async fn set_preview_capture(
+    Extension(workspace): Extension<Workspace>,
+    Extension(captures): Extension<PreviewCaptures>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetPreviewCaptureRequest>,
+) -> Result<ResponseJson<ApiResponse<PreviewCaptureStatus>>, ApiError> {
+    captures.set_capture_port(workspace.id, payload.port);
+
+    deployment
+        .track_if_analytics_allowed(
+            "preview_capture_toggled",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "enabled": payload.port.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        captures.status(workspace.id),
+    )))
+}
+
+pub async fn clear_preview_requests(
+    Extension(workspace): Extension<Workspace>,
+    Extension(captures): Extension<PreviewCaptures>,
+) -> Result<ResponseJson<ApiResponse<PreviewCaptureStatus>>, ApiError> {
+    captures.clear_requests(workspace.id);
+    Ok(ResponseJson(ApiResponse::success(
+        captures.status(workspace.id),
+    )))
+}
+
+pub async fn get_preview_request(
+    Extension(request): Extension<CapturedRequest>,
+) -> Result<ResponseJson<ApiResponse<CapturedRequest>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(request)))
+}
+
+/// Middleware to load the Workspace and one of its captured requests.
+async fn load_preview_request_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Extension(captures): Extension<PreviewCaptures>,
+    Path((workspace_id, request_id)): Path<(Uuid, Uuid)>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let workspace = match Workspace::find_by_id(&deployment.db().pool, workspace_id).await {
+        Ok(Some(workspace)) => workspace,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to fetch Workspace {}: {}", workspace_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let Some(captured) = captures.find_request(workspace.id, request_id) else {
+        tracing::warn!(
+            "Captured request {} not found in workspace {}",
+            request_id,
+            workspace_id
+        );
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    request.extensions_mut().insert(workspace);
+    request.extensions_mut().insert(captured);
+    Ok(next.run(request).await)
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let list_router = Router::new()
+        .route(
+            "/",
+            get(get_preview_requests).delete(clear_preview_requests),
+        )
+        .route("/capture", put(set_preview_capture))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_middleware,
+        ));
+
+    let request_router = Router::new()
+        .route("/{request_id}", get(get_preview_request))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_preview_request_middleware,
+        ));
+
+    list_router.merge(request_router)
+}
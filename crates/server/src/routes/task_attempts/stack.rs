@@ -0,0 +1,312 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    merge::{Merge, MergeStatus},
+    repo::{Repo, RepoError},
+    workspace::Workspace,
+    workspace_checkpoint::CheckpointReason,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use git::GitServiceError;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    pr_stack::{self, StackLayer, StackNode},
+    workspace_checkpoint,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct StackQuery {
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct RestackRequest {
+    pub repo_id: Uuid,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum RestackOutcome {
+    Rebased {
+        pushed: bool,
+    },
+    MergeConflicts {
+        message: String,
+        conflicted_files: Vec<String>,
+    },
+    PushFailed {
+        message: String,
+    },
+    Failed {
+        message: String,
+    },
+    Skipped,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestackLayerResult {
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub base_branch: String,
+    pub outcome: RestackOutcome,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestackResponse {
+    pub layers: Vec<RestackLayerResult>,
+}
+
+pub async fn get_stack(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<StackQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<StackLayer>>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    refresh_parent_prs(&deployment, &workspace, query.repo_id).await?;
+    let layers = pr_stack::load_stack(pool, &workspace, query.repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(layers)))
+}
+
+/// Rebase every layer stacked on this workspace onto its (possibly updated) parent,
+/// force-pushing layers that have an open PR. If this workspace is itself stacked,
+/// it is restacked first. A layer whose parent failed is skipped.
+pub async fn restack(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<RestackRequest>,
+) -> Result<ResponseJson<ApiResponse<RestackResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, request.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    refresh_parent_prs(&deployment, &workspace, repo.id).await?;
+
+    let mut nodes = Vec::new();
+    if pr_stack::find_stack_parent(pool, &workspace, repo.id)
+        .await?
+        .is_some()
+    {
+        nodes.push(StackNode {
+            workspace: workspace.clone(),
+            parent_workspace_id: None,
+            depth: 0,
+        });
+    }
+    nodes.extend(pr_stack::collect_descendants(pool, &workspace, repo.id).await?);
+
+    // Head of each restacked layer before it was rebased, so children only replay
+    // their own commits on top of the rewritten parent.
+    let mut previous_heads: HashMap<Uuid, String> = HashMap::new();
+    let mut blocked: HashSet<Uuid> = HashSet::new();
+    let mut layers = Vec::with_capacity(nodes.len());
+
+    for node in nodes {
+        let layer = node.workspace;
+        let Some(workspace_repo) =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, layer.id, repo.id).await?
+        else {
+            continue;
+        };
+        let parent = pr_stack::find_stack_parent(pool, &layer, repo.id).await?;
+        let base_branch = pr_stack::stack_base_branch(parent.as_ref(), &workspace_repo);
+
+        if node
+            .parent_workspace_id
+            .is_some_and(|id| blocked.contains(&id))
+        {
+            blocked.insert(layer.id);
+            layers.push(RestackLayerResult {
+                workspace_id: layer.id,
+                branch: layer.branch.clone(),
+                base_branch,
+                outcome: RestackOutcome::Skipped,
+            });
+            continue;
+        }
+
+        let old_base = match &parent {
+            Some(p) if !p.merged => previous_heads
+                .get(&p.workspace.id)
+                .cloned()
+                .unwrap_or_else(|| p.workspace.branch.clone()),
+            Some(p) => p.workspace.branch.clone(),
+            None => workspace_repo.target_branch.clone(),
+        };
+
+        let outcome = restack_layer(
+            &deployment,
+            &repo,
+            &layer,
+            &workspace_repo,
+            &base_branch,
+            &old_base,
+            &mut previous_heads,
+        )
+        .await;
+        if matches!(
+            outcome,
+            RestackOutcome::MergeConflicts { .. } | RestackOutcome::Failed { .. }
+        ) {
+            blocked.insert(layer.id);
+        }
+
+        layers.push(RestackLayerResult {
+            workspace_id: layer.id,
+            branch: layer.branch.clone(),
+            base_branch,
+            outcome,
+        });
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_restacked",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+                "layers": layers.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(RestackResponse {
+        layers,
+    })))
+}
+
+/// Ask the git host whether any layer below `workspace` has landed since the PR monitor
+/// last polled, so its children are retargeted before the stack is shown or rebased.
+async fn refresh_parent_prs(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    for parent in pr_stack::unlanded_ancestors(pool, workspace, repo_id).await? {
+        deployment
+            .pr_monitor()
+            .check_workspace_prs(parent.id)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn restack_layer(
+    deployment: &DeploymentImpl,
+    repo: &Repo,
+    layer: &Workspace,
+    workspace_repo: &WorkspaceRepo,
+    base_branch: &str,
+    old_base: &str,
+    previous_heads: &mut HashMap<Uuid, String>,
+) -> RestackOutcome {
+    let pool = &deployment.db().pool;
+
+    let container_ref = match deployment.container().ensure_container_exists(layer).await {
+        Ok(container_ref) => container_ref,
+        Err(e) => {
+            return RestackOutcome::Failed {
+                message: e.to_string(),
+            };
+        }
+    };
+    let worktree_path = PathBuf::from(&container_ref).join(&repo.name);
+
+    workspace_checkpoint::create_auto_checkpoint(
+        pool,
+        deployment.git(),
+        layer.id,
+        Path::new(&container_ref),
+        CheckpointReason::BeforeRebase,
+    )
+    .await;
+
+    let head_before = match deployment.git().get_branch_oid(&repo.path, &layer.branch) {
+        Ok(oid) => oid,
+        Err(e) => {
+            return RestackOutcome::Failed {
+                message: e.to_string(),
+            };
+        }
+    };
+
+    match deployment.git().rebase_branch(
+        &repo.path,
+        &worktree_path,
+        base_branch,
+        old_base,
+        &layer.branch,
+    ) {
+        Ok(_) => {}
+        Err(GitServiceError::MergeConflicts {
+            message,
+            conflicted_files,
+        }) => {
+            return RestackOutcome::MergeConflicts {
+                message,
+                conflicted_files,
+            };
+        }
+        Err(e) => {
+            return RestackOutcome::Failed {
+                message: e.to_string(),
+            };
+        }
+    }
+    previous_heads.insert(layer.id, head_before);
+
+    if workspace_repo.target_branch != base_branch
+        && let Err(e) =
+            WorkspaceRepo::update_target_branch(pool, layer.id, repo.id, base_branch).await
+    {
+        tracing::error!(
+            "Failed to update target branch of restacked workspace {}: {}",
+            layer.id,
+            e
+        );
+    }
+
+    let has_open_pr = Merge::find_by_workspace_and_repo_id(pool, layer.id, repo.id)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .any(|m| matches!(m, Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open)));
+    if !has_open_pr {
+        return RestackOutcome::Rebased { pushed: false };
+    }
+
+    match deployment
+        .git()
+        .push_to_remote(&worktree_path, &layer.branch, true)
+    {
+        Ok(()) => RestackOutcome::Rebased { pushed: true },
+        Err(e) => RestackOutcome::PushFailed {
+            message: e.to_string(),
+        },
+    }
+}
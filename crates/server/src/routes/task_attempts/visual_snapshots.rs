@@ -0,0 +1,427 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    dev_server::{DevServer, DevServerStatus},
+    image::{Image, TaskImage},
+    repo::Repo,
+    repo_dev_process::RepoDevProcess,
+    repo_dev_server_settings::RepoDevServerSettings,
+    visual_snapshot::VisualSnapshot,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    visual_snapshot::{
+        self, BaselineDevServer, DEFAULT_VIEWPORTS, SnapshotRequest, SnapshotViewport,
+    },
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_workspace_middleware};
+
+/// Upper bound on routes × viewports per request, as every pair launches a browser.
+const MAX_SNAPSHOTS_PER_REQUEST: usize = 24;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CaptureVisualSnapshotsRequest {
+    /// Dev server to capture; defaults to the workspace's first ready dev server
+    pub dev_server_id: Option<Uuid>,
+    /// Start the dev server from the target branch too and diff against it
+    #[serde(default)]
+    pub compare_with_target_branch: bool,
+    /// Routes to capture; defaults to `/`
+    pub routes: Option<Vec<String>>,
+    /// Viewport sizes; defaults to a desktop and a mobile size
+    pub viewports: Option<Vec<SnapshotViewport>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum VisualSnapshotRunStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Latest snapshot run of a workspace. Runs take a while, as they may start the target
+/// branch's dev server, so they continue in the background.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct VisualSnapshotRun {
+    pub status: VisualSnapshotRunStatus,
+    pub error: Option<String>,
+    pub snapshot_count: usize,
+    #[ts(type = "Date")]
+    pub started_at: DateTime<Utc>,
+    #[ts(type = "Date | null")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Snapshot runs by workspace, kept in memory for the life of the server.
+#[derive(Clone, Default)]
+pub struct SnapshotRuns(Arc<Mutex<HashMap<Uuid, VisualSnapshotRun>>>);
+
+impl SnapshotRuns {
+    fn get(&self, workspace_id: Uuid) -> Option<VisualSnapshotRun> {
+        self.0.lock().unwrap().get(&workspace_id).cloned()
+    }
+
+    /// Record a new run, unless one is already running for the workspace.
+    fn start(&self, workspace_id: Uuid) -> Option<VisualSnapshotRun> {
+        let mut runs = self.0.lock().unwrap();
+        if runs
+            .get(&workspace_id)
+            .is_some_and(|run| run.status == VisualSnapshotRunStatus::Running)
+        {
+            return None;
+        }
+        let run = VisualSnapshotRun {
+            status: VisualSnapshotRunStatus::Running,
+            error: None,
+            snapshot_count: 0,
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+        runs.insert(workspace_id, run.clone());
+        Some(run)
+    }
+
+    fn finish(&self, workspace_id: Uuid, result: &Result<usize, ApiError>) {
+        if let Some(run) = self.0.lock().unwrap().get_mut(&workspace_id) {
+            match result {
+                Ok(count) => {
+                    run.status = VisualSnapshotRunStatus::Completed;
+                    run.snapshot_count = *count;
+                }
+                Err(e) => {
+                    run.status = VisualSnapshotRunStatus::Failed;
+                    run.error = Some(e.to_string());
+                }
+            }
+            run.finished_at = Some(Utc::now());
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct VisualSnapshotResponse {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub snapshot: VisualSnapshot,
+    /// Paths relative to the worktree, for referencing the images in prompts and PRs
+    pub image_path: Option<String>,
+    pub baseline_image_path: Option<String>,
+    pub diff_image_path: Option<String>,
+}
+
+async fn markdown_path(
+    deployment: &DeploymentImpl,
+    image_id: Option<Uuid>,
+) -> Result<Option<String>, ApiError> {
+    let Some(image_id) = image_id else {
+        return Ok(None);
+    };
+    Ok(Image::find_by_id(&deployment.db().pool, image_id)
+        .await?
+        .map(|image| format!("{}/{}", utils::path::VIBE_IMAGES_DIR, image.file_path)))
+}
+
+async fn to_response(
+    deployment: &DeploymentImpl,
+    snapshot: VisualSnapshot,
+) -> Result<VisualSnapshotResponse, ApiError> {
+    Ok(VisualSnapshotResponse {
+        image_path: markdown_path(deployment, Some(snapshot.image_id)).await?,
+        baseline_image_path: markdown_path(deployment, snapshot.baseline_image_id).await?,
+        diff_image_path: markdown_path(deployment, snapshot.diff_image_id).await?,
+        snapshot,
+    })
+}
+
+pub async fn get_visual_snapshots(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<VisualSnapshotResponse>>>, ApiError> {
+    let snapshots =
+        VisualSnapshot::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    let mut responses = Vec::with_capacity(snapshots.len());
+    for snapshot in snapshots {
+        responses.push(to_response(&deployment, snapshot).await?);
+    }
+    Ok(ResponseJson(ApiResponse::success(responses)))
+}
+
+pub async fn get_visual_snapshot_run(
+    Extension(workspace): Extension<Workspace>,
+    Extension(runs): Extension<SnapshotRuns>,
+) -> Result<ResponseJson<ApiResponse<Option<VisualSnapshotRun>>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(runs.get(workspace.id))))
+}
+
+/// Start screenshotting one of the workspace's ready dev servers. The run continues in
+/// the background; the images are linked to the task and copied into the worktree so
+/// they can be referenced from follow-ups.
+pub async fn capture_visual_snapshots(
+    Extension(workspace): Extension<Workspace>,
+    Extension(runs): Extension<SnapshotRuns>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CaptureVisualSnapshotsRequest>,
+) -> Result<ResponseJson<ApiResponse<VisualSnapshotRun>>, ApiError> {
+    let dev_server = DevServer::find_by_workspace_id(&deployment.db().pool, workspace.id)
+        .await?
+        .into_iter()
+        .filter(|dev_server| dev_server.status == DevServerStatus::Ready)
+        .find(|dev_server| {
+            payload
+                .dev_server_id
+                .is_none_or(|dev_server_id| dev_server.id == dev_server_id)
+        })
+        .ok_or_else(|| {
+            ApiError::BadRequest(
+                "Start the dev server and wait until it's ready before taking visual snapshots"
+                    .to_string(),
+            )
+        })?;
+
+    let routes = payload
+        .routes
+        .filter(|routes| !routes.is_empty())
+        .unwrap_or_else(|| vec!["/".to_string()]);
+    let viewports = payload
+        .viewports
+        .filter(|viewports| !viewports.is_empty())
+        .unwrap_or_else(|| DEFAULT_VIEWPORTS.to_vec());
+    if routes.len() * viewports.len() > MAX_SNAPSHOTS_PER_REQUEST {
+        return Err(ApiError::BadRequest(format!(
+            "At most {MAX_SNAPSHOTS_PER_REQUEST} snapshots can be taken at once"
+        )));
+    }
+    if viewports.iter().any(|viewport| {
+        !(100..=4096).contains(&viewport.width) || !(100..=4096).contains(&viewport.height)
+    }) {
+        return Err(ApiError::BadRequest(
+            "Viewport sizes must be between 100 and 4096 pixels".to_string(),
+        ));
+    }
+    visual_snapshot::validate_routes(&routes)?;
+
+    let run = runs.start(workspace.id).ok_or_else(|| {
+        ApiError::Conflict("Visual snapshots are already being taken".to_string())
+    })?;
+
+    let compare = payload.compare_with_target_branch;
+    tokio::spawn(async move {
+        let result = run_snapshots(
+            &deployment,
+            &workspace,
+            &dev_server,
+            compare,
+            &routes,
+            &viewports,
+        )
+        .await;
+        if let Err(e) = &result {
+            tracing::warn!(
+                "Visual snapshots for workspace {} failed: {}",
+                workspace.id,
+                e
+            );
+        }
+        runs.finish(workspace.id, &result);
+    });
+
+    Ok(ResponseJson(ApiResponse::success(run)))
+}
+
+/// Take the snapshots, starting and stopping the target branch's dev server around them
+/// when comparing. Returns the number of snapshots taken.
+async fn run_snapshots(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    dev_server: &DevServer,
+    compare_with_target_branch: bool,
+    routes: &[String],
+    viewports: &[SnapshotViewport],
+) -> Result<usize, ApiError> {
+    let pool = &deployment.db().pool;
+    let port = u16::try_from(dev_server.port).map_err(|_| {
+        ApiError::BadRequest(format!("Invalid dev server port {}", dev_server.port))
+    })?;
+
+    let mut baseline = None;
+    let result = async {
+        let baseline_port = if compare_with_target_branch {
+            let server =
+                baseline.insert(checkout_target_branch(deployment, workspace, dev_server).await?);
+            Some(start_baseline(deployment, dev_server, server).await?)
+        } else {
+            None
+        };
+        visual_snapshot::capture_snapshots(
+            pool,
+            deployment.image(),
+            &SnapshotRequest {
+                execution_process_id: dev_server.execution_process_id,
+                port,
+                baseline_port,
+                routes,
+                viewports,
+            },
+        )
+        .await
+        .map_err(ApiError::from)
+    }
+    .await;
+    if let Some(baseline) = baseline {
+        baseline.stop().await;
+    }
+    let snapshots = result?;
+
+    let image_ids: Vec<Uuid> = snapshots
+        .iter()
+        .flat_map(|snapshot| {
+            [
+                Some(snapshot.image_id),
+                snapshot.baseline_image_id,
+                snapshot.diff_image_id,
+            ]
+        })
+        .flatten()
+        .collect();
+    TaskImage::associate_many_dedup(pool, workspace.task_id, &image_ids).await?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    let workspace_path = PathBuf::from(container_ref);
+    let base_path = match workspace.agent_working_dir.as_deref() {
+        Some(dir) if !dir.is_empty() => workspace_path.join(dir),
+        _ => workspace_path,
+    };
+    deployment
+        .image()
+        .copy_images_by_ids_to_worktree(&base_path, &image_ids)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "visual_snapshots_captured",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "snapshot_count": snapshots.len(),
+                "has_baseline": compare_with_target_branch,
+            }),
+        )
+        .await;
+
+    Ok(snapshots.len())
+}
+
+/// Check out the workspace's target branch for the dev server's repo, with the repo's
+/// project files copied and directories linked in as for a workspace.
+async fn checkout_target_branch(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    dev_server: &DevServer,
+) -> Result<BaselineDevServer, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, dev_server.repo_id)
+            .await?
+            .ok_or_else(|| ApiError::BadRequest("Repo not in workspace".to_string()))?;
+    let repo = Repo::find_by_id(pool, dev_server.repo_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Repo not found".to_string()))?;
+
+    let baseline =
+        BaselineDevServer::checkout(deployment.git(), &repo.path, &workspace_repo.target_branch)
+            .await?;
+    let checkout = baseline.path();
+    if let Some(copy_files) = &repo.copy_files
+        && !copy_files.trim().is_empty()
+        && let Err(e) = deployment
+            .container()
+            .copy_project_files(&repo.path, &checkout, copy_files)
+            .await
+    {
+        tracing::warn!(
+            "Failed to copy files into the target branch checkout: {}",
+            e
+        );
+    }
+    if let Some(symlink_dirs) = &repo.symlink_dirs
+        && !symlink_dirs.trim().is_empty()
+        && let Err(e) = deployment
+            .container()
+            .link_project_dirs(&repo.path, &checkout, symlink_dirs)
+            .await
+    {
+        tracing::warn!(
+            "Failed to link directories into the target branch checkout: {}",
+            e
+        );
+    }
+    Ok(baseline)
+}
+
+/// Run the command of the captured dev server from the target branch checkout. Returns
+/// the port it listens on.
+async fn start_baseline(
+    deployment: &DeploymentImpl,
+    dev_server: &DevServer,
+    baseline: &mut BaselineDevServer,
+) -> Result<u16, ApiError> {
+    let pool = &deployment.db().pool;
+    let (command, env) = match dev_server.process_name.as_deref() {
+        Some(name) => {
+            let process = RepoDevProcess::find_by_name(pool, dev_server.repo_id, name)
+                .await?
+                .ok_or_else(|| {
+                    ApiError::BadRequest(format!("Dev process '{name}' no longer exists"))
+                })?;
+            (process.command, process.env.0)
+        }
+        None => {
+            let repo = Repo::find_by_id(pool, dev_server.repo_id)
+                .await?
+                .ok_or_else(|| ApiError::BadRequest("Repo not found".to_string()))?;
+            let script = repo
+                .dev_server_script
+                .filter(|script| !script.trim().is_empty())
+                .ok_or_else(|| {
+                    ApiError::BadRequest("The repo has no dev server script".to_string())
+                })?;
+            (script, HashMap::new())
+        }
+    };
+    let settings = RepoDevServerSettings::find_or_default(pool, dev_server.repo_id).await?;
+    Ok(baseline.start(pool, &command, &env, &settings).await?)
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/",
+            get(get_visual_snapshots).post(capture_visual_snapshots),
+        )
+        .route("/run", get(get_visual_snapshot_run))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_middleware,
+        ))
+        .layer(Extension(SnapshotRuns::default()))
+}
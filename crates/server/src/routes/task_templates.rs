@@ -0,0 +1,313 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    repo::{Repo, RepoError},
+    task::{CreateTask, Task, TaskWithAttemptStatus},
+    task_template::{CreateTaskTemplate, TaskTemplate, TaskTemplateImage, TaskTemplateWithImages},
+};
+use deployment::Deployment;
+use executors::profile::ExecutorConfig;
+use serde::Deserialize;
+use services::services::task_template::{RenderedTaskTemplate, render_template, validate_template};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_task_template_middleware,
+    routes::{
+        task_attempts::WorkspaceRepoInput,
+        tasks::{self, CreateAndStartTaskRequest},
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct TaskTemplateQuery {
+    pub project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RenderTaskTemplateRequest {
+    pub values: HashMap<String, String>,
+    /// Repos checked for repo file path variables; defaults to the template's repos
+    pub repo_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskFromTemplateRequest {
+    pub project_id: Uuid,
+    pub values: HashMap<String, String>,
+    /// Overrides the template's default repos and target branches
+    pub repos: Option<Vec<WorkspaceRepoInput>>,
+    /// Overrides the template's default executor
+    pub executor_config: Option<ExecutorConfig>,
+}
+
+fn map_save_error(err: sqlx::Error) -> ApiError {
+    match &err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            ApiError::Conflict("A template with this name already exists".to_string())
+        }
+        _ => ApiError::Database(err),
+    }
+}
+
+pub async fn get_task_templates(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskTemplateQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskTemplateWithImages>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let templates = TaskTemplate::find_all(pool, query.project_id).await?;
+    let mut images = TaskTemplateImage::find_all_grouped(pool).await?;
+
+    let templates = templates
+        .into_iter()
+        .map(|template| TaskTemplateWithImages {
+            image_ids: images.remove(&template.id).unwrap_or_default(),
+            template,
+        })
+        .collect();
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+pub async fn get_task_template(
+    Extension(template): Extension<TaskTemplate>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplateWithImages>>, ApiError> {
+    let image_ids = TaskTemplateImage::find_image_ids(&deployment.db().pool, template.id).await?;
+    Ok(ResponseJson(ApiResponse::success(TaskTemplateWithImages {
+        template,
+        image_ids,
+    })))
+}
+
+pub async fn create_task_template(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskTemplate>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplateWithImages>>, ApiError> {
+    validate_template(&payload)?;
+    let template = TaskTemplate::create(&deployment.db().pool, &payload, Uuid::new_v4())
+        .await
+        .map_err(map_save_error)?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_template_created",
+            serde_json::json!({
+                "template_id": template.id.to_string(),
+                "variable_count": payload.variables.len(),
+                "repo_count": payload.repos.len(),
+                "has_executor": payload.executor_config.is_some(),
+                "has_verification_script": payload.verification_script.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(TaskTemplateWithImages {
+        template,
+        image_ids: payload.image_ids,
+    })))
+}
+
+pub async fn update_task_template(
+    Extension(template): Extension<TaskTemplate>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskTemplate>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplateWithImages>>, ApiError> {
+    validate_template(&payload)?;
+    let template = TaskTemplate::update(&deployment.db().pool, template.id, &payload)
+        .await
+        .map_err(map_save_error)?;
+
+    Ok(ResponseJson(ApiResponse::success(TaskTemplateWithImages {
+        template,
+        image_ids: payload.image_ids,
+    })))
+}
+
+pub async fn delete_task_template(
+    Extension(template): Extension<TaskTemplate>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = TaskTemplate::delete(&deployment.db().pool, template.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub async fn render_task_template(
+    Extension(template): Extension<TaskTemplate>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RenderTaskTemplateRequest>,
+) -> Result<ResponseJson<ApiResponse<RenderedTaskTemplate>>, ApiError> {
+    let repo_ids = payload
+        .repo_ids
+        .unwrap_or_else(|| template.repos.iter().map(|r| r.repo_id).collect());
+    let repos = Repo::find_by_ids(&deployment.db().pool, &repo_ids).await?;
+    let roots: Vec<PathBuf> = repos.into_iter().map(|r| r.path).collect();
+
+    let rendered = render_template(&template, &payload.values, &roots)?;
+    Ok(ResponseJson(ApiResponse::success(rendered)))
+}
+
+pub async fn create_task_from_template(
+    Extension(template): Extension<TaskTemplate>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskFromTemplateRequest>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let (task, _) = prepare_task(&deployment, &template, &payload).await?;
+    let response = tasks::create_task(State(deployment.clone()), Json(task)).await?;
+    track_task_from_template(&deployment, &template, false).await;
+    Ok(response)
+}
+
+pub async fn create_task_from_template_and_start(
+    Extension(template): Extension<TaskTemplate>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskFromTemplateRequest>,
+) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
+    let executor_config = payload
+        .executor_config
+        .clone()
+        .or_else(|| template.executor_config.as_ref().map(|c| c.0.clone()))
+        .ok_or_else(|| {
+            ApiError::BadRequest(
+                "An executor is required when the template has no default".to_string(),
+            )
+        })?;
+    let (task, repos) = prepare_task(&deployment, &template, &payload).await?;
+
+    let response = tasks::create_task_and_start(
+        State(deployment.clone()),
+        Json(CreateAndStartTaskRequest {
+            task,
+            executor_config,
+            repos,
+            linked_issue: None,
+        }),
+    )
+    .await?;
+    track_task_from_template(&deployment, &template, true).await;
+    Ok(response)
+}
+
+/// Render the template for the chosen repos and build the task payload, with the
+/// template's images attached.
+async fn prepare_task(
+    deployment: &DeploymentImpl,
+    template: &TaskTemplate,
+    payload: &CreateTaskFromTemplateRequest,
+) -> Result<(CreateTask, Vec<WorkspaceRepoInput>), ApiError> {
+    if template
+        .project_id
+        .is_some_and(|project_id| project_id != payload.project_id)
+    {
+        return Err(ApiError::BadRequest(
+            "Template belongs to a different project".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    let mut repos = Vec::new();
+    let mut roots = Vec::new();
+    match &payload.repos {
+        Some(inputs) => {
+            for input in inputs {
+                let repo = Repo::find_by_id(pool, input.repo_id)
+                    .await?
+                    .ok_or(RepoError::NotFound)?;
+                roots.push(repo.path);
+                repos.push(WorkspaceRepoInput {
+                    repo_id: input.repo_id,
+                    target_branch: input.target_branch.clone(),
+                });
+            }
+        }
+        None => {
+            for template_repo in template.repos.iter() {
+                let repo = Repo::find_by_id(pool, template_repo.repo_id)
+                    .await?
+                    .ok_or(RepoError::NotFound)?;
+                let target_branch = match template_repo
+                    .target_branch
+                    .clone()
+                    .or(repo.default_target_branch.clone())
+                {
+                    Some(branch) => branch,
+                    None => deployment.git().get_current_branch(&repo.path)?,
+                };
+                roots.push(repo.path);
+                repos.push(WorkspaceRepoInput {
+                    repo_id: template_repo.repo_id,
+                    target_branch,
+                });
+            }
+        }
+    }
+
+    let rendered = render_template(template, &payload.values, &roots)?;
+    let image_ids = TaskTemplateImage::find_image_ids(pool, template.id).await?;
+
+    let task = CreateTask {
+        project_id: payload.project_id,
+        title: rendered.title,
+        description: Some(rendered.prompt),
+        status: None,
+        parent_workspace_id: None,
+        image_ids: (!image_ids.is_empty()).then_some(image_ids),
+    };
+    Ok((task, repos))
+}
+
+async fn track_task_from_template(
+    deployment: &DeploymentImpl,
+    template: &TaskTemplate,
+    started: bool,
+) {
+    deployment
+        .track_if_analytics_allowed(
+            "task_created_from_template",
+            serde_json::json!({
+                "template_id": template.id.to_string(),
+                "started": started,
+            }),
+        )
+        .await;
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let template_router = Router::new()
+        .route(
+            "/",
+            get(get_task_template)
+                .put(update_task_template)
+                .delete(delete_task_template),
+        )
+        .route("/render", post(render_task_template))
+        .route("/create-task", post(create_task_from_template))
+        .route(
+            "/create-and-start",
+            post(create_task_from_template_and_start),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_template_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_task_templates).post(create_task_template))
+        .nest("/{template_id}", template_router);
+
+    Router::new().nest("/task-templates", inner)
+}
@@ -0,0 +1,164 @@
+//! Parsing of task lists for bulk creation.
+//!
+//! Tasks can be supplied as JSON or as CSV with a header row. CSV files need a `title`
+//! column and may have `description` and `status` columns; other columns are ignored.
+
+use std::str::FromStr;
+
+use db::models::task::TaskStatus;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BulkTaskParseError {
+    #[error("CSV is empty")]
+    Empty,
+    #[error("CSV header has no 'title' column")]
+    MissingTitleColumn,
+    #[error("Unterminated quoted field on line {0}")]
+    UnterminatedQuote(usize),
+    #[error("Invalid status '{status}' on line {line}")]
+    InvalidStatus { line: usize, status: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct BulkTaskInput {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: Option<TaskStatus>,
+}
+
+/// Parse CSV text into task inputs. Rows with an empty title are kept so the caller can
+/// report them against their position in the file.
+pub fn parse_task_csv(text: &str) -> Result<Vec<BulkTaskInput>, BulkTaskParseError> {
+    let mut records = parse_records(text)?.into_iter();
+    let (_, header) = records.next().ok_or(BulkTaskParseError::Empty)?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+    };
+    let title_col = column("title").ok_or(BulkTaskParseError::MissingTitleColumn)?;
+    let description_col = column("description");
+    let status_col = column("status");
+
+    records
+        .filter(|(_, fields)| fields.iter().any(|f| !f.trim().is_empty()))
+        .map(|(line, fields)| {
+            let field = |col: Option<usize>| {
+                col.and_then(|c| fields.get(c))
+                    .map(|f| f.trim())
+                    .filter(|f| !f.is_empty())
+            };
+            let status = field(status_col)
+                .map(|s| {
+                    TaskStatus::from_str(&s.to_lowercase().replace([' ', '-', '_'], "")).map_err(
+                        |_| BulkTaskParseError::InvalidStatus {
+                            line,
+                            status: s.to_string(),
+                        },
+                    )
+                })
+                .transpose()?;
+            Ok(BulkTaskInput {
+                title: field(Some(title_col)).unwrap_or_default().to_string(),
+                description: field(description_col).map(str::to_string),
+                status,
+            })
+        })
+        .collect()
+}
+
+/// Split CSV text into records, each tagged with the line it starts on. Quoted fields may
+/// contain commas, newlines and doubled quotes.
+fn parse_records(text: &str) -> Result<Vec<(usize, Vec<String>)>, BulkTaskParseError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (in_quotes, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => in_quotes = false,
+            (false, '"') if field.is_empty() => in_quotes = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut fields)));
+                line += 1;
+                record_line = line;
+            }
+            (_, c) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    if in_quotes {
+        return Err(BulkTaskParseError::UnterminatedQuote(record_line));
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_csv_with_quotes_and_optional_columns() {
+        let csv = "Title,Status,Notes,Description\r\n\
+                   Fix login,todo,x,\"Users see a 500,\n\"\"sometimes\"\"\"\n\
+                   \n\
+                   Add docs,In Progress,,\n";
+        let tasks = parse_task_csv(csv).unwrap();
+        assert_eq!(
+            tasks,
+            vec![
+                BulkTaskInput {
+                    title: "Fix login".to_string(),
+                    description: Some("Users see a 500,\n\"sometimes\"".to_string()),
+                    status: Some(TaskStatus::Todo),
+                },
+                BulkTaskInput {
+                    title: "Add docs".to_string(),
+                    description: None,
+                    status: Some(TaskStatus::InProgress),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_csv_errors() {
+        assert_eq!(
+            parse_task_csv("name\nfoo\n"),
+            Err(BulkTaskParseError::MissingTitleColumn)
+        );
+        assert_eq!(
+            parse_task_csv("title,status\na,todo\nb,later\n"),
+            Err(BulkTaskParseError::InvalidStatus {
+                line: 3,
+                status: "later".to_string()
+            })
+        );
+        assert_eq!(
+            parse_task_csv("title\n\"open\n"),
+            Err(BulkTaskParseError::UnterminatedQuote(2))
+        );
+    }
+}
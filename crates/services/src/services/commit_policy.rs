@@ -0,0 +1,328 @@
+//! Per-repo commit policies.
+//!
+//! A repo's [`RepoCommitPolicy`] decides who agent commits are attributed to, whether
+//! they are signed off or GPG-signed, how their messages are written, and whether the
+//! per-turn history is squashed into a single commit when the workspace is finished
+//! (PR creation or direct merge).
+
+use std::{sync::Arc, time::Duration};
+
+use db::models::{
+    execution_process::ExecutionProcess,
+    repo_commit_policy::{CommitHookMode, CommitMessageStyle, RepoCommitPolicy},
+    session::Session,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+#[cfg(feature = "qa-mode")]
+use executors::executors::qa_mock::QaMockExecutor;
+#[cfg(not(feature = "qa-mode"))]
+use executors::profile::ExecutorConfigs;
+use executors::{
+    actions::{
+        Executable, ExecutorAction, ExecutorActionType,
+        coding_agent_initial::CodingAgentInitialRequest,
+    },
+    approvals::NoopExecutorApprovalService,
+    env::{ExecutionEnv, RepoContext},
+    executors::{ExecutorExitResult, StandardCodingAgentExecutor},
+    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    profile::ExecutorConfig,
+};
+use futures::{TryStreamExt, stream::select};
+use git::CommitOptions;
+use tokio_util::io::ReaderStream;
+use tracing::{debug, warn};
+use utils::{log_msg::LogMsg, msg_store::MsgStore, process::kill_process_group};
+
+use crate::services::container::ContainerService;
+
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+/// Upper bound on how long the merge queue waits for the agent to write a message
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(3 * 60);
+/// How long the agent's remaining output gets to drain once it has exited
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+/// Time given to the normalizer to turn the agent's last output into its reply
+const NORMALIZE_GRACE: Duration = Duration::from_millis(200);
+
+const CONVENTIONAL_MESSAGE_PROMPT: &str = r#"Write a single commit message in the Conventional Commits format that describes all of the changes made in this workspace.
+
+Task: {task}
+
+Use the form `type(scope): subject` with one of: feat, fix, docs, style, refactor, perf, test, build, ci, chore, revert. Keep the subject under 72 characters, in the imperative mood, and optionally add a short body after a blank line.
+
+Do not modify any files, run commands that change the repository, or commit anything. Reply with the commit message only."#;
+
+pub fn commit_options(policy: &RepoCommitPolicy) -> CommitOptions {
+    CommitOptions {
+        author_name: policy.author_name.clone(),
+        author_email: policy.author_email.clone(),
+        signoff: policy.signoff,
+        gpg_sign: policy.gpg_sign,
+        no_verify: policy.hook_mode == CommitHookMode::Skip,
+    }
+}
+
+/// Whether `line` looks like `type(scope)!: subject`.
+pub fn is_conventional_subject(line: &str) -> bool {
+    let Some((prefix, subject)) = line.split_once(": ") else {
+        return false;
+    };
+    if subject.trim().is_empty() {
+        return false;
+    }
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+    let commit_type = match prefix.split_once('(') {
+        Some((commit_type, scope)) => {
+            let Some(scope) = scope.strip_suffix(')') else {
+                return false;
+            };
+            if scope.is_empty() || scope.contains(['(', ')', ' ']) {
+                return false;
+            }
+            commit_type
+        }
+        None => prefix,
+    };
+    CONVENTIONAL_TYPES.contains(&commit_type)
+}
+
+/// Pull a conventional commit message out of free-form agent output: the first
+/// conventional subject line plus whatever body follows it.
+pub fn extract_conventional_message(text: &str) -> Option<String> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim_end())
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect();
+    let start = lines
+        .iter()
+        .position(|line| is_conventional_subject(line.trim().trim_matches('`')))?;
+    let subject = lines[start].trim().trim_matches('`');
+    let body = lines[start + 1..].join("\n");
+    let body = body.trim();
+    Some(if body.is_empty() {
+        subject.to_string()
+    } else {
+        format!("{subject}\n\n{body}")
+    })
+}
+
+/// Best-effort conventional message without involving the agent. Messages that already
+/// follow the format are kept; anything else becomes a `chore:` commit.
+pub fn to_conventional(message: &str) -> String {
+    if let Some(message) = extract_conventional_message(message) {
+        return message;
+    }
+    let mut lines = message.trim().lines();
+    let subject = lines.next().unwrap_or_default().trim();
+    let subject = if subject.is_empty() {
+        "update workspace changes"
+    } else {
+        subject
+    };
+    let body = lines.collect::<Vec<_>>().join("\n");
+    let body = body.trim();
+    if body.is_empty() {
+        format!("chore: {subject}")
+    } else {
+        format!("chore: {subject}\n\n{body}")
+    }
+}
+
+/// Message for a commit made while a request is waiting on it. Never starts an agent
+/// turn: with the conventional style `fallback` is converted.
+pub fn template_commit_message(policy: &RepoCommitPolicy, fallback: &str) -> String {
+    match policy.message_style {
+        CommitMessageStyle::AgentSummary => fallback.to_string(),
+        CommitMessageStyle::Conventional => to_conventional(fallback),
+    }
+}
+
+/// Message for a commit that closes out a workspace in the background (merge queue).
+/// With the conventional style the workspace's coding agent is asked for a message in
+/// a one-off run; if that isn't possible or takes too long, `fallback` is converted.
+pub async fn finish_commit_message<C: ContainerService + Sync>(
+    container: &C,
+    policy: &RepoCommitPolicy,
+    workspace: &Workspace,
+    task_title: &str,
+    fallback: &str,
+) -> String {
+    match policy.message_style {
+        CommitMessageStyle::AgentSummary => fallback.to_string(),
+        CommitMessageStyle::Conventional => {
+            match request_conventional_message(container, workspace, task_title).await {
+                Ok(Some(message)) => message,
+                Ok(None) => to_conventional(fallback),
+                Err(e) => {
+                    warn!(
+                        "Failed to generate commit message for workspace {}: {}",
+                        workspace.id, e
+                    );
+                    to_conventional(fallback)
+                }
+            }
+        }
+    }
+}
+
+/// Ask the workspace's coding agent for a message in a one-off run outside the
+/// workspace's sessions. Nothing is recorded for the run and the user's agent session
+/// isn't resumed, so it neither shows up in the conversation nor goes through the exit
+/// handling of a turn (auto-commit, review status, queued messages).
+async fn request_conventional_message<C: ContainerService + Sync>(
+    container: &C,
+    workspace: &Workspace,
+    task_title: &str,
+) -> Result<Option<String>, anyhow::Error> {
+    let pool = &container.db().pool;
+
+    // Never interrupt or race an agent that is still working in this workspace
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        debug!(
+            "Workspace {} has running processes, skipping commit message generation",
+            workspace.id
+        );
+        return Ok(None);
+    }
+
+    let Some(session) = Session::find_latest_by_workspace_id(pool, workspace.id).await? else {
+        return Ok(None);
+    };
+    let Some(executor_profile_id) =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?
+    else {
+        return Ok(None);
+    };
+
+    let current_dir = container.workspace_to_current_dir(workspace);
+    let request = CodingAgentInitialRequest {
+        prompt: CONVENTIONAL_MESSAGE_PROMPT.replace("{task}", task_title),
+        executor_config: ExecutorConfig::from(executor_profile_id.clone()),
+        working_dir: workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned(),
+    };
+    let working_dir = request.effective_dir(&current_dir);
+    let action = ExecutorAction::new(ExecutorActionType::CodingAgentInitialRequest(request), None);
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let repo_names = repos.into_iter().map(|repo| repo.name).collect();
+    let env = ExecutionEnv::new(
+        RepoContext::new(current_dir.clone(), repo_names),
+        false,
+        String::new(),
+    );
+    let mut spawned = action
+        .spawn(&current_dir, Arc::new(NoopExecutorApprovalService {}), &env)
+        .await?;
+
+    let logs = Arc::new(MsgStore::new());
+    let out = spawned.child.inner().stdout.take().expect("no stdout");
+    let err = spawned.child.inner().stderr.take().expect("no stderr");
+    let out = ReaderStream::new(out)
+        .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+    let err = ReaderStream::new(err)
+        .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+    let forwarder = logs.clone().spawn_forwarder(select(out, err));
+    #[cfg(feature = "qa-mode")]
+    QaMockExecutor.normalize_logs(logs.clone(), &working_dir);
+    #[cfg(not(feature = "qa-mode"))]
+    match ExecutorConfigs::get_cached().get_coding_agent(&executor_profile_id) {
+        Some(executor) => executor.normalize_logs(logs.clone(), &working_dir),
+        None => warn!(
+            "Failed to resolve profile '{:?}' for normalization",
+            executor_profile_id
+        ),
+    }
+
+    // Some agents keep running after their turn and only signal that it finished
+    let exit_signal = spawned.exit_signal.take();
+    let finished = tokio::time::timeout(MESSAGE_TIMEOUT, async {
+        match exit_signal {
+            Some(exit_signal) => tokio::select! {
+                result = exit_signal => matches!(result, Ok(ExecutorExitResult::Success) | Err(_)),
+                status = spawned.child.wait() => status.is_ok_and(|status| status.success()),
+            },
+            None => spawned
+                .child
+                .wait()
+                .await
+                .is_ok_and(|status| status.success()),
+        }
+    })
+    .await;
+    if let Err(e) = kill_process_group(&mut spawned.child).await {
+        warn!("Failed to stop the commit message agent: {}", e);
+    }
+
+    match finished {
+        Ok(true) => {}
+        Ok(false) => {
+            debug!("Commit message agent for workspace {} failed", workspace.id);
+            return Ok(None);
+        }
+        Err(_) => {
+            warn!(
+                "Commit message agent for workspace {} did not finish in time",
+                workspace.id
+            );
+            return Ok(None);
+        }
+    }
+
+    // Let the output drain and the normalizer catch up before reading the reply
+    let _ = tokio::time::timeout(DRAIN_TIMEOUT, forwarder).await;
+    tokio::time::sleep(NORMALIZE_GRACE).await;
+    Ok(last_assistant_message(&logs).and_then(|reply| extract_conventional_message(&reply)))
+}
+
+fn last_assistant_message(logs: &MsgStore) -> Option<String> {
+    logs.get_history().iter().rev().find_map(|msg| {
+        let LogMsg::JsonPatch(patch) = msg else {
+            return None;
+        };
+        let (_, entry) = extract_normalized_entry_from_patch(patch)?;
+        (matches!(entry.entry_type, NormalizedEntryType::AssistantMessage)
+            && !entry.content.trim().is_empty())
+        .then(|| entry.content.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_conventional_subjects() {
+        assert!(is_conventional_subject("feat: add login"));
+        assert!(is_conventional_subject("fix(api)!: handle empty body"));
+        assert!(!is_conventional_subject("Add login"));
+        assert!(!is_conventional_subject("feature: add login"));
+        assert!(!is_conventional_subject("feat(): add login"));
+        assert!(!is_conventional_subject("feat: "));
+    }
+
+    #[test]
+    fn extracts_message_from_agent_reply() {
+        let reply = "Here is the message:\n\n```\nfeat(ui): add dark mode\n\nAdds a toggle.\n```";
+        assert_eq!(
+            extract_conventional_message(reply).as_deref(),
+            Some("feat(ui): add dark mode\n\nAdds a toggle.")
+        );
+        assert_eq!(extract_conventional_message("All done!"), None);
+    }
+
+    #[test]
+    fn falls_back_to_chore() {
+        assert_eq!(to_conventional("Fixed the bug"), "chore: Fixed the bug");
+        assert_eq!(to_conventional("fix: the bug"), "fix: the bug");
+    }
+}
@@ -0,0 +1,335 @@
+//! Agent-driven resolution of rebase and merge conflicts.
+//!
+//! A resolution runs in rounds: the agent is handed the conflicted files, and when its
+//! execution exits the container checks for leftover markers and continues the
+//! operation. If the next rebase step conflicts again, another round is started.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    repo::Repo,
+    session::Session,
+    workspace::Workspace,
+};
+use executors::profile::ExecutorConfig;
+use git::{ConflictOp, GitService};
+use tokio::sync::RwLock;
+use utils::text::truncate_to_char_boundary;
+use uuid::Uuid;
+
+/// How many conflicting rebase steps the agent resolves before handing back to the user
+pub const MAX_ROUNDS: usize = 5;
+const MAX_FILES_IN_PROMPT: usize = 12;
+const MAX_EXCERPT_BYTES_PER_FILE: usize = 4 * 1024;
+const CONTEXT_LINES: usize = 3;
+
+/// An in-progress resolution, kept by the container for the execution of its current round.
+#[derive(Debug, Clone)]
+pub struct ConflictResolution {
+    pub workspace: Workspace,
+    pub session: Session,
+    pub repo: Repo,
+    pub worktree_path: PathBuf,
+    pub target_branch: String,
+    pub executor_config: ExecutorConfig,
+    pub additional_prompt: Option<String>,
+    /// 1-based number of the current round
+    pub round: usize,
+    /// Files that were conflicted when the current round started
+    pub conflicted_files: Vec<String>,
+}
+
+/// In-progress resolutions by the execution of their current round.
+#[derive(Clone, Default)]
+pub struct ConflictResolutions(Arc<RwLock<HashMap<Uuid, ConflictResolution>>>);
+
+impl ConflictResolutions {
+    /// Start a round's execution with `start` and keep the resolution for it. No
+    /// resolution can be taken while a round starts, so an execution that exits before
+    /// `start` returns still finds its resolution.
+    pub async fn start_round<E>(
+        &self,
+        resolution: ConflictResolution,
+        start: impl Future<Output = Result<ExecutionProcess, E>>,
+    ) -> Result<ExecutionProcess, E> {
+        let mut resolutions = self.0.write().await;
+        let execution_process = start.await?;
+        resolutions.insert(execution_process.id, resolution);
+        Ok(execution_process)
+    }
+
+    /// Take the resolution whose round ran in an execution that has exited.
+    pub async fn take(&self, execution_id: Uuid) -> Option<ConflictResolution> {
+        self.0.write().await.remove(&execution_id)
+    }
+}
+
+/// What to do once the agent's execution for a round has exited.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResolutionStep {
+    /// The operation has been completed
+    Resolved,
+    /// The operation stopped on the next conflicting step
+    NextRound,
+    /// The resolution can't go on without the user; the operation is left in progress
+    Stopped(String),
+}
+
+impl ConflictResolution {
+    /// Check the agent's work and continue the operation if no conflict markers remain.
+    pub fn continue_after_round(
+        &self,
+        git: &GitService,
+        status: &ExecutionProcessStatus,
+    ) -> ResolutionStep {
+        if status != &ExecutionProcessStatus::Completed {
+            return ResolutionStep::Stopped(format!(
+                "Conflict resolution stopped because the agent ended with status {status:?}; \
+                 the operation in '{}' is still in progress.",
+                self.repo.display_name
+            ));
+        }
+
+        // The agent may have continued the operation itself
+        if matches!(git.detect_conflict_op(&self.worktree_path), Ok(None)) {
+            return ResolutionStep::Resolved;
+        }
+
+        let remaining = files_with_conflict_markers(&self.worktree_path, &self.conflicted_files);
+        if !remaining.is_empty() {
+            return ResolutionStep::Stopped(format!(
+                "Conflict markers remain in '{}' after the agent's resolution: {}",
+                self.repo.display_name,
+                remaining.join(", ")
+            ));
+        }
+
+        if let Err(e) = git.continue_conflict_op(&self.worktree_path) {
+            // A later rebase step stopping on new conflicts also surfaces as an error
+            let next_conflicts = git
+                .get_conflicted_files(&self.worktree_path)
+                .unwrap_or_default();
+            if next_conflicts.is_empty() {
+                return ResolutionStep::Stopped(format!(
+                    "Failed to continue after resolving conflicts in '{}': {e}",
+                    self.repo.display_name
+                ));
+            }
+        }
+
+        match git.detect_conflict_op(&self.worktree_path) {
+            Ok(None) => ResolutionStep::Resolved,
+            _ if self.round >= MAX_ROUNDS => ResolutionStep::Stopped(format!(
+                "Gave up on resolving conflicts in '{}' after {MAX_ROUNDS} rounds; \
+                 the operation is still in progress.",
+                self.repo.display_name
+            )),
+            _ => ResolutionStep::NextRound,
+        }
+    }
+
+    pub fn build_prompt(
+        &self,
+        op: &ConflictOp,
+        ours: Option<&str>,
+        theirs: Option<&str>,
+    ) -> String {
+        let mut prompt = match op {
+            ConflictOp::Merge => format!(
+                "Merge conflicts while merging into '{}' in repository '{}'.",
+                self.workspace.branch, self.repo.display_name
+            ),
+            _ => format!(
+                "Rebase conflicts while rebasing '{}' onto '{}' in repository '{}'.",
+                self.workspace.branch, self.target_branch, self.repo.display_name
+            ),
+        };
+
+        prompt.push_str("\n\nCommits on either side of the conflict:");
+        prompt.push_str(&format!(
+            "\n- Current (HEAD): {}",
+            ours.unwrap_or("unknown")
+        ));
+        prompt.push_str(&format!("\n- Incoming: {}", theirs.unwrap_or("unknown")));
+
+        let conflicted_files = &self.conflicted_files;
+        if !conflicted_files.is_empty() {
+            prompt.push_str("\n\nFiles with conflicts:");
+            for file in conflicted_files.iter().take(MAX_FILES_IN_PROMPT) {
+                prompt.push_str(&format!("\n- {file}"));
+            }
+            if conflicted_files.len() > MAX_FILES_IN_PROMPT {
+                prompt.push_str(&format!(
+                    "\n- ... and {} more",
+                    conflicted_files.len() - MAX_FILES_IN_PROMPT
+                ));
+            }
+
+            prompt.push_str("\n\nConflicting sections:");
+            for file in conflicted_files.iter().take(MAX_FILES_IN_PROMPT) {
+                let Ok(content) = std::fs::read_to_string(self.worktree_path.join(file)) else {
+                    continue;
+                };
+                let excerpt = conflict_excerpt(&content);
+                if excerpt.is_empty() {
+                    continue;
+                }
+                prompt.push_str(&format!(
+                    "\n\n{file}:\n```\n{}\n```",
+                    truncate_to_char_boundary(&excerpt, MAX_EXCERPT_BYTES_PER_FILE)
+                ));
+            }
+        }
+
+        prompt.push_str(
+            "\n\nResolve every conflict by editing the files so that both sides' intent is kept, \
+             and remove all conflict markers. Do not run `git rebase --continue`, `git commit` or \
+             `git rebase --abort`; the operation is continued automatically once no markers remain.",
+        );
+        if let Some(additional) = self
+            .additional_prompt
+            .as_deref()
+            .filter(|s| !s.trim().is_empty())
+        {
+            prompt.push_str("\n\n");
+            prompt.push_str(additional);
+        }
+        prompt
+    }
+}
+
+fn is_conflict_marker(line: &str) -> bool {
+    line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> ") || line == "======="
+}
+
+/// The conflicted regions of a file with a few lines of surrounding context.
+fn conflict_excerpt(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut start = None;
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with("<<<<<<< ") {
+            start = Some(i);
+        } else if line.starts_with(">>>>>>> ")
+            && let Some(s) = start.take()
+        {
+            let from = s.saturating_sub(CONTEXT_LINES);
+            let to = (i + CONTEXT_LINES).min(lines.len() - 1);
+            match ranges.last_mut() {
+                Some(last) if from <= last.1 + 1 => last.1 = to,
+                _ => ranges.push((from, to)),
+            }
+        }
+    }
+
+    ranges
+        .iter()
+        .map(|(from, to)| lines[*from..=*to].join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n...\n")
+}
+
+fn files_with_conflict_markers(worktree_path: &Path, files: &[String]) -> Vec<String> {
+    files
+        .iter()
+        .filter(|file| {
+            std::fs::read_to_string(worktree_path.join(file))
+                .is_ok_and(|content| content.lines().any(is_conflict_marker))
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        DBService,
+        models::execution_process::ExecutionProcessRunReason,
+        test_utils::{coding_agent_action, create_execution_process, create_session_chain},
+    };
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+
+    #[test]
+    fn excerpt_merges_nearby_conflicts() {
+        let content = "a\nb\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\nc\n\
+                       <<<<<<< HEAD\nx\n=======\ny\n>>>>>>> branch\nd\ne\nf\ng\nh";
+        let excerpt = conflict_excerpt(content);
+        assert!(excerpt.starts_with("a\nb\n<<<<<<< HEAD"));
+        assert!(!excerpt.contains("..."));
+        assert!(excerpt.ends_with("d\ne\nf"));
+    }
+
+    #[test]
+    fn reports_only_files_with_markers_left() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("done.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(
+            dir.path().join("left.rs"),
+            "<<<<<<< HEAD\na\n=======\nb\n>>>>>>> other\n",
+        )
+        .unwrap();
+        let files = vec!["done.rs".to_string(), "left.rs".to_string()];
+        assert_eq!(
+            files_with_conflict_markers(dir.path(), &files),
+            vec!["left.rs".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn round_exiting_before_its_start_returns_keeps_its_resolution() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let chain = create_session_chain(pool, "conflicts").await;
+        let worktree_path = PathBuf::from("/tmp/conflicts-repo");
+        let repo = Repo::find_or_create(pool, &worktree_path, "repo")
+            .await
+            .unwrap();
+        let resolution = ConflictResolution {
+            workspace: chain.workspace,
+            session: chain.session.clone(),
+            repo,
+            worktree_path,
+            target_branch: "main".to_string(),
+            executor_config: ExecutorConfig::new(BaseCodingAgent::ClaudeCode),
+            additional_prompt: None,
+            round: 2,
+            conflicted_files: vec!["src/lib.rs".to_string()],
+        };
+        let resolutions = ConflictResolutions::default();
+
+        // The agent exits at once, so its exit monitor asks for the resolution while the
+        // round is still starting
+        let mut exit_monitor = None;
+        let execution_process = resolutions
+            .start_round(resolution, async {
+                let execution_process = create_execution_process(
+                    pool,
+                    chain.session.id,
+                    coding_agent_action("resolve the conflicts"),
+                    ExecutionProcessRunReason::CodingAgent,
+                    &[],
+                )
+                .await;
+                let monitor_resolutions = resolutions.clone();
+                let execution_id = execution_process.id;
+                exit_monitor = Some(tokio::spawn(async move {
+                    monitor_resolutions.take(execution_id).await
+                }));
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                Ok::<_, sqlx::Error>(execution_process)
+            })
+            .await
+            .unwrap();
+
+        let taken = exit_monitor.unwrap().await.unwrap();
+        assert_eq!(taken.map(|resolution| resolution.round), Some(2));
+        assert!(resolutions.take(execution_process.id).await.is_none());
+    }
+}
@@ -10,11 +10,14 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        conversation_search::ConversationSearch,
+        dev_server::DevServer,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
             ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_logs::ExecutionProcessLogs,
+        execution_process_redactions::ExecutionProcessRedactions,
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
@@ -22,6 +25,8 @@ use db::{
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
         workspace::{Workspace, WorkspaceError},
+        workspace_checkpoint::CheckpointReason,
+        workspace_port::WorkspacePort,
         workspace_repo::WorkspaceRepo,
     },
 };
@@ -32,6 +37,7 @@ use executors::profile::ExecutorConfigs;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
@@ -40,7 +46,7 @@ use executors::{
     profile::{ExecutorConfig, ExecutorProfileId},
 };
 use futures::{StreamExt, future, stream::BoxStream};
-use git::{GitService, GitServiceError};
+use git::{ConflictOp, GitService, GitServiceError};
 use json_patch::Patch;
 use sqlx::Error as SqlxError;
 use thiserror::Error;
@@ -48,16 +54,27 @@ use tokio::{sync::RwLock, task::JoinHandle};
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
+    redact::Redactor,
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
 
 use crate::services::{
-    notification::NotificationService, workspace_manager::WorkspaceError as WorkspaceManagerError,
+    conflict_resolution::{ConflictResolution, ConflictResolutions},
+    context_pack::{ContextPackCache, PendingPacks},
+    conversation_search,
+    notification::NotificationService,
+    queued_message::QueuedMessageError,
+    symbol_index::{SymbolIndexCache, SymbolRoot},
+    workspace_checkpoint,
+    workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
 
+/// How long the startup backfill waits for one process's logs to be normalized
+const CONVERSATION_INDEX_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -76,6 +93,8 @@ pub enum ContainerError {
     Session(#[from] SessionError),
     #[error(transparent)]
     ExecutionProcess(#[from] ExecutionProcessError),
+    #[error(transparent)]
+    QueuedMessage(#[from] QueuedMessageError),
     #[error("Io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
@@ -88,12 +107,22 @@ pub enum ContainerError {
 pub trait ContainerService {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>;
 
+    /// Redactors of running execution processes, which mask secrets in their output
+    fn log_redactors(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<Redactor>>>>;
+
     fn db(&self) -> &DBService;
 
     fn git(&self) -> &GitService;
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn symbol_index_cache(&self) -> &Arc<SymbolIndexCache>;
+
+    fn context_pack_cache(&self) -> &Arc<ContextPackCache>;
+
+    /// Agent conflict resolutions waiting on the execution of their current round
+    fn conflict_resolutions(&self) -> &ConflictResolutions;
+
     async fn touch(&self, workspace: &Workspace) -> Result<(), ContainerError>;
 
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
@@ -344,6 +373,10 @@ pub trait ContainerService {
                 );
             }
         }
+        // No dev server survives a restart, including crashed ones waiting to be restarted
+        if let Err(e) = DevServer::stop_all(&self.db().pool).await {
+            tracing::error!("Failed to mark orphaned dev servers stopped: {}", e);
+        }
         Ok(())
     }
 
@@ -425,6 +458,52 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Index the conversations of finished processes that never made it into the search
+    /// index, e.g. because the server stopped while they were running.
+    async fn backfill_conversation_search(&self) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let ids = ConversationSearch::find_unindexed_execution_processes(pool).await?;
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        tracing::info!("Indexing {} conversations for search", ids.len());
+
+        for id in ids {
+            // A live store is indexed by the exit monitor
+            if self.get_msg_store_by_id(&id).await.is_some() {
+                continue;
+            }
+            let history = match self.stream_normalized_logs_from_db(&id, false).await {
+                Some(stream) => {
+                    match tokio::time::timeout(
+                        CONVERSATION_INDEX_TIMEOUT,
+                        stream.filter_map(|msg| future::ready(msg.ok())).collect(),
+                    )
+                    .await
+                    {
+                        Ok(history) => history,
+                        Err(_) => {
+                            tracing::warn!(
+                                "Timed out normalizing logs of process {} for search",
+                                id
+                            );
+                            continue;
+                        }
+                    }
+                }
+                // Nothing to index, but don't look at it again
+                None => Vec::<LogMsg>::new(),
+            };
+            if let Err(e) = conversation_search::index_execution(pool, id, &history).await {
+                tracing::warn!("Failed to index conversation of process {}: {}", id, e);
+            }
+        }
+
+        Ok(())
+    }
+
     fn cleanup_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
         let repos_with_cleanup: Vec<_> = repos
             .iter()
@@ -443,6 +522,7 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::CleanupScript,
                 working_dir: Some(first.name.clone()),
+                dev_process: None,
             }),
             None,
         );
@@ -454,6 +534,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::CleanupScript,
                     working_dir: Some(repo.name.clone()),
+                    dev_process: None,
                 }),
                 None,
             ));
@@ -480,6 +561,7 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::ArchiveScript,
                 working_dir: Some(first.name.clone()),
+                dev_process: None,
             }),
             None,
         );
@@ -491,6 +573,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::ArchiveScript,
                     working_dir: Some(repo.name.clone()),
+                    dev_process: None,
                 }),
                 None,
             ));
@@ -567,6 +650,15 @@ pub trait ContainerService {
             }
         }
 
+        // Free the workspace's ports for other workspaces
+        if let Err(e) = WorkspacePort::delete_by_workspace_id(pool, workspace_id).await {
+            tracing::error!(
+                "Failed to release ports for workspace {}: {}",
+                workspace_id,
+                e
+            );
+        }
+
         // Run archive script (silently skips if not configured)
         if let Err(e) = self.try_run_archive_script(workspace_id).await {
             tracing::error!(
@@ -594,6 +686,7 @@ pub trait ContainerService {
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::SetupScript,
                 working_dir: Some(first.name.clone()),
+                dev_process: None,
             }),
             None,
         );
@@ -605,6 +698,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.name.clone()),
+                    dev_process: None,
                 }),
                 None,
             ));
@@ -621,6 +715,7 @@ pub trait ContainerService {
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
                     working_dir: Some(repo.name.clone()),
+                    dev_process: None,
                 }),
                 None,
             )
@@ -640,6 +735,7 @@ pub trait ContainerService {
                         language: ScriptRequestLanguage::Bash,
                         context: ScriptContext::SetupScript,
                         working_dir: Some(repo.name.clone()),
+                        dev_process: None,
                     }),
                     Some(Box::new(chained)),
                 );
@@ -687,6 +783,17 @@ pub trait ContainerService {
             .map(|is_clean| !is_clean)
             .unwrap_or(false);
 
+        if perform_git_reset {
+            workspace_checkpoint::create_auto_checkpoint(
+                pool,
+                self.git(),
+                workspace.id,
+                &workspace_dir,
+                CheckpointReason::BeforeReset,
+            )
+            .await;
+        }
+
         for repo in &repos {
             let repo_state = repo_states.iter().find(|s| s.repo_id == repo.id);
             let target_oid = match repo_state.and_then(|s| s.before_head_commit.clone()) {
@@ -780,6 +887,25 @@ pub trait ContainerService {
 
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError>;
 
+    /// Inject a user message into a running execution through the executor that spawned it.
+    async fn send_user_input(
+        &self,
+        execution_process_id: Uuid,
+        message: String,
+    ) -> Result<(), ContainerError>;
+
+    /// Interrupt the current turn of a running execution, keeping the process alive for the
+    /// next user message. The execution is stopped if no message arrives in time.
+    async fn interrupt_execution(&self, execution_process_id: Uuid) -> Result<(), ContainerError>;
+
+    /// Start the next message of the session's follow-up queue as a follow-up execution.
+    /// Returns `None` when the queue is empty or paused, or something else already runs
+    /// in the workspace.
+    async fn start_next_queued_message(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError>;
+
     async fn copy_project_files(
         &self,
         source_dir: &Path,
@@ -787,6 +913,14 @@ pub trait ContainerService {
         copy_files: &str,
     ) -> Result<(), ContainerError>;
 
+    /// Link directories of the project, like `node_modules`, into a checkout.
+    async fn link_project_dirs(
+        &self,
+        source_dir: &Path,
+        target_dir: &Path,
+        symlink_dirs: &str,
+    ) -> Result<(), ContainerError>;
+
     /// Stream diff updates as LogMsg for WebSocket endpoints.
     async fn stream_diff(
         &self,
@@ -881,164 +1015,168 @@ pub trait ContainerService {
             )
         } else {
             // Fallback: load from DB and normalize
-            let log_records =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(records) if !records.is_empty() => records,
-                    Ok(_) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
+            self.stream_normalized_logs_from_db(id, true).await
+        }
+    }
 
-            let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
-                Ok(msgs) => msgs,
+    /// Normalize the logs stored in the database for an execution process. The stream ends
+    /// once the normalizer has processed all of them. `recreate_worktree` restores a cleaned
+    /// up worktree first so paths resolve as they did during the run.
+    async fn stream_normalized_logs_from_db(
+        &self,
+        id: &Uuid,
+        recreate_worktree: bool,
+    ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
+        let log_records =
+            match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
+                Ok(records) if !records.is_empty() => records,
+                Ok(_) => return None, // No logs exist
                 Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                    tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
                     return None;
                 }
             };
 
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
-                }
+        let raw_messages = match ExecutionProcessLogs::parse_logs(&log_records) {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Create temporary store and populate
+        // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+        let temp_store = Arc::new(MsgStore::new());
+        for msg in raw_messages {
+            if matches!(
+                msg,
+                LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+            ) {
+                temp_store.push(msg);
             }
-            temp_store.push_finished();
+        }
+        temp_store.push_finished();
 
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
+        let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => {
+                tracing::error!("No execution process found for ID: {}", id);
+                return None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Get the workspace to determine correct directory
+        let (workspace, _session) =
+            match process.parent_workspace_and_session(&self.db().pool).await {
+                Ok(Some((workspace, session))) => (workspace, session),
                 Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
+                    tracing::error!(
+                        "No workspace/session found for session ID: {}",
+                        process.session_id
+                    );
                     return None;
                 }
                 Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                    tracing::error!(
+                        "Failed to fetch workspace for session {}: {}",
+                        process.session_id,
+                        e
+                    );
                     return None;
                 }
             };
 
-            // Get the workspace to determine correct directory
-            let (workspace, _session) =
-                match process.parent_workspace_and_session(&self.db().pool).await {
-                    Ok(Some((workspace, session))) => (workspace, session),
-                    Ok(None) => {
-                        tracing::error!(
-                            "No workspace/session found for session ID: {}",
-                            process.session_id
-                        );
-                        return None;
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to fetch workspace for session {}: {}",
-                            process.session_id,
-                            e
-                        );
-                        return None;
-                    }
-                };
-
-            if let Err(err) = self.ensure_container_exists(&workspace).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for workspace {}: {}",
-                    workspace.id,
-                    err
-                );
-            }
+        if recreate_worktree && let Err(err) = self.ensure_container_exists(&workspace).await {
+            tracing::warn!(
+                "Failed to recreate worktree before log normalization for workspace {}: {}",
+                workspace.id,
+                err
+            );
+        }
 
-            let current_dir = self.workspace_to_current_dir(&workspace);
+        let current_dir = self.workspace_to_current_dir(&workspace);
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
-                );
-                return None;
-            };
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
 
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_config.profile_id());
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+        // Spawn normalizer on populated store
+        match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                #[cfg(feature = "qa-mode")]
+                {
+                    let executor = QaMockExecutor;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_config.profile_id());
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
+                #[cfg(not(feature = "qa-mode"))]
+                {
+                    let executor = ExecutorConfigs::get_cached()
+                        .get_coding_agent_or_default(&request.executor_config.profile_id());
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
                 #[cfg(feature = "qa-mode")]
-                ExecutorActionType::ReviewRequest(_request) => {
+                {
                     let executor = QaMockExecutor;
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
                 #[cfg(not(feature = "qa-mode"))]
-                ExecutorActionType::ReviewRequest(request) => {
+                {
                     let executor = ExecutorConfigs::get_cached()
                         .get_coding_agent_or_default(&request.executor_config.profile_id());
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
-                }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir));
                 }
             }
-            Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
-                    .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished)
-                    }))
-                    .boxed(),
-            )
+            #[cfg(feature = "qa-mode")]
+            ExecutorActionType::ReviewRequest(_request) => {
+                let executor = QaMockExecutor;
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            #[cfg(not(feature = "qa-mode"))]
+            ExecutorActionType::ReviewRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_config.profile_id());
+                executor.normalize_logs(temp_store.clone(), &current_dir);
+            }
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
+                    process.executor_action()
+                );
+                return None;
+            }
         }
+        Some(
+            temp_store
+                .history_plus_stream()
+                .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed(),
+        )
     }
 
     fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
         let execution_id = *execution_id;
         let msg_stores = self.msg_stores().clone();
+        let log_redactors = self.log_redactors().clone();
         let db = self.db().clone();
 
         tokio::spawn(async move {
@@ -1047,11 +1185,22 @@ pub trait ContainerService {
                 let map = msg_stores.read().await;
                 map.get(&execution_id).cloned()
             };
+            // Output was redacted on its way into the store; this catches what was pushed
+            // to the store directly
+            let redactor = {
+                let map = log_redactors.read().await;
+                map.get(&execution_id).cloned().unwrap_or_default()
+            };
 
             if let Some(store) = store {
                 let mut stream = store.history_plus_stream();
 
                 while let Some(Ok(msg)) = stream.next().await {
+                    let msg = match msg {
+                        LogMsg::Stdout(text) => LogMsg::Stdout(redactor.redact(&text)),
+                        LogMsg::Stderr(text) => LogMsg::Stderr(redactor.redact(&text)),
+                        msg => msg,
+                    };
                     match &msg {
                         LogMsg::Stdout(_) | LogMsg::Stderr(_) => {
                             // Serialize this individual message as a JSONL line
@@ -1123,9 +1272,53 @@ pub trait ContainerService {
                     }
                 }
             }
+
+            let counts = redactor.counts();
+            if counts.total() > 0
+                && let Err(e) =
+                    ExecutionProcessRedactions::save(&db.pool, execution_id, &counts).await
+            {
+                tracing::error!(
+                    "Failed to save redaction counts for execution {}: {}",
+                    execution_id,
+                    e
+                );
+            }
         })
     }
 
+    /// Add the workspace's repository overviews that its agent hasn't been given yet, or
+    /// that changed since, to `prompt`. The packs count as given once the execution is
+    /// started and they are passed to [`ContextPackCache::mark_delivered`].
+    async fn apply_context_packs(
+        &self,
+        workspace: &Workspace,
+        prompt: String,
+    ) -> Result<(String, PendingPacks), ContainerError> {
+        let Some(container_ref) = workspace
+            .container_ref
+            .as_deref()
+            .filter(|container_ref| !container_ref.is_empty())
+        else {
+            return Ok((prompt, PendingPacks::default()));
+        };
+        let repos = WorkspaceRepo::find_repos_with_target_branch_for_workspace(
+            &self.db().pool,
+            workspace.id,
+        )
+        .await?;
+        Ok(self
+            .context_pack_cache()
+            .apply_context_packs(
+                &self.db().pool,
+                self.git(),
+                Path::new(container_ref),
+                &repos,
+                prompt,
+            )
+            .await)
+    }
+
     async fn start_workspace(
         &self,
         workspace: &Workspace,
@@ -1157,7 +1350,20 @@ pub trait ContainerService {
         )
         .await?;
 
-        let prompt = task.to_prompt();
+        // Symbols come from the worktrees the agent will work in
+        let symbol_roots = SymbolRoot::for_repos(
+            &repos,
+            workspace
+                .container_ref
+                .as_deref()
+                .filter(|container_ref| !container_ref.is_empty())
+                .map(Path::new),
+        );
+        let prompt = self
+            .symbol_index_cache()
+            .expand_symbol_references(&task.to_prompt(), &symbol_roots)
+            .await;
+        let (prompt, context_packs) = self.apply_context_packs(&workspace, prompt).await?;
 
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
@@ -1214,6 +1420,9 @@ pub trait ContainerService {
             )
             .await?
         };
+        self.context_pack_cache()
+            .mark_delivered(context_packs)
+            .await;
 
         Ok(execution_process)
     }
@@ -1444,4 +1653,62 @@ pub trait ContainerService {
         tracing::debug!("Started next action: {:?}", next_action);
         Ok(())
     }
+
+    /// Start the next round of an agent conflict resolution. The container continues the
+    /// operation when the round's execution exits.
+    async fn start_conflict_resolution_round(
+        &self,
+        mut resolution: ConflictResolution,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let git = self.git();
+
+        let op = git
+            .detect_conflict_op(&resolution.worktree_path)?
+            .unwrap_or(ConflictOp::Rebase);
+        resolution.conflicted_files = git.get_conflicted_files(&resolution.worktree_path)?;
+        let (ours, theirs) = git.get_conflict_commit_subjects(&resolution.worktree_path, &op);
+        let prompt = resolution.build_prompt(&op, ours.as_deref(), theirs.as_deref());
+
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, resolution.workspace.id).await?;
+        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+        let working_dir = resolution
+            .workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let action_type =
+            match CodingAgentTurn::find_latest_session_info(pool, resolution.session.id).await? {
+                Some(info) => {
+                    ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                        prompt,
+                        session_id: info.session_id,
+                        reset_to_message_id: None,
+                        executor_config: resolution.executor_config.clone(),
+                        working_dir,
+                    })
+                }
+                None => ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                    prompt,
+                    executor_config: resolution.executor_config.clone(),
+                    working_dir,
+                }),
+            };
+        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+
+        let (workspace, session) = (resolution.workspace.clone(), resolution.session.clone());
+        self.conflict_resolutions()
+            .start_round(
+                resolution,
+                self.start_execution(
+                    &workspace,
+                    &session,
+                    &action,
+                    &ExecutionProcessRunReason::CodingAgent,
+                ),
+            )
+            .await
+    }
 }
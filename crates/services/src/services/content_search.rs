@@ -0,0 +1,307 @@
+//! Content (grep-style) search across repositories.
+//!
+//! Unlike [`super::file_search`], which only matches paths, this walks the files of each
+//! root (respecting `.gitignore` the way ripgrep does) and matches their lines against a
+//! literal or regex pattern. Matches are sent over a channel as they are found so callers
+//! can stream them to the client.
+
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use ts_rs::TS;
+use utils::text::truncate_to_char_boundary;
+
+const DEFAULT_MAX_RESULTS: usize = 200;
+const MAX_RESULTS_LIMIT: usize = 2000;
+const MAX_CONTEXT_LINES: usize = 10;
+/// Files larger than this are skipped; they are almost always generated or data files
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+/// Lines are cut to this many bytes in results so minified files don't flood the response
+const MAX_LINE_LEN: usize = 500;
+
+#[derive(Debug, Error)]
+pub enum ContentSearchError {
+    #[error("Search query cannot be empty")]
+    EmptyQuery,
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(#[from] regex::Error),
+}
+
+/// A directory to search, reported in results under `name`
+#[derive(Debug, Clone)]
+pub struct ContentSearchRoot {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContentSearchOptions {
+    /// Treat the query as a regular expression instead of a literal string
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Lines of context to include before and after each match (max 10)
+    pub context_lines: Option<usize>,
+    /// Stop after this many matches (default 200, max 2000)
+    pub max_results: Option<usize>,
+}
+
+impl ContentSearchOptions {
+    fn context_lines(&self) -> usize {
+        self.context_lines.unwrap_or(0).min(MAX_CONTEXT_LINES)
+    }
+
+    fn max_results(&self) -> usize {
+        self.max_results
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_LIMIT)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ContentSearchMatch {
+    /// Path prefixed with the root name, e.g. `my-repo/src/main.rs`
+    pub path: String,
+    /// 1-based line number
+    pub line_number: usize,
+    /// 0-based byte offset of the first match within the line
+    pub column: usize,
+    pub line: String,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentSearchEvent {
+    Match(ContentSearchMatch),
+    /// Always the last event; `truncated` is set when the result limit was reached
+    Done {
+        truncated: bool,
+        files_searched: usize,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ContentSearchResponse {
+    pub matches: Vec<ContentSearchMatch>,
+    pub truncated: bool,
+}
+
+/// Start searching `roots` for `query`, returning a channel of results that ends with
+/// [`ContentSearchEvent::Done`]. The search stops early if the receiver is dropped.
+pub fn search_content(
+    roots: Vec<ContentSearchRoot>,
+    query: &str,
+    options: &ContentSearchOptions,
+) -> Result<mpsc::Receiver<ContentSearchEvent>, ContentSearchError> {
+    if query.is_empty() {
+        return Err(ContentSearchError::EmptyQuery);
+    }
+    let pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()?;
+
+    let searcher = Searcher {
+        regex,
+        context_lines: options.context_lines(),
+        max_results: options.max_results(),
+    };
+    let (tx, rx) = mpsc::channel(64);
+    tokio::task::spawn_blocking(move || searcher.run(&roots, &tx));
+    Ok(rx)
+}
+
+/// Run a search to completion and collect its matches.
+pub async fn collect_content_search(
+    roots: Vec<ContentSearchRoot>,
+    query: &str,
+    options: &ContentSearchOptions,
+) -> Result<ContentSearchResponse, ContentSearchError> {
+    let mut rx = search_content(roots, query, options)?;
+    let mut response = ContentSearchResponse {
+        matches: Vec::new(),
+        truncated: false,
+    };
+    while let Some(event) = rx.recv().await {
+        match event {
+            ContentSearchEvent::Match(m) => response.matches.push(m),
+            ContentSearchEvent::Done { truncated, .. } => response.truncated = truncated,
+        }
+    }
+    Ok(response)
+}
+
+struct Searcher {
+    regex: Regex,
+    context_lines: usize,
+    max_results: usize,
+}
+
+impl Searcher {
+    fn run(&self, roots: &[ContentSearchRoot], tx: &mpsc::Sender<ContentSearchEvent>) {
+        let mut sent = 0;
+        let mut files_searched = 0;
+        let mut truncated = false;
+
+        'roots: for root in roots {
+            let walker = WalkBuilder::new(&root.path)
+                .git_ignore(true)
+                .git_global(true)
+                .git_exclude(true)
+                .hidden(false)
+                .filter_entry(|entry| entry.file_name() != ".git")
+                .sort_by_file_name(|a, b| a.cmp(b))
+                .build();
+
+            for entry in walker.flatten() {
+                if !entry.file_type().is_some_and(|ft| ft.is_file())
+                    || entry.metadata().is_ok_and(|m| m.len() > MAX_FILE_SIZE)
+                {
+                    continue;
+                }
+                let Some(content) = read_text_file(entry.path()) else {
+                    continue;
+                };
+                files_searched += 1;
+
+                let relative = entry
+                    .path()
+                    .strip_prefix(&root.path)
+                    .unwrap_or(entry.path())
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let path = format!("{}/{}", root.name, relative);
+
+                for m in self.search_file(&path, &content) {
+                    if sent == self.max_results {
+                        truncated = true;
+                        break 'roots;
+                    }
+                    if tx.blocking_send(ContentSearchEvent::Match(m)).is_err() {
+                        return;
+                    }
+                    sent += 1;
+                }
+            }
+        }
+
+        let _ = tx.blocking_send(ContentSearchEvent::Done {
+            truncated,
+            files_searched,
+        });
+    }
+
+    fn search_file(&self, path: &str, content: &str) -> Vec<ContentSearchMatch> {
+        let lines: Vec<&str> = content.lines().collect();
+        let display = |line: &str| truncate_to_char_boundary(line, MAX_LINE_LEN).to_string();
+
+        lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let found = self.regex.find(line)?;
+                let before_start = i.saturating_sub(self.context_lines);
+                let after_end = (i + 1 + self.context_lines).min(lines.len());
+                Some(ContentSearchMatch {
+                    path: path.to_string(),
+                    line_number: i + 1,
+                    column: found.start(),
+                    line: display(line),
+                    context_before: lines[before_start..i].iter().map(|l| display(l)).collect(),
+                    context_after: lines[i + 1..after_end].iter().map(|l| display(l)).collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Read a file as UTF-8 text, returning `None` for binary or non-UTF-8 files.
+fn read_text_file(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn search(
+        dir: &Path,
+        query: &str,
+        options: ContentSearchOptions,
+    ) -> ContentSearchResponse {
+        let roots = vec![ContentSearchRoot {
+            name: "repo".to_string(),
+            path: dir.to_path_buf(),
+        }];
+        collect_content_search(roots, query, &options)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn finds_matches_with_context_and_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "ignored.txt\n").unwrap();
+        std::fs::write(
+            dir.path().join("main.rs"),
+            "fn a() {}\nfn Login() {}\nfn c() {}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("ignored.txt"), "login\n").unwrap();
+        std::fs::write(dir.path().join("blob.bin"), b"login\0").unwrap();
+
+        let options = ContentSearchOptions {
+            context_lines: Some(1),
+            ..Default::default()
+        };
+        let response = search(dir.path(), "login", options).await;
+
+        assert!(!response.truncated);
+        assert_eq!(response.matches.len(), 1);
+        let m = &response.matches[0];
+        assert_eq!(m.path, "repo/main.rs");
+        assert_eq!(m.line_number, 2);
+        assert_eq!(m.column, 3);
+        assert_eq!(m.context_before, vec!["fn a() {}"]);
+        assert_eq!(m.context_after, vec!["fn c() {}"]);
+    }
+
+    #[tokio::test]
+    async fn supports_regex_case_sensitivity_and_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "foo1\nFOO2\nfoo3\n").unwrap();
+
+        let sensitive = ContentSearchOptions {
+            regex: true,
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let response = search(dir.path(), r"foo\d", sensitive).await;
+        assert_eq!(response.matches.len(), 2);
+
+        let limited = ContentSearchOptions {
+            max_results: Some(1),
+            ..Default::default()
+        };
+        let response = search(dir.path(), "foo", limited).await;
+        assert_eq!(response.matches.len(), 1);
+        assert!(response.truncated);
+
+        let literal = search(dir.path(), r"foo\d", ContentSearchOptions::default()).await;
+        assert!(literal.matches.is_empty());
+    }
+}
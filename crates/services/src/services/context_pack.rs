@@ -0,0 +1,479 @@
+//! Repository context packs.
+//!
+//! A context pack is a short markdown overview of a repository (project notes, directory
+//! layout, recently active files, README and build manifests) that saves the agent from
+//! exploring the repo from scratch. Packs are generated when a workspace starts and
+//! refreshed before each follow-up, fitted to the repo's token budget and cached by the
+//! target branch's HEAD sha, so they are only rebuilt once the target branch moves or the
+//! settings change. A workspace is only handed a pack again when it changed.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    repo::Repo,
+    repo_context_pack::{ContextPackDelivery, RepoContextPack},
+    workspace_repo::RepoWithTargetBranch,
+};
+use git::GitService;
+use ignore::WalkBuilder;
+use moka::future::Cache;
+use sqlx::SqlitePool;
+use tracing::warn;
+use utils::text::truncate_to_char_boundary;
+
+use super::file_ranker::FileRanker;
+
+/// Directory under the workspace root (next to the worktrees) that packs are written to
+pub const CONTEXT_PACK_DIR: &str = ".vibe-kanban-context";
+
+const CHARS_PER_TOKEN: usize = 4;
+const KEY_FILES: &[&str] = &[
+    "README.md",
+    "README",
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "Makefile",
+];
+const MAX_HOT_FILES: usize = 15;
+/// Stop walking huge repos; the tree summary only needs a rough picture
+const MAX_TREE_FILES: usize = 20_000;
+const MAX_CACHED_WORKTREES: u64 = 100;
+
+#[derive(Clone)]
+struct CachedPack {
+    head_sha: String,
+    settings_updated_at: DateTime<Utc>,
+    content: Arc<String>,
+}
+
+/// Packs handed out by [`ContextPackCache::apply_context_packs`], to record with
+/// [`ContextPackCache::mark_delivered`] once the execution carrying them has started.
+#[must_use]
+#[derive(Default)]
+pub struct PendingPacks(Vec<(PathBuf, Arc<String>)>);
+
+/// Generated packs by worktree, and the pack each worktree's agent was last given. Kept
+/// alongside [`super::symbol_index::SymbolIndexCache`].
+#[derive(Clone)]
+pub struct ContextPackCache {
+    packs: Cache<PathBuf, CachedPack>,
+    delivered: Cache<PathBuf, Arc<String>>,
+}
+
+impl Default for ContextPackCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextPackCache {
+    pub fn new() -> Self {
+        Self {
+            packs: Cache::new(MAX_CACHED_WORKTREES),
+            delivered: Cache::new(MAX_CACHED_WORKTREES),
+        }
+    }
+
+    /// Generate (or reuse) the packs of every enabled repo in a workspace and hand the
+    /// workspace those it hasn't seen yet. Packs delivered as files are written under
+    /// [`CONTEXT_PACK_DIR`]; the returned prompt either embeds the packs or points the
+    /// agent at those files.
+    pub async fn apply_context_packs(
+        &self,
+        pool: &SqlitePool,
+        git: &GitService,
+        workspace_dir: &Path,
+        repos: &[RepoWithTargetBranch],
+        prompt: String,
+    ) -> (String, PendingPacks) {
+        let mut embedded = Vec::new();
+        let mut files = Vec::new();
+        let mut pending = Vec::new();
+
+        for RepoWithTargetBranch {
+            repo,
+            target_branch,
+        } in repos
+        {
+            let settings = match RepoContextPack::find_or_default(pool, repo.id).await {
+                Ok(settings) if settings.enabled => settings,
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!(
+                        "Failed to load context pack settings for {}: {}",
+                        repo.name, e
+                    );
+                    continue;
+                }
+            };
+
+            let worktree_path = workspace_dir.join(&repo.name);
+            let pack = self
+                .context_pack_for_repo(git, repo, &worktree_path, target_branch, &settings)
+                .await;
+            if self
+                .delivered
+                .get(&worktree_path)
+                .await
+                .is_some_and(|delivered| delivered == pack)
+            {
+                continue;
+            }
+            pending.push((worktree_path, pack.clone()));
+
+            match settings.delivery {
+                ContextPackDelivery::Prompt => embedded.push(pack),
+                ContextPackDelivery::File => {
+                    let path = workspace_dir
+                        .join(CONTEXT_PACK_DIR)
+                        .join(format!("{}.md", repo.name));
+                    match write_pack(&path, &pack).await {
+                        Ok(()) => files.push(path),
+                        Err(e) => warn!("Failed to write context pack to {:?}: {}", path, e),
+                    }
+                }
+            }
+        }
+
+        let mut prompt = prompt;
+        for pack in embedded {
+            prompt.push_str("\n\n");
+            prompt.push_str(&pack);
+        }
+        if !files.is_empty() {
+            prompt.push_str("\n\nRepository overviews are available; read them before exploring:");
+            for path in files {
+                prompt.push_str(&format!("\n- {}", path.display()));
+            }
+        }
+        (prompt, PendingPacks(pending))
+    }
+
+    /// Remember the packs as given to their workspaces, so they are only handed out again
+    /// once they change.
+    pub async fn mark_delivered(&self, packs: PendingPacks) {
+        for (worktree_path, pack) in packs.0 {
+            self.delivered.insert(worktree_path, pack).await;
+        }
+    }
+
+    /// Context pack for one repo, rebuilt only when the target branch or settings changed.
+    pub async fn context_pack_for_repo(
+        &self,
+        git: &GitService,
+        repo: &Repo,
+        worktree_path: &Path,
+        target_branch: &str,
+        settings: &RepoContextPack,
+    ) -> Arc<String> {
+        let head_sha = git
+            .get_branch_oid(&repo.path, target_branch)
+            .or_else(|_| git.get_head_info(worktree_path).map(|head| head.oid))
+            .unwrap_or_default();
+
+        if let Some(cached) = self.packs.get(worktree_path).await
+            && !head_sha.is_empty()
+            && cached.head_sha == head_sha
+            && cached.settings_updated_at == settings.updated_at
+        {
+            return cached.content.clone();
+        }
+
+        // Rank from the worktree so files the workspace deleted or renamed aren't listed
+        let stats = FileRanker::new().get_stats(worktree_path).await.ok();
+        let mut hot_files: Vec<_> = stats
+            .iter()
+            .flat_map(|stats| stats.iter())
+            .filter(|(path, _)| worktree_path.join(path).is_file())
+            .collect();
+        hot_files.sort_by(|(a_path, a), (b_path, b)| {
+            a.last_index
+                .cmp(&b.last_index)
+                .then_with(|| b.commit_count.cmp(&a.commit_count))
+                .then_with(|| a_path.cmp(b_path))
+        });
+        let hot_files: Vec<String> = hot_files
+            .into_iter()
+            .take(MAX_HOT_FILES)
+            .map(|(path, stat)| format!("- {} ({} recent commits)", path, stat.commit_count))
+            .collect();
+
+        let root = worktree_path.to_path_buf();
+        let (tree, key_files) =
+            tokio::task::spawn_blocking(move || (tree_summary(&root), key_files(&root)))
+                .await
+                .unwrap_or_default();
+
+        let budget = usize::try_from(settings.token_budget).unwrap_or(0) * CHARS_PER_TOKEN;
+        let content = Arc::new(render_pack(
+            &PackSections {
+                repo_name: &repo.name,
+                target_branch,
+                head_sha: &head_sha,
+                notes: settings.notes.as_deref(),
+                tree: &tree,
+                hot_files: &hot_files,
+                key_files: &key_files,
+            },
+            budget,
+        ));
+
+        if !head_sha.is_empty() {
+            self.packs
+                .insert(
+                    worktree_path.to_path_buf(),
+                    CachedPack {
+                        head_sha,
+                        settings_updated_at: settings.updated_at,
+                        content: content.clone(),
+                    },
+                )
+                .await;
+        }
+        content
+    }
+}
+
+async fn write_pack(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, content).await
+}
+
+struct PackSections<'a> {
+    repo_name: &'a str,
+    target_branch: &'a str,
+    head_sha: &'a str,
+    notes: Option<&'a str>,
+    tree: &'a str,
+    hot_files: &'a [String],
+    key_files: &'a [(String, String)],
+}
+
+/// Lay the sections out in priority order, trimming each so the whole pack stays within
+/// `budget` characters: notes, then directory layout (up to a quarter of the budget),
+/// recently active files (up to a sixth), and key files sharing whatever is left.
+fn render_pack(sections: &PackSections<'_>, budget: usize) -> String {
+    let short_sha = &sections.head_sha[..sections.head_sha.len().min(8)];
+    let mut out = format!(
+        "# Repository overview: {}\n\nGenerated from `{}` at {}.\n",
+        sections.repo_name, sections.target_branch, short_sha
+    );
+
+    let push = |out: &mut String, title: &str, body: &str, limit: usize| {
+        let remaining = budget.saturating_sub(out.len());
+        let header = format!("\n## {title}\n\n");
+        if body.trim().is_empty() || remaining <= header.len() {
+            return;
+        }
+        out.push_str(&header);
+        out.push_str(&truncate_at_line(
+            body.trim_end(),
+            limit.min(remaining - header.len()),
+        ));
+        out.push('\n');
+    };
+
+    if let Some(notes) = sections.notes {
+        push(&mut out, "Project notes", notes, budget);
+    }
+    push(
+        &mut out,
+        "Directory layout",
+        &format!("```\n{}```", sections.tree),
+        budget / 4,
+    );
+    push(
+        &mut out,
+        "Recently active files",
+        &sections.hot_files.join("\n"),
+        budget / 6,
+    );
+    for (i, (name, content)) in sections.key_files.iter().enumerate() {
+        let share = budget.saturating_sub(out.len()) / (sections.key_files.len() - i);
+        push(&mut out, name, &format!("```\n{content}\n```"), share);
+    }
+
+    truncate_to_char_boundary(&out, budget.max(1)).to_string()
+}
+
+/// Cut `text` to at most `limit` bytes at a line boundary, noting that it was truncated.
+fn truncate_at_line(text: &str, limit: usize) -> String {
+    if text.len() <= limit {
+        return text.to_string();
+    }
+    const MARKER: &str = "\n... (truncated)";
+    let cut = truncate_to_char_boundary(text, limit.saturating_sub(MARKER.len()));
+    let cut = cut.rfind('\n').map_or(cut, |i| &cut[..i]);
+    let closes_fence = text.starts_with("```") && cut.matches("```").count() % 2 == 1;
+    format!("{cut}{MARKER}{}", if closes_fence { "\n```" } else { "" })
+}
+
+/// Two-level directory summary with file counts, respecting `.gitignore`.
+fn tree_summary(root: &Path) -> String {
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+
+    let mut top_files = Vec::new();
+    let mut dirs: BTreeMap<String, (usize, BTreeMap<String, usize>)> = BTreeMap::new();
+    for entry in walker
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .take(MAX_TREE_FILES)
+    {
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let parts: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        match parts.as_slice() {
+            [file] => top_files.push(file.clone()),
+            [dir, rest @ ..] => {
+                let (count, subdirs) = dirs.entry(dir.clone()).or_default();
+                *count += 1;
+                if rest.len() > 1 {
+                    *subdirs.entry(rest[0].clone()).or_default() += 1;
+                }
+            }
+            [] => {}
+        }
+    }
+
+    let mut out = String::new();
+    for (dir, (count, subdirs)) in &dirs {
+        out.push_str(&format!("{dir}/ ({count} files)\n"));
+        for (subdir, count) in subdirs {
+            out.push_str(&format!("  {subdir}/ ({count} files)\n"));
+        }
+    }
+    top_files.sort();
+    for file in top_files {
+        out.push_str(&format!("{file}\n"));
+    }
+    out
+}
+
+fn key_files(root: &Path) -> Vec<(String, String)> {
+    KEY_FILES
+        .iter()
+        .filter_map(|name| {
+            let path: PathBuf = root.join(name);
+            let content = std::fs::read_to_string(&path).ok()?;
+            (!content.trim().is_empty()).then(|| (name.to_string(), content.trim().to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{DBService, models::repo_context_pack::UpdateRepoContextPack};
+
+    use super::*;
+
+    #[test]
+    fn summarizes_two_directory_levels() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "src/main.rs",
+            "src/api/mod.rs",
+            "src/api/routes.rs",
+            "Cargo.toml",
+        ] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+
+        assert_eq!(
+            tree_summary(dir.path()),
+            "src/ (3 files)\n  api/ (2 files)\nCargo.toml\n"
+        );
+    }
+
+    #[test]
+    fn fits_pack_within_budget() {
+        let readme = "line\n".repeat(500);
+        let key_files = vec![("README.md".to_string(), readme)];
+        let sections = PackSections {
+            repo_name: "app",
+            target_branch: "main",
+            head_sha: "0123456789abcdef",
+            notes: Some("Use pnpm, not npm."),
+            tree: "src/ (3 files)\n",
+            hot_files: &["- src/main.rs (4 recent commits)".to_string()],
+            key_files: &key_files,
+        };
+
+        let pack = render_pack(&sections, 800);
+        assert!(pack.len() <= 800);
+        assert!(pack.contains("Generated from `main` at 01234567."));
+        assert!(pack.contains("Use pnpm, not npm."));
+        assert!(pack.contains("- src/main.rs (4 recent commits)"));
+        assert!(pack.contains("... (truncated)\n```"));
+    }
+
+    #[tokio::test]
+    async fn hands_a_worktree_its_pack_again_only_once_it_changed() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let workspace_dir = tempfile::tempdir().unwrap();
+        let worktree = workspace_dir.path().join("app");
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(worktree.join("README.md"), "# App\n").unwrap();
+
+        let repo = Repo::find_or_create(pool, &worktree, "app").await.unwrap();
+        let settings = |notes: &str| UpdateRepoContextPack {
+            enabled: true,
+            delivery: ContextPackDelivery::Prompt,
+            token_budget: 2000,
+            notes: Some(notes.to_string()),
+        };
+        RepoContextPack::upsert(pool, repo.id, &settings("Use pnpm."))
+            .await
+            .unwrap();
+        let repos = vec![RepoWithTargetBranch {
+            repo: repo.clone(),
+            target_branch: "main".to_string(),
+        }];
+
+        let cache = ContextPackCache::new();
+        let git = GitService::new();
+        let apply = |prompt: &str| {
+            cache.apply_context_packs(pool, &git, workspace_dir.path(), &repos, prompt.to_string())
+        };
+
+        let (start, packs) = apply("Build the app").await;
+        assert!(start.starts_with("Build the app\n\n# Repository overview: app"));
+        assert!(start.contains("Use pnpm."));
+        // The execution carrying the pack never started
+        drop(packs);
+        let (retry, packs) = apply("Build the app").await;
+        assert_eq!(retry, start);
+        cache.mark_delivered(packs).await;
+
+        let (follow_up, packs) = apply("Follow-up").await;
+        assert_eq!(follow_up, "Follow-up");
+        cache.mark_delivered(packs).await;
+
+        RepoContextPack::upsert(pool, repo.id, &settings("Use yarn."))
+            .await
+            .unwrap();
+        let (changed, _) = apply("Follow-up").await;
+        assert!(changed.contains("Use yarn."));
+    }
+}
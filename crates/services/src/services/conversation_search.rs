@@ -0,0 +1,250 @@
+//! Full-text indexing of agent conversations.
+//!
+//! When an execution process finishes, its normalized conversation is split into
+//! searchable documents (messages, tool commands, touched file paths, errors) and
+//! stored in the `conversation_search` FTS5 table.
+
+use db::models::conversation_search::{
+    ConversationSearch, ConversationSearchDocument, ConversationSearchKind,
+};
+use executors::logs::{
+    ActionType, NormalizedEntry, NormalizedEntryType, utils::conversation::entries_from_history,
+};
+use sqlx::SqlitePool;
+use utils::{log_msg::LogMsg, text::truncate_to_char_boundary};
+use uuid::Uuid;
+
+/// Longer entries are cut off; matches deep inside huge outputs aren't worth the index size
+const MAX_DOCUMENT_LEN: usize = 16 * 1024;
+
+/// Re-index the conversation recorded in `history` for an execution process.
+pub async fn index_execution(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+    history: &[LogMsg],
+) -> Result<(), sqlx::Error> {
+    let documents = documents_from_entries(&entries_from_history(history));
+    ConversationSearch::replace_for_execution(pool, execution_process_id, &documents).await
+}
+
+/// Build search documents from entries keyed by their index in the UI's entry list, so a
+/// match can be scrolled to.
+pub fn documents_from_entries(
+    entries: &[(usize, NormalizedEntry)],
+) -> Vec<ConversationSearchDocument> {
+    let mut documents = Vec::new();
+    for (index, entry) in entries {
+        let (kind, content) = match &entry.entry_type {
+            NormalizedEntryType::UserMessage => {
+                (ConversationSearchKind::UserMessage, entry.content.as_str())
+            }
+            NormalizedEntryType::AssistantMessage => (
+                ConversationSearchKind::AssistantMessage,
+                entry.content.as_str(),
+            ),
+            NormalizedEntryType::ErrorMessage { .. } => {
+                (ConversationSearchKind::Error, entry.content.as_str())
+            }
+            NormalizedEntryType::ToolUse { action_type, .. } => match action_type {
+                ActionType::FileRead { path } | ActionType::FileEdit { path, .. } => {
+                    (ConversationSearchKind::FilePath, path.as_str())
+                }
+                ActionType::CommandRun { command, .. } => {
+                    (ConversationSearchKind::ToolCommand, command.as_str())
+                }
+                ActionType::Search { query } => {
+                    (ConversationSearchKind::ToolCommand, query.as_str())
+                }
+                _ => continue,
+            },
+            _ => continue,
+        };
+
+        let content = content.trim();
+        if content.is_empty() {
+            continue;
+        }
+        documents.push(ConversationSearchDocument {
+            entry_index: *index as i64,
+            kind,
+            content: truncate_to_char_boundary(content, MAX_DOCUMENT_LEN).to_string(),
+        });
+    }
+    documents
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        DBService,
+        models::execution_process::{
+            ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+        },
+        test_utils::{coding_agent_action, create_execution_process, create_session_chain},
+    };
+    use executors::logs::{ToolStatus, utils::ConversationPatch};
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn tool(action_type: ActionType) -> NormalizedEntryType {
+        NormalizedEntryType::ToolUse {
+            tool_name: "tool".to_string(),
+            action_type,
+            status: ToolStatus::Success,
+        }
+    }
+
+    #[test]
+    fn indexes_messages_commands_and_paths_by_entry_index() {
+        let entries: Vec<_> = [
+            entry(NormalizedEntryType::UserMessage, "Fix the login bug"),
+            entry(NormalizedEntryType::Thinking, "hmm"),
+            entry(
+                tool(ActionType::FileRead {
+                    path: "src/auth.rs".to_string(),
+                }),
+                "Read src/auth.rs",
+            ),
+            entry(
+                tool(ActionType::CommandRun {
+                    command: "cargo test".to_string(),
+                    result: None,
+                    category: Default::default(),
+                }),
+                "cargo test",
+            ),
+            entry(NormalizedEntryType::AssistantMessage, "  "),
+        ]
+        .into_iter()
+        .enumerate()
+        .collect();
+
+        let documents = documents_from_entries(&entries);
+        let summary: Vec<_> = documents
+            .iter()
+            .map(|doc| (doc.entry_index, doc.kind, doc.content.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, ConversationSearchKind::UserMessage, "Fix the login bug"),
+                (2, ConversationSearchKind::FilePath, "src/auth.rs"),
+                (3, ConversationSearchKind::ToolCommand, "cargo test"),
+            ]
+        );
+    }
+
+    #[test]
+    fn entry_index_points_at_the_entry_shown_in_the_ui() {
+        // Raw output occupies slots in the UI's list, and a replaced entry keeps its slot
+        let history = vec![
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                0,
+                entry(NormalizedEntryType::UserMessage, "Fix the login bug"),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::add_stdout(
+                1,
+                "Compiling app".to_string(),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                2,
+                entry(NormalizedEntryType::AssistantMessage, "Looking"),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::replace(
+                2,
+                entry(
+                    NormalizedEntryType::AssistantMessage,
+                    "Fixed the session check",
+                ),
+            )),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+                3,
+                entry(
+                    tool(ActionType::CommandRun {
+                        command: "cargo test auth".to_string(),
+                        result: None,
+                        category: Default::default(),
+                    }),
+                    "cargo test auth",
+                ),
+            )),
+        ];
+
+        let documents = documents_from_entries(&entries_from_history(&history));
+        let summary: Vec<_> = documents
+            .iter()
+            .map(|doc| (doc.entry_index, doc.content.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, "Fix the login bug"),
+                (2, "Fixed the session check"),
+                (3, "cargo test auth"),
+            ]
+        );
+    }
+
+    async fn coding_agent_process(pool: &SqlitePool) -> ExecutionProcess {
+        let chain = create_session_chain(pool, "search").await;
+        create_execution_process(
+            pool,
+            chain.session.id,
+            coding_agent_action("fix the login bug"),
+            ExecutionProcessRunReason::CodingAgent,
+            &[],
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn finished_processes_are_unindexed_until_indexed() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let process = coding_agent_process(pool).await;
+
+        // Still running: the exit monitor will index it
+        assert!(
+            ConversationSearch::find_unindexed_execution_processes(pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        ExecutionProcess::update_completion(pool, process.id, ExecutionProcessStatus::Failed, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            ConversationSearch::find_unindexed_execution_processes(pool)
+                .await
+                .unwrap(),
+            vec![process.id]
+        );
+
+        let history = vec![LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+            0,
+            entry(NormalizedEntryType::UserMessage, "Fix the login bug"),
+        ))];
+        index_execution(pool, process.id, &history).await.unwrap();
+        assert!(
+            ConversationSearch::find_unindexed_execution_processes(pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let matches = ConversationSearch::search(pool, "login", None, 10)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].execution_process_id, process.id);
+    }
+}
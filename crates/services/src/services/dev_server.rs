@@ -0,0 +1,515 @@
+//! Port assignment, readiness checks and restart backoff for workspace dev servers.
+//!
+//! Each repo's dev server gets its own free port, passed to the script as `PORT`, so
+//! parallel workspaces don't collide. A dev server is ready once its repo's ready pattern
+//! shows up in the output, its health path answers, or, without either, once the port
+//! accepts connections.
+//!
+//! A repo may instead define several named dev processes, like the lines of a Procfile.
+//! Each runs as its own dev server with its own port, started after the processes it
+//! depends on are ready.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    net::{Ipv4Addr, TcpListener},
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+
+use db::models::{
+    dev_server::{DevServer, DevServerStatus},
+    repo_dev_process::{RepoDevProcess, UpsertRepoDevProcess},
+    repo_dev_server_settings::RepoDevServerSettings,
+};
+use futures::StreamExt;
+use regex::Regex;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::net::TcpStream;
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+/// Environment variable holding the port a dev server should listen on.
+pub const PORT_ENV_VAR: &str = "PORT";
+
+/// Crashes after which a dev server is left stopped until the user starts it again.
+pub const MAX_AUTO_RESTARTS: i64 = 5;
+
+const PORT_ALLOCATION_ATTEMPTS: usize = 20;
+const READY_TIMEOUT: Duration = Duration::from_secs(300);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const HEALTH_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+static PROCESS_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z0-9][A-Za-z0-9_-]*$").unwrap());
+
+/// Output kept for matching the ready pattern, so a match split across chunks is found.
+const OUTPUT_WINDOW: usize = 4096;
+
+#[derive(Debug, Error)]
+pub enum DevProcessError {
+    #[error("Invalid dev process name '{0}': use letters, digits, '-' and '_'")]
+    InvalidName(String),
+    #[error("Dev process '{0}' is defined more than once")]
+    DuplicateName(String),
+    #[error("Dev process '{0}' has no command")]
+    EmptyCommand(String),
+    #[error("Dev process '{process}' depends on unknown process '{dependency}'")]
+    UnknownDependency { process: String, dependency: String },
+    #[error("Dev process '{0}' depends on itself through its dependencies")]
+    DependencyCycle(String),
+}
+
+/// Check a repo's dev processes before they are saved.
+pub fn validate_dev_processes(processes: &[UpsertRepoDevProcess]) -> Result<(), DevProcessError> {
+    let mut names = HashSet::new();
+    for process in processes {
+        if !PROCESS_NAME.is_match(&process.name) {
+            return Err(DevProcessError::InvalidName(process.name.clone()));
+        }
+        if !names.insert(process.name.as_str()) {
+            return Err(DevProcessError::DuplicateName(process.name.clone()));
+        }
+        if process.command.trim().is_empty() {
+            return Err(DevProcessError::EmptyCommand(process.name.clone()));
+        }
+    }
+    dependency_order(
+        processes
+            .iter()
+            .map(|process| (process.name.as_str(), process.depends_on.as_slice())),
+    )
+    .map(|_| ())
+}
+
+/// The processes in the order to start them, each after the processes it depends on.
+pub fn start_order(processes: &[RepoDevProcess]) -> Result<Vec<&RepoDevProcess>, DevProcessError> {
+    let order = dependency_order(
+        processes
+            .iter()
+            .map(|process| (process.name.as_str(), process.depends_on.as_slice())),
+    )?;
+    Ok(order.into_iter().map(|index| &processes[index]).collect())
+}
+
+/// Indices of the processes ordered so dependencies come first, otherwise keeping the
+/// order they were defined in.
+fn dependency_order<'a>(
+    processes: impl Iterator<Item = (&'a str, &'a [String])>,
+) -> Result<Vec<usize>, DevProcessError> {
+    let processes: Vec<_> = processes.collect();
+    let index: HashMap<&str, usize> = processes
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| (*name, i))
+        .collect();
+    for (name, depends_on) in &processes {
+        if let Some(dependency) = depends_on
+            .iter()
+            .find(|dep| !index.contains_key(dep.as_str()))
+        {
+            return Err(DevProcessError::UnknownDependency {
+                process: name.to_string(),
+                dependency: dependency.clone(),
+            });
+        }
+    }
+
+    let mut order = Vec::with_capacity(processes.len());
+    let mut started = vec![false; processes.len()];
+    while order.len() < processes.len() {
+        let next = (0..processes.len()).find(|&i| {
+            !started[i]
+                && processes[i]
+                    .1
+                    .iter()
+                    .all(|dep| started[index[dep.as_str()]])
+        });
+        let Some(next) = next else {
+            // Everything left waits on something else that's left
+            let stuck = (0..processes.len()).find(|&i| !started[i]).unwrap_or(0);
+            return Err(DevProcessError::DependencyCycle(
+                processes[stuck].0.to_string(),
+            ));
+        };
+        started[next] = true;
+        order.push(next);
+    }
+    Ok(order)
+}
+
+/// Environment variable holding the port of a sibling dev process, e.g. `API_PORT` for
+/// a process named `api`.
+pub fn port_env_var(process_name: &str) -> String {
+    let name: String = process_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{name}_PORT")
+}
+
+/// Pick a port for a dev server. `preferred` (the port it had before) is kept while it's
+/// free, so restarts don't move the dev server.
+pub fn allocate_port(preferred: Option<u16>, reserved: &HashSet<u16>) -> io::Result<u16> {
+    if let Some(port) = preferred
+        && port_is_free(port)
+    {
+        return Ok(port);
+    }
+    for _ in 0..PORT_ALLOCATION_ATTEMPTS {
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+            .local_addr()?
+            .port();
+        if !reserved.contains(&port) {
+            return Ok(port);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AddrInUse,
+        "no free port for the dev server",
+    ))
+}
+
+fn port_is_free(port: u16) -> bool {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok()
+}
+
+/// Delay before the next automatic restart: 1s, 2s, 4s, ... up to a minute.
+pub fn restart_backoff(restart_count: i64) -> Duration {
+    let exponent = restart_count.clamp(0, 16) as u32;
+    Duration::from_secs(1 << exponent).min(MAX_RESTART_BACKOFF)
+}
+
+/// What happens to a dev server once its process exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DevServerExit {
+    /// Stopped by the user or exited on its own
+    Stopped,
+    /// Crashed and stays down, as auto restart is off or the workspace is archived
+    Crashed,
+    /// Crashed again after using up its automatic restarts
+    GaveUp,
+    /// Crashed and is started again after the delay
+    Restart(Duration),
+}
+
+/// Decide how to handle an exited dev server, given whether its process failed and how
+/// often it was restarted since the user last started it.
+pub fn on_exit(
+    failed: bool,
+    restart_count: i64,
+    settings: &RepoDevServerSettings,
+    workspace_archived: bool,
+) -> DevServerExit {
+    if !failed {
+        DevServerExit::Stopped
+    } else if !settings.auto_restart || workspace_archived {
+        DevServerExit::Crashed
+    } else if restart_count >= MAX_AUTO_RESTARTS {
+        DevServerExit::GaveUp
+    } else {
+        DevServerExit::Restart(restart_backoff(restart_count))
+    }
+}
+
+/// Wait until the dev server on `port` is ready. Returns `false` when it exits first or
+/// doesn't become ready within five minutes.
+pub async fn wait_until_ready(
+    port: u16,
+    settings: &RepoDevServerSettings,
+    logs: Arc<MsgStore>,
+) -> bool {
+    let ready_pattern =
+        settings
+            .ready_pattern
+            .as_deref()
+            .and_then(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid dev server ready pattern '{pattern}': {e}");
+                    None
+                }
+            });
+
+    let check = async {
+        match (ready_pattern, settings.health_path.as_deref()) {
+            (Some(regex), _) => wait_for_output(&regex, logs.clone()).await,
+            (None, Some(path)) => wait_for_health(port, path).await,
+            (None, None) => wait_for_port(port).await,
+        }
+    };
+    let exited = logs
+        .history_plus_stream()
+        .any(|msg| std::future::ready(matches!(msg, Ok(LogMsg::Finished))));
+
+    tokio::select! {
+        ready = tokio::time::timeout(READY_TIMEOUT, check) => ready.unwrap_or(false),
+        _ = exited => false,
+    }
+}
+
+async fn wait_for_output(regex: &Regex, logs: Arc<MsgStore>) -> bool {
+    let mut output = String::new();
+    let mut stream = logs.history_plus_stream();
+    while let Some(msg) = stream.next().await {
+        match msg {
+            Ok(LogMsg::Stdout(chunk) | LogMsg::Stderr(chunk)) => {
+                output.push_str(&chunk);
+                if regex.is_match(&output) {
+                    return true;
+                }
+                if output.len() > OUTPUT_WINDOW {
+                    let mut cut = output.len() - OUTPUT_WINDOW;
+                    while !output.is_char_boundary(cut) {
+                        cut += 1;
+                    }
+                    output.drain(..cut);
+                }
+            }
+            Ok(LogMsg::Finished) => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+async fn wait_for_health(port: u16, path: &str) -> bool {
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{path}")
+    };
+    let url = format!("http://localhost:{port}{path}");
+    let client = reqwest::Client::new();
+    loop {
+        if let Ok(response) = client
+            .get(&url)
+            .timeout(HEALTH_REQUEST_TIMEOUT)
+            .send()
+            .await
+            && !response.status().is_server_error()
+        {
+            return true;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn wait_for_port(port: u16) -> bool {
+    loop {
+        // `localhost` rather than 127.0.0.1, as some dev servers only listen on ::1
+        if TcpStream::connect(("localhost", port)).await.is_ok() {
+            return true;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Wait until the dev server started by `execution_process_id` is ready. Returns `false`
+/// when it crashes or stops first, or isn't ready within five minutes.
+pub async fn wait_for_dev_server(pool: &SqlitePool, execution_process_id: Uuid) -> bool {
+    let poll = async {
+        loop {
+            match DevServer::find_by_execution_process_id(pool, execution_process_id).await {
+                Ok(Some(dev_server)) => match dev_server.status {
+                    DevServerStatus::Ready => return true,
+                    DevServerStatus::Crashed | DevServerStatus::Stopped => return false,
+                    DevServerStatus::Starting => {}
+                },
+                // Not recorded yet, or restarted under another execution
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::error!("Failed to check dev server status: {}", e);
+                    return false;
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    };
+    tokio::time::timeout(READY_TIMEOUT, poll)
+        .await
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(name: &str, depends_on: &[&str]) -> UpsertRepoDevProcess {
+        UpsertRepoDevProcess {
+            name: name.to_string(),
+            command: format!("npm run {name}"),
+            depends_on: depends_on.iter().map(|dep| dep.to_string()).collect(),
+            env: HashMap::new(),
+        }
+    }
+
+    fn order(processes: &[UpsertRepoDevProcess]) -> Result<Vec<usize>, DevProcessError> {
+        dependency_order(
+            processes
+                .iter()
+                .map(|process| (process.name.as_str(), process.depends_on.as_slice())),
+        )
+    }
+
+    #[test]
+    fn dependencies_start_first() {
+        let processes = [
+            process("web", &["api"]),
+            process("worker", &["db", "api"]),
+            process("api", &["db"]),
+            process("db", &[]),
+        ];
+        assert_eq!(order(&processes).unwrap(), vec![3, 2, 0, 1]);
+        assert!(validate_dev_processes(&processes).is_ok());
+    }
+
+    #[test]
+    fn rejects_cycles_and_unknown_dependencies() {
+        let cycle = [process("a", &["b"]), process("b", &["a"])];
+        assert!(matches!(
+            validate_dev_processes(&cycle),
+            Err(DevProcessError::DependencyCycle(_))
+        ));
+
+        let unknown = [process("web", &["api"])];
+        assert!(matches!(
+            validate_dev_processes(&unknown),
+            Err(DevProcessError::UnknownDependency { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_names() {
+        assert!(matches!(
+            validate_dev_processes(&[process("web", &[]), process("web", &[])]),
+            Err(DevProcessError::DuplicateName(_))
+        ));
+        assert!(matches!(
+            validate_dev_processes(&[process("my web", &[])]),
+            Err(DevProcessError::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn port_env_var_names() {
+        assert_eq!(port_env_var("api"), "API_PORT");
+        assert_eq!(port_env_var("web-ui"), "WEB_UI_PORT");
+    }
+
+    fn settings() -> RepoDevServerSettings {
+        RepoDevServerSettings::default_for(Uuid::new_v4())
+    }
+
+    fn unused_port() -> u16 {
+        TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    #[test]
+    fn allocate_port_keeps_a_free_preferred_port() {
+        let port = unused_port();
+        assert_eq!(allocate_port(Some(port), &HashSet::new()).unwrap(), port);
+    }
+
+    #[test]
+    fn allocate_port_moves_off_a_taken_preferred_port() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let taken = listener.local_addr().unwrap().port();
+        let port = allocate_port(Some(taken), &HashSet::from([taken])).unwrap();
+        assert_ne!(port, taken);
+    }
+
+    #[test]
+    fn allocate_port_skips_reserved_ports() {
+        let mut reserved = HashSet::new();
+        for _ in 0..10 {
+            let port = allocate_port(None, &reserved).unwrap();
+            assert!(reserved.insert(port), "port {port} was reserved");
+        }
+    }
+
+    #[test]
+    fn restart_backoff_doubles_up_to_a_minute() {
+        let delays: Vec<u64> = (0..8).map(|n| restart_backoff(n).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(restart_backoff(-1), Duration::from_secs(1));
+        assert_eq!(restart_backoff(i64::MAX), MAX_RESTART_BACKOFF);
+    }
+
+    #[test]
+    fn crashes_restart_with_backoff_until_the_budget_is_used() {
+        let settings = settings();
+        assert_eq!(on_exit(false, 0, &settings, false), DevServerExit::Stopped);
+        assert_eq!(
+            on_exit(true, 0, &settings, false),
+            DevServerExit::Restart(Duration::from_secs(1))
+        );
+        assert_eq!(
+            on_exit(true, 3, &settings, false),
+            DevServerExit::Restart(Duration::from_secs(8))
+        );
+        assert_eq!(
+            on_exit(true, MAX_AUTO_RESTARTS, &settings, false),
+            DevServerExit::GaveUp
+        );
+    }
+
+    #[test]
+    fn crashes_stay_down_without_auto_restart() {
+        let manual = RepoDevServerSettings {
+            auto_restart: false,
+            ..settings()
+        };
+        assert_eq!(on_exit(true, 0, &manual, false), DevServerExit::Crashed);
+        assert_eq!(on_exit(true, 0, &settings(), true), DevServerExit::Crashed);
+        assert_eq!(on_exit(false, 0, &manual, false), DevServerExit::Stopped);
+    }
+
+    #[tokio::test]
+    async fn ready_once_the_pattern_is_printed() {
+        let settings = RepoDevServerSettings {
+            ready_pattern: Some(r"ready in \d+ ms".to_string()),
+            ..settings()
+        };
+        let logs = Arc::new(MsgStore::new());
+        // The match spans two chunks of output
+        logs.push_stdout("VITE v5.0.0  ready in ");
+        logs.push_stdout("312 ms\n");
+        assert!(wait_until_ready(unused_port(), &settings, logs).await);
+    }
+
+    #[tokio::test]
+    async fn ready_once_the_port_accepts_connections() {
+        let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(wait_until_ready(port, &settings(), Arc::new(MsgStore::new())).await);
+    }
+
+    #[tokio::test]
+    async fn not_ready_when_the_dev_server_exits_first() {
+        let logs = Arc::new(MsgStore::new());
+        logs.push_stderr("Error: Cannot find module 'vite'\n");
+        logs.push_finished();
+        assert!(!wait_until_ready(unused_port(), &settings(), logs.clone()).await);
+
+        let settings = RepoDevServerSettings {
+            ready_pattern: Some("ready".to_string()),
+            ..settings()
+        };
+        assert!(!wait_until_ready(unused_port(), &settings, logs).await);
+    }
+}
@@ -3,8 +3,8 @@ use std::{str::FromStr, sync::Arc};
 use db::{
     DBService,
     models::{
-        execution_process::ExecutionProcess, project::Project, scratch::Scratch, session::Session,
-        task::Task, workspace::Workspace,
+        dev_server::DevServer, execution_process::ExecutionProcess, project::Project,
+        scratch::Scratch, session::Session, task::Task, workspace::Workspace,
     },
 };
 use serde_json::json;
@@ -185,6 +185,23 @@ impl EventService {
                                     // Deletions handled in preupdate hook for reliable data capture
                                     return;
                                 }
+                                (HookTables::DevServers, _) => {
+                                    // Dev server state is streamed as part of its workspace
+                                    if let Ok(Some(dev_server)) =
+                                        DevServer::find_by_rowid(&db.pool, rowid).await
+                                        && let Ok(Some(workspace_with_status)) =
+                                            Workspace::find_by_id_with_status(
+                                                &db.pool,
+                                                dev_server.workspace_id,
+                                            )
+                                            .await
+                                    {
+                                        msg_store_for_hook.push_patch(workspace_patch::replace(
+                                            &workspace_with_status,
+                                        ));
+                                    }
+                                    return;
+                                }
                                 (HookTables::Tasks, _) => {
                                     match Task::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(task)) => RecordTypes::Task(task),
@@ -32,6 +32,8 @@ pub enum HookTables {
     Scratch,
     #[strum(to_string = "projects")]
     Projects,
+    #[strum(to_string = "dev_servers")]
+    DevServers,
 }
 
 #[derive(Serialize, Deserialize, TS)]
@@ -257,6 +257,11 @@ impl GitHostProvider for AzureDevOpsProvider {
         Err(GitHostError::UnsupportedProvider)
     }
 
+    async fn update_pr_base(&self, _pr_url: &str, _base_branch: &str) -> Result<(), GitHostError> {
+        // `az repos pr update` cannot change the target branch of a pull request
+        Err(GitHostError::UnsupportedProvider)
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::AzureDevOps
     }
@@ -19,7 +19,8 @@ use url::Url;
 use utils::shell::resolve_executable_path_blocking;
 
 use crate::services::git_host::types::{
-    CreatePrRequest, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser,
+    CreatePrRequest, IssueInfo, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment,
+    ReviewCommentUser,
 };
 
 #[derive(Debug, Clone)]
@@ -121,6 +122,18 @@ struct GhPrListExtendedResponse {
     base_ref_name: String,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhIssueResponse {
+    number: i64,
+    url: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    body: String,
+    updated_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Error)]
 pub enum GhCliError {
     #[error("GitHub CLI (`gh`) executable not found or not runnable")]
@@ -276,6 +289,12 @@ impl GhCli {
         Self::parse_pr_view(&raw)
     }
 
+    /// Retarget a pull request onto a different base branch.
+    pub fn edit_pr_base(&self, pr_url: &str, base_branch: &str) -> Result<(), GhCliError> {
+        self.run(["pr", "edit", pr_url, "--base", base_branch], None)?;
+        Ok(())
+    }
+
     /// List pull requests for a branch (includes closed/merged).
     pub fn list_prs_for_branch(
         &self,
@@ -361,6 +380,61 @@ impl GhCli {
         Self::parse_pr_review_comments(&raw)
     }
 
+    /// List open issues matching a search query and labels (all labels must match).
+    pub fn list_issues(
+        &self,
+        repo_spec: &str,
+        search: Option<&str>,
+        labels: &[String],
+    ) -> Result<Vec<IssueInfo>, GhCliError> {
+        let mut args: Vec<String> = [
+            "issue",
+            "list",
+            "--repo",
+            repo_spec,
+            "--state",
+            "open",
+            "--limit",
+            "200",
+            "--json",
+            "number,url,title,body,updatedAt",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        if let Some(search) = search {
+            args.push("--search".to_string());
+            args.push(search.to_string());
+        }
+        for label in labels {
+            args.push("--label".to_string());
+            args.push(label.clone());
+        }
+        let raw = self.run(args, None)?;
+        Self::parse_issue_list(&raw)
+    }
+
+    pub fn comment_on_issue(
+        &self,
+        repo_spec: &str,
+        number: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        self.run(
+            [
+                "issue",
+                "comment",
+                &number.to_string(),
+                "--repo",
+                repo_spec,
+                "--body",
+                body,
+            ],
+            None,
+        )?;
+        Ok(())
+    }
+
     pub fn pr_checkout(
         &self,
         repo_path: &Path,
@@ -461,6 +535,24 @@ impl GhCli {
             .collect())
     }
 
+    fn parse_issue_list(raw: &str) -> Result<Vec<IssueInfo>, GhCliError> {
+        let issues: Vec<GhIssueResponse> = serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse gh issue list response: {err}; raw: {raw}"
+            ))
+        })?;
+        Ok(issues
+            .into_iter()
+            .map(|issue| IssueInfo {
+                number: issue.number,
+                url: issue.url,
+                title: issue.title,
+                body: issue.body,
+                updated_at: issue.updated_at,
+            })
+            .collect())
+    }
+
     fn pr_response_to_info(pr: GhPrResponse) -> PullRequestInfo {
         let state = if pr.state.is_empty() {
             "OPEN"
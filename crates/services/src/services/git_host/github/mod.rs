@@ -14,7 +14,7 @@ use tracing::info;
 
 use super::{
     GitHostProvider,
-    types::{CreatePrRequest, GitHostError, OpenPrInfo, ProviderKind, UnifiedPrComment},
+    types::{CreatePrRequest, GitHostError, IssueInfo, OpenPrInfo, ProviderKind, UnifiedPrComment},
 };
 
 #[derive(Debug, Clone)]
@@ -125,6 +125,47 @@ impl GitHubProvider {
         })
         .await
     }
+
+    /// Open issues of `repo` (`owner/name`, optionally prefixed by the host) matching the
+    /// search query and labels.
+    pub async fn list_issues(
+        &self,
+        repo: &str,
+        search: Option<&str>,
+        labels: &[String],
+    ) -> Result<Vec<IssueInfo>, GitHostError> {
+        let cli = self.gh_cli.clone();
+        let repo = repo.to_string();
+        let search = search.map(str::to_string);
+        let labels = labels.to_vec();
+        task::spawn_blocking(move || cli.list_issues(&repo, search.as_deref(), &labels))
+            .await
+            .map_err(|err| {
+                GitHostError::Repository(format!(
+                    "Failed to execute GitHub CLI for listing issues: {err}"
+                ))
+            })?
+            .map_err(Into::into)
+    }
+
+    pub async fn comment_on_issue(
+        &self,
+        repo: &str,
+        number: i64,
+        body: &str,
+    ) -> Result<(), GitHostError> {
+        let cli = self.gh_cli.clone();
+        let repo = repo.to_string();
+        let body = body.to_string();
+        task::spawn_blocking(move || cli.comment_on_issue(&repo, number, &body))
+            .await
+            .map_err(|err| {
+                GitHostError::Repository(format!(
+                    "Failed to execute GitHub CLI for commenting on issue: {err}"
+                ))
+            })?
+            .map_err(Into::into)
+    }
 }
 
 impl From<GhCliError> for GitHostError {
@@ -391,6 +432,46 @@ impl GitHostProvider for GitHubProvider {
         .await
     }
 
+    async fn update_pr_base(&self, pr_url: &str, base_branch: &str) -> Result<(), GitHostError> {
+        let cli = self.gh_cli.clone();
+        let url = pr_url.to_string();
+        let base = base_branch.to_string();
+
+        (|| async {
+            let cli = cli.clone();
+            let url = url.clone();
+            let base = base.clone();
+
+            let result = task::spawn_blocking(move || cli.edit_pr_base(&url, &base))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitHub CLI for editing PR base: {err}"
+                    ))
+                })?;
+            result.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await?;
+
+        info!("Retargeted GitHub PR {} onto {}", pr_url, base_branch);
+        Ok(())
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::GitHub
     }
@@ -11,8 +11,8 @@ use db::models::merge::PullRequestInfo;
 use detection::detect_provider_from_url;
 use enum_dispatch::enum_dispatch;
 pub use types::{
-    CreatePrRequest, GitHostError, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment,
-    ProviderKind, ReviewCommentUser, UnifiedPrComment,
+    CreatePrRequest, GitHostError, IssueInfo, OpenPrInfo, PrComment, PrCommentAuthor,
+    PrReviewComment, ProviderKind, ReviewCommentUser, UnifiedPrComment,
 };
 
 use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
@@ -49,6 +49,9 @@ pub trait GitHostProvider: Send + Sync {
         remote_url: &str,
     ) -> Result<Vec<OpenPrInfo>, GitHostError>;
 
+    /// Change the base branch an open pull request targets.
+    async fn update_pr_base(&self, pr_url: &str, base_branch: &str) -> Result<(), GitHostError>;
+
     fn provider_kind(&self) -> ProviderKind;
 }
 
@@ -146,3 +146,12 @@ pub struct OpenPrInfo {
     pub head_branch: String,
     pub base_branch: String,
 }
+
+#[derive(Debug, Clone)]
+pub struct IssueInfo {
+    pub number: i64,
+    pub url: String,
+    pub title: String,
+    pub body: String,
+    pub updated_at: Option<DateTime<Utc>>,
+}
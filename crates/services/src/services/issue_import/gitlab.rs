@@ -0,0 +1,107 @@
+//! GitLab issues REST API (v4).
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::{ImportedIssue, IssueImportError, check_status, send_json};
+
+const PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+struct GitLabIssue {
+    iid: i64,
+    title: String,
+    description: Option<String>,
+    web_url: String,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+pub struct GitLabClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl GitLabClient {
+    pub fn new(base_url: String, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            token,
+        }
+    }
+
+    /// `project` is the project path (`group/name`) or numeric id.
+    fn project_url(&self, project: &str) -> String {
+        let project: String = url::form_urlencoded::byte_serialize(project.as_bytes()).collect();
+        format!("{}/api/v4/projects/{}", self.base_url, project)
+    }
+
+    fn authorized(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.token {
+            Some(token) => request.header("PRIVATE-TOKEN", token),
+            None => request,
+        }
+    }
+
+    /// Open issues of `project`, reading page after page until the last one or `limit`.
+    pub async fn list_issues(
+        &self,
+        project: &str,
+        search: Option<&str>,
+        labels: &[String],
+        limit: usize,
+    ) -> Result<Vec<ImportedIssue>, IssueImportError> {
+        let mut params = vec![
+            ("state", "opened".to_string()),
+            ("per_page", PAGE_SIZE.to_string()),
+        ];
+        if let Some(search) = search {
+            params.push(("search", search.to_string()));
+        }
+        if !labels.is_empty() {
+            params.push(("labels", labels.join(",")));
+        }
+
+        let url = format!("{}/issues", self.project_url(project));
+        let mut imported = Vec::new();
+        for page in 1.. {
+            let issues: Vec<GitLabIssue> = send_json(
+                self.authorized(
+                    self.client
+                        .get(&url)
+                        .query(&params)
+                        .query(&[("page", page)]),
+                ),
+            )
+            .await?;
+            let last_page = issues.len() < PAGE_SIZE;
+            imported.extend(issues.into_iter().map(|issue| ImportedIssue {
+                external_id: issue.iid.to_string(),
+                url: issue.web_url,
+                title: issue.title,
+                body: issue.description.unwrap_or_default(),
+                updated_at: issue.updated_at,
+            }));
+            if last_page || imported.len() >= limit {
+                break;
+            }
+        }
+        imported.truncate(limit);
+        Ok(imported)
+    }
+
+    pub async fn add_comment(
+        &self,
+        project: &str,
+        iid: &str,
+        body: &str,
+    ) -> Result<(), IssueImportError> {
+        let url = format!("{}/issues/{}/notes", self.project_url(project), iid);
+        let request = self
+            .authorized(self.client.post(url))
+            .json(&serde_json::json!({ "body": body }));
+        check_status(request.send().await?).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! Jira REST API (v2), as served by Jira Cloud, Server and compatible trackers.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::{ImportedIssue, IssueImportError, check_status, send_json};
+
+const PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JiraSearchResponse {
+    issues: Vec<JiraIssue>,
+    /// Number of matching issues across all pages
+    total: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct JiraIssue {
+    key: String,
+    fields: JiraFields,
+}
+
+#[derive(Deserialize)]
+struct JiraFields {
+    #[serde(default)]
+    summary: String,
+    description: Option<String>,
+    updated: Option<String>,
+}
+
+pub struct JiraClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: String,
+    /// With a user, the token is sent as a basic auth password (Jira Cloud); without one,
+    /// as a bearer token (personal access tokens on Jira Server).
+    user: Option<String>,
+}
+
+impl JiraClient {
+    pub fn new(base_url: String, token: String, user: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            token,
+            user,
+        }
+    }
+
+    fn authorized(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.user {
+            Some(user) => request.basic_auth(user, Some(&self.token)),
+            None => request.bearer_auth(&self.token),
+        }
+    }
+
+    /// Unresolved issues of `project_key` carrying every label in `labels` and matching
+    /// the extra JQL condition `jql`, read page after page up to `limit`.
+    pub async fn list_issues(
+        &self,
+        project_key: &str,
+        jql: Option<&str>,
+        labels: &[String],
+        limit: usize,
+    ) -> Result<Vec<ImportedIssue>, IssueImportError> {
+        let mut conditions = vec![
+            format!("project = {}", quote(project_key)),
+            "statusCategory != Done".to_string(),
+        ];
+        conditions.extend(
+            labels
+                .iter()
+                .map(|label| format!("labels = {}", quote(label))),
+        );
+        if let Some(jql) = jql {
+            conditions.push(format!("({jql})"));
+        }
+        let jql = format!("{} ORDER BY updated DESC", conditions.join(" AND "));
+
+        let url = format!("{}/rest/api/2/search", self.base_url);
+        let page_size = PAGE_SIZE.to_string();
+        let mut imported = Vec::new();
+        loop {
+            let start_at = imported.len().to_string();
+            let response: JiraSearchResponse =
+                send_json(self.authorized(self.client.get(&url).query(&[
+                    ("jql", jql.as_str()),
+                    ("fields", "summary,description,updated"),
+                    ("startAt", start_at.as_str()),
+                    ("maxResults", page_size.as_str()),
+                ])))
+                .await?;
+
+            let received = response.issues.len();
+            imported.extend(response.issues.into_iter().map(|issue| ImportedIssue {
+                url: format!("{}/browse/{}", self.base_url, issue.key),
+                external_id: issue.key,
+                title: issue.fields.summary,
+                body: issue.fields.description.unwrap_or_default(),
+                updated_at: issue.fields.updated.as_deref().and_then(parse_timestamp),
+            }));
+            let all_read = match response.total {
+                Some(total) => imported.len() >= total,
+                None => received < PAGE_SIZE,
+            };
+            if received == 0 || all_read || imported.len() >= limit {
+                break;
+            }
+        }
+        imported.truncate(limit);
+        Ok(imported)
+    }
+
+    pub async fn add_comment(&self, key: &str, body: &str) -> Result<(), IssueImportError> {
+        let url = format!("{}/rest/api/2/issue/{}/comment", self.base_url, key);
+        let request = self
+            .authorized(self.client.post(url))
+            .json(&serde_json::json!({ "body": body }));
+        check_status(request.send().await?).await?;
+        Ok(())
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Jira timestamps look like `2024-01-02T10:04:05.000+0200` (no colon in the offset).
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z")
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
@@ -0,0 +1,676 @@
+//! Import of issues from external trackers as local tasks.
+//!
+//! Each [`IssueImportSource`] names a tracker query. Syncing a source creates a task for
+//! every new matching issue and keeps the title and description of imported tasks in
+//! step with the issue. GitHub is read through the `gh` CLI; GitLab and Jira through
+//! their REST APIs, page by page. API tokens are stored encrypted by [`SecretService`].
+
+mod gitlab;
+mod jira;
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        issue_import::{IssueImportSource, IssueTrackerKind, TaskIssueLink},
+        task::{CreateTask, Task},
+    },
+};
+use serde::{Serialize, de::DeserializeOwned};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::time::interval;
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use self::{gitlab::GitLabClient, jira::JiraClient};
+use crate::services::{
+    git_host::{GitHostError, github::GitHubProvider},
+    secrets::{SecretError, SecretService},
+};
+
+/// Upper bound on issues read from a tracker per sync
+const MAX_ISSUES_PER_SYNC: usize = 1000;
+
+#[derive(Debug, Error)]
+pub enum IssueImportError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
+    #[error("Issue tracker request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Issue tracker returned {status}: {body}")]
+    Api { status: u16, body: String },
+    #[error("{0} sources need a base URL")]
+    MissingBaseUrl(&'static str),
+    #[error("{0} sources need an API token")]
+    MissingToken(&'static str),
+    #[error("Invalid issue number '{0}'")]
+    InvalidIssueNumber(String),
+    #[error(transparent)]
+    Secret(#[from] SecretError),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+pub struct IssueSyncSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+/// An issue as read from any tracker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedIssue {
+    /// Issue number (GitHub, GitLab) or key (Jira)
+    pub external_id: String,
+    pub url: String,
+    pub title: String,
+    pub body: String,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+enum TrackerClient {
+    GitHub(GitHubProvider),
+    GitLab(GitLabClient),
+    Jira(JiraClient),
+}
+
+impl TrackerClient {
+    /// Client for `source`, authenticated with its decrypted `token`.
+    fn for_source(
+        source: &IssueImportSource,
+        token: Option<String>,
+    ) -> Result<Self, IssueImportError> {
+        let base_url = |name| {
+            source
+                .base_url
+                .as_deref()
+                .map(|url| url.trim().trim_end_matches('/'))
+                .filter(|url| !url.is_empty())
+                .map(str::to_string)
+                .ok_or(IssueImportError::MissingBaseUrl(name))
+        };
+        Ok(match source.tracker {
+            IssueTrackerKind::Github => Self::GitHub(GitHubProvider::new()?),
+            IssueTrackerKind::Gitlab => Self::GitLab(GitLabClient::new(base_url("GitLab")?, token)),
+            IssueTrackerKind::Jira => Self::Jira(JiraClient::new(
+                base_url("Jira")?,
+                token.ok_or(IssueImportError::MissingToken("Jira"))?,
+                source.api_user.clone(),
+            )),
+        })
+    }
+
+    /// Client for `source`, decrypting its stored API token.
+    async fn connect(
+        secrets: &SecretService,
+        source: &IssueImportSource,
+    ) -> Result<Self, IssueImportError> {
+        let token = match source.api_token.as_deref() {
+            Some(encrypted) => Some(secrets.decrypt_value(encrypted).await?),
+            None => None,
+        };
+        Self::for_source(source, token)
+    }
+
+    async fn list_issues(
+        &self,
+        source: &IssueImportSource,
+    ) -> Result<Vec<ImportedIssue>, IssueImportError> {
+        let query = source
+            .query
+            .as_deref()
+            .map(str::trim)
+            .filter(|q| !q.is_empty());
+        match self {
+            Self::GitHub(provider) => Ok(provider
+                .list_issues(&source.repository, query, &source.labels)
+                .await?
+                .into_iter()
+                .map(|issue| ImportedIssue {
+                    external_id: issue.number.to_string(),
+                    url: issue.url,
+                    title: issue.title,
+                    body: issue.body,
+                    updated_at: issue.updated_at,
+                })
+                .collect()),
+            Self::GitLab(client) => {
+                client
+                    .list_issues(
+                        &source.repository,
+                        query,
+                        &source.labels,
+                        MAX_ISSUES_PER_SYNC,
+                    )
+                    .await
+            }
+            Self::Jira(client) => {
+                client
+                    .list_issues(
+                        &source.repository,
+                        query,
+                        &source.labels,
+                        MAX_ISSUES_PER_SYNC,
+                    )
+                    .await
+            }
+        }
+    }
+
+    async fn add_comment(
+        &self,
+        source: &IssueImportSource,
+        external_id: &str,
+        body: &str,
+    ) -> Result<(), IssueImportError> {
+        match self {
+            Self::GitHub(provider) => {
+                let number = external_id
+                    .parse()
+                    .map_err(|_| IssueImportError::InvalidIssueNumber(external_id.to_string()))?;
+                Ok(provider
+                    .comment_on_issue(&source.repository, number, body)
+                    .await?)
+            }
+            Self::GitLab(client) => {
+                client
+                    .add_comment(&source.repository, external_id, body)
+                    .await
+            }
+            Self::Jira(client) => client.add_comment(external_id, body).await,
+        }
+    }
+}
+
+/// Send a request and decode its JSON body, turning error statuses into
+/// [`IssueImportError::Api`].
+async fn send_json<T: DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+) -> Result<T, IssueImportError> {
+    let response = check_status(request.send().await?).await?;
+    Ok(response.json().await?)
+}
+
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, IssueImportError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(IssueImportError::Api {
+        status: status.as_u16(),
+        body: utils::text::truncate_to_char_boundary(body.trim(), 500).to_string(),
+    })
+}
+
+/// Task description for an issue: its body followed by a link back to it.
+fn task_description(issue: &ImportedIssue) -> String {
+    let body = issue.body.trim();
+    if body.is_empty() {
+        format!("Imported from {}", issue.url)
+    } else {
+        format!("{body}\n\nImported from {}", issue.url)
+    }
+}
+
+/// Import new issues of `source` as tasks and update tasks whose issue changed since the
+/// last sync. Issues whose task was deleted are not imported again.
+pub async fn sync_source(
+    pool: &SqlitePool,
+    secrets: &SecretService,
+    source: &IssueImportSource,
+) -> Result<IssueSyncSummary, IssueImportError> {
+    let issues = TrackerClient::connect(secrets, source)
+        .await?
+        .list_issues(source)
+        .await?;
+
+    let mut summary = IssueSyncSummary::default();
+    for issue in issues {
+        let description = task_description(&issue);
+        let Some(link) =
+            TaskIssueLink::find_by_external_id(pool, source.id, &issue.external_id).await?
+        else {
+            let task = Task::create(
+                pool,
+                &CreateTask::from_title_description(
+                    source.project_id,
+                    issue.title.clone(),
+                    Some(description),
+                ),
+                Uuid::new_v4(),
+            )
+            .await?;
+            TaskIssueLink::create(
+                pool,
+                task.id,
+                source.id,
+                &issue.external_id,
+                &issue.url,
+                issue.updated_at,
+            )
+            .await?;
+            summary.created += 1;
+            continue;
+        };
+
+        let task = match link.task_id {
+            Some(task_id) => Task::find_by_id(pool, task_id).await?,
+            None => None,
+        };
+        let changed = issue.updated_at.is_none() || issue.updated_at != link.issue_updated_at;
+        match task {
+            Some(task)
+                if changed
+                    && (task.title != issue.title
+                        || task.description.as_deref() != Some(description.as_str())) =>
+            {
+                Task::update(
+                    pool,
+                    task.id,
+                    task.project_id,
+                    issue.title.clone(),
+                    Some(description),
+                    task.status,
+                    task.parent_workspace_id,
+                )
+                .await?;
+                summary.updated += 1;
+            }
+            _ => summary.unchanged += 1,
+        }
+        if changed {
+            TaskIssueLink::update_issue(pool, link.id, &issue.url, issue.updated_at).await?;
+        }
+    }
+
+    IssueImportSource::mark_synced(pool, source.id).await?;
+    Ok(summary)
+}
+
+/// Comment `pr_url` on the issue a task was imported from, if its source asks for it and
+/// the link has not been posted already.
+pub async fn comment_pr_link(
+    pool: &SqlitePool,
+    secrets: &SecretService,
+    task_id: Uuid,
+    pr_url: &str,
+) -> Result<(), IssueImportError> {
+    let Some(link) = TaskIssueLink::find_by_task_id(pool, task_id).await? else {
+        return Ok(());
+    };
+    if link.pr_comment_url.as_deref() == Some(pr_url) {
+        return Ok(());
+    }
+    let Some(source) = IssueImportSource::find_by_id(pool, link.source_id).await? else {
+        return Ok(());
+    };
+    if !source.comment_pr_link {
+        return Ok(());
+    }
+
+    TrackerClient::connect(secrets, &source)
+        .await?
+        .add_comment(
+            &source,
+            &link.external_id,
+            &format!("Pull request: {pr_url}"),
+        )
+        .await?;
+    TaskIssueLink::set_pr_comment_url(pool, link.id, pr_url).await?;
+    Ok(())
+}
+
+/// Periodically syncs every import source
+pub struct IssueImportService {
+    db: DBService,
+    secrets: SecretService,
+    poll_interval: Duration,
+}
+
+impl IssueImportService {
+    pub async fn spawn(db: DBService, secrets: SecretService) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            secrets,
+            poll_interval: Duration::from_secs(600),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting issue import service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            let sources = match IssueImportSource::find_all(&self.db.pool, None).await {
+                Ok(sources) => sources,
+                Err(e) => {
+                    warn!("Failed to load issue import sources: {}", e);
+                    continue;
+                }
+            };
+            for source in sources {
+                match sync_source(&self.db.pool, &self.secrets, &source).await {
+                    Ok(summary) => {
+                        debug!("Synced issue import source {}: {:?}", source.id, summary)
+                    }
+                    Err(e) => warn!("Failed to sync issue import source {}: {}", source.id, e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use db::{models::issue_import::CreateIssueImportSource, test_utils::create_project};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Serves `routes` (path prefix, JSON body) over HTTP and records each request as
+    /// its request line followed by its body.
+    async fn fixture_server(
+        routes: Vec<(&'static str, &'static str)>,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut raw = Vec::new();
+                let mut buf = [0u8; 4096];
+                let (head, body) = loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    raw.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&raw).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= length || n == 0 {
+                            break (head.to_string(), body.to_string());
+                        }
+                    }
+                };
+                let request_line = head.lines().next().unwrap_or_default().to_string();
+                let path = request_line.split(' ').nth(1).unwrap_or_default();
+                let response = routes
+                    .iter()
+                    .find(|(prefix, _)| path.starts_with(prefix))
+                    .map(|(_, json)| *json)
+                    .unwrap_or("{}");
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(format!("{request_line}\n{body}"));
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                );
+                stream.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+        (base_url, requests)
+    }
+
+    fn source(tracker: IssueTrackerKind, base_url: &str, repository: &str) -> IssueImportSource {
+        IssueImportSource {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            tracker,
+            base_url: Some(format!("{base_url}/")),
+            repository: repository.to_string(),
+            query: Some("crash".to_string()),
+            labels: sqlx::types::Json(vec!["bug".to_string(), "ui".to_string()]),
+            api_token: None,
+            api_user: Some("me@example.com".to_string()),
+            comment_pr_link: true,
+            last_synced_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_and_comments_on_gitlab_issues() {
+        let (base_url, requests) = fixture_server(vec![(
+            "/api/v4/projects/group%2Fapp/issues?",
+            r#"[{"iid": 7, "title": "Crash on save", "description": null,
+                 "web_url": "https://gitlab.example/group/app/-/issues/7",
+                 "updated_at": "2026-01-02T03:04:05.000Z"}]"#,
+        )])
+        .await;
+        let source = source(IssueTrackerKind::Gitlab, &base_url, "group/app");
+        let client = TrackerClient::for_source(&source, Some("token".to_string())).unwrap();
+
+        let issues = client.list_issues(&source).await.unwrap();
+        assert_eq!(
+            issues,
+            vec![ImportedIssue {
+                external_id: "7".to_string(),
+                url: "https://gitlab.example/group/app/-/issues/7".to_string(),
+                title: "Crash on save".to_string(),
+                body: String::new(),
+                updated_at: Some("2026-01-02T03:04:05Z".parse().unwrap()),
+            }]
+        );
+
+        client
+            .add_comment(&source, "7", "Pull request: https://example/pr/1")
+            .await
+            .unwrap();
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with(
+            "GET /api/v4/projects/group%2Fapp/issues?state=opened&per_page=100&search=crash&labels=bug%2Cui&page=1 "
+        ));
+        assert!(requests[1].starts_with("POST /api/v4/projects/group%2Fapp/issues/7/notes "));
+        assert!(requests[1].ends_with(r#"{"body":"Pull request: https://example/pr/1"}"#));
+    }
+
+    #[tokio::test]
+    async fn reads_jira_issues_with_jql_filters() {
+        let (base_url, requests) = fixture_server(vec![(
+            "/rest/api/2/search?",
+            r#"{"issues": [{"key": "APP-12", "fields": {"summary": "Login fails",
+                 "description": "Steps to reproduce", "updated": "2026-01-02T10:04:05.000+0200"}}]}"#,
+        )])
+        .await;
+        let source = source(IssueTrackerKind::Jira, &base_url, "APP");
+        let client = TrackerClient::for_source(&source, Some("token".to_string())).unwrap();
+
+        let issues = client.list_issues(&source).await.unwrap();
+        assert_eq!(
+            issues,
+            vec![ImportedIssue {
+                external_id: "APP-12".to_string(),
+                url: format!("{base_url}/browse/APP-12"),
+                title: "Login fails".to_string(),
+                body: "Steps to reproduce".to_string(),
+                updated_at: Some("2026-01-02T08:04:05Z".parse().unwrap()),
+            }]
+        );
+
+        let requests = requests.lock().unwrap();
+        let request_line = requests[0].lines().next().unwrap();
+        let (_, query) = request_line.split_once('?').unwrap();
+        let params: Vec<(String, String)> =
+            url::form_urlencoded::parse(query.trim_end_matches(" HTTP/1.1").as_bytes())
+                .into_owned()
+                .collect();
+        assert!(params.contains(&(
+            "jql".to_string(),
+            r#"project = "APP" AND statusCategory != Done AND labels = "bug" AND labels = "ui" AND (crash) ORDER BY updated DESC"#
+                .to_string()
+        )));
+        assert!(params.contains(&("startAt".to_string(), "0".to_string())));
+    }
+
+    fn gitlab_issues(issues: &[(i64, &str, &str)]) -> &'static str {
+        let issues: Vec<_> = issues
+            .iter()
+            .map(|(iid, title, updated_at)| {
+                serde_json::json!({
+                    "iid": iid,
+                    "title": title,
+                    "description": "From the tracker",
+                    "web_url": format!("https://gitlab.example/group/app/-/issues/{iid}"),
+                    "updated_at": updated_at,
+                })
+            })
+            .collect();
+        Box::leak(serde_json::to_string(&issues).unwrap().into_boxed_str())
+    }
+
+    async fn gitlab_source(pool: &SqlitePool, base_url: &str) -> IssueImportSource {
+        let project = create_project(pool, "issues").await;
+        IssueImportSource::create(
+            pool,
+            &CreateIssueImportSource {
+                project_id: project.id,
+                tracker: IssueTrackerKind::Gitlab,
+                base_url: Some(base_url.to_string()),
+                repository: "group/app".to_string(),
+                query: None,
+                labels: vec![],
+                api_token: None,
+                api_user: None,
+                comment_pr_link: false,
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn project_task_titles(pool: &SqlitePool, project_id: Uuid) -> Vec<String> {
+        let mut titles: Vec<String> =
+            Task::find_by_project_id_with_attempt_status(pool, project_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|task| task.task.title)
+                .collect();
+        titles.sort();
+        titles
+    }
+
+    #[tokio::test]
+    async fn sync_creates_updates_and_does_not_reimport_issues() {
+        let db = DBService::new_in_memory().await.unwrap();
+        let pool = &db.pool;
+        let secrets = SecretService::new(pool.clone());
+        const ISSUES: &str = "/api/v4/projects/group%2Fapp/issues?";
+
+        let (base_url, _) = fixture_server(vec![(
+            ISSUES,
+            gitlab_issues(&[
+                (7, "Crash on save", "2026-01-02T03:04:05Z"),
+                (8, "Slow search", "2026-01-02T03:04:05Z"),
+            ]),
+        )])
+        .await;
+        let source = gitlab_source(pool, &base_url).await;
+
+        let summary = sync_source(pool, &secrets, &source).await.unwrap();
+        assert_eq!(
+            (summary.created, summary.updated, summary.unchanged),
+            (2, 0, 0)
+        );
+        assert_eq!(
+            project_task_titles(pool, source.project_id).await,
+            vec!["Crash on save", "Slow search"]
+        );
+
+        // Syncing the same issues again changes nothing
+        let summary = sync_source(pool, &secrets, &source).await.unwrap();
+        assert_eq!(
+            (summary.created, summary.updated, summary.unchanged),
+            (0, 0, 2)
+        );
+
+        // A renamed issue updates its task; an issue whose task was deleted stays gone
+        let link = TaskIssueLink::find_by_external_id(pool, source.id, "8")
+            .await
+            .unwrap()
+            .unwrap();
+        Task::delete(pool, link.task_id.unwrap()).await.unwrap();
+        let (base_url, _) = fixture_server(vec![(
+            ISSUES,
+            gitlab_issues(&[
+                (7, "Crash on save as", "2026-01-03T03:04:05Z"),
+                (8, "Slow search", "2026-01-02T03:04:05Z"),
+            ]),
+        )])
+        .await;
+        let moved = IssueImportSource {
+            base_url: Some(base_url),
+            ..source.clone()
+        };
+        let summary = sync_source(pool, &secrets, &moved).await.unwrap();
+        assert_eq!(
+            (summary.created, summary.updated, summary.unchanged),
+            (0, 1, 1)
+        );
+        assert_eq!(
+            project_task_titles(pool, source.project_id).await,
+            vec!["Crash on save as"]
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_every_page_of_gitlab_issues() {
+        let first_page: Vec<(i64, String)> =
+            (1..=100).map(|iid| (iid, format!("Issue {iid}"))).collect();
+        let first_page: Vec<(i64, &str, &str)> = first_page
+            .iter()
+            .map(|(iid, title)| (*iid, title.as_str(), "2026-01-02T03:04:05Z"))
+            .collect();
+        let (base_url, requests) = fixture_server(vec![
+            (
+                "/api/v4/projects/group%2Fapp/issues?state=opened&per_page=100&page=1",
+                gitlab_issues(&first_page),
+            ),
+            (
+                "/api/v4/projects/group%2Fapp/issues?state=opened&per_page=100&page=2",
+                gitlab_issues(&[(101, "Issue 101", "2026-01-02T03:04:05Z")]),
+            ),
+        ])
+        .await;
+        let source = IssueImportSource {
+            query: None,
+            labels: sqlx::types::Json(vec![]),
+            ..source(IssueTrackerKind::Gitlab, &base_url, "group/app")
+        };
+        let client = TrackerClient::for_source(&source, None).unwrap();
+
+        let issues = client.list_issues(&source).await.unwrap();
+        assert_eq!(issues.len(), 101);
+        assert_eq!(issues[100].external_id, "101");
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,993 @@
+//! Merge queue for local direct merges.
+//!
+//! Workspaces queued against the same repository and target branch are landed one at a
+//! time: each is rebased onto the latest target, squashed into a single commit, verified
+//! with the repository's `verification_script` inside its worktree, and the target is
+//! then fast-forwarded to exactly that verified commit. If the target moves while a
+//! workspace is being verified, it is rebased and verified again. Entries are stored in
+//! the database, so queued merges are picked up again after a restart.
+
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use db::models::{
+    merge::Merge,
+    queued_merge::{CreateQueuedMerge, QueuedMerge, QueuedMergeStatus},
+    repo::Repo,
+    repo_commit_policy::RepoCommitPolicy,
+    task::{Task, TaskStatus},
+    workspace::Workspace,
+};
+use futures::{StreamExt, stream::BoxStream};
+use git::{CommitOptions, GitService, GitServiceError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    process::Command,
+    sync::broadcast,
+};
+use tokio_stream::wrappers::BroadcastStream;
+use tracing::{error, info, warn};
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, process::kill_process_group, shell::get_shell_command};
+use uuid::Uuid;
+
+use crate::services::{
+    analytics::AnalyticsContext, commit_policy, container::ContainerService, pr_stack,
+};
+
+/// How many times a workspace is rebased and re-verified when the target keeps moving
+const MAX_ATTEMPTS: usize = 3;
+const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Only the tail of the verification output is kept, that is where failures show up
+const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+/// Finished entries are dropped from the queue after this long
+const FINISHED_RETENTION: chrono::Duration = chrono::Duration::hours(1);
+
+#[derive(Debug, Error)]
+pub enum MergeQueueError {
+    #[error("Workspace is already queued for merge into this branch")]
+    AlreadyQueued,
+    #[error("Merge queue entry not found")]
+    NotFound,
+    #[error("Merge queue entry is already being processed")]
+    NotCancellable,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum MergeQueueFailure {
+    MergeConflicts {
+        message: String,
+        conflicted_files: Vec<String>,
+    },
+    VerificationFailed {
+        exit_code: Option<i32>,
+        output: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "status", rename_all = "snake_case")]
+#[ts(tag = "status", rename_all = "snake_case")]
+pub enum MergeQueueEntryStatus {
+    Queued,
+    Rebasing,
+    Verifying,
+    Merging,
+    Merged { merge_commit: String },
+    Failed { failure: MergeQueueFailure },
+    Cancelled,
+}
+
+impl MergeQueueEntryStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            MergeQueueEntryStatus::Merged { .. }
+                | MergeQueueEntryStatus::Failed { .. }
+                | MergeQueueEntryStatus::Cancelled
+        )
+    }
+
+    fn from_record(record: &QueuedMerge) -> Self {
+        match record.status {
+            QueuedMergeStatus::Queued => MergeQueueEntryStatus::Queued,
+            QueuedMergeStatus::Rebasing => MergeQueueEntryStatus::Rebasing,
+            QueuedMergeStatus::Verifying => MergeQueueEntryStatus::Verifying,
+            QueuedMergeStatus::Merging => MergeQueueEntryStatus::Merging,
+            QueuedMergeStatus::Merged => MergeQueueEntryStatus::Merged {
+                merge_commit: record.merge_commit.clone().unwrap_or_default(),
+            },
+            QueuedMergeStatus::Failed => MergeQueueEntryStatus::Failed {
+                failure: record
+                    .failure
+                    .as_deref()
+                    .and_then(|failure| serde_json::from_str(failure).ok())
+                    .unwrap_or_else(|| MergeQueueFailure::message("Unknown failure")),
+            },
+            QueuedMergeStatus::Cancelled => MergeQueueEntryStatus::Cancelled,
+        }
+    }
+
+    /// Split into the stored status, merge commit and JSON-encoded failure.
+    fn into_record(self) -> (QueuedMergeStatus, Option<String>, Option<String>) {
+        match self {
+            MergeQueueEntryStatus::Queued => (QueuedMergeStatus::Queued, None, None),
+            MergeQueueEntryStatus::Rebasing => (QueuedMergeStatus::Rebasing, None, None),
+            MergeQueueEntryStatus::Verifying => (QueuedMergeStatus::Verifying, None, None),
+            MergeQueueEntryStatus::Merging => (QueuedMergeStatus::Merging, None, None),
+            MergeQueueEntryStatus::Merged { merge_commit } => {
+                (QueuedMergeStatus::Merged, Some(merge_commit), None)
+            }
+            MergeQueueEntryStatus::Failed { failure } => {
+                (QueuedMergeStatus::Failed, None, Some(failure.to_json()))
+            }
+            MergeQueueEntryStatus::Cancelled => (QueuedMergeStatus::Cancelled, None, None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct MergeQueueEntry {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub branch: String,
+    /// Zero-based position among the queued entries for the same target, if still waiting
+    pub position: Option<usize>,
+    pub status: MergeQueueEntryStatus,
+    pub enqueued_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MergeQueueEntry {
+    fn from_record(record: QueuedMerge, position: Option<usize>) -> Self {
+        Self {
+            status: MergeQueueEntryStatus::from_record(&record),
+            id: record.id,
+            workspace_id: record.workspace_id,
+            repo_id: record.repo_id,
+            target_branch: record.target_branch,
+            branch: record.branch,
+            position,
+            enqueued_at: record.created_at,
+            updated_at: record.updated_at,
+        }
+    }
+}
+
+/// Number the waiting entries of each lane; `records` must be in enqueue order.
+fn with_positions(records: Vec<QueuedMerge>) -> Vec<MergeQueueEntry> {
+    let mut waiting: HashMap<LaneKey, usize> = HashMap::new();
+    records
+        .into_iter()
+        .map(|record| {
+            let position = (record.status == QueuedMergeStatus::Queued).then(|| {
+                let ahead = waiting
+                    .entry((record.repo_id, record.target_branch.clone()))
+                    .or_default();
+                *ahead += 1;
+                *ahead - 1
+            });
+            MergeQueueEntry::from_record(record, position)
+        })
+        .collect()
+}
+
+/// Everything needed to queue a workspace for merge.
+#[derive(Debug, Clone)]
+pub struct MergeQueueRequest {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub commit_message: String,
+}
+
+type LaneKey = (Uuid, String);
+
+/// Merge queue with one serial worker per repository and target branch.
+#[derive(Clone)]
+pub struct MergeQueueService<C: ContainerService> {
+    container: C,
+    analytics: Option<AnalyticsContext>,
+    active_lanes: Arc<tokio::sync::Mutex<HashSet<LaneKey>>>,
+    updates: broadcast::Sender<MergeQueueEntry>,
+}
+
+impl<C: ContainerService + Clone + Send + Sync + 'static> MergeQueueService<C> {
+    pub fn new(container: C, analytics: Option<AnalyticsContext>) -> Self {
+        let (updates, _) = broadcast::channel(256);
+        Self {
+            container,
+            analytics,
+            active_lanes: Arc::new(tokio::sync::Mutex::new(HashSet::new())),
+            updates,
+        }
+    }
+
+    fn pool(&self) -> &SqlitePool {
+        &self.container.db().pool
+    }
+
+    /// Start workers for entries left queued by a previous run. Entries that were being
+    /// processed are failed rather than retried, their worktree may be half-rebased.
+    pub async fn resume(&self) -> Result<(), sqlx::Error> {
+        let pool = self.pool();
+        let interrupted = QueuedMerge::fail_in_progress(
+            pool,
+            &MergeQueueFailure::message("Interrupted by a restart").to_json(),
+        )
+        .await?;
+        if interrupted > 0 {
+            warn!(
+                "Failed {} merge queue entries interrupted by a restart",
+                interrupted
+            );
+        }
+
+        let mut active_lanes = self.active_lanes.lock().await;
+        for lane in QueuedMerge::find_queued_lanes(pool).await? {
+            if active_lanes.insert(lane.clone()) {
+                self.spawn_lane(lane);
+            }
+        }
+        Ok(())
+    }
+
+    /// Queue a workspace for merge, starting a worker for its target if none is running.
+    pub async fn enqueue(
+        &self,
+        branch: String,
+        request: MergeQueueRequest,
+    ) -> Result<MergeQueueEntry, MergeQueueError> {
+        let pool = self.pool();
+        let lane = (request.repo_id, request.target_branch.clone());
+        let mut active_lanes = self.active_lanes.lock().await;
+
+        QueuedMerge::delete_finished_older_than(pool, FINISHED_RETENTION).await?;
+        if QueuedMerge::exists_unfinished(pool, request.workspace_id, request.repo_id).await? {
+            return Err(MergeQueueError::AlreadyQueued);
+        }
+
+        let record = QueuedMerge::create(
+            pool,
+            &CreateQueuedMerge {
+                workspace_id: request.workspace_id,
+                repo_id: request.repo_id,
+                target_branch: request.target_branch,
+                branch,
+                commit_message: request.commit_message,
+            },
+            Uuid::new_v4(),
+        )
+        .await?;
+        self.publish(&record).await;
+
+        if active_lanes.insert(lane.clone()) {
+            self.spawn_lane(lane);
+        }
+
+        self.get(record.id).await?.ok_or(MergeQueueError::NotFound)
+    }
+
+    /// Cancel an entry that has not started processing yet.
+    pub async fn cancel(&self, entry_id: Uuid) -> Result<MergeQueueEntry, MergeQueueError> {
+        let pool = self.pool();
+        // A no-op once a worker has claimed the entry
+        if !QueuedMerge::transition(
+            pool,
+            entry_id,
+            QueuedMergeStatus::Queued,
+            QueuedMergeStatus::Cancelled,
+        )
+        .await?
+        {
+            return Err(match QueuedMerge::find_by_id(pool, entry_id).await? {
+                Some(_) => MergeQueueError::NotCancellable,
+                None => MergeQueueError::NotFound,
+            });
+        }
+        if let Some(record) = QueuedMerge::find_by_id(pool, entry_id).await? {
+            self.publish(&record).await;
+        }
+        self.get(entry_id).await?.ok_or(MergeQueueError::NotFound)
+    }
+
+    pub async fn get(&self, entry_id: Uuid) -> Result<Option<MergeQueueEntry>, sqlx::Error> {
+        let pool = self.pool();
+        let Some(record) = QueuedMerge::find_by_id(pool, entry_id).await? else {
+            return Ok(None);
+        };
+        let position = if record.status == QueuedMergeStatus::Queued {
+            QueuedMerge::find_queued_in_lane(pool, record.repo_id, &record.target_branch)
+                .await?
+                .iter()
+                .position(|r| r.id == record.id)
+        } else {
+            None
+        };
+        Ok(Some(MergeQueueEntry::from_record(record, position)))
+    }
+
+    /// Entries in enqueue order, optionally limited to a single repository.
+    pub async fn list(&self, repo_id: Option<Uuid>) -> Result<Vec<MergeQueueEntry>, sqlx::Error> {
+        Ok(with_positions(
+            QueuedMerge::find_all(self.pool(), repo_id).await?,
+        ))
+    }
+
+    /// Stream queue entries as JSON patches against `/entries`, starting with a snapshot.
+    pub async fn stream_raw(
+        &self,
+        repo_id: Option<Uuid>,
+    ) -> Result<BoxStream<'static, Result<LogMsg, std::io::Error>>, sqlx::Error> {
+        // Subscribe before taking the snapshot so no update falls in between
+        let receiver = self.updates.subscribe();
+        let entries: serde_json::Map<String, serde_json::Value> = self
+            .list(repo_id)
+            .await?
+            .into_iter()
+            .map(|e| (e.id.to_string(), serde_json::to_value(e).unwrap()))
+            .collect();
+        let initial_patch = json!([
+            {
+                "op": "replace",
+                "path": "/entries",
+                "value": entries
+            }
+        ]);
+        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+
+        let updates = BroadcastStream::new(receiver).filter_map(move |update| async move {
+            let entry = update.ok()?;
+            if repo_id.is_some_and(|id| id != entry.repo_id) {
+                return None;
+            }
+            let patch = json!([
+                {
+                    "op": "add",
+                    "path": format!("/entries/{}", entry.id),
+                    "value": entry
+                }
+            ]);
+            serde_json::from_value(patch)
+                .ok()
+                .map(|patch| Ok(LogMsg::JsonPatch(patch)))
+        });
+
+        Ok(
+            futures::stream::iter(vec![Ok(initial_msg), Ok(LogMsg::Ready)])
+                .chain(updates)
+                .boxed(),
+        )
+    }
+
+    async fn publish(&self, record: &QueuedMerge) {
+        let records = match QueuedMerge::find_all(self.pool(), Some(record.repo_id)).await {
+            Ok(records) => records,
+            Err(e) => {
+                error!("Failed to load merge queue entries: {}", e);
+                return;
+            }
+        };
+        // Positions of the other waiting entries in the lane shift along with this one
+        for entry in with_positions(records) {
+            if entry.target_branch == record.target_branch {
+                let _ = self.updates.send(entry);
+            }
+        }
+    }
+
+    async fn set_status(&self, entry_id: Uuid, status: MergeQueueEntryStatus) {
+        let pool = self.pool();
+        let (status, merge_commit, failure) = status.into_record();
+        if let Err(e) = QueuedMerge::update_status(
+            pool,
+            entry_id,
+            status,
+            merge_commit.as_deref(),
+            failure.as_deref(),
+        )
+        .await
+        {
+            error!("Failed to update merge queue entry {}: {}", entry_id, e);
+            return;
+        }
+        if let Ok(Some(record)) = QueuedMerge::find_by_id(pool, entry_id).await {
+            self.publish(&record).await;
+        }
+    }
+
+    fn spawn_lane(&self, lane: LaneKey) {
+        let service = self.clone();
+        tokio::spawn(async move { service.run_lane(lane).await });
+    }
+
+    /// Claim the oldest queued entry of a lane; entries cancelled meanwhile are skipped.
+    async fn claim_next(&self, lane: &LaneKey) -> Result<Option<QueuedMerge>, sqlx::Error> {
+        let pool = self.pool();
+        for record in QueuedMerge::find_queued_in_lane(pool, lane.0, &lane.1).await? {
+            if QueuedMerge::transition(
+                pool,
+                record.id,
+                QueuedMergeStatus::Queued,
+                QueuedMergeStatus::Rebasing,
+            )
+            .await?
+            {
+                return Ok(Some(record));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn run_lane(&self, lane: LaneKey) {
+        info!("Starting merge queue worker for {} ({})", lane.1, lane.0);
+        loop {
+            let record = {
+                let mut active_lanes = self.active_lanes.lock().await;
+                match self.claim_next(&lane).await {
+                    Ok(Some(record)) => record,
+                    Ok(None) => {
+                        active_lanes.remove(&lane);
+                        return;
+                    }
+                    Err(e) => {
+                        error!(
+                            "Merge queue worker for {} ({}) stopped: {}",
+                            lane.1, lane.0, e
+                        );
+                        active_lanes.remove(&lane);
+                        return;
+                    }
+                }
+            };
+            self.set_status(record.id, MergeQueueEntryStatus::Rebasing)
+                .await;
+
+            let status = match self.process(&record).await {
+                Ok(merge_commit) => MergeQueueEntryStatus::Merged { merge_commit },
+                Err(failure) => {
+                    warn!(
+                        "Merge queue entry {} for workspace {} failed: {:?}",
+                        record.id, record.workspace_id, failure
+                    );
+                    MergeQueueEntryStatus::Failed { failure }
+                }
+            };
+            self.set_status(record.id, status).await;
+        }
+    }
+
+    async fn process(&self, record: &QueuedMerge) -> Result<String, MergeQueueFailure> {
+        let pool = self.pool();
+
+        let workspace = Workspace::find_by_id(pool, record.workspace_id)
+            .await
+            .map_err(MergeQueueFailure::from_error)?
+            .ok_or_else(|| MergeQueueFailure::message("Workspace not found"))?;
+        let repo = Repo::find_by_id(pool, record.repo_id)
+            .await
+            .map_err(MergeQueueFailure::from_error)?
+            .ok_or_else(|| MergeQueueFailure::message("Repository not found"))?;
+        let container_ref = self
+            .container
+            .ensure_container_exists(&workspace)
+            .await
+            .map_err(MergeQueueFailure::from_error)?;
+        let worktree_path = PathBuf::from(&container_ref).join(&repo.name);
+
+        let policy = RepoCommitPolicy::find_or_default(pool, repo.id)
+            .await
+            .map_err(MergeQueueFailure::from_error)?;
+        let task_title = workspace
+            .parent_task(pool)
+            .await
+            .map_err(MergeQueueFailure::from_error)?
+            .map(|task| task.title)
+            .unwrap_or_else(|| workspace.branch.clone());
+        let commit_message = commit_policy::finish_commit_message(
+            &self.container,
+            &policy,
+            &workspace,
+            &task_title,
+            &record.commit_message,
+        )
+        .await;
+        let commit_options = commit_policy::commit_options(&policy);
+        let verification_script = repo
+            .verification_script
+            .as_deref()
+            .filter(|s| !s.trim().is_empty());
+        let entry_id = record.id;
+        let worktree = worktree_path.as_path();
+
+        let merge_commit = land_branch(
+            self.container.git(),
+            &repo.path,
+            &worktree_path,
+            &workspace.branch,
+            &record.target_branch,
+            &commit_message,
+            &commit_options,
+            move |status| self.set_status(entry_id, status),
+            move || async move {
+                match verification_script {
+                    Some(script) => run_verification_script(script, worktree).await,
+                    None => Ok(()),
+                }
+            },
+        )
+        .await?;
+
+        self.finish_merge(&workspace, record, &merge_commit)
+            .await
+            .map_err(MergeQueueFailure::from_error)?;
+        Ok(merge_commit)
+    }
+
+    async fn finish_merge(
+        &self,
+        workspace: &Workspace,
+        record: &QueuedMerge,
+        merge_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        let pool = self.pool();
+
+        Merge::create_direct(
+            pool,
+            workspace.id,
+            record.repo_id,
+            &record.target_branch,
+            merge_commit,
+        )
+        .await?;
+        Task::update_status(pool, workspace.task_id, TaskStatus::Done).await?;
+        pr_stack::retarget_after_landing(pool, workspace, record.repo_id, &record.target_branch)
+            .await;
+        if !workspace.pinned
+            && let Err(e) = self.container.archive_workspace(workspace.id).await
+        {
+            error!("Failed to archive workspace {}: {}", workspace.id, e);
+        }
+
+        if let Some(analytics) = &self.analytics {
+            analytics.analytics_service.track_event(
+                &analytics.user_id,
+                "task_attempt_merged",
+                Some(json!({
+                    "task_id": workspace.task_id.to_string(),
+                    "workspace_id": workspace.id.to_string(),
+                    "merge_queue": true,
+                })),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Rebase `branch` onto `target_branch`, squash it into a single commit, verify that
+/// commit and fast-forward the target to it. If the target moved in the meantime nothing
+/// lands, and the branch is rebased and verified again up to `MAX_ATTEMPTS` times.
+#[allow(clippy::too_many_arguments)]
+async fn land_branch<S, SF, V, VF>(
+    git: &GitService,
+    repo_path: &Path,
+    worktree_path: &Path,
+    branch: &str,
+    target_branch: &str,
+    commit_message: &str,
+    commit_options: &CommitOptions,
+    mut on_status: S,
+    mut verify: V,
+) -> Result<String, MergeQueueFailure>
+where
+    S: FnMut(MergeQueueEntryStatus) -> SF,
+    SF: Future<Output = ()>,
+    V: FnMut() -> VF,
+    VF: Future<Output = Result<(), MergeQueueFailure>>,
+{
+    // Only the branch's own commits are replayed, starting from where it forked off
+    let mut old_base = git
+        .get_fork_point(worktree_path, target_branch, branch)
+        .map_err(MergeQueueFailure::from_git)?;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        if attempt > 1 {
+            on_status(MergeQueueEntryStatus::Rebasing).await;
+        }
+        git.rebase_branch(repo_path, worktree_path, target_branch, &old_base, branch)
+            .map_err(MergeQueueFailure::from_git)?;
+        // The target commit the branch now builds on, even if the target moved since
+        let verified_base = git
+            .get_fork_point(worktree_path, target_branch, branch)
+            .map_err(MergeQueueFailure::from_git)?;
+        let verified_commit = git
+            .squash_branch_commits(
+                worktree_path,
+                branch,
+                target_branch,
+                commit_message,
+                commit_options,
+            )
+            .map_err(MergeQueueFailure::from_git)?
+            .ok_or_else(|| {
+                MergeQueueFailure::message(format!(
+                    "Branch '{branch}' has no changes to merge into '{target_branch}'"
+                ))
+            })?;
+
+        on_status(MergeQueueEntryStatus::Verifying).await;
+        verify().await?;
+
+        on_status(MergeQueueEntryStatus::Merging).await;
+        match git.fast_forward_branch(repo_path, target_branch, &verified_base, &verified_commit) {
+            Ok(()) => return Ok(verified_commit),
+            // Someone else moved the target while we were verifying
+            Err(GitServiceError::BranchesDiverged(_)) => old_base = verified_base,
+            Err(e) => return Err(MergeQueueFailure::from_git(e)),
+        }
+    }
+
+    Err(MergeQueueFailure::message(format!(
+        "Target branch '{target_branch}' kept moving after {MAX_ATTEMPTS} attempts"
+    )))
+}
+
+impl MergeQueueFailure {
+    fn message(message: impl Into<String>) -> Self {
+        MergeQueueFailure::Error {
+            message: message.into(),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    fn from_error(e: impl std::fmt::Display) -> Self {
+        Self::message(e.to_string())
+    }
+
+    fn from_git(e: GitServiceError) -> Self {
+        match e {
+            GitServiceError::MergeConflicts {
+                message,
+                conflicted_files,
+            } => MergeQueueFailure::MergeConflicts {
+                message,
+                conflicted_files,
+            },
+            e => Self::from_error(e),
+        }
+    }
+}
+
+async fn run_verification_script(
+    script: &str,
+    worktree_path: &Path,
+) -> Result<(), MergeQueueFailure> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut child = Command::new(shell_cmd)
+        .arg(shell_arg)
+        .arg(script)
+        .current_dir(worktree_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .group_spawn()
+        .map_err(MergeQueueFailure::from_error)?;
+
+    let mut stdout = child.inner().stdout.take().expect("stdout is piped");
+    let mut stderr = child.inner().stderr.take().expect("stderr is piped");
+    let result = tokio::time::timeout(VERIFICATION_TIMEOUT, async {
+        let (out, err, status) =
+            tokio::join!(read_tail(&mut stdout), read_tail(&mut stderr), child.wait());
+        let mut output = out?;
+        output.extend_from_slice(&err?);
+        status.map(|status| (status, output))
+    })
+    .await;
+
+    let (status, output) = match result {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            kill_verification_script(&mut child).await;
+            return Err(MergeQueueFailure::from_error(e));
+        }
+        Err(_) => {
+            kill_verification_script(&mut child).await;
+            return Err(MergeQueueFailure::VerificationFailed {
+                exit_code: None,
+                output: format!(
+                    "Verification script timed out after {} minutes",
+                    VERIFICATION_TIMEOUT.as_secs() / 60
+                ),
+            });
+        }
+    };
+
+    if status.success() {
+        return Ok(());
+    }
+    Err(MergeQueueFailure::VerificationFailed {
+        exit_code: status.code(),
+        output: output_tail(&output),
+    })
+}
+
+/// Kill the script together with anything it started in the background.
+async fn kill_verification_script(child: &mut AsyncGroupChild) {
+    if let Err(e) = kill_process_group(child).await {
+        warn!("Failed to kill verification script: {}", e);
+    }
+}
+
+/// Read a stream to its end, keeping only the last [`MAX_OUTPUT_BYTES`] bytes.
+async fn read_tail(reader: &mut (impl AsyncRead + Unpin)) -> std::io::Result<Vec<u8>> {
+    let mut tail = Vec::with_capacity(2 * MAX_OUTPUT_BYTES);
+    let mut chunk = [0u8; 8192];
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(tail);
+        }
+        tail.extend_from_slice(&chunk[..n]);
+        if tail.len() > 2 * MAX_OUTPUT_BYTES {
+            tail.drain(..tail.len() - MAX_OUTPUT_BYTES);
+        }
+    }
+}
+
+fn output_tail(output: &[u8]) -> String {
+    let output = String::from_utf8_lossy(output);
+    if output.len() <= MAX_OUTPUT_BYTES {
+        return output.into_owned();
+    }
+    let mut start = output.len() - MAX_OUTPUT_BYTES;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    output[start..].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use git::GitCli;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    struct Fixture {
+        _root: TempDir,
+        repo_path: PathBuf,
+        worktree_path: PathBuf,
+    }
+
+    impl Fixture {
+        /// A repo with `main` checked out and a `vk/feature` worktree forked from it.
+        fn new() -> Self {
+            let root = TempDir::new().unwrap();
+            let repo_path = root.path().join("repo");
+            let worktree_path = root.path().join("worktree");
+            GitService::new()
+                .initialize_repo_with_main_branch(&repo_path)
+                .unwrap();
+            let git = GitCli::new();
+            git.git(&repo_path, ["config", "user.name", "Test User"])
+                .unwrap();
+            git.git(&repo_path, ["config", "user.email", "test@example.com"])
+                .unwrap();
+            git.git(&repo_path, ["checkout", "-f", "main"]).unwrap();
+            commit_file(&repo_path, "shared.txt", "base\n");
+            git.git(
+                &repo_path,
+                [
+                    "worktree",
+                    "add",
+                    "-b",
+                    "vk/feature",
+                    worktree_path.to_str().unwrap(),
+                    "main",
+                ],
+            )
+            .unwrap();
+            Self {
+                _root: root,
+                repo_path,
+                worktree_path,
+            }
+        }
+
+        async fn land(
+            &self,
+            verify: impl FnMut() -> Result<(), MergeQueueFailure>,
+        ) -> (Result<String, MergeQueueFailure>, Vec<String>) {
+            let statuses = Mutex::new(Vec::new());
+            let verify = Mutex::new(verify);
+            let result = land_branch(
+                &GitService::new(),
+                &self.repo_path,
+                &self.worktree_path,
+                "vk/feature",
+                "main",
+                "Land feature",
+                &CommitOptions::default(),
+                |status| {
+                    let status = serde_json::to_value(&status).unwrap()["status"]
+                        .as_str()
+                        .unwrap()
+                        .to_string();
+                    statuses.lock().unwrap().push(status);
+                    async {}
+                },
+                || {
+                    let result = (verify.lock().unwrap())();
+                    async move { result }
+                },
+            )
+            .await;
+            (result, statuses.into_inner().unwrap())
+        }
+    }
+
+    fn commit_file(path: &Path, file: &str, content: &str) -> String {
+        std::fs::write(path.join(file), content).unwrap();
+        let git = GitCli::new();
+        git.git(path, ["add", file]).unwrap();
+        git.git(path, ["commit", "-m", &format!("Update {file}")])
+            .unwrap();
+        head(path, "HEAD")
+    }
+
+    fn head(path: &Path, rev: &str) -> String {
+        GitCli::new()
+            .git(path, ["rev-parse", rev])
+            .unwrap()
+            .trim()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn fast_forwards_target_to_verified_commit() {
+        let fixture = Fixture::new();
+        commit_file(&fixture.worktree_path, "feature.txt", "one\n");
+        commit_file(&fixture.worktree_path, "feature.txt", "two\n");
+        let base = head(&fixture.repo_path, "main");
+
+        let (result, statuses) = fixture.land(|| Ok(())).await;
+        let merge_commit = result.unwrap();
+
+        // Squashed into one commit on top of the old target, and landed as is
+        assert_eq!(head(&fixture.repo_path, "main"), merge_commit);
+        assert_eq!(head(&fixture.repo_path, "main~1"), base);
+        assert_eq!(head(&fixture.worktree_path, "HEAD"), merge_commit);
+        assert_eq!(
+            std::fs::read_to_string(fixture.repo_path.join("feature.txt")).unwrap(),
+            "two\n"
+        );
+        assert_eq!(statuses, vec!["verifying", "merging"]);
+    }
+
+    #[tokio::test]
+    async fn reverifies_when_target_moves_during_verification() {
+        let fixture = Fixture::new();
+        let old_base = head(&fixture.repo_path, "main");
+        commit_file(&fixture.worktree_path, "feature.txt", "feature\n");
+
+        let verifications = AtomicUsize::new(0);
+        let repo_path = fixture.repo_path.clone();
+        let moved_to = Mutex::new(None);
+        let (result, statuses) = fixture
+            .land(|| {
+                // Someone lands another change while the first verification runs
+                if verifications.fetch_add(1, Ordering::SeqCst) == 0 {
+                    *moved_to.lock().unwrap() =
+                        Some(commit_file(&repo_path, "other.txt", "other\n"));
+                }
+                Ok(())
+            })
+            .await;
+        let merge_commit = result.unwrap();
+        let moved_to = moved_to.into_inner().unwrap().unwrap();
+
+        assert_eq!(verifications.load(Ordering::SeqCst), 2);
+        assert_ne!(moved_to, old_base);
+        // The commit verified second builds on the moved target and is what landed
+        assert_eq!(head(&fixture.repo_path, "main"), merge_commit);
+        assert_eq!(head(&fixture.repo_path, "main~1"), moved_to);
+        assert!(fixture.worktree_path.join("other.txt").exists());
+        assert_eq!(
+            statuses,
+            vec!["verifying", "merging", "rebasing", "verifying", "merging"]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_conflicts_without_touching_target() {
+        let fixture = Fixture::new();
+        commit_file(&fixture.worktree_path, "shared.txt", "feature\n");
+        let target = commit_file(&fixture.repo_path, "shared.txt", "main\n");
+
+        let verifications = AtomicUsize::new(0);
+        let (result, statuses) = fixture
+            .land(|| {
+                verifications.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+
+        match result {
+            Err(MergeQueueFailure::MergeConflicts {
+                conflicted_files, ..
+            }) => assert_eq!(conflicted_files, vec!["shared.txt".to_string()]),
+            other => panic!("expected merge conflicts, got {other:?}"),
+        }
+        assert_eq!(head(&fixture.repo_path, "main"), target);
+        assert_eq!(verifications.load(Ordering::SeqCst), 0);
+        assert!(statuses.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fails_verification_without_landing() {
+        let fixture = Fixture::new();
+        commit_file(&fixture.worktree_path, "feature.txt", "feature\n");
+        let base = head(&fixture.repo_path, "main");
+
+        let (result, _) = fixture
+            .land(|| {
+                Err(MergeQueueFailure::VerificationFailed {
+                    exit_code: Some(1),
+                    output: "tests failed".to_string(),
+                })
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(MergeQueueFailure::VerificationFailed { .. })
+        ));
+        assert_eq!(head(&fixture.repo_path, "main"), base);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn verification_output_keeps_only_its_tail() {
+        let dir = TempDir::new().unwrap();
+        let script = "head -c 200000 /dev/zero | tr '\\0' x; echo; echo last line >&2; exit 3";
+
+        let result = run_verification_script(script, dir.path()).await;
+
+        let Err(MergeQueueFailure::VerificationFailed { exit_code, output }) = result else {
+            panic!("expected the verification to fail");
+        };
+        assert_eq!(exit_code, Some(3));
+        assert!(output.len() <= MAX_OUTPUT_BYTES);
+        assert!(output.ends_with("last line\n"));
+    }
+}
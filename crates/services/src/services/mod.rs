@@ -1,8 +1,15 @@
 pub mod analytics;
 pub mod approvals;
 pub mod auth;
+pub mod bulk_tasks;
+pub mod commit_policy;
 pub mod config;
+pub mod conflict_resolution;
 pub mod container;
+pub mod content_search;
+pub mod context_pack;
+pub mod conversation_search;
+pub mod dev_server;
 pub mod diff_stream;
 pub mod events;
 pub mod file_ranker;
@@ -11,16 +18,25 @@ pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git_host;
 pub mod image;
+pub mod issue_import;
+pub mod merge_queue;
 pub mod migration;
 pub mod notification;
 pub mod oauth_credentials;
 pub mod pr_monitor;
+pub mod pr_stack;
 pub mod project;
 #[cfg(feature = "qa-mode")]
 pub mod qa_repos;
 pub mod queued_message;
 pub mod remote_client;
 pub mod remote_sync;
+pub mod replay_fixture;
 pub mod repo;
+pub mod secrets;
+pub mod symbol_index;
+pub mod task_template;
+pub mod visual_snapshot;
+pub mod workspace_checkpoint;
 pub mod workspace_manager;
 pub mod worktree_manager;
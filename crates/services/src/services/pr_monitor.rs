@@ -15,11 +15,13 @@ use sqlx::error::Error as SqlxError;
 use thiserror::Error;
 use tokio::time::interval;
 use tracing::{debug, error, info, warn};
+use uuid::Uuid;
 
 use crate::services::{
     analytics::AnalyticsContext,
     container::ContainerService,
     git_host::{self, GitHostError, GitHostProvider},
+    pr_stack,
     remote_client::RemoteClient,
     remote_sync,
 };
@@ -46,6 +48,7 @@ impl PrMonitorError {
 }
 
 /// Service to monitor PRs and update task status when they are merged
+#[derive(Clone)]
 pub struct PrMonitorService<C: ContainerService> {
     db: DBService,
     poll_interval: Duration,
@@ -54,20 +57,24 @@ pub struct PrMonitorService<C: ContainerService> {
     remote_client: Option<RemoteClient>,
 }
 
-impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
-    pub async fn spawn(
+impl<C: ContainerService + Clone + Send + Sync + 'static> PrMonitorService<C> {
+    pub fn new(
         db: DBService,
         analytics: Option<AnalyticsContext>,
         container: C,
         remote_client: Option<RemoteClient>,
-    ) -> tokio::task::JoinHandle<()> {
-        let service = Self {
+    ) -> Self {
+        Self {
             db,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
             container,
             remote_client,
-        };
+        }
+    }
+
+    pub fn spawn(&self) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
         tokio::spawn(async move {
             service.start().await;
         })
@@ -118,8 +125,31 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         Ok(())
     }
 
+    /// Check the open PRs of a single workspace right away instead of waiting for the
+    /// next poll, e.g. when a stacked child needs to know whether its parent landed.
+    /// Returns whether any of them was merged.
+    pub async fn check_workspace_prs(&self, workspace_id: Uuid) -> Result<bool, SqlxError> {
+        let mut merged = false;
+        for merge in Merge::find_by_workspace_id(&self.db.pool, workspace_id).await? {
+            let Merge::Pr(pr_merge) = merge else {
+                continue;
+            };
+            if !matches!(pr_merge.pr_info.status, MergeStatus::Open) {
+                continue;
+            }
+            match self.check_pr_status(&pr_merge).await {
+                Ok(status) => merged |= matches!(status, MergeStatus::Merged),
+                Err(e) => warn!(
+                    "Error checking PR #{} for workspace {}: {}",
+                    pr_merge.pr_info.number, workspace_id, e
+                ),
+            }
+        }
+        Ok(merged)
+    }
+
     /// Check the status of a specific PR
-    async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
+    async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<MergeStatus, PrMonitorError> {
         let git_host = git_host::GitHostService::from_url(&pr_merge.pr_info.url)?;
         let pr_status = git_host.get_pr_status(&pr_merge.pr_info.url).await?;
 
@@ -152,6 +182,13 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
                     pr_merge.pr_info.number, workspace.task_id
                 );
                 Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                pr_stack::retarget_after_landing(
+                    &self.db.pool,
+                    &workspace,
+                    pr_merge.repo_id,
+                    &pr_merge.target_branch_name,
+                )
+                .await;
                 if !workspace.pinned
                     && let Err(e) = self.container.archive_workspace(workspace.id).await
                 {
@@ -175,7 +212,7 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
             }
         }
 
-        Ok(())
+        Ok(pr_status.status)
     }
 
     /// Sync PR status to remote server
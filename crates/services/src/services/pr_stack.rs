@@ -0,0 +1,503 @@
+//! Stacked pull requests.
+//!
+//! A workspace whose task was created from another workspace (`parent_workspace_id`)
+//! forms a stack with it: its PR targets the parent's branch until the parent lands,
+//! after which it is retargeted onto whatever the parent merged into.
+
+use std::collections::HashSet;
+
+use db::models::{
+    merge::{Merge, MergeStatus, PullRequestInfo},
+    task::Task,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tracing::{error, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::git_host::{self, GitHostProvider};
+
+/// The workspace a stacked workspace builds on, for a single repository.
+#[derive(Debug, Clone)]
+pub struct StackParent {
+    pub workspace: Workspace,
+    /// Branch the parent itself targets for this repository
+    pub target_branch: String,
+    /// Whether the parent has already landed (direct merge or merged PR)
+    pub merged: bool,
+}
+
+/// A workspace together with its position in a stack.
+#[derive(Debug, Clone)]
+pub struct StackNode {
+    pub workspace: Workspace,
+    pub parent_workspace_id: Option<Uuid>,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct StackLayer {
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub parent_workspace_id: Option<Uuid>,
+    pub depth: usize,
+    pub branch: String,
+    /// Branch this layer's PR should target
+    pub base_branch: String,
+    pub pr: Option<PullRequestInfo>,
+    pub merged: bool,
+}
+
+fn has_landed(merges: &[Merge]) -> bool {
+    merges.iter().any(|m| match m {
+        Merge::Direct(_) => true,
+        Merge::Pr(pr) => matches!(pr.pr_info.status, MergeStatus::Merged),
+    })
+}
+
+/// Find the parent layer of `workspace` for `repo_id`, if the workspace is stacked
+/// on another workspace that also contains the repository.
+pub async fn find_stack_parent(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<Option<StackParent>, WorkspaceError> {
+    let task = Task::find_by_id(pool, workspace.task_id)
+        .await?
+        .ok_or(WorkspaceError::TaskNotFound)?;
+    let Some(parent_workspace_id) = task.parent_workspace_id else {
+        return Ok(None);
+    };
+    let Some(parent) = Workspace::find_by_id(pool, parent_workspace_id).await? else {
+        return Ok(None);
+    };
+    let Some(parent_repo) =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, parent.id, repo_id).await?
+    else {
+        return Ok(None);
+    };
+
+    let merges = Merge::find_by_workspace_and_repo_id(pool, parent.id, repo_id).await?;
+
+    Ok(Some(StackParent {
+        workspace: parent,
+        target_branch: parent_repo.target_branch,
+        merged: has_landed(&merges),
+    }))
+}
+
+/// Branch a stacked workspace should target: the parent's branch while the parent is
+/// unmerged, the parent's own target once it has landed, otherwise the configured target.
+pub fn stack_base_branch(parent: Option<&StackParent>, workspace_repo: &WorkspaceRepo) -> String {
+    match parent {
+        Some(parent) if !parent.merged => parent.workspace.branch.clone(),
+        Some(parent) if workspace_repo.target_branch == parent.workspace.branch => {
+            parent.target_branch.clone()
+        }
+        _ => workspace_repo.target_branch.clone(),
+    }
+}
+
+pub async fn resolve_base_branch(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    workspace_repo: &WorkspaceRepo,
+) -> Result<String, WorkspaceError> {
+    let parent = find_stack_parent(pool, workspace, workspace_repo.repo_id).await?;
+    Ok(stack_base_branch(parent.as_ref(), workspace_repo))
+}
+
+/// Workspaces stacked directly on `workspace` that also contain `repo_id`, oldest first.
+async fn find_stack_children(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<Vec<Workspace>, WorkspaceError> {
+    let mut children = Vec::new();
+    for task in Task::find_children_by_workspace_id(pool, workspace.id).await? {
+        for child in Workspace::fetch_all(pool, Some(task.id)).await? {
+            if WorkspaceRepo::find_by_workspace_and_repo_id(pool, child.id, repo_id)
+                .await?
+                .is_some()
+            {
+                children.push(child);
+            }
+        }
+    }
+    children.sort_by_key(|w| w.created_at);
+    Ok(children)
+}
+
+/// All layers stacked on top of `workspace` (excluding it), parents before children.
+pub async fn collect_descendants(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<Vec<StackNode>, WorkspaceError> {
+    let mut visited = HashSet::from([workspace.id]);
+    let mut nodes = Vec::new();
+    let mut pending = vec![(workspace.clone(), 0usize)];
+
+    while let Some((parent, depth)) = pending.pop() {
+        let children = find_stack_children(pool, &parent, repo_id).await?;
+        // Reverse so the oldest child is visited first
+        for child in children.into_iter().rev() {
+            if visited.insert(child.id) {
+                pending.push((child, depth + 1));
+            }
+        }
+        if parent.id != workspace.id {
+            let parent_workspace_id = Task::find_by_id(pool, parent.task_id)
+                .await?
+                .and_then(|t| t.parent_workspace_id);
+            nodes.push(StackNode {
+                workspace: parent,
+                parent_workspace_id,
+                depth,
+            });
+        }
+    }
+
+    Ok(nodes)
+}
+
+/// Load the whole stack `workspace` belongs to, from the bottom-most layer upwards.
+pub async fn load_stack(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<Vec<StackLayer>, WorkspaceError> {
+    let mut root = workspace.clone();
+    let mut visited = HashSet::from([root.id]);
+    while let Some(parent) = find_stack_parent(pool, &root, repo_id).await? {
+        if !visited.insert(parent.workspace.id) {
+            break;
+        }
+        root = parent.workspace;
+    }
+
+    let mut nodes = vec![StackNode {
+        workspace: root.clone(),
+        parent_workspace_id: None,
+        depth: 0,
+    }];
+    nodes.extend(collect_descendants(pool, &root, repo_id).await?);
+
+    let mut layers = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let Some(workspace_repo) =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, node.workspace.id, repo_id).await?
+        else {
+            continue;
+        };
+        let task = Task::find_by_id(pool, node.workspace.task_id)
+            .await?
+            .ok_or(WorkspaceError::TaskNotFound)?;
+        let parent = find_stack_parent(pool, &node.workspace, repo_id).await?;
+        let merges = Merge::find_by_workspace_and_repo_id(pool, node.workspace.id, repo_id).await?;
+        let pr = merges.iter().find_map(|m| match m {
+            Merge::Pr(pr) => Some(pr.pr_info.clone()),
+            Merge::Direct(_) => None,
+        });
+
+        layers.push(StackLayer {
+            workspace_id: node.workspace.id,
+            task_id: task.id,
+            task_title: task.title,
+            parent_workspace_id: parent.as_ref().map(|p| p.workspace.id),
+            depth: node.depth,
+            branch: node.workspace.branch.clone(),
+            base_branch: stack_base_branch(parent.as_ref(), &workspace_repo),
+            pr,
+            merged: has_landed(&merges),
+        });
+    }
+
+    Ok(layers)
+}
+
+/// Retarget the layers stacked on `workspace` right after it lands, instead of waiting for
+/// the PR monitor to notice. Failures are logged.
+pub async fn retarget_after_landing(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    repo_id: Uuid,
+    new_base: &str,
+) {
+    if let Err(e) = retarget_children_after_merge(pool, workspace, repo_id, new_base).await {
+        error!(
+            "Failed to retarget stacked workspaces of {}: {}",
+            workspace.id, e
+        );
+    }
+}
+
+/// The layers below `workspace` that have not landed yet, nearest first.
+pub async fn unlanded_ancestors(
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<Vec<Workspace>, WorkspaceError> {
+    let mut ancestors = Vec::new();
+    let mut current = workspace.clone();
+    let mut visited = HashSet::from([current.id]);
+    while let Some(parent) = find_stack_parent(pool, &current, repo_id).await? {
+        if !visited.insert(parent.workspace.id) {
+            break;
+        }
+        if !parent.merged {
+            ancestors.push(parent.workspace.clone());
+        }
+        current = parent.workspace;
+    }
+    Ok(ancestors)
+}
+
+/// Point the layers stacked on `parent` at `new_base` once `parent` has landed,
+/// retargeting any open PRs on the git host. Returns the number of retargeted PRs.
+pub async fn retarget_children_after_merge(
+    pool: &SqlitePool,
+    parent: &Workspace,
+    repo_id: Uuid,
+    new_base: &str,
+) -> Result<usize, WorkspaceError> {
+    let updated = WorkspaceRepo::update_target_branch_for_children_of_workspace(
+        pool,
+        parent.id,
+        &parent.branch,
+        new_base,
+    )
+    .await?;
+    if updated > 0 {
+        info!(
+            "Retargeted {} stacked workspace repos from '{}' onto '{}'",
+            updated, parent.branch, new_base
+        );
+    }
+
+    let mut retargeted = 0;
+    for child in find_stack_children(pool, parent, repo_id).await? {
+        for merge in Merge::find_by_workspace_and_repo_id(pool, child.id, repo_id).await? {
+            let Merge::Pr(pr) = merge else {
+                continue;
+            };
+            if !matches!(pr.pr_info.status, MergeStatus::Open)
+                || pr.target_branch_name != parent.branch
+            {
+                continue;
+            }
+
+            let result = match git_host::GitHostService::from_url(&pr.pr_info.url) {
+                Ok(git_host) => git_host.update_pr_base(&pr.pr_info.url, new_base).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => {
+                    Merge::update_target_branch_name(pool, pr.id, new_base).await?;
+                    retargeted += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to retarget stacked PR #{} for workspace {} onto '{}': {}",
+                        pr.pr_info.number, child.id, new_base, e
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(retargeted)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use db::{
+        DBService,
+        models::{repo::Repo, task::CreateTask, workspace_repo::CreateWorkspaceRepo},
+        test_utils::{create_project, create_workspace},
+    };
+
+    use super::*;
+
+    struct Fixture {
+        db: DBService,
+        project_id: Uuid,
+        repo_id: Uuid,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let db = DBService::new_in_memory().await.unwrap();
+            let project = create_project(&db.pool, "stack").await;
+            let repo = Repo::find_or_create(&db.pool, Path::new("/tmp/stack-repo"), "repo")
+                .await
+                .unwrap();
+            Self {
+                db,
+                project_id: project.id,
+                repo_id: repo.id,
+            }
+        }
+
+        /// Create a task stacked on `parent` (if any) with one workspace targeting
+        /// the parent's branch, or `main` for the root.
+        async fn layer(&self, name: &str, parent: Option<&Workspace>) -> Workspace {
+            let pool = &self.db.pool;
+            let mut create_task =
+                CreateTask::from_title_description(self.project_id, name.to_string(), None);
+            create_task.parent_workspace_id = parent.map(|p| p.id);
+            let task = Task::create(pool, &create_task, Uuid::new_v4())
+                .await
+                .unwrap();
+            let workspace = create_workspace(pool, task.id, &format!("vk/{name}")).await;
+            WorkspaceRepo::create_many(
+                pool,
+                workspace.id,
+                &[CreateWorkspaceRepo {
+                    repo_id: self.repo_id,
+                    target_branch: parent.map_or("main".to_string(), |p| p.branch.clone()),
+                }],
+            )
+            .await
+            .unwrap();
+            // Timestamps have millisecond resolution and order siblings
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            workspace
+        }
+
+        async fn target_branch(&self, workspace: &Workspace) -> String {
+            WorkspaceRepo::find_by_workspace_and_repo_id(&self.db.pool, workspace.id, self.repo_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .target_branch
+        }
+    }
+
+    #[tokio::test]
+    async fn loads_stack_parents_before_children() {
+        let fixture = Fixture::new().await;
+        let root = fixture.layer("root", None).await;
+        let first = fixture.layer("first", Some(&root)).await;
+        let second = fixture.layer("second", Some(&root)).await;
+        let nested = fixture.layer("nested", Some(&first)).await;
+
+        // The whole stack is loaded from any layer
+        let layers = load_stack(&fixture.db.pool, &nested, fixture.repo_id)
+            .await
+            .unwrap();
+        let order: Vec<_> = layers.iter().map(|l| (l.workspace_id, l.depth)).collect();
+        assert_eq!(
+            order,
+            vec![(root.id, 0), (first.id, 1), (nested.id, 2), (second.id, 1)]
+        );
+
+        assert_eq!(layers[0].parent_workspace_id, None);
+        assert_eq!(layers[0].base_branch, "main");
+        assert_eq!(layers[2].parent_workspace_id, Some(first.id));
+        assert_eq!(layers[2].base_branch, first.branch);
+        assert!(layers.iter().all(|l| !l.merged));
+
+        let ancestors = unlanded_ancestors(&fixture.db.pool, &nested, fixture.repo_id)
+            .await
+            .unwrap();
+        let ancestor_ids: Vec<_> = ancestors.iter().map(|w| w.id).collect();
+        assert_eq!(ancestor_ids, vec![first.id, root.id]);
+    }
+
+    #[tokio::test]
+    async fn retargets_children_when_parent_lands() {
+        let fixture = Fixture::new().await;
+        let pool = &fixture.db.pool;
+        let root = fixture.layer("root", None).await;
+        let first = fixture.layer("first", Some(&root)).await;
+        let second = fixture.layer("second", Some(&root)).await;
+        let nested = fixture.layer("nested", Some(&first)).await;
+
+        Merge::create_direct(pool, root.id, fixture.repo_id, "main", "abc123")
+            .await
+            .unwrap();
+        let retargeted = retarget_children_after_merge(pool, &root, fixture.repo_id, "main")
+            .await
+            .unwrap();
+        // No open PRs to retarget on the host
+        assert_eq!(retargeted, 0);
+
+        assert_eq!(fixture.target_branch(&first).await, "main");
+        assert_eq!(fixture.target_branch(&second).await, "main");
+        // Layers further up keep building on their own unmerged parent
+        assert_eq!(fixture.target_branch(&nested).await, first.branch);
+
+        let first_repo =
+            WorkspaceRepo::find_by_workspace_and_repo_id(pool, first.id, fixture.repo_id)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(
+            resolve_base_branch(pool, &first, &first_repo)
+                .await
+                .unwrap(),
+            "main"
+        );
+
+        let layers = load_stack(pool, &first, fixture.repo_id).await.unwrap();
+        assert!(layers[0].merged);
+        assert_eq!(
+            unlanded_ancestors(pool, &nested, fixture.repo_id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn base_branch_follows_parent_until_it_lands() {
+        let workspace_repo = |target: &str| WorkspaceRepo {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            target_branch: target.to_string(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let parent = |merged: bool| StackParent {
+            workspace: Workspace {
+                id: Uuid::new_v4(),
+                task_id: Uuid::new_v4(),
+                container_ref: None,
+                branch: "vk/parent".to_string(),
+                agent_working_dir: None,
+                setup_completed_at: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                archived: false,
+                pinned: false,
+                name: None,
+            },
+            target_branch: "main".to_string(),
+            merged,
+        };
+
+        assert_eq!(
+            stack_base_branch(Some(&parent(false)), &workspace_repo("vk/parent")),
+            "vk/parent"
+        );
+        assert_eq!(
+            stack_base_branch(Some(&parent(true)), &workspace_repo("vk/parent")),
+            "main"
+        );
+        // Already retargeted elsewhere by hand
+        assert_eq!(
+            stack_base_branch(Some(&parent(true)), &workspace_repo("develop")),
+            "develop"
+        );
+        assert_eq!(stack_base_branch(None, &workspace_repo("main")), "main");
+    }
+}
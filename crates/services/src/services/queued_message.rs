@@ -1,21 +1,25 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
 
-use chrono::{DateTime, Utc};
-use dashmap::DashMap;
-use db::models::scratch::DraftFollowUpData;
+use db::{
+    DBService,
+    models::queued_message::{QueuePause, QueuedMessage},
+};
+use executors::profile::ExecutorConfig;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::OwnedMutexGuard;
 use ts_rs::TS;
 use uuid::Uuid;
 
-/// Represents a queued follow-up message for a session
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-pub struct QueuedMessage {
-    /// The session this message is queued for
-    pub session_id: Uuid,
-    /// The follow-up data (message + variant)
-    pub data: DraftFollowUpData,
-    /// Timestamp when the message was queued
-    pub queued_at: DateTime<Utc>,
+#[derive(Debug, Error)]
+pub enum QueuedMessageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("New order must list every queued message of the session exactly once")]
+    InvalidOrder,
 }
 
 /// Status of the queue for a session (for frontend display)
@@ -24,67 +28,303 @@ pub struct QueuedMessage {
 pub enum QueueStatus {
     /// No message queued
     Empty,
-    /// Message is queued and waiting for execution to complete
-    Queued { message: QueuedMessage },
+    /// Messages are queued and waiting for execution to complete
+    Queued {
+        /// Queued messages in the order they will run
+        messages: Vec<QueuedMessage>,
+        /// Set when the queue stopped draining after a failed or stopped execution
+        paused: Option<QueuePause>,
+    },
 }
 
-/// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+/// Persisted, ordered queue of follow-up messages per session.
+/// Messages are drained one at a time as executions finish; a failed or stopped
+/// execution pauses the queue until it is resumed.
 #[derive(Clone)]
 pub struct QueuedMessageService {
-    queue: Arc<DashMap<Uuid, QueuedMessage>>,
+    db: DBService,
+    drain_locks: Arc<Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>>>,
 }
 
 impl QueuedMessageService {
-    pub fn new() -> Self {
+    pub fn new(db: DBService) -> Self {
         Self {
-            queue: Arc::new(DashMap::new()),
+            db,
+            drain_locks: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
-    /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
-        let queued = QueuedMessage {
+    /// Append a message to the end of a session's queue.
+    pub async fn queue_message(
+        &self,
+        session_id: Uuid,
+        message: &str,
+        executor_config: Option<&ExecutorConfig>,
+    ) -> Result<QueuedMessage, QueuedMessageError> {
+        Ok(QueuedMessage::create(
+            &self.db.pool,
+            Uuid::new_v4(),
             session_id,
-            data,
-            queued_at: Utc::now(),
-        };
-        self.queue.insert(session_id, queued.clone());
-        queued
+            message,
+            executor_config,
+        )
+        .await?)
+    }
+
+    pub async fn update_message(
+        &self,
+        message_id: Uuid,
+        message: &str,
+        executor_config: Option<&ExecutorConfig>,
+    ) -> Result<QueuedMessage, QueuedMessageError> {
+        Ok(QueuedMessage::update(&self.db.pool, message_id, message, executor_config).await?)
+    }
+
+    /// Remove one queued message, clearing the pause once the queue is empty.
+    pub async fn remove_message(&self, queued: &QueuedMessage) -> Result<(), QueuedMessageError> {
+        QueuedMessage::delete(&self.db.pool, queued.id).await?;
+        if !self.has_queued(queued.session_id).await? {
+            QueuePause::delete(&self.db.pool, queued.session_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Set the order of a session's queue. `ordered_ids` must contain every queued
+    /// message of the session exactly once.
+    pub async fn reorder(
+        &self,
+        session_id: Uuid,
+        ordered_ids: &[Uuid],
+    ) -> Result<(), QueuedMessageError> {
+        let queued: HashSet<Uuid> = QueuedMessage::find_by_session_id(&self.db.pool, session_id)
+            .await?
+            .into_iter()
+            .map(|message| message.id)
+            .collect();
+        let requested: HashSet<Uuid> = ordered_ids.iter().copied().collect();
+        if requested.len() != ordered_ids.len() || requested != queued {
+            return Err(QueuedMessageError::InvalidOrder);
+        }
+        QueuedMessage::reorder(&self.db.pool, session_id, ordered_ids).await?;
+        Ok(())
     }
 
-    /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Remove every queued message of a session and clear its pause.
+    pub async fn clear(&self, session_id: Uuid) -> Result<(), QueuedMessageError> {
+        QueuedMessage::delete_by_session_id(&self.db.pool, session_id).await?;
+        QueuePause::delete(&self.db.pool, session_id).await?;
+        Ok(())
     }
 
-    /// Get the queued message for a session (if any)
-    pub fn get_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.get(&session_id).map(|r| r.clone())
+    /// Stop draining a session's queue. Does nothing when nothing is queued.
+    pub async fn pause(&self, session_id: Uuid, reason: &str) -> Result<(), QueuedMessageError> {
+        if self.has_queued(session_id).await? {
+            QueuePause::upsert(&self.db.pool, session_id, reason).await?;
+        }
+        Ok(())
     }
 
-    /// Take (remove and return) the queued message for a session.
-    /// Used by finalization flow to consume the queued message.
-    pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    pub async fn resume(&self, session_id: Uuid) -> Result<(), QueuedMessageError> {
+        QueuePause::delete(&self.db.pool, session_id).await?;
+        Ok(())
     }
 
-    /// Check if a session has a queued message
-    pub fn has_queued(&self, session_id: Uuid) -> bool {
-        self.queue.contains_key(&session_id)
+    /// Wait until no one else is draining the session's queue. Whoever holds the guard
+    /// decides whether the next message runs, so two exits or resumes can't both start one.
+    pub async fn lock_drain(&self, session_id: Uuid) -> OwnedMutexGuard<()> {
+        let lock = self
+            .drain_locks
+            .lock()
+            .unwrap()
+            .entry(session_id)
+            .or_default()
+            .clone();
+        lock.lock_owned().await
+    }
+
+    /// Take the message that should run next off the queue, or `None` when the queue is
+    /// empty or paused. A message whose follow-up then fails to start is put back with
+    /// [`Self::restore_message`].
+    pub async fn take_next_message(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, QueuedMessageError> {
+        Ok(QueuedMessage::take_next(&self.db.pool, session_id).await?)
+    }
+
+    pub async fn restore_message(&self, queued: &QueuedMessage) -> Result<(), QueuedMessageError> {
+        QueuedMessage::restore(&self.db.pool, queued).await?;
+        Ok(())
+    }
+
+    /// Check if a session has any queued messages
+    pub async fn has_queued(&self, session_id: Uuid) -> Result<bool, QueuedMessageError> {
+        Ok(QueuedMessage::exists_for_session(&self.db.pool, session_id).await?)
     }
 
     /// Get queue status for frontend display
-    pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
-        match self.get_queued(session_id) {
-            Some(msg) => QueueStatus::Queued { message: msg },
-            None => QueueStatus::Empty,
+    pub async fn get_status(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        let messages = QueuedMessage::find_by_session_id(&self.db.pool, session_id).await?;
+        if messages.is_empty() {
+            return Ok(QueueStatus::Empty);
         }
+        let paused = QueuePause::find_by_session_id(&self.db.pool, session_id).await?;
+        Ok(QueueStatus::Queued { messages, paused })
     }
 }
 
-impl Default for QueuedMessageService {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use db::test_utils::create_session_chain;
+
+    use super::*;
+
+    async fn queue_with_session() -> (QueuedMessageService, Uuid) {
+        let db = DBService::new_in_memory().await.unwrap();
+        let chain = create_session_chain(&db.pool, "queue").await;
+        (QueuedMessageService::new(db), chain.session.id)
+    }
+
+    /// Drain the queue the way the container does, one message at a time.
+    async fn drain(queue: &QueuedMessageService, session_id: Uuid) -> Vec<String> {
+        let mut consumed = Vec::new();
+        while let Some(next) = queue.take_next_message(session_id).await.unwrap() {
+            consumed.push(next.message);
+        }
+        consumed
+    }
+
+    #[tokio::test]
+    async fn consumes_messages_in_enqueue_order() {
+        let (queue, session_id) = queue_with_session().await;
+        for message in ["add tests", "update docs", "run clippy"] {
+            queue
+                .queue_message(session_id, message, None)
+                .await
+                .unwrap();
+        }
+
+        let QueueStatus::Queued { messages, paused } = queue.get_status(session_id).await.unwrap()
+        else {
+            panic!("expected a queued status");
+        };
+        assert!(paused.is_none());
+        assert_eq!(
+            messages.iter().map(|m| m.position).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+
+        assert_eq!(
+            drain(&queue, session_id).await,
+            vec!["add tests", "update docs", "run clippy"]
+        );
+        assert!(matches!(
+            queue.get_status(session_id).await.unwrap(),
+            QueueStatus::Empty
+        ));
+    }
+
+    #[tokio::test]
+    async fn reorder_and_edit_change_what_runs_next() {
+        let (queue, session_id) = queue_with_session().await;
+        let first = queue
+            .queue_message(session_id, "first", None)
+            .await
+            .unwrap();
+        let second = queue
+            .queue_message(session_id, "second", None)
+            .await
+            .unwrap();
+        let third = queue
+            .queue_message(session_id, "third", None)
+            .await
+            .unwrap();
+
+        // A new order has to list every queued message exactly once
+        assert!(matches!(
+            queue.reorder(session_id, &[third.id, first.id]).await,
+            Err(QueuedMessageError::InvalidOrder)
+        ));
+        assert!(matches!(
+            queue
+                .reorder(session_id, &[third.id, third.id, first.id])
+                .await,
+            Err(QueuedMessageError::InvalidOrder)
+        ));
+
+        queue
+            .reorder(session_id, &[third.id, first.id, second.id])
+            .await
+            .unwrap();
+        queue
+            .update_message(first.id, "first, edited", None)
+            .await
+            .unwrap();
+        queue.remove_message(&second).await.unwrap();
+
+        assert_eq!(
+            drain(&queue, session_id).await,
+            vec!["third", "first, edited"]
+        );
+    }
+
+    #[tokio::test]
+    async fn paused_queue_holds_messages_until_resumed() {
+        let (queue, session_id) = queue_with_session().await;
+
+        // Nothing to hold back: pausing an empty queue is a no-op
+        queue.pause(session_id, "failed").await.unwrap();
+        queue
+            .queue_message(session_id, "first", None)
+            .await
+            .unwrap();
+        assert!(queue.has_queued(session_id).await.unwrap());
+
+        queue.pause(session_id, "Execution failed").await.unwrap();
+        assert!(queue.take_next_message(session_id).await.unwrap().is_none());
+        let QueueStatus::Queued { paused, .. } = queue.get_status(session_id).await.unwrap() else {
+            panic!("expected a queued status");
+        };
+        assert_eq!(paused.unwrap().reason, "Execution failed");
+
+        // Queueing more does not resume on its own
+        queue
+            .queue_message(session_id, "second", None)
+            .await
+            .unwrap();
+        assert!(queue.take_next_message(session_id).await.unwrap().is_none());
+
+        queue.resume(session_id).await.unwrap();
+        assert_eq!(drain(&queue, session_id).await, vec!["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn messages_are_taken_once_and_can_be_put_back() {
+        let (queue, session_id) = queue_with_session().await;
+        for message in ["first", "second"] {
+            queue
+                .queue_message(session_id, message, None)
+                .await
+                .unwrap();
+        }
+
+        // Drains racing each other never get the same message
+        let taken = futures::future::join_all((0..4).map(|_| queue.take_next_message(session_id)))
+            .await
+            .into_iter()
+            .filter_map(|taken| taken.unwrap())
+            .collect::<Vec<_>>();
+        let mut messages: Vec<_> = taken.iter().map(|m| m.message.as_str()).collect();
+        messages.sort();
+        assert_eq!(messages, vec!["first", "second"]);
+
+        // A message whose follow-up failed to start goes back to the front
+        let first = taken.iter().find(|m| m.message == "first").unwrap();
+        queue
+            .queue_message(session_id, "third", None)
+            .await
+            .unwrap();
+        queue.restore_message(first).await.unwrap();
+        assert_eq!(drain(&queue, session_id).await, vec!["first", "third"]);
     }
 }
@@ -0,0 +1,271 @@
+//! Recording replay fixtures from finished executions.
+//!
+//! A fixture bundles the raw output an agent produced with the file edits it made, so
+//! the run can be played back by [`executors::executors::replay::Replay`]
+//! without the vendor CLI installed.
+
+use db::models::{
+    execution_process::ExecutionProcess, execution_process_logs::ExecutionProcessLogs,
+    execution_process_repo_state::ExecutionProcessRepoState, repo::Repo,
+};
+use executors::{
+    actions::ExecutorActionType,
+    executors::replay::{ReplayFileChange, ReplayFixture, ReplayLogEntry, ReplayStream},
+};
+use git::{DiffTarget, GitService, GitServiceError};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tracing::warn;
+use utils::{diff::DiffChangeKind, log_msg::LogMsg};
+
+#[derive(Debug, Error)]
+pub enum ReplayFixtureError {
+    #[error("Only coding agent executions can be recorded as fixtures")]
+    NotCodingAgent,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error("Failed to parse stored logs: {0}")]
+    Logs(#[from] serde_json::Error),
+}
+
+pub async fn record_fixture(
+    pool: &SqlitePool,
+    git: &GitService,
+    process: &ExecutionProcess,
+) -> Result<ReplayFixture, ReplayFixtureError> {
+    let executor_config = match process.executor_action().map(|action| action.typ()) {
+        Ok(ExecutorActionType::CodingAgentInitialRequest(request)) => {
+            request.executor_config.clone()
+        }
+        Ok(ExecutorActionType::CodingAgentFollowUpRequest(request)) => {
+            request.executor_config.clone()
+        }
+        Ok(ExecutorActionType::ReviewRequest(request)) => request.executor_config.clone(),
+        _ => return Err(ReplayFixtureError::NotCodingAgent),
+    };
+
+    let records = ExecutionProcessLogs::find_by_execution_id(pool, process.id).await?;
+    let started_at = records.first().map(|record| record.inserted_at);
+    let mut logs = Vec::new();
+    for record in &records {
+        let offset_ms = started_at
+            .map(|start| (record.inserted_at - start).num_milliseconds().max(0) as u64)
+            .unwrap_or_default();
+        for msg in ExecutionProcessLogs::parse_logs(std::slice::from_ref(record))? {
+            let (stream, content) = match msg {
+                LogMsg::Stdout(content) => (ReplayStream::Stdout, content),
+                LogMsg::Stderr(content) => (ReplayStream::Stderr, content),
+                _ => continue,
+            };
+            logs.push(ReplayLogEntry {
+                offset_ms,
+                stream,
+                content,
+            });
+        }
+    }
+
+    let mut file_changes = Vec::new();
+    for state in ExecutionProcessRepoState::find_by_execution_process_id(pool, process.id).await? {
+        let (Some(before), Some(after)) = (&state.before_head_commit, &state.after_head_commit)
+        else {
+            continue;
+        };
+        if before == after {
+            continue;
+        }
+        let Some(repo) = Repo::find_by_id(pool, state.repo_id).await? else {
+            continue;
+        };
+        let diffs = git.get_diffs(
+            DiffTarget::Range {
+                repo_path: &repo.path,
+                from_commit: before,
+                to_commit: after,
+            },
+            None,
+        )?;
+
+        for diff in diffs {
+            if matches!(diff.change, DiffChangeKind::Renamed)
+                && let Some(old_path) = diff.old_path.clone()
+            {
+                file_changes.push(ReplayFileChange {
+                    repo: repo.name.clone(),
+                    path: old_path,
+                    content: None,
+                });
+            }
+            let Some(path) = diff.new_path.clone().or(diff.old_path.clone()) else {
+                continue;
+            };
+            let content = match diff.change {
+                DiffChangeKind::Deleted => None,
+                _ if diff.content_omitted || diff.new_content.is_none() => {
+                    warn!(
+                        "Leaving {}/{} out of the fixture: content is binary or too large",
+                        repo.name, path
+                    );
+                    continue;
+                }
+                _ => diff.new_content,
+            };
+            file_changes.push(ReplayFileChange {
+                repo: repo.name.clone(),
+                path,
+                content,
+            });
+        }
+    }
+
+    Ok(ReplayFixture {
+        executor_config,
+        logs,
+        file_changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use db::{
+        DBService,
+        models::{
+            execution_process::ExecutionProcessRunReason,
+            execution_process_repo_state::CreateExecutionProcessRepoState,
+        },
+        test_utils::{coding_agent_action, create_execution_process, create_session_chain},
+    };
+    use executors::{
+        env::{ExecutionEnv, RepoContext},
+        executors::{StandardCodingAgentExecutor, replay::Replay},
+    };
+    use git::GitCli;
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+
+    fn git(path: &Path, args: &[&str]) -> String {
+        GitCli::new().git(path, args).unwrap().trim().to_string()
+    }
+
+    fn commit_all(path: &Path, message: &str) -> String {
+        git(path, &["add", "-A"]);
+        git(path, &["commit", "-q", "-m", message]);
+        git(path, &["rev-parse", "HEAD"])
+    }
+
+    /// Create an execution that changed `repo` from `before` to `after` and printed to
+    /// both streams.
+    async fn recorded_execution(
+        pool: &SqlitePool,
+        repo: &Repo,
+        before: &str,
+        after: &str,
+    ) -> ExecutionProcess {
+        let chain = create_session_chain(pool, "replay").await;
+        let process = create_execution_process(
+            pool,
+            chain.session.id,
+            coding_agent_action("edit the readme"),
+            ExecutionProcessRunReason::CodingAgent,
+            &[CreateExecutionProcessRepoState {
+                repo_id: repo.id,
+                before_head_commit: Some(before.to_string()),
+                after_head_commit: Some(after.to_string()),
+                merge_commit: None,
+            }],
+        )
+        .await;
+
+        for msg in [
+            LogMsg::Stdout("{\"type\":\"system\"}\n".to_string()),
+            LogMsg::Stderr("warning: slow\n".to_string()),
+            LogMsg::Stdout("{\"type\":\"result\"}\n".to_string()),
+        ] {
+            let line = format!("{}\n", serde_json::to_string(&msg).unwrap());
+            ExecutionProcessLogs::append_log_line(pool, process.id, &line)
+                .await
+                .unwrap();
+        }
+        process
+    }
+
+    #[tokio::test]
+    async fn exported_fixture_replays_output_and_file_changes() {
+        let root = tempfile::tempdir().unwrap();
+        let repo_path = root.path().join("source");
+        std::fs::create_dir_all(&repo_path).unwrap();
+        git(&repo_path, &["init", "-q", "-b", "main"]);
+        git(&repo_path, &["config", "user.name", "Test"]);
+        git(&repo_path, &["config", "user.email", "test@example.com"]);
+        std::fs::write(repo_path.join("README.md"), "old\n").unwrap();
+        std::fs::write(repo_path.join("obsolete.txt"), "remove me\n").unwrap();
+        let before = commit_all(&repo_path, "initial");
+        std::fs::write(repo_path.join("README.md"), "new\n").unwrap();
+        std::fs::create_dir_all(repo_path.join("src")).unwrap();
+        std::fs::write(repo_path.join("src/lib.rs"), "pub fn added() {}\n").unwrap();
+        std::fs::remove_file(repo_path.join("obsolete.txt")).unwrap();
+        let after = commit_all(&repo_path, "agent changes");
+
+        let db = DBService::new_in_memory().await.unwrap();
+        let repo = Repo::find_or_create(&db.pool, &repo_path, "app")
+            .await
+            .unwrap();
+        let process = recorded_execution(&db.pool, &repo, &before, &after).await;
+
+        // Export the fixture the way `GET /execution-processes/{id}/fixture` does
+        let fixture = record_fixture(&db.pool, &GitService::new(), &process)
+            .await
+            .unwrap();
+        let fixture_path = root.path().join("fixture.json");
+        std::fs::write(&fixture_path, serde_json::to_string(&fixture).unwrap()).unwrap();
+
+        // A workspace checked out at the state before the recorded run
+        let workspace_root = root.path().join("workspace");
+        let worktree = workspace_root.join(&repo.name);
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(worktree.join("README.md"), "old\n").unwrap();
+        std::fs::write(worktree.join("obsolete.txt"), "remove me\n").unwrap();
+
+        let replay = Replay {
+            fixture_path: fixture_path.to_string_lossy().to_string(),
+            speed: Some(0.0),
+        };
+        let env = ExecutionEnv::new(
+            RepoContext::new(workspace_root.clone(), vec![repo.name.clone()]),
+            false,
+            String::new(),
+        );
+        let mut spawned = replay.spawn(&workspace_root, "", &env).await.unwrap();
+        let mut stdout = spawned.child.inner().stdout.take().unwrap();
+        let mut stderr = spawned.child.inner().stderr.take().unwrap();
+        let (mut out, mut err) = (String::new(), String::new());
+        let (out_read, err_read) = tokio::join!(
+            stdout.read_to_string(&mut out),
+            stderr.read_to_string(&mut err)
+        );
+        out_read.unwrap();
+        err_read.unwrap();
+        let exit = spawned.exit_signal.take().unwrap().await.unwrap();
+
+        assert!(matches!(
+            exit,
+            executors::executors::ExecutorExitResult::Success
+        ));
+        assert_eq!(out, "{\"type\":\"system\"}\n{\"type\":\"result\"}\n");
+        assert_eq!(err, "warning: slow\n");
+        assert_eq!(
+            std::fs::read_to_string(worktree.join("README.md")).unwrap(),
+            "new\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(worktree.join("src/lib.rs")).unwrap(),
+            "pub fn added() {}\n"
+        );
+        assert!(!worktree.join("obsolete.txt").exists());
+    }
+}
@@ -0,0 +1,333 @@
+//! Encrypted store of secret environment variables.
+//!
+//! Secrets are scoped to a project, a repo or a coding agent and injected into the
+//! environment of matching processes when they spawn. Values are encrypted with
+//! AES-256-GCM under a local master key, kept in the OS keyring or, where there is none,
+//! in a key file next to the database. The API only ever lists secret names. Other
+//! credentials kept in the database, like issue tracker tokens, are sealed with the same
+//! key.
+
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::Path,
+    sync::{Arc, LazyLock},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use db::models::{
+    issue_import::IssueImportSource,
+    secret::{Secret, SecretScope, UpsertSecret},
+};
+use executors::executors::BaseCodingAgent;
+use regex::Regex;
+use ring::{
+    aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey},
+    rand::{SecureRandom, SystemRandom},
+};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+use utils::assets::secrets_key_path;
+use uuid::Uuid;
+
+const KEYRING_SERVICE: &str = if cfg!(debug_assertions) {
+    "vibe-kanban-dev"
+} else {
+    "vibe-kanban"
+};
+const KEYRING_USER: &str = "secrets-key";
+const KEY_LEN: usize = 32;
+
+static SECRET_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap());
+
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid secret name '{0}': use letters, digits and underscores")]
+    InvalidName(String),
+    #[error("A secret must apply to exactly one project, repo or coding agent")]
+    InvalidScope,
+    #[error("Secret not found")]
+    NotFound,
+    #[error("Secrets key unavailable: {0}")]
+    Key(String),
+    #[error("Failed to encrypt secret")]
+    Encrypt,
+    #[error("Failed to decrypt secret")]
+    Decrypt,
+}
+
+#[derive(Clone)]
+pub struct SecretService {
+    pool: SqlitePool,
+    key: Arc<OnceCell<[u8; KEY_LEN]>>,
+}
+
+impl SecretService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            key: Arc::new(OnceCell::new()),
+        }
+    }
+
+    pub async fn list(&self, scope: &SecretScope) -> Result<Vec<Secret>, SecretError> {
+        validate_scope(scope)?;
+        Ok(Secret::find_by_scope(&self.pool, scope).await?)
+    }
+
+    /// Encrypt and store a secret, replacing the one of the same name in its scope.
+    pub async fn set(&self, data: &UpsertSecret) -> Result<Secret, SecretError> {
+        validate_scope(&data.scope)?;
+        if !SECRET_NAME.is_match(&data.name) {
+            return Err(SecretError::InvalidName(data.name.clone()));
+        }
+        let ciphertext = encrypt(self.key().await?, &data.value)?;
+        Ok(Secret::upsert(&self.pool, &data.scope, &data.name, &ciphertext).await?)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), SecretError> {
+        if Secret::delete(&self.pool, id).await? == 0 {
+            return Err(SecretError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Decrypted secrets for a process: the project's, then each repo's, then the coding
+    /// agent's, with later scopes overriding earlier ones.
+    pub async fn env_for(
+        &self,
+        project_id: Option<Uuid>,
+        repo_ids: &[Uuid],
+        executor: Option<BaseCodingAgent>,
+    ) -> Result<HashMap<String, String>, SecretError> {
+        let mut scopes = Vec::new();
+        scopes.extend(project_id.map(|project_id| SecretScope {
+            project_id: Some(project_id),
+            ..Default::default()
+        }));
+        scopes.extend(repo_ids.iter().map(|repo_id| SecretScope {
+            repo_id: Some(*repo_id),
+            ..Default::default()
+        }));
+        scopes.extend(executor.map(|executor| SecretScope {
+            executor: Some(executor),
+            ..Default::default()
+        }));
+
+        let mut secrets = Vec::new();
+        for scope in &scopes {
+            secrets.extend(Secret::find_by_scope(&self.pool, scope).await?);
+        }
+        let mut env = HashMap::new();
+        if secrets.is_empty() {
+            return Ok(env);
+        }
+
+        let key = self.key().await?;
+        for secret in secrets {
+            match decrypt(key, &secret.ciphertext) {
+                Some(value) => {
+                    env.insert(secret.name, value);
+                }
+                None => tracing::warn!("Failed to decrypt secret '{}', skipping it", secret.name),
+            }
+        }
+        Ok(env)
+    }
+
+    /// Encrypt a credential that is stored outside the secrets table.
+    pub async fn encrypt_value(&self, value: &str) -> Result<String, SecretError> {
+        encrypt(self.key().await?, value)
+    }
+
+    pub async fn decrypt_value(&self, encoded: &str) -> Result<String, SecretError> {
+        decrypt(self.key().await?, encoded).ok_or(SecretError::Decrypt)
+    }
+
+    async fn key(&self) -> Result<&[u8; KEY_LEN], SecretError> {
+        self.key
+            .get_or_try_init(|| async {
+                // A new key would make stored secrets unreadable, so only fall back to a
+                // key file while there is nothing to lose
+                let has_secrets = Secret::exists(&self.pool).await?
+                    || IssueImportSource::exists_encrypted_credentials(&self.pool).await?;
+                tokio::task::spawn_blocking(move || load_or_create_key(has_secrets))
+                    .await
+                    .map_err(|e| SecretError::Key(e.to_string()))?
+            })
+            .await
+    }
+}
+
+fn validate_scope(scope: &SecretScope) -> Result<(), SecretError> {
+    let set = [
+        scope.project_id.is_some(),
+        scope.repo_id.is_some(),
+        scope.executor.is_some(),
+    ];
+    if set.into_iter().filter(|is_set| *is_set).count() != 1 {
+        return Err(SecretError::InvalidScope);
+    }
+    Ok(())
+}
+
+fn cipher(key: &[u8; KEY_LEN]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).expect("key has the AES-256 length"))
+}
+
+/// Base64 of a fresh random nonce followed by the ciphertext and tag.
+fn encrypt(key: &[u8; KEY_LEN], value: &str) -> Result<String, SecretError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| SecretError::Encrypt)?;
+    let mut in_out = value.as_bytes().to_vec();
+    cipher(key)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut in_out,
+        )
+        .map_err(|_| SecretError::Encrypt)?;
+
+    let mut combined = nonce.to_vec();
+    combined.extend_from_slice(&in_out);
+    Ok(STANDARD.encode(combined))
+}
+
+fn decrypt(key: &[u8; KEY_LEN], encoded: &str) -> Option<String> {
+    let decoded = STANDARD.decode(encoded).ok()?;
+    if decoded.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = decoded.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = cipher(key)
+        .open_in_place(nonce, Aad::empty(), &mut in_out)
+        .ok()?;
+    String::from_utf8(plaintext.to_vec()).ok()
+}
+
+/// Read the master key from the key file if there is one, otherwise from the OS keyring,
+/// creating it on first use.
+fn load_or_create_key(has_secrets: bool) -> Result<[u8; KEY_LEN], SecretError> {
+    let path = secrets_key_path();
+    if path.exists() {
+        let encoded =
+            std::fs::read_to_string(&path).map_err(|e| SecretError::Key(e.to_string()))?;
+        return decode_key(&encoded);
+    }
+
+    let keyring_error = match keyring_key(has_secrets) {
+        Ok(key) => return Ok(key),
+        Err(e) if has_secrets => return Err(e),
+        Err(e) => e,
+    };
+    tracing::info!(
+        "OS keyring unavailable ({}), keeping the secrets key in {}",
+        keyring_error,
+        path.display()
+    );
+    let key = generate_key().map_err(SecretError::Key)?;
+    write_key_file(&path, &key).map_err(|e| SecretError::Key(e.to_string()))?;
+    Ok(key)
+}
+
+/// Read the master key from the OS keyring. A missing key is only created while no
+/// secrets are stored, since replacing it would leave them unreadable.
+fn keyring_key(has_secrets: bool) -> Result<[u8; KEY_LEN], SecretError> {
+    let keyring_error = |e: keyring::Error| SecretError::Key(format!("OS keyring: {e}"));
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).map_err(keyring_error)?;
+    match entry.get_password() {
+        Ok(encoded) => decode_key(&encoded),
+        Err(keyring::Error::NoEntry) if has_secrets => Err(SecretError::Key(
+            "secrets key missing from the OS keyring, stored secrets cannot be read".to_string(),
+        )),
+        Err(keyring::Error::NoEntry) => {
+            let key = generate_key().map_err(SecretError::Key)?;
+            let encoded = STANDARD.encode(key);
+            entry.set_password(&encoded).map_err(keyring_error)?;
+            // Some keyring backends accept the write without keeping it
+            match entry.get_password() {
+                Ok(stored) if stored == encoded => Ok(key),
+                _ => Err(SecretError::Key(
+                    "the OS keyring did not keep the key".to_string(),
+                )),
+            }
+        }
+        Err(e) => Err(keyring_error(e)),
+    }
+}
+
+fn generate_key() -> Result<[u8; KEY_LEN], String> {
+    let mut key = [0u8; KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| "no system randomness".to_string())?;
+    Ok(key)
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; KEY_LEN], SecretError> {
+    STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+        .ok_or_else(|| SecretError::Key("malformed secrets key".to_string()))
+}
+
+fn write_key_file(path: &Path, key: &[u8; KEY_LEN]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut opts = std::fs::OpenOptions::new();
+    opts.create_new(true).write(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    let mut file = opts.open(path)?;
+    file.write_all(STANDARD.encode(key).as_bytes())?;
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_values() {
+        let key = generate_key().unwrap();
+        let encoded = encrypt(&key, "hunter2").unwrap();
+        assert_eq!(decrypt(&key, &encoded).as_deref(), Some("hunter2"));
+        assert_ne!(encrypt(&key, "hunter2").unwrap(), encoded);
+
+        let other = generate_key().unwrap();
+        assert_eq!(decrypt(&other, &encoded), None);
+    }
+
+    #[test]
+    fn scope_must_be_single() {
+        assert!(validate_scope(&SecretScope::default()).is_err());
+        assert!(
+            validate_scope(&SecretScope {
+                project_id: Some(Uuid::new_v4()),
+                repo_id: Some(Uuid::new_v4()),
+                executor: None,
+            })
+            .is_err()
+        );
+        assert!(
+            validate_scope(&SecretScope {
+                executor: Some(BaseCodingAgent::ClaudeCode),
+                ..Default::default()
+            })
+            .is_ok()
+        );
+    }
+}
@@ -0,0 +1,440 @@
+//! Tree-sitter symbol index for repositories.
+//!
+//! Each checkout is parsed on first use and its top-level definitions (functions, methods,
+//! structs, classes, ...) are cached for a short while. The index backs symbol search and
+//! the expansion of `@symbol` references in task prompts into file path + snippet context.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+
+use db::models::repo::Repo;
+use ignore::WalkBuilder;
+use moka::future::Cache;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use tree_sitter::{Language, Node, Parser};
+use ts_rs::TS;
+
+/// Symbols go stale as agents edit files, so indexes are rebuilt after this long
+const INDEX_TTL: Duration = Duration::from_secs(120);
+const MAX_INDEXED_FILE_SIZE: u64 = 512 * 1024;
+/// Definitions longer than this are cut off when expanded into a prompt
+const MAX_SNIPPET_LINES: usize = 80;
+/// Upper bound on expanded references per prompt, and definitions per referenced name
+const MAX_EXPANDED_REFERENCES: usize = 10;
+const MAX_DEFINITIONS_PER_REFERENCE: usize = 3;
+
+static SYMBOL_REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s(\[,])@([A-Za-z_][A-Za-z0-9_]*)").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Struct,
+    Enum,
+    Trait,
+    Interface,
+    Class,
+    Type,
+}
+
+impl SymbolKind {
+    fn label(self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Method => "method",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Class => "class",
+            SymbolKind::Type => "type",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct CodeSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Path relative to the repository root; search results prefix it with the repo name
+    pub path: String,
+    /// 1-based first line of the definition
+    pub start_line: usize,
+    /// 1-based last line of the definition
+    pub end_line: usize,
+}
+
+/// A repo checkout to index, reported in results under `name`
+#[derive(Debug, Clone)]
+pub struct SymbolRoot {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl SymbolRoot {
+    /// Roots for `repos`, in the workspace's worktrees when `workspace_dir` is given and in
+    /// the main checkouts otherwise.
+    pub fn for_repos(repos: &[Repo], workspace_dir: Option<&Path>) -> Vec<Self> {
+        repos
+            .iter()
+            .map(|repo| SymbolRoot {
+                name: repo.name.clone(),
+                path: workspace_dir
+                    .map(|dir| dir.join(&repo.name))
+                    .unwrap_or_else(|| repo.path.clone()),
+            })
+            .collect()
+    }
+}
+
+/// Per-checkout symbol index cache, kept alongside [`super::file_search::FileSearchCache`]
+#[derive(Clone)]
+pub struct SymbolIndexCache {
+    cache: Cache<PathBuf, Arc<Vec<CodeSymbol>>>,
+}
+
+impl Default for SymbolIndexCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolIndexCache {
+    pub fn new() -> Self {
+        Self {
+            cache: Cache::builder()
+                .max_capacity(50)
+                .time_to_live(INDEX_TTL)
+                .build(),
+        }
+    }
+
+    async fn symbols_for(&self, repo_path: &Path) -> Arc<Vec<CodeSymbol>> {
+        let path = repo_path.to_path_buf();
+        self.cache
+            .get_with(path.clone(), async move {
+                let symbols = tokio::task::spawn_blocking(move || index_repo(&path))
+                    .await
+                    .unwrap_or_else(|e| {
+                        warn!("Symbol indexing task failed: {}", e);
+                        Vec::new()
+                    });
+                Arc::new(symbols)
+            })
+            .await
+    }
+
+    /// Case-insensitive symbol search across `roots`: exact matches first, then prefix,
+    /// then substring matches. Paths are prefixed with the root's name.
+    pub async fn search(&self, roots: &[SymbolRoot], query: &str, limit: usize) -> Vec<CodeSymbol> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let query_lower = query.to_lowercase();
+
+        let mut ranked = Vec::new();
+        for root in roots {
+            for symbol in self.symbols_for(&root.path).await.iter() {
+                let name_lower = symbol.name.to_lowercase();
+                let rank = if symbol.name == query {
+                    0
+                } else if name_lower == query_lower {
+                    1
+                } else if name_lower.starts_with(&query_lower) {
+                    2
+                } else if name_lower.contains(&query_lower) {
+                    3
+                } else {
+                    continue;
+                };
+                ranked.push((
+                    rank,
+                    CodeSymbol {
+                        path: format!("{}/{}", root.name, symbol.path),
+                        ..symbol.clone()
+                    },
+                ));
+            }
+        }
+
+        ranked.sort_by(|(a_rank, a), (b_rank, b)| {
+            a_rank
+                .cmp(b_rank)
+                .then_with(|| a.name.len().cmp(&b.name.len()))
+                .then_with(|| a.path.cmp(&b.path))
+                .then_with(|| a.start_line.cmp(&b.start_line))
+        });
+        ranked.truncate(limit);
+        ranked.into_iter().map(|(_, symbol)| symbol).collect()
+    }
+
+    /// Append the definitions of every `@symbol` mentioned in `prompt` that exists in
+    /// `roots`. References that don't resolve are left alone.
+    pub async fn expand_symbol_references(&self, prompt: &str, roots: &[SymbolRoot]) -> String {
+        let names = symbol_references(prompt);
+        if names.is_empty() {
+            return prompt.to_string();
+        }
+
+        let mut sections = Vec::new();
+        for name in names.iter().take(MAX_EXPANDED_REFERENCES) {
+            let mut found = 0;
+            for root in roots {
+                for symbol in self.symbols_for(&root.path).await.iter() {
+                    if symbol.name != *name || found == MAX_DEFINITIONS_PER_REFERENCE {
+                        continue;
+                    }
+                    if let Some(section) = symbol_section(root, symbol) {
+                        sections.push(section);
+                        found += 1;
+                    }
+                }
+            }
+        }
+
+        if sections.is_empty() {
+            return prompt.to_string();
+        }
+        format!(
+            "{}\n\n## Referenced symbols\n\n{}",
+            prompt.trim_end(),
+            sections.join("\n\n")
+        )
+    }
+}
+
+/// Distinct names referenced as `@name` in `text`, in order of appearance. Mentions that
+/// continue with `.` or `/` are file references, not symbols.
+fn symbol_references(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for captures in SYMBOL_REFERENCE.captures_iter(text) {
+        let name = captures.get(1).unwrap();
+        let mut rest = text[name.end()..].chars();
+        if matches!(rest.next(), Some('.' | '/')) && rest.next().is_some_and(char::is_alphanumeric)
+        {
+            continue;
+        }
+        if !names.iter().any(|n| n == name.as_str()) {
+            names.push(name.as_str().to_string());
+        }
+    }
+    names
+}
+
+fn symbol_section(root: &SymbolRoot, symbol: &CodeSymbol) -> Option<String> {
+    let content = std::fs::read_to_string(root.path.join(&symbol.path)).ok()?;
+    let lines: Vec<&str> = content
+        .lines()
+        .skip(symbol.start_line - 1)
+        .take(symbol.end_line + 1 - symbol.start_line)
+        .collect();
+    let mut snippet = lines
+        .iter()
+        .take(MAX_SNIPPET_LINES)
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if lines.len() > MAX_SNIPPET_LINES {
+        snippet.push_str("\n...");
+    }
+    Some(format!(
+        "### `{}` ({}) in `{}/{}:{}-{}`\n```\n{}\n```",
+        symbol.name,
+        symbol.kind.label(),
+        root.name,
+        symbol.path,
+        symbol.start_line,
+        symbol.end_line,
+        snippet
+    ))
+}
+
+fn index_repo(repo_path: &Path) -> Vec<CodeSymbol> {
+    let walker = WalkBuilder::new(repo_path)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+
+    let mut parser = Parser::new();
+    let mut symbols = Vec::new();
+    for entry in walker.flatten() {
+        let path = entry.path();
+        let Some(language) = language_for(path) else {
+            continue;
+        };
+        if !entry.file_type().is_some_and(|ft| ft.is_file())
+            || entry
+                .metadata()
+                .is_ok_and(|m| m.len() > MAX_INDEXED_FILE_SIZE)
+        {
+            continue;
+        }
+        let Ok(source) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        if parser.set_language(&language).is_err() {
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(repo_path) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        symbols.extend(extract_symbols(&mut parser, &source, &relative));
+    }
+    symbols
+}
+
+fn language_for(path: &Path) -> Option<Language> {
+    let language = match path.extension()?.to_str()? {
+        "rs" => tree_sitter_rust::LANGUAGE,
+        "ts" | "mts" | "cts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX,
+        "js" | "jsx" | "mjs" | "cjs" => tree_sitter_javascript::LANGUAGE,
+        "py" => tree_sitter_python::LANGUAGE,
+        "go" => tree_sitter_go::LANGUAGE,
+        _ => return None,
+    };
+    Some(language.into())
+}
+
+/// Collect definitions from one file. Function bodies aren't descended into, so local
+/// helpers and callbacks don't crowd out the definitions people refer to.
+fn extract_symbols(parser: &mut Parser, source: &str, path: &str) -> Vec<CodeSymbol> {
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let mut symbols = Vec::new();
+    // (node, whether it sits inside an impl/class/trait body)
+    let mut stack = vec![(tree.root_node(), false)];
+    while let Some((node, in_type_body)) = stack.pop() {
+        let classified = classify(node, in_type_body);
+        if let Some((kind, name)) = classified
+            && let Ok(name) = name.utf8_text(source.as_bytes())
+        {
+            symbols.push(CodeSymbol {
+                name: name.to_string(),
+                kind,
+                path: path.to_string(),
+                start_line: node.start_position().row + 1,
+                end_line: node.end_position().row + 1,
+            });
+        }
+
+        let children_in_type_body = match classified.map(|(kind, _)| kind) {
+            Some(SymbolKind::Class | SymbolKind::Trait) => true,
+            Some(_) => continue,
+            None => in_type_body || node.kind() == "impl_item",
+        };
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            stack.push((child, children_in_type_body));
+        }
+    }
+
+    symbols.sort_by_key(|symbol| symbol.start_line);
+    symbols
+}
+
+/// Map a syntax node to the symbol it defines and the node holding its name. Node kinds
+/// are shared across the supported grammars wherever they mean the same thing.
+fn classify(node: Node<'_>, in_type_body: bool) -> Option<(SymbolKind, Node<'_>)> {
+    let function_or_method = if in_type_body {
+        SymbolKind::Method
+    } else {
+        SymbolKind::Function
+    };
+    let kind = match node.kind() {
+        "function_item"
+        | "function_signature_item"
+        | "function_declaration"
+        | "generator_function_declaration"
+        | "function_definition" => function_or_method,
+        "method_definition" | "method_declaration" => SymbolKind::Method,
+        "struct_item" => SymbolKind::Struct,
+        "enum_item" | "enum_declaration" => SymbolKind::Enum,
+        "trait_item" => SymbolKind::Trait,
+        "interface_declaration" => SymbolKind::Interface,
+        "class_declaration" | "abstract_class_declaration" | "class_definition" => {
+            SymbolKind::Class
+        }
+        "type_item" | "type_alias_declaration" => SymbolKind::Type,
+        // Go: `type Foo struct { ... }`
+        "type_spec" => match node.child_by_field_name("type")?.kind() {
+            "struct_type" => SymbolKind::Struct,
+            "interface_type" => SymbolKind::Interface,
+            _ => SymbolKind::Type,
+        },
+        // JS/TS: `const Foo = () => ...`
+        "variable_declarator" => match node.child_by_field_name("value")?.kind() {
+            "arrow_function" | "function_expression" | "function" => SymbolKind::Function,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some((kind, node.child_by_field_name("name")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols(file: &str, source: &str) -> Vec<(String, SymbolKind, usize)> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&language_for(Path::new(file)).unwrap())
+            .unwrap();
+        extract_symbols(&mut parser, source, file)
+            .into_iter()
+            .map(|s| (s.name, s.kind, s.start_line))
+            .collect()
+    }
+
+    #[test]
+    fn extracts_rust_definitions() {
+        let source = "struct Config {\n    port: u16,\n}\n\nimpl Config {\n    fn load() -> Self {\n        fn helper() {}\n        todo!()\n    }\n}\n\nfn main() {}\n";
+        assert_eq!(
+            symbols("main.rs", source),
+            vec![
+                ("Config".to_string(), SymbolKind::Struct, 1),
+                ("load".to_string(), SymbolKind::Method, 6),
+                ("main".to_string(), SymbolKind::Function, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_typescript_definitions() {
+        let source = "export interface Props { id: string }\nexport const Card = (props: Props) => {\n  const onClick = () => {};\n  return null;\n};\nclass Store {\n  load() {}\n}\n";
+        assert_eq!(
+            symbols("card.tsx", source),
+            vec![
+                ("Props".to_string(), SymbolKind::Interface, 1),
+                ("Card".to_string(), SymbolKind::Function, 2),
+                ("Store".to_string(), SymbolKind::Class, 6),
+                ("load".to_string(), SymbolKind::Method, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_symbol_references_but_not_file_mentions() {
+        assert_eq!(
+            symbol_references(
+                "Update @Config and (@load_config), see @src/main.rs and @README.md, mail a@b.com. @Config"
+            ),
+            vec!["Config".to_string(), "load_config".to_string()]
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Validation and rendering of task templates.
+//!
+//! Template titles and prompts reference variables as `{{name}}`. Rendering checks the
+//! supplied values against each variable's kind (free text, one of a fixed set of
+//! choices, or a file inside one of the task's repositories) before substituting them.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+    sync::LazyLock,
+};
+
+use db::models::task_template::{
+    CreateTaskTemplate, TaskTemplate, TemplateVariable, TemplateVariableKind,
+};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use thiserror::Error;
+use ts_rs::TS;
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap());
+static VARIABLE_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap());
+
+#[derive(Debug, Error)]
+pub enum TaskTemplateError {
+    #[error("Template name cannot be empty")]
+    EmptyName,
+    #[error("Template title cannot be empty")]
+    EmptyTitle,
+    #[error("Invalid variable name '{0}': use letters, digits and underscores")]
+    InvalidVariableName(String),
+    #[error("Variable '{0}' is declared more than once")]
+    DuplicateVariable(String),
+    #[error("Choice variable '{0}' needs at least one option")]
+    NoChoices(String),
+    #[error("Default value of '{0}' is not one of its options")]
+    InvalidDefault(String),
+    #[error("Template uses undeclared variable '{0}'")]
+    UndeclaredVariable(String),
+    #[error("Unknown variable '{0}'")]
+    UnknownVariable(String),
+    #[error("Missing value for required variable '{0}'")]
+    MissingValue(String),
+    #[error("'{value}' is not an option for '{name}'")]
+    InvalidChoice { name: String, value: String },
+    #[error("'{value}' for '{name}' is not a file in the task's repositories")]
+    InvalidRepoFilePath { name: String, value: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+pub struct RenderedTaskTemplate {
+    pub title: String,
+    pub prompt: String,
+}
+
+/// Check a template definition before it is saved.
+pub fn validate_template(data: &CreateTaskTemplate) -> Result<(), TaskTemplateError> {
+    if data.name.trim().is_empty() {
+        return Err(TaskTemplateError::EmptyName);
+    }
+    if data.title.trim().is_empty() {
+        return Err(TaskTemplateError::EmptyTitle);
+    }
+
+    let mut names = HashSet::new();
+    for variable in &data.variables {
+        if !VARIABLE_NAME.is_match(&variable.name) {
+            return Err(TaskTemplateError::InvalidVariableName(
+                variable.name.clone(),
+            ));
+        }
+        if !names.insert(variable.name.as_str()) {
+            return Err(TaskTemplateError::DuplicateVariable(variable.name.clone()));
+        }
+        if let TemplateVariableKind::Choice { options } = &variable.kind {
+            if options.is_empty() {
+                return Err(TaskTemplateError::NoChoices(variable.name.clone()));
+            }
+            if variable
+                .default
+                .as_ref()
+                .is_some_and(|default| !options.contains(default))
+            {
+                return Err(TaskTemplateError::InvalidDefault(variable.name.clone()));
+            }
+        }
+    }
+
+    for text in [&data.title, &data.prompt] {
+        if let Some(undeclared) = PLACEHOLDER
+            .captures_iter(text)
+            .map(|c| c[1].to_string())
+            .find(|name| !names.contains(name.as_str()))
+        {
+            return Err(TaskTemplateError::UndeclaredVariable(undeclared));
+        }
+    }
+    Ok(())
+}
+
+/// Validate `values` and substitute them into the template. `repo_roots` are the
+/// repositories the task will run in; repo file path variables must name a file in one
+/// of them.
+pub fn render_template(
+    template: &TaskTemplate,
+    values: &HashMap<String, String>,
+    repo_roots: &[PathBuf],
+) -> Result<RenderedTaskTemplate, TaskTemplateError> {
+    if let Some(unknown) = values
+        .keys()
+        .find(|name| !template.variables.iter().any(|v| &v.name == *name))
+    {
+        return Err(TaskTemplateError::UnknownVariable(unknown.clone()));
+    }
+
+    let resolved = template
+        .variables
+        .iter()
+        .map(|variable| {
+            let value = resolve_value(variable, values.get(&variable.name), repo_roots)?;
+            Ok((variable.name.as_str(), value))
+        })
+        .collect::<Result<HashMap<_, _>, TaskTemplateError>>()?;
+
+    let substitute = |text: &str| {
+        PLACEHOLDER
+            .replace_all(text, |c: &Captures| {
+                resolved.get(&c[1]).cloned().unwrap_or_default()
+            })
+            .into_owned()
+    };
+
+    let title = substitute(&template.title).trim().to_string();
+    if title.is_empty() {
+        return Err(TaskTemplateError::EmptyTitle);
+    }
+
+    let mut prompt = substitute(&template.prompt);
+    if let Some(script) = template
+        .verification_script
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        prompt.push_str(&format!(
+            "\n\n## Verification\n\nBefore finishing, run the following and fix any failures:\n\n```bash\n{script}\n```"
+        ));
+    }
+
+    Ok(RenderedTaskTemplate { title, prompt })
+}
+
+fn resolve_value(
+    variable: &TemplateVariable,
+    given: Option<&String>,
+    repo_roots: &[PathBuf],
+) -> Result<String, TaskTemplateError> {
+    let value = given
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .or(variable.default.as_deref());
+    let Some(value) = value else {
+        return if variable.required {
+            Err(TaskTemplateError::MissingValue(variable.name.clone()))
+        } else {
+            Ok(String::new())
+        };
+    };
+
+    match &variable.kind {
+        TemplateVariableKind::String => {}
+        TemplateVariableKind::Choice { options } => {
+            if !options.iter().any(|option| option == value) {
+                return Err(TaskTemplateError::InvalidChoice {
+                    name: variable.name.clone(),
+                    value: value.to_string(),
+                });
+            }
+        }
+        TemplateVariableKind::RepoFilePath => {
+            if !is_repo_file(value, repo_roots) {
+                return Err(TaskTemplateError::InvalidRepoFilePath {
+                    name: variable.name.clone(),
+                    value: value.to_string(),
+                });
+            }
+        }
+    }
+    Ok(value.to_string())
+}
+
+/// A relative path that stays inside the repo and names an existing file in one of them.
+fn is_repo_file(value: &str, repo_roots: &[PathBuf]) -> bool {
+    let path = Path::new(value);
+    let contained = path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    contained && repo_roots.iter().any(|root| root.join(path).is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn variable(name: &str, kind: TemplateVariableKind, required: bool) -> TemplateVariable {
+        TemplateVariable {
+            name: name.to_string(),
+            label: None,
+            kind,
+            required,
+            default: None,
+        }
+    }
+
+    fn template(title: &str, prompt: &str, variables: Vec<TemplateVariable>) -> TaskTemplate {
+        TaskTemplate {
+            id: Uuid::new_v4(),
+            project_id: None,
+            name: "bump".to_string(),
+            description: None,
+            title: title.to_string(),
+            prompt: prompt.to_string(),
+            variables: Json(variables),
+            executor_config: None,
+            repos: Json(Vec::new()),
+            verification_script: Some("cargo check".to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn renders_variables_and_verification_script() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        let template = template(
+            "Bump {{ crate_name }}",
+            "Bump {{crate_name}} to {{level}} in {{manifest}}.",
+            vec![
+                variable("crate_name", TemplateVariableKind::String, true),
+                variable(
+                    "level",
+                    TemplateVariableKind::Choice {
+                        options: vec!["patch".to_string(), "minor".to_string()],
+                    },
+                    true,
+                ),
+                variable("manifest", TemplateVariableKind::RepoFilePath, true),
+            ],
+        );
+        let roots = vec![dir.path().to_path_buf()];
+
+        let rendered = render_template(
+            &template,
+            &values(&[
+                ("crate_name", "serde"),
+                ("level", "minor"),
+                ("manifest", "Cargo.toml"),
+            ]),
+            &roots,
+        )
+        .unwrap();
+        assert_eq!(rendered.title, "Bump serde");
+        assert!(
+            rendered
+                .prompt
+                .starts_with("Bump serde to minor in Cargo.toml.\n\n## Verification")
+        );
+        assert!(rendered.prompt.ends_with("```bash\ncargo check\n```"));
+
+        let err = render_template(
+            &template,
+            &values(&[
+                ("crate_name", "serde"),
+                ("level", "major"),
+                ("manifest", "Cargo.toml"),
+            ]),
+            &roots,
+        );
+        assert!(matches!(err, Err(TaskTemplateError::InvalidChoice { .. })));
+
+        let err = render_template(
+            &template,
+            &values(&[("level", "patch"), ("manifest", "../Cargo.toml")]),
+            &roots,
+        );
+        assert!(matches!(err, Err(TaskTemplateError::MissingValue(name)) if name == "crate_name"));
+
+        let err = render_template(
+            &template,
+            &values(&[
+                ("crate_name", "serde"),
+                ("level", "patch"),
+                ("manifest", "../Cargo.toml"),
+            ]),
+            &roots,
+        );
+        assert!(matches!(
+            err,
+            Err(TaskTemplateError::InvalidRepoFilePath { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_undeclared_and_duplicate_variables() {
+        let mut data = CreateTaskTemplate {
+            project_id: None,
+            name: "bump".to_string(),
+            description: None,
+            title: "Bump {{crate_name}}".to_string(),
+            prompt: "Fix breakages from {{version}}".to_string(),
+            variables: vec![variable("crate_name", TemplateVariableKind::String, true)],
+            executor_config: None,
+            repos: Vec::new(),
+            verification_script: None,
+            image_ids: Vec::new(),
+        };
+        assert!(matches!(
+            validate_template(&data),
+            Err(TaskTemplateError::UndeclaredVariable(name)) if name == "version"
+        ));
+
+        data.variables
+            .push(variable("version", TemplateVariableKind::String, false));
+        assert!(validate_template(&data).is_ok());
+
+        data.variables
+            .push(variable("version", TemplateVariableKind::String, false));
+        assert!(matches!(
+            validate_template(&data),
+            Err(TaskTemplateError::DuplicateVariable(_))
+        ));
+    }
+}
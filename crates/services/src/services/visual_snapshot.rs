@@ -0,0 +1,543 @@
+//! Visual snapshots of a workspace's dev server.
+//!
+//! Each route is screenshotted at every requested viewport size with a headless Chrome
+//! or Chromium and stored through [`ImageService`]. Only dev servers on localhost ports
+//! assigned by vibe-kanban are captured. For a comparison, the target branch is checked
+//! out into a temporary worktree and its dev server started there as
+//! [`BaselineDevServer`]; the same routes are captured from it and compared pixel by
+//! pixel, producing a diff image with the changed pixels highlighted.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::Cursor,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use db::models::{
+    dev_server::DevServer,
+    repo_dev_server_settings::RepoDevServerSettings,
+    visual_snapshot::{CreateVisualSnapshot, VisualSnapshot},
+    workspace_port::WorkspacePort,
+};
+use futures::{StreamExt, TryStreamExt, stream};
+use git::{GitService, GitServiceError};
+use image::{ImageFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tempfile::TempDir;
+use thiserror::Error;
+use tokio::process::Command;
+use tokio_util::io::ReaderStream;
+use ts_rs::TS;
+use url::Url;
+use utils::{
+    log_msg::LogMsg,
+    msg_store::MsgStore,
+    shell::{get_shell_command, resolve_executable_path},
+};
+use uuid::Uuid;
+
+use super::{
+    dev_server,
+    image::{ImageError, ImageService},
+};
+
+/// Browser executables looked up on PATH, in order.
+const BROWSER_CANDIDATES: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+    "chrome",
+    "msedge",
+];
+
+#[cfg(target_os = "macos")]
+const MACOS_BROWSER_PATHS: &[&str] = &[
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+];
+
+const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Per-channel difference above which a pixel counts as changed, so antialiasing
+/// noise doesn't show up in the diff.
+const PIXEL_TOLERANCE: u8 = 16;
+
+pub const DEFAULT_VIEWPORTS: &[SnapshotViewport] = &[
+    SnapshotViewport {
+        width: 1280,
+        height: 800,
+    },
+    SnapshotViewport {
+        width: 390,
+        height: 844,
+    },
+];
+
+#[derive(Debug, Error)]
+pub enum VisualSnapshotError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Image(#[from] ImageError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to decode screenshot: {0}")]
+    Decode(#[from] image::ImageError),
+    #[error("No Chrome or Chromium browser found on PATH")]
+    BrowserNotFound,
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Invalid route '{0}': use a path on the dev server, like /settings")]
+    InvalidRoute(String),
+    #[error("The target branch's dev server did not become ready")]
+    BaselineNotReady,
+    #[error("Screenshot of {url} failed: {message}")]
+    Screenshot { url: String, message: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct SnapshotViewport {
+    pub width: u32,
+    pub height: u32,
+}
+
+pub struct SnapshotRequest<'a> {
+    /// Running dev server the snapshots are linked to
+    pub execution_process_id: Uuid,
+    /// Port assigned to the workspace's dev server
+    pub port: u16,
+    /// Port of a dev server running the target branch
+    pub baseline_port: Option<u16>,
+    pub routes: &'a [String],
+    pub viewports: &'a [SnapshotViewport],
+}
+
+/// Screenshot every route at every viewport size, diffing against the baseline dev
+/// server when there is one.
+pub async fn capture_snapshots(
+    pool: &SqlitePool,
+    images: &ImageService,
+    request: &SnapshotRequest<'_>,
+) -> Result<Vec<VisualSnapshot>, VisualSnapshotError> {
+    validate_routes(request.routes)?;
+    let browser = find_browser().await?;
+    let base_url = local_url(request.port);
+    let baseline_url = request.baseline_port.map(local_url);
+
+    let mut snapshots = Vec::new();
+    for route in request.routes {
+        for viewport in request.viewports {
+            let name = snapshot_name(route, viewport);
+            let screenshot =
+                take_screenshot(&browser, &route_url(&base_url, route)?, viewport).await?;
+            let image = images
+                .store_image(&screenshot, &format!("{name}.png"))
+                .await?;
+
+            let (baseline_image_id, diff_image_id, diff_ratio) = match &baseline_url {
+                Some(baseline_url) => {
+                    let baseline =
+                        take_screenshot(&browser, &route_url(baseline_url, route)?, viewport)
+                            .await?;
+                    let baseline_image = images
+                        .store_image(&baseline, &format!("{name}_baseline.png"))
+                        .await?;
+                    let (diff_ratio, diff) =
+                        tokio::task::spawn_blocking(move || pixel_diff(&screenshot, &baseline))
+                            .await
+                            .map_err(std::io::Error::other)??;
+                    let diff_image = images
+                        .store_image(&diff, &format!("{name}_diff.png"))
+                        .await?;
+                    (
+                        Some(baseline_image.id),
+                        Some(diff_image.id),
+                        Some(diff_ratio),
+                    )
+                }
+                None => (None, None, None),
+            };
+
+            snapshots.push(
+                VisualSnapshot::create(
+                    pool,
+                    &CreateVisualSnapshot {
+                        execution_process_id: request.execution_process_id,
+                        route: route.clone(),
+                        viewport_width: viewport.width.into(),
+                        viewport_height: viewport.height.into(),
+                        image_id: image.id,
+                        baseline_image_id,
+                        diff_image_id,
+                        diff_ratio,
+                    },
+                )
+                .await?,
+            );
+        }
+    }
+    Ok(snapshots)
+}
+
+async fn find_browser() -> Result<PathBuf, VisualSnapshotError> {
+    for candidate in BROWSER_CANDIDATES {
+        if let Some(path) = resolve_executable_path(candidate).await {
+            return Ok(path);
+        }
+    }
+    #[cfg(target_os = "macos")]
+    for path in MACOS_BROWSER_PATHS {
+        let path = Path::new(path);
+        if path.is_file() {
+            return Ok(path.to_path_buf());
+        }
+    }
+    Err(VisualSnapshotError::BrowserNotFound)
+}
+
+fn local_url(port: u16) -> Url {
+    Url::parse(&format!("http://localhost:{port}/")).expect("localhost URL is valid")
+}
+
+/// The URL of `route` on the dev server at `base_url`. Routes that would leave the dev
+/// server, like `//example.com` or `http://...`, are rejected.
+fn route_url(base_url: &Url, route: &str) -> Result<Url, VisualSnapshotError> {
+    let invalid = || VisualSnapshotError::InvalidRoute(route.to_string());
+    if !route.starts_with('/') || route.starts_with("//") || route.contains('\\') {
+        return Err(invalid());
+    }
+    let url = base_url.join(route).map_err(|_| invalid())?;
+    if url.origin() != base_url.origin() {
+        return Err(invalid());
+    }
+    Ok(url)
+}
+
+/// Check that every route is a path on the dev server.
+pub fn validate_routes(routes: &[String]) -> Result<(), VisualSnapshotError> {
+    let base_url = local_url(80);
+    routes
+        .iter()
+        .try_for_each(|route| route_url(&base_url, route).map(|_| ()))
+}
+
+/// File name for a snapshot, e.g. `settings_profile_1280x800` for `/settings/profile`.
+fn snapshot_name(route: &str, viewport: &SnapshotViewport) -> String {
+    let route = route
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_matches('/')
+        .replace('/', "_");
+    let route = if route.is_empty() { "index" } else { &route };
+    format!("{route}_{}x{}", viewport.width, viewport.height)
+}
+
+async fn take_screenshot(
+    browser: &Path,
+    url: &Url,
+    viewport: &SnapshotViewport,
+) -> Result<Vec<u8>, VisualSnapshotError> {
+    let screenshot_error = |message: String| VisualSnapshotError::Screenshot {
+        url: url.to_string(),
+        message,
+    };
+
+    // A fresh profile per screenshot keeps runs independent of the user's browser
+    let dir = tempfile::tempdir()?;
+    let output_path = dir.path().join("screenshot.png");
+    let mut command = Command::new(browser);
+    command
+        .arg("--headless=new")
+        .arg("--disable-gpu")
+        .arg("--hide-scrollbars")
+        .arg("--no-first-run")
+        .arg("--no-default-browser-check")
+        // Give client-side rendering time to settle before the capture
+        .arg("--virtual-time-budget=5000")
+        .arg(format!(
+            "--user-data-dir={}",
+            dir.path().join("profile").display()
+        ))
+        .arg(format!(
+            "--window-size={},{}",
+            viewport.width, viewport.height
+        ))
+        .arg(format!("--screenshot={}", output_path.display()))
+        .arg(url.as_str())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let output = tokio::time::timeout(SCREENSHOT_TIMEOUT, command.output())
+        .await
+        .map_err(|_| screenshot_error("timed out".to_string()))??;
+
+    if !output_path.is_file() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("browser exited with {}", output.status));
+        return Err(screenshot_error(message));
+    }
+    Ok(tokio::fs::read(&output_path).await?)
+}
+
+/// Compare two PNG screenshots. Returns the fraction of differing pixels and a PNG of
+/// `current` faded out with the changed pixels painted red. Areas covered by only one
+/// of the images count as changed.
+fn pixel_diff(current: &[u8], baseline: &[u8]) -> Result<(f64, Vec<u8>), VisualSnapshotError> {
+    let current = image::load_from_memory(current)?.to_rgba8();
+    let baseline = image::load_from_memory(baseline)?.to_rgba8();
+    let width = current.width().max(baseline.width());
+    let height = current.height().max(baseline.height());
+
+    let mut diff = RgbaImage::new(width, height);
+    let mut changed: u64 = 0;
+    for (x, y, pixel) in diff.enumerate_pixels_mut() {
+        let a = current.get_pixel_checked(x, y);
+        let b = baseline.get_pixel_checked(x, y);
+        let is_changed = match (a, b) {
+            (Some(a), Some(b)) => {
+                a.0.iter()
+                    .zip(b.0.iter())
+                    .any(|(a, b)| a.abs_diff(*b) > PIXEL_TOLERANCE)
+            }
+            _ => true,
+        };
+        *pixel = if is_changed {
+            changed += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let [r, g, b, _] = a.map(|p| p.0).unwrap_or([255; 4]);
+            let luma = (u16::from(r) * 3 + u16::from(g) * 6 + u16::from(b)) / 10;
+            let faded = (255 - (255 - luma) / 4) as u8;
+            Rgba([faded, faded, faded, 255])
+        };
+    }
+
+    let total = u64::from(width) * u64::from(height);
+    let ratio = if total == 0 {
+        0.0
+    } else {
+        changed as f64 / total as f64
+    };
+
+    let mut png = Vec::new();
+    diff.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok((ratio, png))
+}
+
+/// Dev server of a repo's target branch, run from a temporary detached checkout for the
+/// length of a snapshot run. Call [`Self::stop`] when done, which also removes the
+/// checkout.
+pub struct BaselineDevServer {
+    git: GitService,
+    repo_path: PathBuf,
+    dir: TempDir,
+    child: Option<AsyncGroupChild>,
+}
+
+impl BaselineDevServer {
+    /// Check out `target_branch` of the repo at `repo_path` into a temporary worktree.
+    pub async fn checkout(
+        git: &GitService,
+        repo_path: &Path,
+        target_branch: &str,
+    ) -> Result<Self, VisualSnapshotError> {
+        let dir = tempfile::Builder::new()
+            .prefix("vibe-kanban-baseline-")
+            .tempdir()?;
+        let baseline = Self {
+            git: git.clone(),
+            repo_path: repo_path.to_path_buf(),
+            dir,
+            child: None,
+        };
+
+        let git = baseline.git.clone();
+        let repo_path = baseline.repo_path.clone();
+        let checkout = baseline.path();
+        let target_branch = target_branch.to_string();
+        tokio::task::spawn_blocking(move || {
+            git.add_detached_worktree(&repo_path, &checkout, &target_branch)
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+        Ok(baseline)
+    }
+
+    /// Root of the checkout.
+    pub fn path(&self) -> PathBuf {
+        self.dir.path().join("checkout")
+    }
+
+    /// Run the dev server `command` from the checkout on a free port and wait until it's
+    /// ready, as configured for the repo. Returns the port.
+    pub async fn start(
+        &mut self,
+        pool: &SqlitePool,
+        command: &str,
+        env: &HashMap<String, String>,
+        settings: &RepoDevServerSettings,
+    ) -> Result<u16, VisualSnapshotError> {
+        let mut reserved = DevServer::reserved_ports(pool).await?;
+        reserved.extend(WorkspacePort::reserved_ports(pool).await?);
+        let reserved: HashSet<u16> = reserved
+            .into_iter()
+            .filter_map(|port| u16::try_from(port).ok())
+            .collect();
+        let port = dev_server::allocate_port(None, &reserved)?;
+
+        let (shell, shell_arg) = get_shell_command();
+        let mut child = Command::new(shell)
+            .arg(shell_arg)
+            .arg(command)
+            .current_dir(self.path())
+            .envs(env)
+            .env(dev_server::PORT_ENV_VAR, port.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .group_spawn()?;
+
+        let logs = Arc::new(MsgStore::new());
+        if let (Some(out), Some(err)) = (child.inner().stdout.take(), child.inner().stderr.take()) {
+            let out = ReaderStream::new(out)
+                .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+            let err = ReaderStream::new(err)
+                .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+            // Both pipes close when the dev server exits
+            let output = stream::select(out, err).chain(stream::once(async {
+                Ok::<_, std::io::Error>(LogMsg::Finished)
+            }));
+            logs.clone().spawn_forwarder(output);
+        }
+        self.child = Some(child);
+
+        if !dev_server::wait_until_ready(port, settings, logs).await {
+            return Err(VisualSnapshotError::BaselineNotReady);
+        }
+        Ok(port)
+    }
+
+    /// Stop the dev server and remove the checkout.
+    pub async fn stop(mut self) {
+        if let Some(mut child) = self.child.take()
+            && let Err(e) = utils::process::kill_process_group(&mut child).await
+        {
+            tracing::warn!("Failed to stop the target branch's dev server: {}", e);
+        }
+
+        let git = self.git.clone();
+        let repo_path = self.repo_path.clone();
+        let checkout = self.path();
+        let removed =
+            tokio::task::spawn_blocking(move || git.remove_worktree(&repo_path, &checkout, true))
+                .await;
+        if let Ok(Err(e)) = removed {
+            tracing::warn!("Failed to remove the target branch checkout: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+        let image = RgbaImage::from_pixel(width, height, Rgba(color));
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    fn viewport(width: u32, height: u32) -> SnapshotViewport {
+        SnapshotViewport { width, height }
+    }
+
+    #[test]
+    fn identical_screenshots_have_no_diff() {
+        let screenshot = png(10, 10, [10, 20, 30, 255]);
+        let (ratio, diff) = pixel_diff(&screenshot, &screenshot).unwrap();
+        assert_eq!(ratio, 0.0);
+
+        let diff = image::load_from_memory(&diff).unwrap().to_rgba8();
+        assert_eq!(diff.dimensions(), (10, 10));
+        assert!(diff.pixels().all(|pixel| pixel.0 != [255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn small_color_differences_are_tolerated() {
+        let current = png(4, 4, [100, 100, 100, 255]);
+        let baseline = png(4, 4, [100 + PIXEL_TOLERANCE, 100, 100, 255]);
+        assert_eq!(pixel_diff(&current, &baseline).unwrap().0, 0.0);
+
+        let baseline = png(4, 4, [101 + PIXEL_TOLERANCE, 100, 100, 255]);
+        assert_eq!(pixel_diff(&current, &baseline).unwrap().0, 1.0);
+    }
+
+    #[test]
+    fn size_mismatch_counts_as_changed() {
+        // The baseline covers the top half of the current screenshot
+        let current = png(10, 10, [0, 0, 0, 255]);
+        let baseline = png(10, 5, [0, 0, 0, 255]);
+        let (ratio, diff) = pixel_diff(&current, &baseline).unwrap();
+        assert_eq!(ratio, 0.5);
+
+        let diff = image::load_from_memory(&diff).unwrap().to_rgba8();
+        assert_eq!(diff.get_pixel(0, 9).0, [255, 0, 0, 255]);
+        assert_ne!(diff.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn snapshot_names() {
+        assert_eq!(snapshot_name("/", &viewport(1280, 800)), "index_1280x800");
+        assert_eq!(
+            snapshot_name("/settings/profile/", &viewport(390, 844)),
+            "settings_profile_390x844"
+        );
+        assert_eq!(
+            snapshot_name("/search?q=a#top", &viewport(390, 844)),
+            "search_390x844"
+        );
+    }
+
+    #[test]
+    fn routes_stay_on_the_dev_server() {
+        let base_url = local_url(5173);
+        assert_eq!(
+            route_url(&base_url, "/settings?tab=1").unwrap().as_str(),
+            "http://localhost:5173/settings?tab=1"
+        );
+        for route in [
+            "settings",
+            "//example.com/",
+            "/\\example.com",
+            "http://localhost:22/",
+            "",
+        ] {
+            assert!(
+                matches!(
+                    route_url(&base_url, route),
+                    Err(VisualSnapshotError::InvalidRoute(_))
+                ),
+                "{route} should be rejected"
+            );
+        }
+        assert!(validate_routes(&["/".to_string(), "/a/b".to_string()]).is_ok());
+    }
+}
@@ -0,0 +1,519 @@
+//! Workspace checkpoints.
+//!
+//! A checkpoint records, for every repo in a workspace, HEAD plus a snapshot commit of the
+//! uncommitted changes (untracked files included). Unlike resetting a session to an
+//! earlier agent turn, restoring a checkpoint also brings back manual edits and work that
+//! was never committed. Snapshot commits are kept alive by a hidden
+//! `refs/vibe-kanban/checkpoints/<id>` ref in each repo.
+//!
+//! Checkpoints are created on request and automatically before risky operations (rebase,
+//! session reset, conflict abort); only the newest [`MAX_AUTO_CHECKPOINTS`] automatic ones
+//! of a workspace are kept.
+
+use std::{collections::HashMap, path::Path};
+
+use db::models::{
+    repo::Repo,
+    workspace_checkpoint::{
+        CheckpointReason, WorkspaceCheckpoint, WorkspaceCheckpointRepo,
+        WorkspaceCheckpointWithRepos,
+    },
+    workspace_repo::WorkspaceRepo,
+};
+use git::{Commit, DiffTarget, GitService, GitServiceError};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tracing::warn;
+use ts_rs::TS;
+use utils::diff::Diff;
+use uuid::Uuid;
+
+pub const MAX_AUTO_CHECKPOINTS: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Invalid snapshot commit '{0}'")]
+    InvalidCommit(String),
+}
+
+/// Changes in one repo's worktree since a checkpoint was taken.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CheckpointRepoDiff {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub diffs: Vec<Diff>,
+}
+
+fn default_name(reason: CheckpointReason) -> &'static str {
+    match reason {
+        CheckpointReason::Manual => "Checkpoint",
+        CheckpointReason::BeforeRebase => "Before rebase",
+        CheckpointReason::BeforeReset => "Before reset",
+        CheckpointReason::BeforeConflictAbort => "Before conflict abort",
+        CheckpointReason::BeforeRestore => "Before restore",
+    }
+}
+
+/// Snapshot every repo of a workspace whose worktrees live under `workspace_dir`.
+pub async fn create_checkpoint(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace_id: Uuid,
+    workspace_dir: &Path,
+    name: Option<&str>,
+    reason: CheckpointReason,
+) -> Result<WorkspaceCheckpointWithRepos, CheckpointError> {
+    let name = name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(default_name(reason));
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace_id).await?;
+    let checkpoint =
+        WorkspaceCheckpoint::create(pool, Uuid::new_v4(), workspace_id, name, reason).await?;
+
+    let mut states = Vec::with_capacity(repos.len());
+    for repo in &repos {
+        let state = snapshot_repo(pool, git, &checkpoint, repo, workspace_dir).await;
+        match state {
+            Ok(state) => states.push(state),
+            Err(e) => {
+                // Don't leave a checkpoint behind that only covers some repos
+                if let Err(cleanup_err) = remove_checkpoint(pool, git, &checkpoint, &repos).await {
+                    warn!(
+                        "Failed to clean up partial checkpoint {}: {}",
+                        checkpoint.id, cleanup_err
+                    );
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    if reason != CheckpointReason::Manual {
+        prune_auto_checkpoints(pool, git, workspace_id, &repos).await;
+    }
+
+    Ok(WorkspaceCheckpointWithRepos {
+        checkpoint,
+        repos: states,
+    })
+}
+
+async fn snapshot_repo(
+    pool: &SqlitePool,
+    git: &GitService,
+    checkpoint: &WorkspaceCheckpoint,
+    repo: &Repo,
+    workspace_dir: &Path,
+) -> Result<WorkspaceCheckpointRepo, CheckpointError> {
+    let worktree_path = workspace_dir.join(&repo.name);
+    let (head, snapshot) = git.create_worktree_snapshot(
+        &worktree_path,
+        &WorkspaceCheckpoint::ref_name(checkpoint.id),
+        &format!("vibe-kanban checkpoint: {}", checkpoint.name),
+    )?;
+    Ok(WorkspaceCheckpointRepo::create(pool, checkpoint.id, repo.id, &head, &snapshot).await?)
+}
+
+/// Create a checkpoint before a risky operation. Failures are logged rather than returned
+/// so they never block the operation itself.
+pub async fn create_auto_checkpoint(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace_id: Uuid,
+    workspace_dir: &Path,
+    reason: CheckpointReason,
+) -> Option<WorkspaceCheckpointWithRepos> {
+    match create_checkpoint(pool, git, workspace_id, workspace_dir, None, reason).await {
+        Ok(checkpoint) => Some(checkpoint),
+        Err(e) => {
+            warn!(
+                "Failed to create {:?} checkpoint for workspace {}: {}",
+                reason, workspace_id, e
+            );
+            None
+        }
+    }
+}
+
+/// Checkpoints of a workspace with their repo states, newest first.
+pub async fn list_checkpoints(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<Vec<WorkspaceCheckpointWithRepos>, sqlx::Error> {
+    let checkpoints = WorkspaceCheckpoint::find_by_workspace_id(pool, workspace_id).await?;
+    let mut states: HashMap<Uuid, Vec<WorkspaceCheckpointRepo>> = HashMap::new();
+    for state in WorkspaceCheckpointRepo::find_by_workspace_id(pool, workspace_id).await? {
+        states.entry(state.checkpoint_id).or_default().push(state);
+    }
+    Ok(checkpoints
+        .into_iter()
+        .map(|checkpoint| WorkspaceCheckpointWithRepos {
+            repos: states.remove(&checkpoint.id).unwrap_or_default(),
+            checkpoint,
+        })
+        .collect())
+}
+
+/// Put every repo covered by `checkpoint` back into its recorded state, discarding the
+/// current changes. The current state is checkpointed first and returned, so a restore
+/// can itself be undone. Repos added to the workspace after the checkpoint are untouched.
+pub async fn restore_checkpoint(
+    pool: &SqlitePool,
+    git: &GitService,
+    checkpoint: &WorkspaceCheckpoint,
+    workspace_dir: &Path,
+) -> Result<WorkspaceCheckpointWithRepos, CheckpointError> {
+    let backup = create_checkpoint(
+        pool,
+        git,
+        checkpoint.workspace_id,
+        workspace_dir,
+        Some(&format!("Before restoring '{}'", checkpoint.name)),
+        CheckpointReason::BeforeRestore,
+    )
+    .await?;
+
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, checkpoint.workspace_id).await?;
+    for state in WorkspaceCheckpointRepo::find_by_checkpoint_id(pool, checkpoint.id).await? {
+        let Some(repo) = repos.iter().find(|repo| repo.id == state.repo_id) else {
+            continue;
+        };
+        git.restore_worktree_snapshot(
+            &workspace_dir.join(&repo.name),
+            &state.head_commit,
+            &state.snapshot_commit,
+        )?;
+    }
+    Ok(backup)
+}
+
+/// Changes in each repo's worktree since `checkpoint`, uncommitted and untracked files
+/// included.
+pub async fn diff_checkpoint(
+    pool: &SqlitePool,
+    git: &GitService,
+    checkpoint: &WorkspaceCheckpoint,
+    workspace_dir: &Path,
+) -> Result<Vec<CheckpointRepoDiff>, CheckpointError> {
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, checkpoint.workspace_id).await?;
+    let mut result = Vec::new();
+    for state in WorkspaceCheckpointRepo::find_by_checkpoint_id(pool, checkpoint.id).await? {
+        let Some(repo) = repos.iter().find(|repo| repo.id == state.repo_id) else {
+            continue;
+        };
+        let oid = git2::Oid::from_str(&state.snapshot_commit)
+            .map_err(|_| CheckpointError::InvalidCommit(state.snapshot_commit.clone()))?;
+        let diffs = git.get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: &workspace_dir.join(&repo.name),
+                base_commit: &Commit::new(oid),
+            },
+            None,
+        )?;
+        result.push(CheckpointRepoDiff {
+            repo_id: repo.id,
+            repo_name: repo.name.clone(),
+            diffs,
+        });
+    }
+    Ok(result)
+}
+
+/// Delete a checkpoint and the refs keeping its snapshots alive.
+pub async fn delete_checkpoint(
+    pool: &SqlitePool,
+    git: &GitService,
+    checkpoint: &WorkspaceCheckpoint,
+) -> Result<(), CheckpointError> {
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, checkpoint.workspace_id).await?;
+    remove_checkpoint(pool, git, checkpoint, &repos).await
+}
+
+async fn remove_checkpoint(
+    pool: &SqlitePool,
+    git: &GitService,
+    checkpoint: &WorkspaceCheckpoint,
+    repos: &[Repo],
+) -> Result<(), CheckpointError> {
+    let refname = WorkspaceCheckpoint::ref_name(checkpoint.id);
+    for repo in repos {
+        git.delete_ref(&repo.path, &refname)?;
+    }
+    WorkspaceCheckpoint::delete(pool, checkpoint.id).await?;
+    Ok(())
+}
+
+async fn prune_auto_checkpoints(
+    pool: &SqlitePool,
+    git: &GitService,
+    workspace_id: Uuid,
+    repos: &[Repo],
+) {
+    let checkpoints = match WorkspaceCheckpoint::find_by_workspace_id(pool, workspace_id).await {
+        Ok(checkpoints) => checkpoints,
+        Err(e) => {
+            warn!(
+                "Failed to load checkpoints of workspace {}: {}",
+                workspace_id, e
+            );
+            return;
+        }
+    };
+    let stale = checkpoints
+        .iter()
+        .filter(|checkpoint| checkpoint.reason != CheckpointReason::Manual)
+        .skip(MAX_AUTO_CHECKPOINTS);
+    for checkpoint in stale {
+        if let Err(e) = remove_checkpoint(pool, git, checkpoint, repos).await {
+            warn!("Failed to prune checkpoint {}: {}", checkpoint.id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use db::{
+        DBService,
+        models::{workspace::Workspace, workspace_repo::CreateWorkspaceRepo},
+        test_utils::{create_project, create_task, create_workspace},
+    };
+    use git::GitCli;
+    use tempfile::TempDir;
+    use utils::diff::DiffChangeKind;
+
+    use super::*;
+
+    struct Fixture {
+        _root: TempDir,
+        db: DBService,
+        git: GitService,
+        repo_path: PathBuf,
+        workspace_dir: PathBuf,
+        workspace: Workspace,
+    }
+
+    impl Fixture {
+        /// A workspace with one repo, `app`, whose worktree has `tracked.txt` committed.
+        async fn new() -> Self {
+            let root = TempDir::new().unwrap();
+            let repo_path = root.path().join("repos").join("app");
+            let workspace_dir = root.path().join("workspace");
+            let git = GitService::new();
+            git.initialize_repo_with_main_branch(&repo_path).unwrap();
+            let cli = GitCli::new();
+            cli.git(&repo_path, ["config", "user.name", "Test User"])
+                .unwrap();
+            cli.git(&repo_path, ["config", "user.email", "test@example.com"])
+                .unwrap();
+            cli.git(
+                &repo_path,
+                [
+                    "worktree",
+                    "add",
+                    "-b",
+                    "vk/checkpoints",
+                    workspace_dir.join("app").to_str().unwrap(),
+                    "main",
+                ],
+            )
+            .unwrap();
+
+            let db = DBService::new_in_memory().await.unwrap();
+            let project = create_project(&db.pool, "checkpoints").await;
+            let task = create_task(&db.pool, project.id, "checkpoints").await;
+            let workspace = create_workspace(&db.pool, task.id, "vk/checkpoints").await;
+            let repo = Repo::find_or_create(&db.pool, &repo_path, "app")
+                .await
+                .unwrap();
+            WorkspaceRepo::create_many(
+                &db.pool,
+                workspace.id,
+                &[CreateWorkspaceRepo {
+                    repo_id: repo.id,
+                    target_branch: "main".to_string(),
+                }],
+            )
+            .await
+            .unwrap();
+
+            let fixture = Self {
+                _root: root,
+                db,
+                git,
+                repo_path,
+                workspace_dir,
+                workspace,
+            };
+            fixture.write("tracked.txt", "committed\n");
+            fixture.worktree_git(&["add", "tracked.txt"]);
+            fixture.worktree_git(&["commit", "-m", "Add tracked.txt"]);
+            fixture
+        }
+
+        fn worktree(&self) -> PathBuf {
+            self.workspace_dir.join("app")
+        }
+
+        fn worktree_git(&self, args: &[&str]) -> String {
+            GitCli::new().git(&self.worktree(), args).unwrap()
+        }
+
+        fn write(&self, file: &str, content: &str) {
+            std::fs::write(self.worktree().join(file), content).unwrap();
+        }
+
+        fn read(&self, file: &str) -> Option<String> {
+            std::fs::read_to_string(self.worktree().join(file)).ok()
+        }
+
+        fn has_ref(&self, checkpoint_id: Uuid) -> bool {
+            let refname = WorkspaceCheckpoint::ref_name(checkpoint_id);
+            GitCli::new()
+                .git(
+                    &self.repo_path,
+                    ["rev-parse", "--verify", "--quiet", refname.as_str()],
+                )
+                .is_ok()
+        }
+
+        async fn checkpoint(&self, reason: CheckpointReason) -> WorkspaceCheckpointWithRepos {
+            create_checkpoint(
+                &self.db.pool,
+                &self.git,
+                self.workspace.id,
+                &self.workspace_dir,
+                None,
+                reason,
+            )
+            .await
+            .unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn restores_uncommitted_and_untracked_files() {
+        let fixture = Fixture::new().await;
+        let head = fixture.worktree_git(&["rev-parse", "HEAD"]);
+        fixture.write("tracked.txt", "edited\n");
+        fixture.write("notes.txt", "untracked\n");
+        let checkpoint = fixture.checkpoint(CheckpointReason::Manual).await;
+        assert_eq!(checkpoint.checkpoint.name, "Checkpoint");
+        assert_eq!(checkpoint.repos[0].head_commit, head.trim());
+
+        fixture.write("tracked.txt", "later\n");
+        fixture.worktree_git(&["commit", "-am", "Later change"]);
+        std::fs::remove_file(fixture.worktree().join("notes.txt")).unwrap();
+        fixture.write("scratch.txt", "added after the checkpoint\n");
+
+        let backup = restore_checkpoint(
+            &fixture.db.pool,
+            &fixture.git,
+            &checkpoint.checkpoint,
+            &fixture.workspace_dir,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(fixture.worktree_git(&["rev-parse", "HEAD"]), head);
+        assert_eq!(fixture.read("tracked.txt").as_deref(), Some("edited\n"));
+        assert_eq!(fixture.read("notes.txt").as_deref(), Some("untracked\n"));
+        assert_eq!(fixture.read("scratch.txt"), None);
+        // The restore itself can be undone
+        assert_eq!(backup.checkpoint.reason, CheckpointReason::BeforeRestore);
+        assert!(fixture.has_ref(backup.checkpoint.id));
+    }
+
+    #[tokio::test]
+    async fn diffs_worktree_against_checkpoint() {
+        let fixture = Fixture::new().await;
+        let checkpoint = fixture.checkpoint(CheckpointReason::Manual).await;
+        fixture.write("tracked.txt", "edited\n");
+        fixture.write("notes.txt", "untracked\n");
+
+        let diffs = diff_checkpoint(
+            &fixture.db.pool,
+            &fixture.git,
+            &checkpoint.checkpoint,
+            &fixture.workspace_dir,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].repo_name, "app");
+        let mut changes: Vec<_> = diffs[0]
+            .diffs
+            .iter()
+            .map(|diff| (diff.new_path.clone().unwrap(), diff.change.clone()))
+            .collect();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        assert!(matches!(
+            changes.as_slice(),
+            [
+                (notes, DiffChangeKind::Added),
+                (tracked, DiffChangeKind::Modified),
+            ] if notes == "notes.txt" && tracked == "tracked.txt"
+        ));
+    }
+
+    #[tokio::test]
+    async fn prunes_oldest_automatic_checkpoints() {
+        let fixture = Fixture::new().await;
+        let manual = fixture.checkpoint(CheckpointReason::Manual).await;
+        let oldest = fixture.checkpoint(CheckpointReason::BeforeRebase).await;
+        let mut newest = Vec::new();
+        for _ in 0..MAX_AUTO_CHECKPOINTS {
+            let checkpoint = create_auto_checkpoint(
+                &fixture.db.pool,
+                &fixture.git,
+                fixture.workspace.id,
+                &fixture.workspace_dir,
+                CheckpointReason::BeforeReset,
+            )
+            .await
+            .unwrap();
+            newest.push(checkpoint.checkpoint.id);
+        }
+
+        let listed = list_checkpoints(&fixture.db.pool, fixture.workspace.id)
+            .await
+            .unwrap();
+        let ids: Vec<_> = listed.iter().map(|c| c.checkpoint.id).collect();
+        let expected: Vec<_> = newest
+            .iter()
+            .rev()
+            .copied()
+            .chain([manual.checkpoint.id])
+            .collect();
+        assert_eq!(ids, expected);
+        assert!(!fixture.has_ref(oldest.checkpoint.id));
+        assert!(fixture.has_ref(manual.checkpoint.id));
+    }
+
+    #[tokio::test]
+    async fn deleting_a_checkpoint_drops_its_ref() {
+        let fixture = Fixture::new().await;
+        let checkpoint = fixture.checkpoint(CheckpointReason::Manual).await;
+        assert!(fixture.has_ref(checkpoint.checkpoint.id));
+
+        delete_checkpoint(&fixture.db.pool, &fixture.git, &checkpoint.checkpoint)
+            .await
+            .unwrap();
+
+        assert!(!fixture.has_ref(checkpoint.checkpoint.id));
+        assert!(
+            list_checkpoints(&fixture.db.pool, fixture.workspace.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -36,6 +36,11 @@ pub fn credentials_path() -> std::path::PathBuf {
     asset_dir().join("credentials.json")
 }
 
+/// Local master key for stored secrets, used when the OS keyring isn't available.
+pub fn secrets_key_path() -> std::path::PathBuf {
+    asset_dir().join("secrets.key")
+}
+
 #[derive(RustEmbed)]
 #[folder = "../../assets/sounds"]
 pub struct SoundAssets;
@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback } from 'react';
 import { useTranslation } from 'react-i18next';
+import { useQuery } from '@tanstack/react-query';
 import {
   Play,
   Square,
@@ -46,6 +47,20 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
 
   const { logs, error: logsError } = useLogStream(selectedProcess?.id ?? '');
 
+  // Refetched when the process finishes, to pick up the final counts
+  const { data: redactions } = useQuery({
+    queryKey: [
+      'process-redactions',
+      selectedProcess?.id,
+      selectedProcess?.status,
+    ],
+    queryFn: () => executionProcessesApi.getRedactions(selectedProcess!.id),
+    enabled: !!selectedProcess,
+  });
+  const redactedCount = redactions
+    ? Object.values(redactions).reduce((sum, count) => sum + count, 0)
+    : 0;
+
   useEffect(() => {
     setLocalProcessDetails({});
     setLoadingProcessId(null);
@@ -281,6 +296,14 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
               {t('processes.detailsTitle')}
             </h2>
             <div className="flex items-center gap-2">
+              {redactedCount > 0 && (
+                <span
+                  className="text-xs text-muted-foreground"
+                  title={t('processes.redactedTooltip')}
+                >
+                  {t('processes.redacted', { count: redactedCount })}
+                </span>
+              )}
               <button
                 onClick={handleCopyLogs}
                 disabled={logs.length === 0}
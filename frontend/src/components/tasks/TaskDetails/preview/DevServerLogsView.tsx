@@ -2,7 +2,7 @@ import { useState, useEffect } from 'react';
 import { useTranslation } from 'react-i18next';
 import { Terminal, ChevronDown } from 'lucide-react';
 import ProcessLogsViewer from '../ProcessLogsViewer';
-import { getDevServerWorkingDir } from '@/lib/devServerUtils';
+import { getDevServerLabel } from '@/lib/devServerUtils';
 import { cn } from '@/lib/utils';
 import { ExecutionProcess } from 'shared/types';
 
@@ -84,7 +84,7 @@ export function DevServerLogsView({
                   )}
                   onClick={() => setActiveProcessId(process.id)}
                 >
-                  {getDevServerWorkingDir(process) ?? 'Dev Server'}
+                  {getDevServerLabel(process) ?? 'Dev Server'}
                 </button>
               ))}
             </div>
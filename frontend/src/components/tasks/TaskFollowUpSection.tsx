@@ -1,10 +1,10 @@
 import {
   Loader2,
+  PauseCircle,
   Send,
   StopCircle,
   AlertCircle,
   Clock,
-  X,
   Paperclip,
   Terminal,
   MessageSquare,
@@ -29,6 +29,7 @@ import { ScratchType, type TaskWithAttemptStatus } from 'shared/types';
 import { useBranchStatus } from '@/hooks';
 import { useAttemptRepo } from '@/hooks/useAttemptRepo';
 import { useAttemptExecution } from '@/hooks/useAttemptExecution';
+import { useAgentTurnControls } from '@/hooks/useAgentTurnControls';
 import { useUserSystem } from '@/components/ConfigProvider';
 import { cn } from '@/lib/utils';
 //
@@ -42,23 +43,19 @@ import { useProject } from '@/contexts/ProjectContext';
 import { VariantSelector } from '@/components/tasks/VariantSelector';
 import { useAttemptBranch } from '@/hooks/useAttemptBranch';
 import { FollowUpConflictSection } from '@/components/tasks/follow-up/FollowUpConflictSection';
+import { QueuedMessageList } from '@/components/tasks/follow-up/QueuedMessageList';
 import { ClickedElementsBanner } from '@/components/tasks/ClickedElementsBanner';
 import WYSIWYGEditor from '@/components/ui/wysiwyg';
 import { useRetryUi } from '@/contexts/RetryUiContext';
 import { useFollowUpSend } from '@/hooks/useFollowUpSend';
 import { useVariant } from '@/hooks/useVariant';
-import type {
-  DraftFollowUpData,
-  ExecutorProfileId,
-  QueueStatus,
-} from 'shared/types';
+import type { DraftFollowUpData } from 'shared/types';
 import { getLatestConfigFromProcesses } from '@/utils/executor';
 import { buildResolveConflictsInstructions } from '@/lib/conflicts';
 import { useTranslation } from 'react-i18next';
 import { useScratch } from '@/hooks/useScratch';
 import { useDebouncedCallback } from '@/hooks/useDebouncedCallback';
-import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
-import { queueApi } from '@/lib/api';
+import { useSessionQueueInteraction } from '@/hooks/useSessionQueueInteraction';
 import { imagesApi, attemptsApi } from '@/lib/api';
 import { PrCommentsDialog } from '@/components/dialogs/tasks/PrCommentsDialog';
 import type { NormalizedComment } from '@/components/ui/wysiwyg/nodes/pr-comment-node';
@@ -240,58 +237,18 @@ export function TaskFollowUpSection({
   const { activeRetryProcessId } = useRetryUi();
   const isRetryActive = !!activeRetryProcessId;
 
-  // Queue status for queuing follow-up messages while agent is running
-  const queryClient = useQueryClient();
-  const QUEUE_STATUS_KEY = 'queue-status';
-
+  // Follow-ups queued to run after the current execution
   const {
-    data: queueStatus = { status: 'empty' as const },
-    refetch: refreshQueueStatus,
-  } = useQuery<QueueStatus>({
-    queryKey: [QUEUE_STATUS_KEY, sessionId],
-    queryFn: () => queueApi.getStatus(sessionId!),
-    enabled: !!sessionId,
-  });
-
-  const isQueued = queueStatus.status === 'queued';
-  const queuedMessage = isQueued
-    ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>).message
-    : null;
-
-  const queueMutation = useMutation({
-    mutationFn: (data: DraftFollowUpData) => queueApi.queue(sessionId!, data),
-    onSuccess: (status) => {
-      queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
-    },
-  });
-
-  const cancelMutation = useMutation({
-    mutationFn: () => queueApi.cancel(sessionId!),
-    onSuccess: (status) => {
-      queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
-    },
-  });
-
-  const queueMessage = useCallback(
-    async (message: string, executorProfileId: ExecutorProfileId) => {
-      if (!sessionId) return;
-      await queueMutation.mutateAsync({
-        message,
-        executor_config: {
-          executor: executorProfileId.executor,
-          variant: executorProfileId.variant,
-        },
-      });
-    },
-    [sessionId, queueMutation]
-  );
-
-  const cancelQueue = useCallback(async () => {
-    if (!sessionId) return;
-    await cancelMutation.mutateAsync();
-  }, [sessionId, cancelMutation]);
-
-  const isQueueLoading = queueMutation.isPending || cancelMutation.isPending;
+    queuedMessages,
+    queuePause,
+    isQueueLoading,
+    queueMessage,
+    updateQueuedMessage,
+    removeQueuedMessage,
+    moveQueuedMessage,
+    resumeQueue,
+    refreshQueueStatus,
+  } = useSessionQueueInteraction({ sessionId });
 
   // Track previous process count to detect new processes
   const prevProcessCountRef = useRef(processes.length);
@@ -312,21 +269,8 @@ export function TaskFollowUpSection({
     // Refresh when a new process starts (could be queued message consumption or follow-up)
     if (processes.length > prevCount) {
       refreshQueueStatus();
-      // Re-sync local message from current scratch state
-      // If scratch was deleted, scratchData will be undefined, so localMessage becomes ''
-      setLocalMessage(scratchData?.message ?? '');
     }
-  }, [
-    isAttemptRunning,
-    workspaceId,
-    processes.length,
-    refreshQueueStatus,
-    scratchData?.message,
-  ]);
-
-  // When queued, display the queued message content so user can edit it
-  const displayMessage =
-    isQueued && queuedMessage ? queuedMessage.data.message : localMessage;
+  }, [isAttemptRunning, workspaceId, processes.length, refreshQueueStatus]);
 
   // Check if there's a pending approval - users shouldn't be able to type during approvals
   const { entries } = useEntries();
@@ -356,7 +300,6 @@ export function TaskFollowUpSection({
       onAfterSendCleanup: () => {
         cancelDebouncedSave(); // Cancel any pending debounced save to avoid race condition
         setLocalMessage(''); // Clear local state immediately
-        // Scratch deletion is handled by the backend when the queued message is consumed
       },
     });
 
@@ -368,7 +311,6 @@ export function TaskFollowUpSection({
 
     if (isRetryActive) return false; // disable typing while retry editor is active
     if (hasPendingApproval) return false; // disable typing during approval
-    // Note: isQueued no longer blocks typing - editing auto-cancels the queue
     return true;
   }, [
     workspaceId,
@@ -431,11 +373,6 @@ export function TaskFollowUpSection({
       return;
     }
 
-    // Cancel any pending debounced save and save immediately before queueing
-    // This prevents the race condition where the debounce fires after queueing
-    cancelDebouncedSave();
-    await saveToScratch(localMessage, selectedVariant);
-
     // Combine all the content that would be sent (same as follow-up send)
     const { prompt } = buildAgentPrompt(
       localMessage,
@@ -443,12 +380,18 @@ export function TaskFollowUpSection({
         Boolean
       )
     );
-    if (latestProfileId) {
-      await queueMessage(prompt, {
-        executor: latestProfileId.executor,
-        variant: selectedVariant,
-      });
-    }
+    if (!latestProfileId) return;
+    await queueMessage(prompt, {
+      executor: latestProfileId.executor,
+      variant: selectedVariant,
+    });
+
+    // The queued message owns the prompt now, so start a fresh draft
+    cancelDebouncedSave();
+    setLocalMessage('');
+    await saveToScratch('', selectedVariant);
+    clearComments();
+    clearClickedElements();
   }, [
     localMessage,
     conflictResolutionInstructions,
@@ -459,22 +402,76 @@ export function TaskFollowUpSection({
     queueMessage,
     cancelDebouncedSave,
     saveToScratch,
+    clearComments,
+    clearClickedElements,
+  ]);
+
+  const {
+    canSendInput,
+    canInterrupt,
+    sendInput,
+    interrupt,
+    isSendingInput,
+    isInterrupting,
+  } = useAgentTurnControls({
+    processes,
+    agent: latestProfileId?.executor,
+  });
+
+  // Send the message into the running turn instead of queueing it
+  const handleSendInput = useCallback(async () => {
+    const { prompt } = buildAgentPrompt(
+      localMessage,
+      [conflictResolutionInstructions, clickedMarkdown, reviewMarkdown].filter(
+        Boolean
+      )
+    );
+    if (!prompt.trim()) return;
+    try {
+      await sendInput(prompt);
+    } catch (error) {
+      console.error('Failed to send message to the running agent:', error);
+      return;
+    }
+
+    cancelDebouncedSave();
+    setLocalMessage('');
+    await saveToScratch('', selectedVariant);
+    clearComments();
+    clearClickedElements();
+  }, [
+    localMessage,
+    conflictResolutionInstructions,
+    reviewMarkdown,
+    clickedMarkdown,
+    sendInput,
+    selectedVariant,
+    cancelDebouncedSave,
+    saveToScratch,
+    clearComments,
+    clearClickedElements,
   ]);
 
+  const handleInterrupt = useCallback(async () => {
+    try {
+      await interrupt();
+    } catch (error) {
+      console.error('Failed to interrupt the running agent:', error);
+    }
+  }, [interrupt]);
+
   // Keyboard shortcut handler - send follow-up or queue depending on state
   const handleSubmitShortcut = useCallback(
     (e?: KeyboardEvent) => {
       e?.preventDefault();
       if (isAttemptRunning) {
-        // When running, CMD+Enter queues the message (if not already queued)
-        if (!isQueued) {
-          handleQueueMessage();
-        }
+        // When running, CMD+Enter appends the message to the queue
+        handleQueueMessage();
       } else {
         onSendFollowUp();
       }
     },
-    [isAttemptRunning, isQueued, handleQueueMessage, onSendFollowUp]
+    [isAttemptRunning, handleQueueMessage, onSendFollowUp]
   );
 
   // Ref to access setFollowUpMessage without adding it as a dependency
@@ -489,19 +486,6 @@ export function TaskFollowUpSection({
     followUpErrorRef.current = followUpError;
   }, [followUpError]);
 
-  // Helper to get current queue state from cache (avoids ref-sync pattern)
-  const getQueueState = useCallback(() => {
-    const status = queryClient.getQueryData<QueueStatus>([
-      QUEUE_STATUS_KEY,
-      sessionId,
-    ]);
-    const queued = status?.status === 'queued';
-    const message = queued
-      ? (status as Extract<QueueStatus, { status: 'queued' }>).message
-      : null;
-    return { isQueued: queued, queuedMessage: message };
-  }, [queryClient, sessionId]);
-
   // Handle image paste - upload to container and insert markdown
   const handlePasteFiles = useCallback(
     async (files: File[]) => {
@@ -513,34 +497,19 @@ export function TaskFollowUpSection({
           // Append markdown image to current message
           const imageMarkdown = `![${response.original_name}](${response.file_path})`;
 
-          // If queued, cancel queue and use queued message as base (same as editor change behavior)
-          const {
-            isQueued: currentlyQueued,
-            queuedMessage: currentQueuedMessage,
-          } = getQueueState();
-          if (currentlyQueued && currentQueuedMessage) {
-            cancelMutation.mutate();
-            const base = currentQueuedMessage.data.message;
-            const newMessage = base
-              ? `${base}\n\n${imageMarkdown}`
+          setLocalMessage((prev) => {
+            const newMessage = prev
+              ? `${prev}\n\n${imageMarkdown}`
               : imageMarkdown;
-            setLocalMessage(newMessage);
-            setFollowUpMessageRef.current(newMessage);
-          } else {
-            setLocalMessage((prev) => {
-              const newMessage = prev
-                ? `${prev}\n\n${imageMarkdown}`
-                : imageMarkdown;
-              setFollowUpMessageRef.current(newMessage); // Debounced save to scratch
-              return newMessage;
-            });
-          }
+            setFollowUpMessageRef.current(newMessage); // Debounced save to scratch
+            return newMessage;
+          });
         } catch (error) {
           console.error('Failed to upload image:', error);
         }
       }
     },
-    [workspaceId, getQueueState, cancelMutation]
+    [workspaceId]
   );
 
   // Attachment button - file input ref and handlers
@@ -598,37 +567,22 @@ export function TaskFollowUpSection({
       const markdown = markdownBlocks.join('\n\n');
 
       // Same pattern as image paste
-      const { isQueued: currentlyQueued, queuedMessage: currentQueuedMessage } =
-        getQueueState();
-      if (currentlyQueued && currentQueuedMessage) {
-        cancelMutation.mutate();
-        const base = currentQueuedMessage.data.message;
-        const newMessage = base ? `${base}\n\n${markdown}` : markdown;
-        setLocalMessage(newMessage);
+      setLocalMessage((prev) => {
+        const newMessage = prev ? `${prev}\n\n${markdown}` : markdown;
         setFollowUpMessageRef.current(newMessage);
-      } else {
-        setLocalMessage((prev) => {
-          const newMessage = prev ? `${prev}\n\n${markdown}` : markdown;
-          setFollowUpMessageRef.current(newMessage);
-          return newMessage;
-        });
-      }
+        return newMessage;
+      });
     }
-  }, [workspaceId, getSelectedRepoId, getQueueState, cancelMutation]);
+  }, [workspaceId, getSelectedRepoId]);
 
   // Stable onChange handler for WYSIWYGEditor
   const handleEditorChange = useCallback(
     (value: string) => {
-      // Auto-cancel queue when user starts editing
-      const { isQueued: currentlyQueued } = getQueueState();
-      if (currentlyQueued) {
-        cancelMutation.mutate();
-      }
       setLocalMessage(value); // Immediate update for UI responsiveness
       setFollowUpMessageRef.current(value); // Debounced save to scratch
       if (followUpErrorRef.current) setFollowUpError(null);
     },
-    [setFollowUpError, getQueueState, cancelMutation]
+    [setFollowUpError]
   );
 
   // Memoize placeholder to avoid re-renders
@@ -744,18 +698,16 @@ export function TaskFollowUpSection({
             {/* Clicked elements notice and actions */}
             <ClickedElementsBanner />
 
-            {/* Queued message indicator */}
-            {isQueued && queuedMessage && (
-              <div className="flex items-center gap-2 text-sm text-muted-foreground bg-muted p-3 rounded-md border">
-                <Clock className="h-4 w-4 flex-shrink-0" />
-                <div className="font-medium">
-                  {t(
-                    'followUp.queuedMessage',
-                    'Message queued - will execute when current run finishes'
-                  )}
-                </div>
-              </div>
-            )}
+            {/* Follow-ups waiting behind the current run */}
+            <QueuedMessageList
+              messages={queuedMessages}
+              pause={queuePause}
+              disabled={isQueueLoading}
+              onMove={moveQueuedMessage}
+              onUpdate={updateQueuedMessage}
+              onRemove={removeQueuedMessage}
+              onResume={resumeQueue}
+            />
 
             <div
               className="flex flex-col gap-2"
@@ -769,7 +721,7 @@ export function TaskFollowUpSection({
             >
               <WYSIWYGEditor
                 placeholder={editorPlaceholder}
-                value={displayMessage}
+                value={localMessage}
                 onChange={handleEditorChange}
                 disabled={!isEditable}
                 onPasteFiles={handlePasteFiles}
@@ -868,43 +820,60 @@ export function TaskFollowUpSection({
 
           {isAttemptRunning ? (
             <div className="flex items-center gap-2">
-              {/* Queue/Cancel Queue button when running */}
-              {isQueued ? (
+              {canSendInput && (
                 <Button
-                  onClick={cancelQueue}
-                  disabled={isQueueLoading}
+                  onClick={handleSendInput}
+                  disabled={
+                    isSendingInput ||
+                    (!localMessage.trim() &&
+                      !conflictResolutionInstructions &&
+                      !reviewMarkdown &&
+                      !clickedMarkdown)
+                  }
                   size="sm"
-                  variant="outline"
                 >
-                  {isQueueLoading ? (
+                  {isSendingInput ? (
                     <Loader2 className="animate-spin h-4 w-4 mr-2" />
                   ) : (
-                    <>
-                      <X className="h-4 w-4 mr-2" />
-                      {t('followUp.cancelQueue', 'Cancel Queue')}
-                    </>
+                    <Send className="h-4 w-4 mr-2" />
                   )}
+                  {t('conversation.actions.sendNow')}
                 </Button>
-              ) : (
+              )}
+              {/* Queue button when running - appends to the queue */}
+              <Button
+                onClick={handleQueueMessage}
+                disabled={
+                  isQueueLoading ||
+                  (!localMessage.trim() &&
+                    !conflictResolutionInstructions &&
+                    !reviewMarkdown &&
+                    !clickedMarkdown)
+                }
+                size="sm"
+              >
+                {isQueueLoading ? (
+                  <Loader2 className="animate-spin h-4 w-4 mr-2" />
+                ) : (
+                  <>
+                    <Clock className="h-4 w-4 mr-2" />
+                    {t('followUp.queue', 'Queue')}
+                  </>
+                )}
+              </Button>
+              {canInterrupt && (
                 <Button
-                  onClick={handleQueueMessage}
-                  disabled={
-                    isQueueLoading ||
-                    (!localMessage.trim() &&
-                      !conflictResolutionInstructions &&
-                      !reviewMarkdown &&
-                      !clickedMarkdown)
-                  }
+                  onClick={handleInterrupt}
+                  disabled={isInterrupting}
                   size="sm"
+                  variant="outline"
                 >
-                  {isQueueLoading ? (
+                  {isInterrupting ? (
                     <Loader2 className="animate-spin h-4 w-4 mr-2" />
                   ) : (
-                    <>
-                      <Clock className="h-4 w-4 mr-2" />
-                      {t('followUp.queue', 'Queue')}
-                    </>
+                    <PauseCircle className="h-4 w-4 mr-2" />
                   )}
+                  {t('conversation.actions.interrupt')}
                 </Button>
               )}
               <Button
@@ -0,0 +1,167 @@
+import { useState } from 'react';
+import {
+  ArrowDown,
+  ArrowUp,
+  Clock,
+  Pause,
+  Pencil,
+  Play,
+  Trash2,
+} from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import { Button } from '@/components/ui/button';
+import { Textarea } from '@/components/ui/textarea';
+import type { QueuedMessage, QueuePause } from 'shared/types';
+
+type Props = {
+  messages: QueuedMessage[];
+  pause: QueuePause | null;
+  disabled: boolean;
+  onMove: (messageId: string, offset: -1 | 1) => void;
+  onUpdate: (queued: QueuedMessage, message: string) => void;
+  onRemove: (messageId: string) => void;
+  onResume: () => void;
+};
+
+/** Follow-ups queued behind the current run, in the order they will run */
+export function QueuedMessageList({
+  messages,
+  pause,
+  disabled,
+  onMove,
+  onUpdate,
+  onRemove,
+  onResume,
+}: Props) {
+  const { t } = useTranslation('tasks');
+  const [editingId, setEditingId] = useState<string | null>(null);
+  const [draft, setDraft] = useState('');
+
+  if (messages.length === 0) return null;
+
+  const startEdit = (queued: QueuedMessage) => {
+    setEditingId(queued.id);
+    setDraft(queued.message);
+  };
+
+  const saveEdit = (queued: QueuedMessage) => {
+    if (draft.trim() && draft !== queued.message) {
+      onUpdate(queued, draft);
+    }
+    setEditingId(null);
+  };
+
+  return (
+    <div className="flex flex-col gap-2 text-sm bg-muted p-3 rounded-md border">
+      <div className="flex items-center gap-2 text-muted-foreground">
+        <Clock className="h-4 w-4 flex-shrink-0" />
+        <span className="font-medium">
+          {t('followUp.queuedCount', { count: messages.length })}
+        </span>
+      </div>
+
+      {pause && (
+        <div className="flex items-center gap-2 text-warning">
+          <Pause className="h-4 w-4 flex-shrink-0" />
+          <span className="flex-1 min-w-0 truncate">
+            {t('followUp.queuePaused', { reason: pause.reason })}
+          </span>
+          <Button
+            size="sm"
+            variant="outline"
+            onClick={onResume}
+            disabled={disabled}
+          >
+            <Play className="h-4 w-4 mr-2" />
+            {t('followUp.resumeQueue')}
+          </Button>
+        </div>
+      )}
+
+      <ol className="flex flex-col gap-1">
+        {messages.map((queued, index) => (
+          <li
+            key={queued.id}
+            className="flex items-start gap-2 bg-background p-2 rounded border"
+          >
+            <span className="text-muted-foreground tabular-nums">
+              {index + 1}.
+            </span>
+            {editingId === queued.id ? (
+              <div className="flex-1 flex flex-col gap-2">
+                <Textarea
+                  value={draft}
+                  onChange={(e) => setDraft(e.target.value)}
+                  className="min-h-[60px]"
+                  autoFocus
+                />
+                <div className="flex gap-2 justify-end">
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={() => setEditingId(null)}
+                  >
+                    {t('followUp.cancelEditQueued')}
+                  </Button>
+                  <Button
+                    size="sm"
+                    onClick={() => saveEdit(queued)}
+                    disabled={disabled || !draft.trim()}
+                  >
+                    {t('followUp.saveQueued')}
+                  </Button>
+                </div>
+              </div>
+            ) : (
+              <>
+                <p
+                  className="flex-1 min-w-0 whitespace-pre-wrap break-words line-clamp-3"
+                >
+                  {queued.message}
+                </p>
+                <div className="flex items-center gap-1">
+                  <Button
+                    variant="icon"
+                    onClick={() => onMove(queued.id, -1)}
+                    disabled={disabled || index === 0}
+                    title={t('followUp.moveUp')}
+                    aria-label={t('followUp.moveUp')}
+                  >
+                    <ArrowUp className="h-4 w-4" />
+                  </Button>
+                  <Button
+                    variant="icon"
+                    onClick={() => onMove(queued.id, 1)}
+                    disabled={disabled || index === messages.length - 1}
+                    title={t('followUp.moveDown')}
+                    aria-label={t('followUp.moveDown')}
+                  >
+                    <ArrowDown className="h-4 w-4" />
+                  </Button>
+                  <Button
+                    variant="icon"
+                    onClick={() => startEdit(queued)}
+                    disabled={disabled}
+                    title={t('followUp.edit')}
+                    aria-label={t('followUp.edit')}
+                  >
+                    <Pencil className="h-4 w-4" />
+                  </Button>
+                  <Button
+                    variant="icon"
+                    onClick={() => onRemove(queued.id)}
+                    disabled={disabled}
+                    title={t('followUp.removeQueued')}
+                    aria-label={t('followUp.removeQueued')}
+                  >
+                    <Trash2 className="h-4 w-4" />
+                  </Button>
+                </div>
+              </>
+            )}
+          </li>
+        ))}
+      </ol>
+    </div>
+  );
+}
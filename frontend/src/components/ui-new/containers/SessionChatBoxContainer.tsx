@@ -8,6 +8,7 @@ import {
   type BaseCodingAgent,
 } from 'shared/types';
 import { useAttemptExecution } from '@/hooks/useAttemptExecution';
+import { useAgentTurnControls } from '@/hooks/useAgentTurnControls';
 import { useAttemptRepo } from '@/hooks/useAttemptRepo';
 import { useExecutionProcesses } from '@/hooks/useExecutionProcesses';
 import { useUserSystem } from '@/components/ConfigProvider';
@@ -412,8 +413,7 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
   // Queue interaction
   const {
     isQueued,
-    queuedMessage,
-    queuedConfig,
+    queuedMessages,
     isQueueLoading,
     queueMessage,
     cancelQueue,
@@ -490,12 +490,13 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
 
     const { prompt } = buildAgentPrompt(localMessage, [reviewMarkdown]);
 
-    cancelDebouncedSave();
-    await saveToScratch(localMessage, executorConfig);
     await queueMessage(prompt, executorConfig);
 
-    // Clear local state after queueing (same as handleSend)
+    // Clear local state after queueing (same as handleSend); the draft is
+    // emptied rather than deleted so the executor choice survives
+    cancelDebouncedSave();
     setLocalMessage('');
+    await saveToScratch('', executorConfig);
     clearUploadedImages();
     reviewContext?.clearComments();
   }, [
@@ -510,10 +511,49 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
     reviewContext,
   ]);
 
+  const { canSendInput, canInterrupt, sendInput, interrupt } =
+    useAgentTurnControls({ processes, agent: effectiveExecutor });
+
+  // Send the message into the running turn instead of queueing it
+  const handleSendInput = useCallback(async () => {
+    if (!localMessage.trim() && !reviewMarkdown) return;
+
+    const { prompt } = buildAgentPrompt(localMessage, [reviewMarkdown]);
+    try {
+      await sendInput(prompt);
+    } catch (error) {
+      console.error('Failed to send message to the running agent:', error);
+      return;
+    }
+
+    cancelDebouncedSave();
+    setLocalMessage('');
+    if (executorConfig) await saveToScratch('', executorConfig);
+    clearUploadedImages();
+    reviewContext?.clearComments();
+  }, [
+    localMessage,
+    reviewMarkdown,
+    sendInput,
+    cancelDebouncedSave,
+    setLocalMessage,
+    executorConfig,
+    saveToScratch,
+    clearUploadedImages,
+    reviewContext,
+  ]);
+
+  const handleInterrupt = useCallback(async () => {
+    try {
+      await interrupt();
+    } catch (error) {
+      console.error('Failed to interrupt the running agent:', error);
+    }
+  }, [interrupt]);
+
   // Editor change handler
   const handleEditorChange = useCallback(
     (value: string) => {
-      if (isQueued) cancelQueue();
       if (executorConfig) {
         handleMessageChange(value, executorConfig);
       } else {
@@ -522,8 +562,6 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
       if (sendError) clearError();
     },
     [
-      isQueued,
-      cancelQueue,
       handleMessageChange,
       executorConfig,
       sendError,
@@ -556,23 +594,6 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
     feedbackContext?.exitFeedbackMode();
   }, [feedbackContext]);
 
-  // Handle cancel queue - restore message to editor
-  const handleCancelQueue = useCallback(async () => {
-    if (queuedMessage) {
-      setLocalMessage(queuedMessage);
-    }
-    if (queuedConfig) {
-      setExecutorOverrides(queuedConfig);
-    }
-    await cancelQueue();
-  }, [
-    queuedMessage,
-    queuedConfig,
-    setLocalMessage,
-    setExecutorOverrides,
-    cancelQueue,
-  ]);
-
   // Message edit retry mutation
   const editRetryMutation = useMessageEditRetry(sessionId ?? '', () => {
     // On success, clear edit mode and reset editor
@@ -746,18 +767,10 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
   });
 
   // During loading, render with empty editor to preserve container UI
-  // In approval mode, don't show queued message - it's for follow-up, not approval response
   const editorValue = useMemo(() => {
     if (isScratchLoading || !hasInitialValue) return '';
-    if (pendingApproval) return localMessage;
-    return queuedMessage ?? localMessage;
-  }, [
-    isScratchLoading,
-    hasInitialValue,
-    pendingApproval,
-    queuedMessage,
-    localMessage,
-  ]);
+    return localMessage;
+  }, [isScratchLoading, hasInitialValue, localMessage]);
 
   // In placeholder mode, render a disabled version to maintain visual structure
   if (mode === 'placeholder') {
@@ -815,8 +828,10 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
       actions={{
         onSend: handleSend,
         onQueue: handleQueueMessage,
-        onCancelQueue: handleCancelQueue,
+        onCancelQueue: cancelQueue,
         onStop: stopExecution,
+        onSendInput: canSendInput ? handleSendInput : undefined,
+        onInterrupt: canInterrupt ? handleInterrupt : undefined,
         onPasteFiles: uploadFiles,
       }}
       session={{
@@ -894,6 +909,7 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
             }
           : undefined
       }
+      queuedCount={queuedMessages.length}
       localImages={localImages}
       dropzone={{ getRootProps, getInputProps, isDragActive }}
       modelSelector={{
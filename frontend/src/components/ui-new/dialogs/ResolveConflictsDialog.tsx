@@ -152,6 +152,7 @@ const ResolveConflictsDialogImpl =
             retry_process_id: null,
             force_when_dirty: null,
             perform_git_reset: null,
+            preview_request_ids: null,
           });
 
           // Invalidate queries and wait for them to complete
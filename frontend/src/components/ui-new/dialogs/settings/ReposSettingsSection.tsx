@@ -6,7 +6,15 @@ import { GitBranchIcon, SpinnerIcon } from '@phosphor-icons/react';
 import { useRepoBranches } from '@/hooks/useRepoBranches';
 import { useScriptPlaceholders } from '@/hooks/useScriptPlaceholders';
 import { repoApi } from '@/lib/api';
-import type { Repo, UpdateRepo } from 'shared/types';
+import type {
+  CommitHookMode,
+  CommitMessageStyle,
+  ContextPackDelivery,
+  Repo,
+  RepoCommitPolicy,
+  RepoContextPack,
+  UpdateRepo,
+} from 'shared/types';
 import { SearchableDropdownContainer } from '../../containers/SearchableDropdownContainer';
 import {
   DropdownMenu,
@@ -32,7 +40,10 @@ interface RepoScriptsFormState {
   parallel_setup_script: boolean;
   cleanup_script: string;
   archive_script: string;
+  verification_script: string;
   copy_files: string;
+  template_files: string;
+  symlink_dirs: string;
   dev_server_script: string;
 }
 
@@ -45,11 +56,67 @@ function repoToFormState(repo: Repo): RepoScriptsFormState {
     parallel_setup_script: repo.parallel_setup_script,
     cleanup_script: repo.cleanup_script ?? '',
     archive_script: repo.archive_script ?? '',
+    verification_script: repo.verification_script ?? '',
     copy_files: repo.copy_files ?? '',
+    template_files: repo.template_files ?? '',
+    symlink_dirs: repo.symlink_dirs ?? '',
     dev_server_script: repo.dev_server_script ?? '',
   };
 }
 
+interface CommitFormState {
+  message_style: CommitMessageStyle;
+  squash_on_finish: boolean;
+  signoff: boolean;
+  gpg_sign: boolean;
+  hook_mode: CommitHookMode;
+  author_name: string;
+  author_email: string;
+}
+
+function policyToFormState(policy: RepoCommitPolicy): CommitFormState {
+  return {
+    message_style: policy.message_style,
+    squash_on_finish: policy.squash_on_finish,
+    signoff: policy.signoff,
+    gpg_sign: policy.gpg_sign,
+    hook_mode: policy.hook_mode,
+    author_name: policy.author_name ?? '',
+    author_email: policy.author_email ?? '',
+  };
+}
+
+interface ContextPackFormState {
+  enabled: boolean;
+  delivery: ContextPackDelivery;
+  token_budget: string;
+  notes: string;
+}
+
+function contextPackToFormState(pack: RepoContextPack): ContextPackFormState {
+  return {
+    enabled: pack.enabled,
+    delivery: pack.delivery,
+    token_budget: String(pack.token_budget),
+    notes: pack.notes ?? '',
+  };
+}
+
+const MESSAGE_STYLES: CommitMessageStyle[] = ['agent_summary', 'conventional'];
+const HOOK_MODES: CommitHookMode[] = ['run', 'skip', 'feedback'];
+const CONTEXT_PACK_DELIVERIES: ContextPackDelivery[] = ['file', 'prompt'];
+const TEMPLATE_PLACEHOLDERS = [
+  'port',
+  'port.<process>',
+  'workspace_id',
+  'workspace_short_id',
+  'branch',
+  'repo_name',
+  'db_name',
+]
+  .map((name) => `{{${name}}}`)
+  .join(', ');
+
 interface ReposSettingsSectionProps {
   initialState?: { repoId?: string };
 }
@@ -94,8 +161,22 @@ export function ReposSettingsSection({
 
   const [selectedRepo, setSelectedRepo] = useState<Repo | null>(null);
 
+  const { data: commitPolicy } = useQuery({
+    queryKey: ['repoCommitPolicy', selectedRepoId],
+    queryFn: () => repoApi.getCommitPolicy(selectedRepoId),
+    enabled: !!selectedRepoId,
+  });
+
+  const { data: contextPack } = useQuery({
+    queryKey: ['repoContextPack', selectedRepoId],
+    queryFn: () => repoApi.getContextPack(selectedRepoId),
+    enabled: !!selectedRepoId,
+  });
+
   // Form state
   const [draft, setDraft] = useState<RepoScriptsFormState | null>(null);
+  const [policyDraft, setPolicyDraft] = useState<CommitFormState | null>(null);
+  const [packDraft, setPackDraft] = useState<ContextPackFormState | null>(null);
   const [saving, setSaving] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [success, setSuccess] = useState(false);
@@ -104,10 +185,27 @@ export function ReposSettingsSection({
   const placeholders = useScriptPlaceholders();
 
   // Check for unsaved changes
+  const hasPolicyChanges = useMemo(() => {
+    if (!policyDraft || !commitPolicy) return false;
+    return !isEqual(policyDraft, policyToFormState(commitPolicy));
+  }, [policyDraft, commitPolicy]);
+
+  const hasPackChanges = useMemo(() => {
+    if (!packDraft || !contextPack) return false;
+    return !isEqual(packDraft, contextPackToFormState(contextPack));
+  }, [packDraft, contextPack]);
+
+  const packBudgetInvalid =
+    !!packDraft && !/^\d+$/.test(packDraft.token_budget.trim());
+
   const hasUnsavedChanges = useMemo(() => {
     if (!draft || !selectedRepo) return false;
-    return !isEqual(draft, repoToFormState(selectedRepo));
-  }, [draft, selectedRepo]);
+    return (
+      !isEqual(draft, repoToFormState(selectedRepo)) ||
+      hasPolicyChanges ||
+      hasPackChanges
+    );
+  }, [draft, selectedRepo, hasPolicyChanges, hasPackChanges]);
 
   // Handle repo selection
   const handleRepoSelect = useCallback(
@@ -125,6 +223,8 @@ export function ReposSettingsSection({
         setError(null);
       }
 
+      setPolicyDraft(null);
+      setPackDraft(null);
       setSelectedRepoId(id);
     },
     [hasUnsavedChanges, selectedRepoId, t]
@@ -152,8 +252,26 @@ export function ReposSettingsSection({
     setDraft(repoToFormState(nextRepo));
   }, [repos, selectedRepoId, hasUnsavedChanges]);
 
+  // Populate policy draft once the selected repo's policy has loaded
+  useEffect(() => {
+    if (!commitPolicy || commitPolicy.repo_id !== selectedRepoId) return;
+    if (hasPolicyChanges) return;
+    setPolicyDraft(policyToFormState(commitPolicy));
+  }, [commitPolicy, selectedRepoId, hasPolicyChanges]);
+
+  // Populate context pack draft once the selected repo's settings have loaded
+  useEffect(() => {
+    if (!contextPack || contextPack.repo_id !== selectedRepoId) return;
+    if (hasPackChanges) return;
+    setPackDraft(contextPackToFormState(contextPack));
+  }, [contextPack, selectedRepoId, hasPackChanges]);
+
   const handleSave = async () => {
     if (!draft || !selectedRepo) return;
+    if (hasPackChanges && packBudgetInvalid) {
+      setError(t('settings.repos.contextPack.tokenBudget.invalid'));
+      return;
+    }
 
     setSaving(true);
     setError(null);
@@ -167,7 +285,10 @@ export function ReposSettingsSection({
         setup_script: draft.setup_script.trim() || null,
         cleanup_script: draft.cleanup_script.trim() || null,
         archive_script: draft.archive_script.trim() || null,
+        verification_script: draft.verification_script.trim() || null,
         copy_files: draft.copy_files.trim() || null,
+        template_files: draft.template_files.trim() || null,
+        symlink_dirs: draft.symlink_dirs.trim() || null,
         parallel_setup_script: draft.parallel_setup_script,
         dev_server_script: draft.dev_server_script.trim() || null,
       };
@@ -178,6 +299,42 @@ export function ReposSettingsSection({
       queryClient.setQueryData(['repos'], (old: Repo[] | undefined) =>
         old?.map((r) => (r.id === updatedRepo.id ? updatedRepo : r))
       );
+
+      if (policyDraft && hasPolicyChanges) {
+        const updatedPolicy = await repoApi.updateCommitPolicy(
+          selectedRepo.id,
+          {
+            message_style: policyDraft.message_style,
+            squash_on_finish: policyDraft.squash_on_finish,
+            signoff: policyDraft.signoff,
+            gpg_sign: policyDraft.gpg_sign,
+            hook_mode: policyDraft.hook_mode,
+            author_name: policyDraft.author_name.trim() || null,
+            author_email: policyDraft.author_email.trim() || null,
+          }
+        );
+        queryClient.setQueryData(
+          ['repoCommitPolicy', selectedRepo.id],
+          updatedPolicy
+        );
+        setPolicyDraft(policyToFormState(updatedPolicy));
+      }
+
+      if (packDraft && hasPackChanges) {
+        const updatedPack = await repoApi.updateContextPack(selectedRepo.id, {
+          enabled: packDraft.enabled,
+          delivery: packDraft.delivery,
+          token_budget: Number(
+            packDraft.token_budget.trim()
+          ) as unknown as bigint,
+          notes: packDraft.notes.trim() || null,
+        });
+        queryClient.setQueryData(
+          ['repoContextPack', selectedRepo.id],
+          updatedPack
+        );
+        setPackDraft(contextPackToFormState(updatedPack));
+      }
       setSuccess(true);
       setTimeout(() => setSuccess(false), 3000);
     } catch (err) {
@@ -192,6 +349,43 @@ export function ReposSettingsSection({
   const handleDiscard = () => {
     if (!selectedRepo) return;
     setDraft(repoToFormState(selectedRepo));
+    if (commitPolicy) setPolicyDraft(policyToFormState(commitPolicy));
+    if (contextPack) setPackDraft(contextPackToFormState(contextPack));
+  };
+
+  const updatePolicyDraft = (updates: Partial<CommitFormState>) => {
+    setPolicyDraft((prev) => {
+      if (!prev) return prev;
+      return { ...prev, ...updates };
+    });
+  };
+
+  const updatePackDraft = (updates: Partial<ContextPackFormState>) => {
+    setPackDraft((prev) => {
+      if (!prev) return prev;
+      return { ...prev, ...updates };
+    });
+  };
+
+  const deliveryLabel = (delivery: ContextPackDelivery) =>
+    delivery === 'prompt'
+      ? t('settings.repos.contextPack.delivery.prompt')
+      : t('settings.repos.contextPack.delivery.file');
+
+  const messageStyleLabel = (style: CommitMessageStyle) =>
+    style === 'conventional'
+      ? t('settings.repos.commits.messageStyle.conventional')
+      : t('settings.repos.commits.messageStyle.agentSummary');
+
+  const hookModeLabel = (mode: CommitHookMode) => {
+    switch (mode) {
+      case 'skip':
+        return t('settings.repos.commits.hooks.skip');
+      case 'feedback':
+        return t('settings.repos.commits.hooks.feedback');
+      default:
+        return t('settings.repos.commits.hooks.run');
+    }
   };
 
   const updateDraft = (updates: Partial<RepoScriptsFormState>) => {
@@ -439,6 +633,20 @@ export function ReposSettingsSection({
               />
             </SettingsField>
 
+            <SettingsField
+              label={t('settings.repos.scripts.verification.label')}
+              description={t('settings.repos.scripts.verification.helper')}
+            >
+              <SettingsTextarea
+                value={draft.verification_script}
+                onChange={(value) =>
+                  updateDraft({ verification_script: value })
+                }
+                placeholder={placeholders.verification}
+                monospace
+              />
+            </SettingsField>
+
             <SettingsField
               label={t('settings.repos.scripts.copyFiles.label')}
               description={t('settings.repos.scripts.copyFiles.helper')}
@@ -450,8 +658,214 @@ export function ReposSettingsSection({
                 rows={3}
               />
             </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.scripts.templateFiles.label')}
+              description={t('settings.repos.scripts.templateFiles.helper', {
+                placeholders: TEMPLATE_PLACEHOLDERS,
+              })}
+            >
+              <SettingsTextarea
+                value={draft.template_files}
+                onChange={(value) => updateDraft({ template_files: value })}
+                placeholder={t(
+                  'settings.repos.scripts.templateFiles.placeholder'
+                )}
+                rows={3}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.scripts.symlinkDirs.label')}
+              description={t('settings.repos.scripts.symlinkDirs.helper')}
+            >
+              <SettingsTextarea
+                value={draft.symlink_dirs}
+                onChange={(value) => updateDraft({ symlink_dirs: value })}
+                placeholder={t(
+                  'settings.repos.scripts.symlinkDirs.placeholder'
+                )}
+                rows={2}
+              />
+            </SettingsField>
           </SettingsCard>
 
+          {policyDraft && (
+            <SettingsCard
+              title={t('settings.repos.commits.title')}
+              description={t('settings.repos.commits.description')}
+            >
+              <SettingsField
+                label={t('settings.repos.commits.messageStyle.label')}
+                description={t('settings.repos.commits.messageStyle.helper')}
+              >
+                <DropdownMenu>
+                  <DropdownMenuTrigger asChild>
+                    <DropdownMenuTriggerButton
+                      label={messageStyleLabel(policyDraft.message_style)}
+                      className="w-full justify-between"
+                    />
+                  </DropdownMenuTrigger>
+                  <DropdownMenuContent className="w-[var(--radix-dropdown-menu-trigger-width)]">
+                    {MESSAGE_STYLES.map((style) => (
+                      <DropdownMenuItem
+                        key={style}
+                        onClick={() =>
+                          updatePolicyDraft({ message_style: style })
+                        }
+                      >
+                        {messageStyleLabel(style)}
+                      </DropdownMenuItem>
+                    ))}
+                  </DropdownMenuContent>
+                </DropdownMenu>
+              </SettingsField>
+
+              <SettingsCheckbox
+                id="commit-squash-on-finish"
+                label={t('settings.repos.commits.squash.label')}
+                description={t('settings.repos.commits.squash.helper')}
+                checked={policyDraft.squash_on_finish}
+                onChange={(checked) =>
+                  updatePolicyDraft({ squash_on_finish: checked })
+                }
+              />
+
+              <SettingsCheckbox
+                id="commit-signoff"
+                label={t('settings.repos.commits.signoff.label')}
+                description={t('settings.repos.commits.signoff.helper')}
+                checked={policyDraft.signoff}
+                onChange={(checked) => updatePolicyDraft({ signoff: checked })}
+              />
+
+              <SettingsCheckbox
+                id="commit-gpg-sign"
+                label={t('settings.repos.commits.gpgSign.label')}
+                description={t('settings.repos.commits.gpgSign.helper')}
+                checked={policyDraft.gpg_sign}
+                onChange={(checked) => updatePolicyDraft({ gpg_sign: checked })}
+              />
+
+              <SettingsField
+                label={t('settings.repos.commits.hooks.label')}
+                description={t('settings.repos.commits.hooks.helper')}
+              >
+                <DropdownMenu>
+                  <DropdownMenuTrigger asChild>
+                    <DropdownMenuTriggerButton
+                      label={hookModeLabel(policyDraft.hook_mode)}
+                      className="w-full justify-between"
+                    />
+                  </DropdownMenuTrigger>
+                  <DropdownMenuContent className="w-[var(--radix-dropdown-menu-trigger-width)]">
+                    {HOOK_MODES.map((mode) => (
+                      <DropdownMenuItem
+                        key={mode}
+                        onClick={() => updatePolicyDraft({ hook_mode: mode })}
+                      >
+                        {hookModeLabel(mode)}
+                      </DropdownMenuItem>
+                    ))}
+                  </DropdownMenuContent>
+                </DropdownMenu>
+              </SettingsField>
+
+              <SettingsField
+                label={t('settings.repos.commits.author.label')}
+                description={t('settings.repos.commits.author.helper')}
+              >
+                <div className="flex gap-base">
+                  <SettingsInput
+                    value={policyDraft.author_name}
+                    onChange={(value) =>
+                      updatePolicyDraft({ author_name: value })
+                    }
+                    placeholder={t(
+                      'settings.repos.commits.author.namePlaceholder'
+                    )}
+                  />
+                  <SettingsInput
+                    value={policyDraft.author_email}
+                    onChange={(value) =>
+                      updatePolicyDraft({ author_email: value })
+                    }
+                    placeholder={t(
+                      'settings.repos.commits.author.emailPlaceholder'
+                    )}
+                  />
+                </div>
+              </SettingsField>
+            </SettingsCard>
+          )}
+
+          {packDraft && (
+            <SettingsCard
+              title={t('settings.repos.contextPack.title')}
+              description={t('settings.repos.contextPack.description')}
+            >
+              <SettingsCheckbox
+                id="context-pack-enabled"
+                label={t('settings.repos.contextPack.enabled.label')}
+                description={t('settings.repos.contextPack.enabled.helper')}
+                checked={packDraft.enabled}
+                onChange={(checked) => updatePackDraft({ enabled: checked })}
+              />
+
+              <SettingsField
+                label={t('settings.repos.contextPack.delivery.label')}
+                description={t('settings.repos.contextPack.delivery.helper')}
+              >
+                <DropdownMenu>
+                  <DropdownMenuTrigger asChild>
+                    <DropdownMenuTriggerButton
+                      label={deliveryLabel(packDraft.delivery)}
+                      className="w-full justify-between"
+                      disabled={!packDraft.enabled}
+                    />
+                  </DropdownMenuTrigger>
+                  <DropdownMenuContent className="w-[var(--radix-dropdown-menu-trigger-width)]">
+                    {CONTEXT_PACK_DELIVERIES.map((delivery) => (
+                      <DropdownMenuItem
+                        key={delivery}
+                        onClick={() => updatePackDraft({ delivery })}
+                      >
+                        {deliveryLabel(delivery)}
+                      </DropdownMenuItem>
+                    ))}
+                  </DropdownMenuContent>
+                </DropdownMenu>
+              </SettingsField>
+
+              <SettingsField
+                label={t('settings.repos.contextPack.tokenBudget.label')}
+                description={t('settings.repos.contextPack.tokenBudget.helper')}
+              >
+                <SettingsInput
+                  value={packDraft.token_budget}
+                  onChange={(value) => updatePackDraft({ token_budget: value })}
+                  error={packBudgetInvalid}
+                  disabled={!packDraft.enabled}
+                />
+              </SettingsField>
+
+              <SettingsField
+                label={t('settings.repos.contextPack.notes.label')}
+                description={t('settings.repos.contextPack.notes.helper')}
+              >
+                <SettingsTextarea
+                  value={packDraft.notes}
+                  onChange={(value) => updatePackDraft({ notes: value })}
+                  placeholder={t(
+                    'settings.repos.contextPack.notes.placeholder'
+                  )}
+                  rows={4}
+                  disabled={!packDraft.enabled}
+                />
+              </SettingsField>
+            </SettingsCard>
+          )}
+
           <SettingsSaveBar
             show={hasUnsavedChanges}
             saving={saving}
@@ -72,6 +72,10 @@ interface ActionsProps {
   onQueue: () => void;
   onCancelQueue: () => void;
   onStop: () => void;
+  /** Send the message into the running turn; set when the agent accepts it */
+  onSendInput?: () => void;
+  /** Interrupt the running turn; set when the agent supports it */
+  onInterrupt?: () => void;
   onPasteFiles: (files: File[]) => void;
 }
 
@@ -143,6 +147,8 @@ interface SessionChatBoxProps {
   editMode?: EditModeProps;
   approvalMode?: ApprovalModeProps;
   reviewComments?: ReviewCommentsProps;
+  /** Number of follow-ups waiting in the queue */
+  queuedCount?: number;
   toolbarActions?: ToolbarActionsProps;
   modelSelector?: ModelSelectorProps;
   error?: string | null;
@@ -176,6 +182,7 @@ export function SessionChatBox({
   editMode,
   approvalMode,
   reviewComments,
+  queuedCount = 0,
   toolbarActions,
   modelSelector,
   error,
@@ -257,7 +264,7 @@ export function SessionChatBox({
       feedbackMode?.onSubmitFeedback();
     } else if (isInEditMode && canSend) {
       editMode?.onSubmitEdit();
-    } else if (status === 'running' && canSend) {
+    } else if (isRunning && canSend) {
       actions.onQueue();
     } else if (status === 'idle' && canSend) {
       actions.onSend();
@@ -299,6 +306,22 @@ export function SessionChatBox({
   const linesAdded = stats?.linesAdded;
   const linesRemoved = stats?.linesRemoved;
 
+  // Turn controls offered while the agent is running, if it supports them
+  const sendInputButton = actions.onSendInput && (
+    <PrimaryButton
+      onClick={actions.onSendInput}
+      disabled={!canSend}
+      value={t('conversation.actions.sendNow')}
+    />
+  );
+  const interruptButton = actions.onInterrupt && (
+    <PrimaryButton
+      onClick={actions.onInterrupt}
+      variant="secondary"
+      value={t('conversation.actions.interrupt')}
+    />
+  );
+
   // Render action buttons based on status
   const renderActionButtons = () => {
     // Feedback mode takes precedence
@@ -410,11 +433,13 @@ export function SessionChatBox({
       case 'running':
         return (
           <>
+            {sendInputButton}
             <PrimaryButton
               onClick={actions.onQueue}
               disabled={!canSend}
               value={t('conversation.actions.queue')}
             />
+            {interruptButton}
             <PrimaryButton
               onClick={actions.onStop}
               variant="secondary"
@@ -427,11 +452,19 @@ export function SessionChatBox({
       case 'queued':
         return (
           <>
+            {sendInputButton}
+            <PrimaryButton
+              onClick={actions.onQueue}
+              disabled={!canSend}
+              value={t('conversation.actions.queue')}
+            />
             <PrimaryButton
               onClick={actions.onCancelQueue}
+              variant="secondary"
               value={t('conversation.actions.cancelQueue')}
               actionIcon={XIcon}
             />
+            {interruptButton}
             <PrimaryButton
               onClick={actions.onStop}
               variant="secondary"
@@ -500,7 +533,7 @@ export function SessionChatBox({
         >
           <ClockIcon className="h-4 w-4 text-low" />
           <span className="text-sm text-low">
-            {t('followUp.queuedMessage')}
+            {t('followUp.queuedCount', { count: queuedCount })}
           </span>
         </div>
       );
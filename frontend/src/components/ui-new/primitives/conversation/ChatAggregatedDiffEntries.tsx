@@ -215,6 +215,7 @@ export function ChatAggregatedDiffEntries({
         failed: 6,
         denied: 5,
         timed_out: 4,
+        cancelled: 4,
         pending_approval: 3,
         created: 2,
         success: 1,
@@ -78,6 +78,7 @@ export function ChatAggregatedToolEntries({
         failed: 6,
         denied: 5,
         timed_out: 4,
+        cancelled: 4,
         pending_approval: 3,
         created: 2,
         success: 1,
@@ -49,7 +49,8 @@ export function ChatSubagentEntry({
     const isError =
       statusType === 'failed' ||
       statusType === 'denied' ||
-      statusType === 'timed_out';
+      statusType === 'timed_out' ||
+      statusType === 'cancelled';
     const isPending =
       statusType === 'created' || statusType === 'pending_approval';
 
@@ -73,7 +74,8 @@ export function ChatSubagentEntry({
     return (
       status.status === 'failed' ||
       status.status === 'denied' ||
-      status.status === 'timed_out'
+      status.status === 'timed_out' ||
+      status.status === 'cancelled'
     );
   }, [status]);
 
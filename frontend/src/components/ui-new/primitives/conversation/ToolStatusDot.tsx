@@ -14,7 +14,8 @@ export function ToolStatusDot({ status, className }: ToolStatusDotProps) {
   const isError =
     statusType === 'failed' ||
     statusType === 'denied' ||
-    statusType === 'timed_out';
+    statusType === 'timed_out' ||
+    statusType === 'cancelled';
   const isPending =
     statusType === 'created' || statusType === 'pending_approval';
 
@@ -2,7 +2,7 @@ import { ArrowSquareOutIcon, SpinnerIcon } from '@phosphor-icons/react';
 import { useTranslation } from 'react-i18next';
 import { cn } from '@/lib/utils';
 import { VirtualizedProcessLogs } from '../containers/VirtualizedProcessLogs';
-import { getDevServerWorkingDir } from '@/lib/devServerUtils';
+import { getDevServerLabel } from '@/lib/devServerUtils';
 import type { ExecutionProcess, PatchType } from 'shared/types';
 
 type LogEntry = Extract<PatchType, { type: 'STDOUT' } | { type: 'STDERR' }>;
@@ -68,7 +68,7 @@ export function PreviewControls({
                 )}
                 onClick={() => onTabChange(process.id)}
               >
-                {getDevServerWorkingDir(process) ??
+                {getDevServerLabel(process) ??
                   t('preview.browser.devServerFallback')}
               </button>
             ))}
@@ -0,0 +1,100 @@
+import { useCallback, useMemo } from 'react';
+import { useMutation } from '@tanstack/react-query';
+import { executionProcessesApi } from '@/lib/api';
+import { useUserSystem } from '@/components/ConfigProvider';
+import {
+  BaseAgentCapability,
+  type BaseCodingAgent,
+  type ExecutionProcess,
+} from 'shared/types';
+
+interface UseAgentTurnControlsOptions {
+  /** Execution processes of the current session */
+  processes: ExecutionProcess[];
+  /** Agent running the session */
+  agent: BaseCodingAgent | null | undefined;
+}
+
+interface UseAgentTurnControlsResult {
+  /** Whether the running agent accepts messages during its turn */
+  canSendInput: boolean;
+  /** Whether the running agent can interrupt its turn and keep the session */
+  canInterrupt: boolean;
+  /** Send a message into the running turn */
+  sendInput: (message: string) => Promise<void>;
+  /** Interrupt the running turn; the agent then waits for the next message */
+  interrupt: () => Promise<void>;
+  isSendingInput: boolean;
+  isInterrupting: boolean;
+}
+
+/**
+ * Controls for a coding agent turn in progress, limited to what the agent
+ * advertises in its capabilities.
+ */
+export function useAgentTurnControls({
+  processes,
+  agent,
+}: UseAgentTurnControlsOptions): UseAgentTurnControlsResult {
+  const { capabilities } = useUserSystem();
+
+  const runningProcess = useMemo(
+    () =>
+      [...processes]
+        .reverse()
+        .find(
+          (process) =>
+            process.run_reason === 'codingagent' &&
+            process.status === 'running'
+        ),
+    [processes]
+  );
+
+  const agentCapabilities = (agent && capabilities?.[agent]) || [];
+  const canSendInput =
+    !!runningProcess &&
+    agentCapabilities.includes(BaseAgentCapability.USER_INPUT);
+  const canInterrupt =
+    !!runningProcess &&
+    agentCapabilities.includes(BaseAgentCapability.INTERRUPT);
+
+  const inputMutation = useMutation({
+    mutationFn: ({
+      processId,
+      message,
+    }: {
+      processId: string;
+      message: string;
+    }) => executionProcessesApi.sendUserInput(processId, { message }),
+  });
+
+  const interruptMutation = useMutation({
+    mutationFn: (processId: string) =>
+      executionProcessesApi.interruptExecutionProcess(processId),
+  });
+
+  const sendInput = useCallback(
+    async (message: string) => {
+      if (!runningProcess) return;
+      await inputMutation.mutateAsync({
+        processId: runningProcess.id,
+        message,
+      });
+    },
+    [runningProcess, inputMutation]
+  );
+
+  const interrupt = useCallback(async () => {
+    if (!runningProcess) return;
+    await interruptMutation.mutateAsync(runningProcess.id);
+  }, [runningProcess, interruptMutation]);
+
+  return {
+    canSendInput,
+    canInterrupt,
+    sendInput,
+    interrupt,
+    isSendingInput: inputMutation.isPending,
+    isInterrupting: interruptMutation.isPending,
+  };
+}
@@ -35,6 +35,7 @@ export function useCreateSession() {
         retry_process_id: null,
         force_when_dirty: null,
         perform_git_reset: null,
+        preview_request_ids: null,
       };
       await sessionsApi.followUp(session.id, body);
 
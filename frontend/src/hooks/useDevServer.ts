@@ -6,7 +6,7 @@ import { workspaceSummaryKeys } from '@/components/ui-new/hooks/useWorkspaces';
 import {
   filterRunningDevServers,
   filterDevServerProcesses,
-  deduplicateDevServersByWorkingDir,
+  deduplicateDevServers,
 } from '@/lib/devServerUtils';
 
 interface UseDevServerOptions {
@@ -30,9 +30,7 @@ export function useDevServer(
 
   const devServerProcesses = useMemo(
     () =>
-      deduplicateDevServersByWorkingDir(
-        filterDevServerProcesses(attemptData.processes)
-      ),
+      deduplicateDevServers(filterDevServerProcesses(attemptData.processes)),
     [attemptData.processes]
   );
 
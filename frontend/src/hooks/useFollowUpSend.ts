@@ -50,6 +50,7 @@ export function useFollowUpSend({
         retry_process_id: null,
         force_when_dirty: null,
         perform_git_reset: null,
+        preview_request_ids: null,
       };
       await sessionsApi.followUp(sessionId, body);
       if (!isSlashCommand) {
@@ -21,5 +21,7 @@ export function useScriptPlaceholders(): ScriptPlaceholders {
       '#!/bin/bash\n# Add cleanup commands here...\n# This runs after coding agent execution',
     archive:
       '#!/bin/bash\n# Add archive commands here...\n# This runs when the workspace is archived',
+    verification:
+      '#!/bin/bash\nnpm test\n# Must succeed before the merge queue lands the workspace',
   };
 }
@@ -1,7 +1,12 @@
 import { useCallback } from 'react';
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import { queueApi } from '@/lib/api';
-import type { ExecutorConfig, QueueStatus } from 'shared/types';
+import type {
+  ExecutorConfig,
+  QueuedMessage,
+  QueuePause,
+  QueueStatus,
+} from 'shared/types';
 
 interface UseSessionQueueInteractionOptions {
   /** Session ID for queue operations */
@@ -9,20 +14,31 @@ interface UseSessionQueueInteractionOptions {
 }
 
 interface UseSessionQueueInteractionResult {
-  /** Whether a message is currently queued */
+  /** Whether any message is currently queued */
   isQueued: boolean;
-  /** The queued message content, if any */
-  queuedMessage: string | null;
-  /** The executor config from the queued message, if any */
-  queuedConfig: ExecutorConfig | null;
+  /** Queued messages in the order they will run */
+  queuedMessages: QueuedMessage[];
+  /** Set when the queue stopped draining after a failed or stopped run */
+  queuePause: QueuePause | null;
   /** Whether a queue operation is in progress */
   isQueueLoading: boolean;
-  /** Queue a message for later execution */
+  /** Append a message to the end of the queue */
   queueMessage: (
     message: string,
     executorConfig: ExecutorConfig
   ) => Promise<void>;
-  /** Cancel the queued message */
+  /** Replace the text of one queued message, keeping its executor */
+  updateQueuedMessage: (
+    queued: QueuedMessage,
+    message: string
+  ) => Promise<void>;
+  /** Remove one queued message */
+  removeQueuedMessage: (messageId: string) => Promise<void>;
+  /** Move one queued message up (-1) or down (+1) in the queue */
+  moveQueuedMessage: (messageId: string, offset: -1 | 1) => Promise<void>;
+  /** Resume a paused queue */
+  resumeQueue: () => Promise<void>;
+  /** Cancel every queued message */
   cancelQueue: () => Promise<void>;
   /** Refresh queue status from server */
   refreshQueueStatus: () => Promise<void>;
@@ -47,13 +63,16 @@ export function useSessionQueueInteraction({
       enabled: !!sessionId,
     });
 
-  const isQueued = queueStatus.status === 'queued';
-  const queuedMessageData = isQueued
-    ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>).message
-    : null;
-  const queuedMessage = queuedMessageData?.data.message ?? null;
-  const queuedConfig: ExecutorConfig | null =
-    queuedMessageData?.data.executor_config ?? null;
+  const queued =
+    queueStatus.status === 'queued'
+      ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>)
+      : null;
+  const queuedMessages = queued?.messages ?? [];
+
+  // Every queue endpoint answers with the new status
+  const setStatus = (status: QueueStatus) => {
+    queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
+  };
 
   // Mutation for queueing a message
   const queueMutation = useMutation({
@@ -68,17 +87,44 @@ export function useSessionQueueInteraction({
         message,
         executor_config: executorConfig,
       }),
-    onSuccess: (status) => {
-      queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
-    },
+    onSuccess: setStatus,
+  });
+
+  const updateMutation = useMutation({
+    mutationFn: ({
+      queued,
+      message,
+    }: {
+      queued: QueuedMessage;
+      message: string;
+    }) =>
+      queueApi.update(sessionId!, queued.id, {
+        message,
+        executor_config: queued.executor_config,
+      }),
+    onSuccess: setStatus,
+  });
+
+  const removeMutation = useMutation({
+    mutationFn: (messageId: string) => queueApi.remove(sessionId!, messageId),
+    onSuccess: setStatus,
+  });
+
+  const reorderMutation = useMutation({
+    mutationFn: (messageIds: string[]) =>
+      queueApi.reorder(sessionId!, messageIds),
+    onSuccess: setStatus,
+  });
+
+  const resumeMutation = useMutation({
+    mutationFn: () => queueApi.resume(sessionId!),
+    onSuccess: setStatus,
   });
 
   // Mutation for cancelling the queue
   const cancelMutation = useMutation({
     mutationFn: () => queueApi.cancel(sessionId!),
-    onSuccess: (status) => {
-      queryClient.setQueryData([QUEUE_STATUS_KEY, sessionId], status);
-    },
+    onSuccess: setStatus,
   });
 
   const queueMessage = useCallback(
@@ -92,6 +138,40 @@ export function useSessionQueueInteraction({
     [sessionId, queueMutation]
   );
 
+  const updateQueuedMessage = useCallback(
+    async (queued: QueuedMessage, message: string) => {
+      if (!sessionId) return;
+      await updateMutation.mutateAsync({ queued, message });
+    },
+    [sessionId, updateMutation]
+  );
+
+  const removeQueuedMessage = useCallback(
+    async (messageId: string) => {
+      if (!sessionId) return;
+      await removeMutation.mutateAsync(messageId);
+    },
+    [sessionId, removeMutation]
+  );
+
+  const moveQueuedMessage = useCallback(
+    async (messageId: string, offset: -1 | 1) => {
+      if (!sessionId) return;
+      const ids = queuedMessages.map((message) => message.id);
+      const from = ids.indexOf(messageId);
+      const to = from + offset;
+      if (from < 0 || to < 0 || to >= ids.length) return;
+      [ids[from], ids[to]] = [ids[to], ids[from]];
+      await reorderMutation.mutateAsync(ids);
+    },
+    [sessionId, queuedMessages, reorderMutation]
+  );
+
+  const resumeQueue = useCallback(async () => {
+    if (!sessionId) return;
+    await resumeMutation.mutateAsync();
+  }, [sessionId, resumeMutation]);
+
   const cancelQueue = useCallback(async () => {
     if (!sessionId) return;
     await cancelMutation.mutateAsync();
@@ -103,11 +183,21 @@ export function useSessionQueueInteraction({
   }, [sessionId, refetch]);
 
   return {
-    isQueued,
-    queuedMessage,
-    queuedConfig,
-    isQueueLoading: queueMutation.isPending || cancelMutation.isPending,
+    isQueued: queued !== null,
+    queuedMessages,
+    queuePause: queued?.paused ?? null,
+    isQueueLoading:
+      queueMutation.isPending ||
+      updateMutation.isPending ||
+      removeMutation.isPending ||
+      reorderMutation.isPending ||
+      resumeMutation.isPending ||
+      cancelMutation.isPending,
     queueMessage,
+    updateQueuedMessage,
+    removeQueuedMessage,
+    moveQueuedMessage,
+    resumeQueue,
     cancelQueue,
     refreshQueueStatus,
   };
@@ -20,16 +20,46 @@ import {
   Project,
   Repo,
   RepoWithTargetBranch,
+  RepoCommitPolicy,
+  RepoContextPack,
+  RepoDevProcess,
+  RepoDevServerSettings,
   CreateProject,
   CreateProjectRepo,
   UpdateRepo,
+  UpdateRepoCommitPolicy,
+  UpdateRepoContextPack,
+  UpdateRepoDevServerSettings,
+  UpsertRepoDevProcess,
+  Secret,
+  SecretScope,
+  UpsertSecret,
+  ProjectRedactionSettings,
+  UpdateProjectRedactionSettings,
+  RedactionCounts,
   SearchMode,
   SearchResult,
+  ContentSearchResponse,
+  CodeSymbol,
   Task,
   TaskRelationships,
   Tag,
   TagSearchParams,
   TaskWithAttemptStatus,
+  TaskTemplateWithImages,
+  CreateTaskTemplate,
+  RenderTaskTemplateRequest,
+  RenderedTaskTemplate,
+  CreateTaskFromTemplateRequest,
+  BulkOperationResponse,
+  BulkCreateTasksRequest,
+  BulkStartAttemptsRequest,
+  BulkStopRequest,
+  BulkCleanupWorkspacesRequest,
+  BulkUpdateStatusRequest,
+  CreateIssueImportSource,
+  IssueImportSource,
+  IssueSyncSummary,
   UpdateProject,
   UpdateTask,
   UpdateTag,
@@ -49,7 +79,6 @@ import {
   AvailabilityInfo,
   BaseCodingAgent,
   ExecutorConfig,
-  DraftFollowUpData,
   AgentPresetOptionsQuery,
   RunAgentSetupRequest,
   RunAgentSetupResponse,
@@ -79,12 +108,29 @@ import {
   TokenResponse,
   CurrentUserResponse,
   QueueStatus,
+  QueueMessageRequest,
+  SendUserInputRequest,
   PrCommentsResponse,
+  StackLayer,
+  RestackRequest,
+  RestackResponse,
+  CheckpointRepoDiff,
+  CreateCheckpointRequest,
+  WorkspaceCheckpointWithRepos,
+  CapturedRequest,
+  PreviewCaptureStatus,
+  SetPreviewCaptureRequest,
+  CaptureVisualSnapshotsRequest,
+  VisualSnapshotResponse,
+  VisualSnapshotRun,
+  MergeQueueEntry,
   MergeTaskAttemptRequest,
   PushTaskAttemptRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
   ContinueRebaseRequest,
+  ResolveConflictsRequest,
+  ResolveConflictsError,
   Session,
   Workspace,
   StartReviewRequest,
@@ -311,6 +357,29 @@ export const projectsApi = {
     return handleApiResponse<Repo[]>(response);
   },
 
+  getRedactionSettings: async (
+    projectId: string
+  ): Promise<ProjectRedactionSettings> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/redaction-settings`
+    );
+    return handleApiResponse<ProjectRedactionSettings>(response);
+  },
+
+  updateRedactionSettings: async (
+    projectId: string,
+    data: UpdateProjectRedactionSettings
+  ): Promise<ProjectRedactionSettings> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/redaction-settings`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectRedactionSettings>(response);
+  },
+
   addRepository: async (
     projectId: string,
     data: CreateProjectRepo
@@ -599,6 +668,20 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  enqueueMerge: async (
+    attemptId: string,
+    data: MergeTaskAttemptRequest
+  ): Promise<MergeQueueEntry> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/merge/queue`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<MergeQueueEntry>(response);
+  },
+
   push: async (
     attemptId: string,
     data: PushTaskAttemptRequest
@@ -683,6 +766,22 @@ export const attemptsApi = {
     return handleApiResponse<void>(response);
   },
 
+  resolveConflictsWithAgent: async (
+    attemptId: string,
+    data: ResolveConflictsRequest
+  ): Promise<Result<ExecutionProcess, ResolveConflictsError>> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponseAsResult<ExecutionProcess, ResolveConflictsError>(
+      response
+    );
+  },
+
   continueRebase: async (
     attemptId: string,
     data: ContinueRebaseRequest
@@ -733,6 +832,19 @@ export const attemptsApi = {
     return handleApiResponse<ExecutionProcess[]>(response);
   },
 
+  restartDevServer: async (
+    attemptId: string,
+    devServerId: string
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/dev-servers/${devServerId}/restart`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
   setupGhCli: async (attemptId: string): Promise<ExecutionProcess> => {
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/gh-cli-setup`,
@@ -795,6 +907,172 @@ export const attemptsApi = {
     return handleApiResponse<PrCommentsResponse>(response);
   },
 
+  getStack: async (
+    attemptId: string,
+    repoId: string
+  ): Promise<StackLayer[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/stack?repo_id=${encodeURIComponent(repoId)}`
+    );
+    return handleApiResponse<StackLayer[]>(response);
+  },
+
+  restack: async (
+    attemptId: string,
+    data: RestackRequest
+  ): Promise<RestackResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/stack/restack`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RestackResponse>(response);
+  },
+
+  getCheckpoints: async (
+    attemptId: string
+  ): Promise<WorkspaceCheckpointWithRepos[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints`
+    );
+    return handleApiResponse<WorkspaceCheckpointWithRepos[]>(response);
+  },
+
+  createCheckpoint: async (
+    attemptId: string,
+    data: CreateCheckpointRequest
+  ): Promise<WorkspaceCheckpointWithRepos> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<WorkspaceCheckpointWithRepos>(response);
+  },
+
+  /** Restore a checkpoint; returns the checkpoint of the state it replaced */
+  restoreCheckpoint: async (
+    attemptId: string,
+    checkpointId: string
+  ): Promise<WorkspaceCheckpointWithRepos> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints/${checkpointId}/restore`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<WorkspaceCheckpointWithRepos>(response);
+  },
+
+  getCheckpointDiff: async (
+    attemptId: string,
+    checkpointId: string
+  ): Promise<CheckpointRepoDiff[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints/${checkpointId}/diff`
+    );
+    return handleApiResponse<CheckpointRepoDiff[]>(response);
+  },
+
+  deleteCheckpoint: async (
+    attemptId: string,
+    checkpointId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/checkpoints/${checkpointId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getPreviewRequests: async (
+    attemptId: string
+  ): Promise<PreviewCaptureStatus> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-requests`
+    );
+    return handleApiResponse<PreviewCaptureStatus>(response);
+  },
+
+  /** Record the dev server on `port` through the preview proxy, or stop */
+  setPreviewCapture: async (
+    attemptId: string,
+    data: SetPreviewCaptureRequest
+  ): Promise<PreviewCaptureStatus> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-requests/capture`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PreviewCaptureStatus>(response);
+  },
+
+  getPreviewRequest: async (
+    attemptId: string,
+    requestId: string
+  ): Promise<CapturedRequest> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-requests/${requestId}`
+    );
+    return handleApiResponse<CapturedRequest>(response);
+  },
+
+  clearPreviewRequests: async (
+    attemptId: string
+  ): Promise<PreviewCaptureStatus> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-requests`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<PreviewCaptureStatus>(response);
+  },
+
+  getVisualSnapshots: async (
+    attemptId: string
+  ): Promise<VisualSnapshotResponse[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/visual-snapshots`
+    );
+    return handleApiResponse<VisualSnapshotResponse[]>(response);
+  },
+
+  /**
+   * Start screenshotting a ready dev server, optionally diffing against the
+   * target branch. Poll `getVisualSnapshotRun` until the run finishes.
+   */
+  captureVisualSnapshots: async (
+    attemptId: string,
+    data: CaptureVisualSnapshotsRequest
+  ): Promise<VisualSnapshotRun> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/visual-snapshots`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<VisualSnapshotRun>(response);
+  },
+
+  getVisualSnapshotRun: async (
+    attemptId: string
+  ): Promise<VisualSnapshotRun | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/visual-snapshots/run`
+    );
+    return handleApiResponse<VisualSnapshotRun | null>(response);
+  },
+
   /** Mark all coding agent turns for a workspace as seen */
   markSeen: async (attemptId: string): Promise<void> => {
     const response = await makeRequest(
@@ -837,6 +1115,13 @@ export const executionProcessesApi = {
     return handleApiResponse<ExecutionProcessRepoState[]>(response);
   },
 
+  getRedactions: async (processId: string): Promise<RedactionCounts> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/redactions`
+    );
+    return handleApiResponse<RedactionCounts>(response);
+  },
+
   stopExecutionProcess: async (processId: string): Promise<void> => {
     const response = await makeRequest(
       `/api/execution-processes/${processId}/stop`,
@@ -846,6 +1131,29 @@ export const executionProcessesApi = {
     );
     return handleApiResponse<void>(response);
   },
+  sendUserInput: async (
+    processId: string,
+    data: SendUserInputRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/input`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  interruptExecutionProcess: async (processId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/interrupt`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // File System APIs
@@ -892,6 +1200,72 @@ export const repoApi = {
     return handleApiResponse<Repo>(response);
   },
 
+  getCommitPolicy: async (repoId: string): Promise<RepoCommitPolicy> => {
+    const response = await makeRequest(`/api/repos/${repoId}/commit-policy`);
+    return handleApiResponse<RepoCommitPolicy>(response);
+  },
+
+  updateCommitPolicy: async (
+    repoId: string,
+    data: UpdateRepoCommitPolicy
+  ): Promise<RepoCommitPolicy> => {
+    const response = await makeRequest(`/api/repos/${repoId}/commit-policy`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<RepoCommitPolicy>(response);
+  },
+
+  getContextPack: async (repoId: string): Promise<RepoContextPack> => {
+    const response = await makeRequest(`/api/repos/${repoId}/context-pack`);
+    return handleApiResponse<RepoContextPack>(response);
+  },
+
+  updateContextPack: async (
+    repoId: string,
+    data: UpdateRepoContextPack
+  ): Promise<RepoContextPack> => {
+    const response = await makeRequest(`/api/repos/${repoId}/context-pack`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<RepoContextPack>(response);
+  },
+
+  getDevServerSettings: async (
+    repoId: string
+  ): Promise<RepoDevServerSettings> => {
+    const response = await makeRequest(`/api/repos/${repoId}/dev-server`);
+    return handleApiResponse<RepoDevServerSettings>(response);
+  },
+
+  updateDevServerSettings: async (
+    repoId: string,
+    data: UpdateRepoDevServerSettings
+  ): Promise<RepoDevServerSettings> => {
+    const response = await makeRequest(`/api/repos/${repoId}/dev-server`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<RepoDevServerSettings>(response);
+  },
+
+  getDevProcesses: async (repoId: string): Promise<RepoDevProcess[]> => {
+    const response = await makeRequest(`/api/repos/${repoId}/dev-processes`);
+    return handleApiResponse<RepoDevProcess[]>(response);
+  },
+
+  updateDevProcesses: async (
+    repoId: string,
+    processes: UpsertRepoDevProcess[]
+  ): Promise<RepoDevProcess[]> => {
+    const response = await makeRequest(`/api/repos/${repoId}/dev-processes`, {
+      method: 'PUT',
+      body: JSON.stringify(processes),
+    });
+    return handleApiResponse<RepoDevProcess[]>(response);
+  },
+
   register: async (data: {
     path: string;
     display_name?: string;
@@ -1034,6 +1408,185 @@ export const tagsApi = {
   },
 };
 
+// Task Templates APIs
+export const taskTemplatesApi = {
+  list: async (projectId?: string): Promise<TaskTemplateWithImages[]> => {
+    const queryParam = projectId
+      ? `?project_id=${encodeURIComponent(projectId)}`
+      : '';
+    const response = await makeRequest(`/api/task-templates${queryParam}`);
+    return handleApiResponse<TaskTemplateWithImages[]>(response);
+  },
+
+  get: async (templateId: string): Promise<TaskTemplateWithImages> => {
+    const response = await makeRequest(`/api/task-templates/${templateId}`);
+    return handleApiResponse<TaskTemplateWithImages>(response);
+  },
+
+  create: async (data: CreateTaskTemplate): Promise<TaskTemplateWithImages> => {
+    const response = await makeRequest('/api/task-templates', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskTemplateWithImages>(response);
+  },
+
+  update: async (
+    templateId: string,
+    data: CreateTaskTemplate
+  ): Promise<TaskTemplateWithImages> => {
+    const response = await makeRequest(`/api/task-templates/${templateId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskTemplateWithImages>(response);
+  },
+
+  delete: async (templateId: string): Promise<void> => {
+    const response = await makeRequest(`/api/task-templates/${templateId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  render: async (
+    templateId: string,
+    data: RenderTaskTemplateRequest
+  ): Promise<RenderedTaskTemplate> => {
+    const response = await makeRequest(
+      `/api/task-templates/${templateId}/render`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RenderedTaskTemplate>(response);
+  },
+
+  createTask: async (
+    templateId: string,
+    data: CreateTaskFromTemplateRequest
+  ): Promise<Task> => {
+    const response = await makeRequest(
+      `/api/task-templates/${templateId}/create-task`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Task>(response);
+  },
+
+  createAndStart: async (
+    templateId: string,
+    data: CreateTaskFromTemplateRequest
+  ): Promise<TaskWithAttemptStatus> => {
+    const response = await makeRequest(
+      `/api/task-templates/${templateId}/create-and-start`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TaskWithAttemptStatus>(response);
+  },
+};
+
+// Bulk APIs
+export const bulkApi = {
+  createTasks: async (
+    data: BulkCreateTasksRequest
+  ): Promise<BulkOperationResponse> => {
+    const response = await makeRequest('/api/bulk/tasks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkOperationResponse>(response);
+  },
+
+  startAttempts: async (
+    data: BulkStartAttemptsRequest
+  ): Promise<BulkOperationResponse> => {
+    const response = await makeRequest('/api/bulk/tasks/start', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkOperationResponse>(response);
+  },
+
+  updateStatus: async (
+    data: BulkUpdateStatusRequest
+  ): Promise<BulkOperationResponse> => {
+    const response = await makeRequest('/api/bulk/tasks/status', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkOperationResponse>(response);
+  },
+
+  stop: async (data: BulkStopRequest): Promise<BulkOperationResponse> => {
+    const response = await makeRequest('/api/bulk/stop', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkOperationResponse>(response);
+  },
+
+  cleanupWorkspaces: async (
+    data: BulkCleanupWorkspacesRequest
+  ): Promise<BulkOperationResponse> => {
+    const response = await makeRequest('/api/bulk/workspaces/cleanup', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<BulkOperationResponse>(response);
+  },
+};
+
+// Issue Import APIs
+export const issueImportsApi = {
+  list: async (projectId?: string): Promise<IssueImportSource[]> => {
+    const queryParam = projectId
+      ? `?project_id=${encodeURIComponent(projectId)}`
+      : '';
+    const response = await makeRequest(`/api/issue-imports${queryParam}`);
+    return handleApiResponse<IssueImportSource[]>(response);
+  },
+
+  create: async (data: CreateIssueImportSource): Promise<IssueImportSource> => {
+    const response = await makeRequest('/api/issue-imports', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<IssueImportSource>(response);
+  },
+
+  update: async (
+    sourceId: string,
+    data: CreateIssueImportSource
+  ): Promise<IssueImportSource> => {
+    const response = await makeRequest(`/api/issue-imports/${sourceId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<IssueImportSource>(response);
+  },
+
+  delete: async (sourceId: string): Promise<void> => {
+    const response = await makeRequest(`/api/issue-imports/${sourceId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  sync: async (sourceId: string): Promise<IssueSyncSummary> => {
+    const response = await makeRequest(`/api/issue-imports/${sourceId}/sync`, {
+      method: 'POST',
+    });
+    return handleApiResponse<IssueSyncSummary>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
@@ -1444,11 +1997,12 @@ export const agentsApi = {
 // Queue API for session follow-up messages
 export const queueApi = {
   /**
-   * Queue a follow-up message to be executed when current execution finishes
+   * Append a follow-up message to run after the current execution and any
+   * messages queued before it
    */
   queue: async (
     sessionId: string,
-    data: DraftFollowUpData
+    data: QueueMessageRequest
   ): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
       method: 'POST',
@@ -1458,7 +2012,7 @@ export const queueApi = {
   },
 
   /**
-   * Cancel a queued follow-up message
+   * Cancel every queued follow-up message
    */
   cancel: async (sessionId: string): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
@@ -1474,6 +2028,107 @@ export const queueApi = {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`);
     return handleApiResponse<QueueStatus>(response);
   },
+
+  update: async (
+    sessionId: string,
+    messageId: string,
+    data: QueueMessageRequest
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  remove: async (
+    sessionId: string,
+    messageId: string
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Reorder the queue; messageIds must list every queued message
+   */
+  reorder: async (
+    sessionId: string,
+    messageIds: string[]
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/order`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ message_ids: messageIds }),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Resume a queue paused after a failed or stopped execution
+   */
+  resume: async (sessionId: string): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/resume`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+};
+
+// Merge queue API for serialized local merges
+export const mergeQueueApi = {
+  list: async (repoId?: string): Promise<MergeQueueEntry[]> => {
+    const query = repoId ? `?repo_id=${encodeURIComponent(repoId)}` : '';
+    const response = await makeRequest(`/api/merge-queue${query}`);
+    return handleApiResponse<MergeQueueEntry[]>(response);
+  },
+
+  cancel: async (entryId: string): Promise<MergeQueueEntry> => {
+    const response = await makeRequest(`/api/merge-queue/${entryId}/cancel`, {
+      method: 'POST',
+    });
+    return handleApiResponse<MergeQueueEntry>(response);
+  },
+};
+
+// Secrets API (values are write-only)
+export const secretsApi = {
+  list: async (scope: SecretScope): Promise<Secret[]> => {
+    const params = new URLSearchParams();
+    if (scope.project_id) params.set('project_id', scope.project_id);
+    if (scope.repo_id) params.set('repo_id', scope.repo_id);
+    if (scope.executor) params.set('executor', scope.executor);
+    const response = await makeRequest(`/api/secrets?${params.toString()}`);
+    return handleApiResponse<Secret[]>(response);
+  },
+
+  set: async (data: UpsertSecret): Promise<Secret> => {
+    const response = await makeRequest('/api/secrets', {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Secret>(response);
+  },
+
+  delete: async (secretId: string): Promise<void> => {
+    const response = await makeRequest(`/api/secrets/${secretId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Migration API
@@ -1503,4 +2158,56 @@ export const searchApi = {
     );
     return handleApiResponse<SearchResult[]>(response);
   },
+
+  searchContent: async (
+    query: string,
+    scope: { projectId: string } | { workspaceId: string },
+    opts?: {
+      regex?: boolean;
+      caseSensitive?: boolean;
+      contextLines?: number;
+      maxResults?: number;
+    },
+    options?: RequestInit
+  ): Promise<ContentSearchResponse> => {
+    const params = new URLSearchParams({ q: query });
+    if ('projectId' in scope) {
+      params.set('project_id', scope.projectId);
+    } else {
+      params.set('workspace_id', scope.workspaceId);
+    }
+    if (opts?.regex) params.set('regex', 'true');
+    if (opts?.caseSensitive) params.set('case_sensitive', 'true');
+    if (opts?.contextLines !== undefined) {
+      params.set('context_lines', String(opts.contextLines));
+    }
+    if (opts?.maxResults !== undefined) {
+      params.set('max_results', String(opts.maxResults));
+    }
+    const response = await makeRequest(
+      `/api/search/content?${params.toString()}`,
+      options
+    );
+    return handleApiResponse<ContentSearchResponse>(response);
+  },
+
+  searchSymbols: async (
+    repoIds: string[],
+    query: string,
+    workspaceId?: string,
+    options?: RequestInit
+  ): Promise<CodeSymbol[]> => {
+    const params = new URLSearchParams({
+      q: query,
+      repo_ids: repoIds.join(','),
+    });
+    if (workspaceId) {
+      params.set('workspace_id', workspaceId);
+    }
+    const response = await makeRequest(
+      `/api/search/symbols?${params.toString()}`,
+      options
+    );
+    return handleApiResponse<CodeSymbol[]>(response);
+  },
 };
@@ -14,24 +14,51 @@ export function getDevServerWorkingDir(
 }
 
 /**
- * Deduplicate dev server processes by working directory, keeping the latest
- * process for each unique directory.
+ * Extract the name of the repo dev process a dev server process runs, if any.
  */
-export function deduplicateDevServersByWorkingDir(
+export function getDevServerProcessName(
+  process: ExecutionProcess
+): string | null {
+  const typ = process.executor_action?.typ;
+  if (typ && 'type' in typ && typ.type === 'ScriptRequest') {
+    return (typ as { dev_process?: string | null }).dev_process ?? null;
+  }
+  return null;
+}
+
+/**
+ * Label for a dev server tab: the working directory, followed by the dev
+ * process name for repos with several dev processes.
+ */
+export function getDevServerLabel(process: ExecutionProcess): string | null {
+  const workingDir = getDevServerWorkingDir(process);
+  const processName = getDevServerProcessName(process);
+  if (processName) {
+    return workingDir ? `${workingDir} · ${processName}` : processName;
+  }
+  return workingDir;
+}
+
+/**
+ * Deduplicate dev server processes by working directory and dev process,
+ * keeping the latest process for each.
+ */
+export function deduplicateDevServers(
   processes: ExecutionProcess[]
 ): ExecutionProcess[] {
-  const byWorkingDir = new Map<string, ExecutionProcess>();
+  const byKey = new Map<string, ExecutionProcess>();
   for (const process of processes) {
     const workingDir = getDevServerWorkingDir(process) ?? 'unknown';
-    const existing = byWorkingDir.get(workingDir);
+    const key = `${workingDir}/${getDevServerProcessName(process) ?? ''}`;
+    const existing = byKey.get(key);
     if (
       !existing ||
       new Date(process.started_at) > new Date(existing.started_at)
     ) {
-      byWorkingDir.set(workingDir, process);
+      byKey.set(key, process);
     }
   }
-  return Array.from(byWorkingDir.values());
+  return Array.from(byKey.values());
 }
 
 /**
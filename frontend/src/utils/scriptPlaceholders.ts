@@ -3,6 +3,7 @@ interface ScriptPlaceholders {
   dev: string;
   cleanup: string;
   archive: string;
+  verification: string;
 }
 
 interface ScriptPlaceholderStrategy {
@@ -24,6 +25,9 @@ REM This runs after coding agent execution - only if changes were made`,
       archive: `@echo off
 REM Add archive commands here...
 REM This runs when the workspace is archived`,
+      verification: `@echo off
+npm test
+REM Must succeed before the merge queue lands the workspace`,
     };
   }
 }
@@ -39,6 +43,8 @@ class UnixScriptPlaceholderStrategy implements ScriptPlaceholderStrategy {
 # This runs after coding agent execution - only if changes were made`,
       archive: `# Add archive commands here...
 # This runs when the workspace is archived`,
+      verification: `npm test
+# Must succeed before the merge queue lands the workspace`,
     };
   }
 }
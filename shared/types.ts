@@ -18,9 +18,148 @@ score: bigint, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type ConversationSearchKind = "user_message" | "assistant_message" | "tool_command" | "file_path" | "error";
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, default_working_dir?: string | null, };
+export type ConversationSearchMatch = { project_id: string, task_id: string, task_title: string, workspace_id: string, session_id: string, execution_process_id: string, 
+/**
+ * Index of the matching entry in the process's normalized conversation
+ */
+entry_index: bigint, kind: ConversationSearchKind, 
+/**
+ * Matching text with the hits wrapped in `**`
+ */
+snippet: string, created_at: Date, };
+
+export type ContentSearchMatch = { 
+/**
+ * Path prefixed with the root name, e.g. `my-repo/src/main.rs`
+ */
+path: string, 
+/**
+ * 1-based line number
+ */
+line_number: number, 
+/**
+ * 0-based byte offset of the first match within the line
+ */
+column: number, line: string, context_before: Array<string>, context_after: Array<string>, };
+
+export type ContentSearchEvent = { "type": "match" } & ContentSearchMatch | { "type": "done", truncated: boolean, files_searched: number, };
+
+export type ContentSearchResponse = { matches: Array<ContentSearchMatch>, truncated: boolean, };
+
+export type SymbolKind = "function" | "method" | "struct" | "enum" | "trait" | "interface" | "class" | "type";
+
+export type CodeSymbol = { name: string, kind: SymbolKind, 
+/**
+ * Path relative to the repository root; search results prefix it with the repo name
+ */
+path: string, 
+/**
+ * 1-based first line of the definition
+ */
+start_line: number, 
+/**
+ * 1-based last line of the definition
+ */
+end_line: number, };
+
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, verification_script: string | null, copy_files: string | null, 
+/**
+ * Files copied like `copy_files`, with `{{placeholders}}` such as `{{port}}` or
+ * `{{db_name}}` filled in with the workspace's values
+ */
+template_files: string | null, 
+/**
+ * Directories, like `node_modules`, linked into each worktree instead of copied
+ */
+symlink_dirs: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, verification_script?: string | null, copy_files?: string | null, template_files?: string | null, symlink_dirs?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, default_working_dir?: string | null, };
+
+export type CommitMessageStyle = "agent_summary" | "conventional";
+
+export type CommitHookMode = "run" | "skip" | "feedback";
+
+export type RepoCommitPolicy = { repo_id: string, message_style: CommitMessageStyle, 
+/**
+ * Squash all per-turn commits into one when a PR is opened or the branch is merged.
+ */
+squash_on_finish: boolean, signoff: boolean, gpg_sign: boolean, hook_mode: CommitHookMode, 
+/**
+ * Identity used for agent commits instead of the user's git config.
+ */
+author_name: string | null, author_email: string | null, created_at: Date, updated_at: Date, };
+
+export type UpdateRepoCommitPolicy = { message_style: CommitMessageStyle, squash_on_finish: boolean, signoff: boolean, gpg_sign: boolean, hook_mode: CommitHookMode, author_name: string | null, author_email: string | null, };
+
+export type ContextPackDelivery = "file" | "prompt";
+
+export type RepoContextPack = { repo_id: string, enabled: boolean, delivery: ContextPackDelivery, 
+/**
+ * Upper bound on the pack size, in approximate tokens (4 characters each).
+ */
+token_budget: bigint, 
+/**
+ * Free-form project notes included at the top of the pack.
+ */
+notes: string | null, created_at: Date, updated_at: Date, };
+
+export type UpdateRepoContextPack = { enabled: boolean, delivery: ContextPackDelivery, token_budget: bigint, notes: string | null, };
+
+export type RepoDevServerSettings = { repo_id: string, 
+/**
+ * Path polled over HTTP until it answers without a server error, e.g. `/health`
+ */
+health_path: string | null, 
+/**
+ * Regex matched against the dev server output, e.g. `ready in \d+ ms`
+ */
+ready_pattern: string | null, 
+/**
+ * Restart the dev server with backoff when it crashes
+ */
+auto_restart: boolean, created_at: Date, updated_at: Date, };
+
+export type UpdateRepoDevServerSettings = { health_path: string | null, ready_pattern: string | null, auto_restart: boolean, };
+
+export type RepoDevProcess = { id: string, repo_id: string, name: string, 
+/**
+ * Shell command run from the repo's worktree
+ */
+command: string, 
+/**
+ * Processes of the same repo that must be ready before this one starts
+ */
+depends_on: Array<string>, 
+/**
+ * Extra environment variables for the process
+ */
+env: Record<string, string>, created_at: Date, updated_at: Date, };
+
+export type UpsertRepoDevProcess = { name: string, command: string, depends_on: Array<string>, env: { [key in string]?: string }, };
+
+export type Secret = { id: string, project_id: string | null, repo_id: string | null, executor: BaseCodingAgent | null, 
+/**
+ * Environment variable name
+ */
+name: string, created_at: Date, updated_at: Date, };
+
+export type SecretScope = { project_id: string | null, repo_id: string | null, executor: BaseCodingAgent | null, };
+
+export type UpsertSecret = { name: string, 
+/**
+ * Plaintext value; stored encrypted and never returned
+ */
+value: string, } & SecretScope;
+
+export type ProjectRedactionSettings = { project_id: string, 
+/**
+ * Exact values, or names of variables whose values, that are never masked
+ */
+allowlist: Array<string>, created_at: Date, updated_at: Date, };
+
+export type UpdateProjectRedactionSettings = { allowlist: Array<string>, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
@@ -30,7 +169,47 @@ export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string,
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, verification_script: string | null, copy_files: string | null, 
+/**
+ * Files copied like `copy_files`, with `{{placeholders}}` such as `{{port}}` or
+ * `{{db_name}}` filled in with the workspace's values
+ */
+template_files: string | null, 
+/**
+ * Directories, like `node_modules`, linked into each worktree instead of copied
+ */
+symlink_dirs: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+
+export type CheckpointReason = "manual" | "before_rebase" | "before_reset" | "before_conflict_abort" | "before_restore";
+
+export type WorkspaceCheckpoint = { id: string, workspace_id: string, name: string, reason: CheckpointReason, created_at: Date, };
+
+export type WorkspaceCheckpointRepo = { id: string, checkpoint_id: string, repo_id: string, head_commit: string, 
+/**
+ * Commit on top of `head_commit` holding the uncommitted changes, untracked files
+ * included.
+ */
+snapshot_commit: string, created_at: Date, };
+
+export type WorkspaceCheckpointWithRepos = { repos: Array<WorkspaceCheckpointRepo>, id: string, workspace_id: string, name: string, reason: CheckpointReason, created_at: Date, };
+
+export type VisualSnapshot = { id: string, 
+/**
+ * Dev server the screenshot was taken from
+ */
+execution_process_id: string, route: string, viewport_width: bigint, viewport_height: bigint, image_id: string, 
+/**
+ * Same route served from the target branch
+ */
+baseline_image_id: string | null, 
+/**
+ * Changed pixels highlighted on top of the screenshot
+ */
+diff_image_id: string | null, 
+/**
+ * Fraction of pixels that differ from the baseline, between 0 and 1
+ */
+diff_ratio: number | null, created_at: Date, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -38,6 +217,88 @@ export type CreateTag = { tag_name: string, content: string, };
 
 export type UpdateTag = { tag_name: string | null, content: string | null, };
 
+export type TemplateVariableKind = { "type": "string" } | { "type": "choice", options: Array<string>, } | { "type": "repo_file_path" };
+
+export type TemplateVariable = { 
+/**
+ * Referenced as `{{name}}` in the template title and prompt.
+ */
+name: string, label: string | null, kind: TemplateVariableKind, required: boolean, default: string | null, };
+
+export type TemplateRepo = { repo_id: string, 
+/**
+ * Falls back to the repo's default target branch when not set.
+ */
+target_branch: string | null, };
+
+export type TaskTemplate = { id: string, 
+/**
+ * `None` for templates available in every project.
+ */
+project_id: string | null, name: string, description: string | null, title: string, prompt: string, variables: Array<TemplateVariable>, executor_config: ExecutorConfig | null, repos: Array<TemplateRepo>, 
+/**
+ * Shell script the agent is asked to run before finishing.
+ */
+verification_script: string | null, created_at: Date, updated_at: Date, };
+
+export type TaskTemplateWithImages = { image_ids: Array<string>, id: string, 
+/**
+ * `None` for templates available in every project.
+ */
+project_id: string | null, name: string, description: string | null, title: string, prompt: string, variables: Array<TemplateVariable>, executor_config: ExecutorConfig | null, repos: Array<TemplateRepo>, 
+/**
+ * Shell script the agent is asked to run before finishing.
+ */
+verification_script: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateTaskTemplate = { project_id: string | null, name: string, description: string | null, title: string, prompt: string, variables: Array<TemplateVariable>, executor_config: ExecutorConfig | null, repos: Array<TemplateRepo>, verification_script: string | null, image_ids: Array<string>, };
+
+export type RenderedTaskTemplate = { title: string, prompt: string, };
+
+export type IssueTrackerKind = "github" | "gitlab" | "jira";
+
+export type IssueImportSource = { id: string, project_id: string, tracker: IssueTrackerKind, 
+/**
+ * API root for GitLab and Jira, e.g. `https://gitlab.com`.
+ */
+base_url: string | null, 
+/**
+ * `owner/repo` on GitHub, the project path on GitLab, the project key on Jira.
+ */
+repository: string, 
+/**
+ * Search text (GitHub, GitLab) or a JQL condition (Jira) issues must match.
+ */
+query: string | null, labels: Array<string>, 
+/**
+ * Account the API token belongs to, for Jira basic auth.
+ */
+api_user: string | null, 
+/**
+ * Comment the workspace PR link on the issue once the PR is created.
+ */
+comment_pr_link: boolean, last_synced_at: Date | null, created_at: Date, updated_at: Date, };
+
+export type CreateIssueImportSource = { project_id: string, tracker: IssueTrackerKind, base_url: string | null, repository: string, query: string | null, labels: Array<string>, 
+/**
+ * Left unchanged on update when not set.
+ */
+api_token: string | null, api_user: string | null, comment_pr_link: boolean, };
+
+export type TaskIssueLink = { id: string, 
+/**
+ * `None` once the imported task has been deleted.
+ */
+task_id: string | null, source_id: string, 
+/**
+ * Issue number (GitHub, GitLab) or key (Jira).
+ */
+external_id: string, url: string, issue_updated_at: Date | null, pr_comment_url: string | null, created_at: Date, updated_at: Date, };
+
+export type IssueSyncSummary = { created: number, updated: number, unchanged: number, };
+
+export type BulkTaskInput = { title: string, description: string | null, status: TaskStatus | null, };
+
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
@@ -136,7 +397,31 @@ export type CreateImage = { file_path: string, original_name: string, mime_type:
 
 export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
 
-export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
+export type DevServerStatus = "starting" | "ready" | "crashed" | "stopped";
+
+export type DevServer = { id: string, workspace_id: string, repo_id: string, 
+/**
+ * Name of the repo's dev process, `None` for its single dev server script
+ */
+process_name: string | null, 
+/**
+ * Latest execution of the repo's dev server script
+ */
+execution_process_id: string, 
+/**
+ * Port passed to the dev server script as `PORT`
+ */
+port: bigint, status: DevServerStatus, 
+/**
+ * Automatic restarts since the dev server was last started by the user or came up ready
+ */
+restart_count: bigint, created_at: Date, updated_at: Date, };
+
+export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, 
+/**
+ * Dev servers started in the workspace, one per repo
+ */
+dev_servers: Array<DevServer>, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
 
 export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
 
@@ -182,6 +467,24 @@ additions: number | null, deletions: number | null, repoId: string | null, };
 
 export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
 
+export type RedactionCounts = { 
+/**
+ * Values of secrets and sensitive environment variables
+ */
+secrets: number, 
+/**
+ * Cloud provider and service API keys
+ */
+api_keys: number, github_tokens: number, private_keys: number, 
+/**
+ * Values assigned to sensitive-looking variables, e.g. `API_TOKEN=...`
+ */
+assignments: number, 
+/**
+ * Long random-looking strings
+ */
+high_entropy: number, };
+
 export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, };
 
 export type LoginStatus = { "status": "loggedout" } | { "status": "loggedin", profile: ProfileResponse, };
@@ -254,6 +557,67 @@ export type InitRepoRequest = { parent_path: string, folder_name: string, };
 
 export type TagSearchParams = { search: string | null, };
 
+export type RenderTaskTemplateRequest = { values: { [key in string]?: string }, 
+/**
+ * Repos checked for repo file path variables; defaults to the template's repos
+ */
+repo_ids: Array<string> | null, };
+
+export type CreateTaskFromTemplateRequest = { project_id: string, values: { [key in string]?: string }, 
+/**
+ * Overrides the template's default repos and target branches
+ */
+repos: Array<WorkspaceRepoInput> | null, 
+/**
+ * Overrides the template's default executor
+ */
+executor_config: ExecutorConfig | null, };
+
+export type BulkItemResult = { 
+/**
+ * Id of the task or workspace; `None` when a task could not be created
+ */
+id: string | null, 
+/**
+ * Task title or workspace branch, to identify the item in reports
+ */
+name: string, success: boolean, error: string | null, };
+
+export type BulkOperationResponse = { results: Array<BulkItemResult>, succeeded: number, failed: number, };
+
+export type BulkCreateTasksRequest = { project_id: string, tasks: Array<BulkTaskInput>, 
+/**
+ * CSV with a `title` column and optional `description` and `status` columns; its
+ * rows are created after `tasks`
+ */
+csv: string | null, };
+
+export type BulkStartAttemptsRequest = { project_id: string, 
+/**
+ * Only start these tasks; defaults to every task in the project
+ */
+task_ids: Array<string> | null, 
+/**
+ * Only start tasks with this status
+ */
+status: TaskStatus | null, executor_config: ExecutorConfig, repos: Array<WorkspaceRepoInput>, };
+
+export type BulkStopRequest = { project_id: string, include_dev_servers: boolean, };
+
+export type WorkspaceCleanupAction = "archive" | "delete";
+
+export type BulkCleanupWorkspacesRequest = { project_id: string, 
+/**
+ * Workspaces without activity for this many days are cleaned up
+ */
+older_than_days: number, action: WorkspaceCleanupAction, 
+/**
+ * Report the matching workspaces without changing them
+ */
+dry_run: boolean, };
+
+export type BulkUpdateStatusRequest = { task_ids: Array<string>, status: TaskStatus, };
+
 export type TokenResponse = { access_token: string, expires_at: string | null, };
 
 export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
@@ -280,7 +644,11 @@ export type AgentPresetOptionsQuery = { executor: BaseCodingAgent, variant: stri
 
 export type CurrentUserResponse = { user_id: string, };
 
-export type CreateFollowUpAttempt = { prompt: string, executor_config: ExecutorConfig, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+export type CreateFollowUpAttempt = { prompt: string, executor_config: ExecutorConfig, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, 
+/**
+ * Requests recorded by the preview proxy to append to the prompt
+ */
+preview_request_ids: Array<string> | null, };
 
 export type ResetProcessRequest = { process_id: string, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
@@ -296,6 +664,20 @@ export type RenameBranchRequest = { new_branch_name: string, };
 
 export type RenameBranchResponse = { branch: string, };
 
+export type QueueMessageRequest = { message: string, 
+/**
+ * Executor for this message; the session's latest executor is used when unset
+ */
+executor_config: ExecutorConfig | null, };
+
+export type ReorderQueueRequest = { 
+/**
+ * Every queued message of the session, in the new order
+ */
+message_ids: Array<string>, };
+
+export type SendUserInputRequest = { message: string, };
+
 export type StartReviewRequest = { executor_config: ExecutorConfig, additional_prompt: string | null, use_all_workspace_commits: boolean, };
 
 export type ReviewError = { "type": "process_already_running" };
@@ -330,6 +712,10 @@ export type ContinueRebaseRequest = { repo_id: string, };
 
 export type AbortConflictsRequest = { repo_id: string, };
 
+export type ResolveConflictsRequest = { repo_id: string, session_id: string, executor_config: ExecutorConfig, additional_prompt: string | null, };
+
+export type ResolveConflictsError = { "type": "no_conflicts_in_progress" } | { "type": "unsupported_operation", op: ConflictOp, } | { "type": "process_already_running" };
+
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
@@ -348,6 +734,114 @@ export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_n
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
+export type StackQuery = { repo_id: string, };
+
+export type RestackRequest = { repo_id: string, };
+
+export type RestackOutcome = { "type": "rebased", pushed: boolean, } | { "type": "merge_conflicts", message: string, conflicted_files: Array<string>, } | { "type": "push_failed", message: string, } | { "type": "failed", message: string, } | { "type": "skipped" };
+
+export type RestackLayerResult = { workspace_id: string, branch: string, base_branch: string, outcome: RestackOutcome, };
+
+export type RestackResponse = { layers: Array<RestackLayerResult>, };
+
+export type StackLayer = { workspace_id: string, task_id: string, task_title: string, parent_workspace_id: string | null, depth: number, branch: string, 
+/**
+ * Branch this layer's PR should target
+ */
+base_branch: string, pr: PullRequestInfo | null, merged: boolean, };
+
+export type CreateCheckpointRequest = { name: string | null, };
+
+export type CheckpointRepoDiff = { repo_id: string, repo_name: string, diffs: Array<Diff>, };
+
+export type SetPreviewCaptureRequest = { 
+/**
+ * Dev server port to record through the preview proxy; `null` stops recording
+ */
+port: number | null, };
+
+export type CapturedHeader = { name: string, 
+/**
+ * Credentials are replaced with `[REDACTED]`
+ */
+value: string, };
+
+export type CapturedBody = { 
+/**
+ * Body decoded as UTF-8, cut at the capture limit
+ */
+text: string, truncated: boolean, };
+
+export type CapturedRequestSummary = { id: string, method: string, 
+/**
+ * Path and query string as sent to the dev server
+ */
+path: string, 
+/**
+ * Response status, `None` when the dev server could not be reached
+ */
+status: number | null, error: string | null, duration_ms: bigint, request_size: bigint, response_size: bigint, request_content_type: string | null, response_content_type: string | null, started_at: Date, };
+
+export type CapturedRequest = { request_headers: Array<CapturedHeader>, response_headers: Array<CapturedHeader>, 
+/**
+ * `None` when the body was empty or not text
+ */
+request_body: CapturedBody | null, response_body: CapturedBody | null, } & CapturedRequestSummary;
+
+export type PreviewCaptureStatus = { 
+/**
+ * Dev server port being recorded, `None` when capture is off
+ */
+port: number | null, 
+/**
+ * Recorded requests, newest first
+ */
+requests: Array<CapturedRequestSummary>, };
+
+export type SnapshotViewport = { width: number, height: number, };
+
+export type CaptureVisualSnapshotsRequest = { 
+/**
+ * Dev server to capture; defaults to the workspace's first ready dev server
+ */
+dev_server_id: string | null, 
+/**
+ * Start the dev server from the target branch too and diff against it
+ */
+compare_with_target_branch: boolean, 
+/**
+ * Routes to capture; defaults to `/`
+ */
+routes: Array<string> | null, 
+/**
+ * Viewport sizes; defaults to a desktop and a mobile size
+ */
+viewports: Array<SnapshotViewport> | null, };
+
+export type VisualSnapshotResponse = { 
+/**
+ * Paths relative to the worktree, for referencing the images in prompts and PRs
+ */
+image_path: string | null, baseline_image_path: string | null, diff_image_path: string | null, } & VisualSnapshot;
+
+export type VisualSnapshotRunStatus = "running" | "completed" | "failed";
+
+/**
+ * Latest snapshot run of a workspace. Runs take a while, as they may start the target
+ * branch's dev server, so they continue in the background.
+ */
+export type VisualSnapshotRun = { status: VisualSnapshotRunStatus, error: string | null, snapshot_count: number, started_at: Date, finished_at: Date | null, };
+
+export type MergeQueueFailure = { "type": "merge_conflicts", message: string, conflicted_files: Array<string>, } | { "type": "verification_failed", exit_code: number | null, output: string, } | { "type": "error", message: string, };
+
+export type MergeQueueEntryStatus = { "status": "queued" } | { "status": "rebasing" } | { "status": "verifying" } | { "status": "merging" } | { "status": "merged", merge_commit: string, } | { "status": "failed", failure: MergeQueueFailure, } | { "status": "cancelled" };
+
+export type MergeQueueEntry = { id: string, workspace_id: string, repo_id: string, target_branch: string, branch: string, 
+/**
+ * Zero-based position among the queued entries for the same target, if still waiting
+ */
+position: number | null, status: MergeQueueEntryStatus, enqueued_at: string, updated_at: string, };
+
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
 
 export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
@@ -470,21 +964,27 @@ export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
-export type QueuedMessage = { 
+export type QueuedMessage = { id: string, session_id: string, 
 /**
- * The session this message is queued for
+ * Messages run in ascending position order
  */
-session_id: string, 
+position: bigint, message: string, 
 /**
- * The follow-up data (message + variant)
+ * Executor override for this message; the session's latest executor is used when unset
  */
-data: DraftFollowUpData, 
+executor_config: ExecutorConfig | null, created_at: Date, updated_at: Date, };
+
+export type QueuePause = { session_id: string, reason: string, created_at: Date, };
+
+export type QueueStatus = { "status": "empty" } | { "status": "queued", 
 /**
- * Timestamp when the message was queued
+ * Queued messages in the order they will run
  */
-queued_at: string, };
-
-export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
+messages: Array<QueuedMessage>, 
+/**
+ * Set when the queue stopped draining after a failed or stopped execution
+ */
+paused: QueuePause | null, };
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
@@ -527,13 +1027,17 @@ export type ScriptRequest = { script: string, language: ScriptRequestLanguage, c
  * Optional relative path to execute the script in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Name of the repo dev process this script runs, for repos with several
+ */
+dev_process: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", REPLAY = "REPLAY" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "REPLAY": Replay };
 
 export type SlashCommandDescription = { 
 /**
@@ -573,11 +1077,11 @@ models?: Array<string>,
  */
 reasoning_by_model?: { [key in string]?: string }, };
 
-export type ExecutorProfile = { recently_used_models?: ExecutorRecentModels | null, } & ({ [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } });
+export type ExecutorProfile = { recently_used_models?: ExecutorRecentModels | null, } & ({ [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "REPLAY": Replay } });
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorProfile }, };
 
-export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE" }
+export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE", USER_INPUT = "USER_INPUT", INTERRUPT = "INTERRUPT" }
 
 export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, agent?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
@@ -615,6 +1119,8 @@ export type QwenCode = { append_prompt: AppendPrompt, model?: string | null, age
 
 export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
+export type Replay = { fixture_path: string, speed?: number | null, };
+
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
@@ -695,7 +1201,7 @@ value: JsonValue, };
 
 export type ToolResultValueType = { "type": "markdown" } | { "type": "json" };
 
-export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" };
+export type ToolStatus = { "status": "created" } | { "status": "success" } | { "status": "failed" } | { "status": "denied", reason: string | null, } | { "status": "pending_approval", approval_id: string, requested_at: string, timeout_at: string, } | { "status": "timed_out" } | { "status": "cancelled" };
 
 export type PatchType = { "type": "NORMALIZED_ENTRY", "content": NormalizedEntry } | { "type": "STDOUT", "content": string } | { "type": "STDERR", "content": string } | { "type": "DIFF", "content": Diff };
 